@@ -21,6 +21,42 @@ pub struct Application {
     pub category: Option<String>,
 }
 
+impl Application {
+    /// `Experimental` or `Stable`, from `isExperimental`.
+    pub fn support_level(&self) -> &'static str {
+        if self.is_experimental { "Experimental" } else { "Stable" }
+    }
+}
+
+/// Active applications sharing one [`Application::support_level`].
+pub struct ApplicationGroup<'a> {
+    pub level: &'static str,
+    pub applications: Vec<&'a Application>,
+}
+
+impl ApplicationGroup<'_> {
+    pub fn is_experimental(&self) -> bool {
+        self.level == "Experimental"
+    }
+}
+
+/// Active applications grouped by [`Application::support_level`], stable
+/// first; names are sorted within a group and empty groups are dropped.
+pub fn group_by_support_level(applications: &[Application]) -> Vec<ApplicationGroup<'_>> {
+    ["Stable", "Experimental"]
+        .into_iter()
+        .map(|level| {
+            let mut apps: Vec<&Application> = applications
+                .iter()
+                .filter(|a| a.is_active && a.support_level() == level)
+                .collect();
+            apps.sort_by_key(|a| a.name.to_lowercase());
+            ApplicationGroup { level, applications: apps }
+        })
+        .filter(|group| !group.applications.is_empty())
+        .collect()
+}
+
 /// Load available one-click applications from the API
 pub async fn load_applications(
     client: &reqwest::Client,
@@ -73,3 +109,42 @@ pub async fn load_applications(
     
     applications
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(name: &str, is_active: bool, is_experimental: bool) -> Application {
+        Application {
+            id: name.to_lowercase(),
+            name: name.to_string(),
+            price: 0.0,
+            pricing_type: String::new(),
+            is_active,
+            logo_url: None,
+            tag: String::new(),
+            is_experimental,
+            description: None,
+            os_family: String::new(),
+            os_list: Vec::new(),
+            category: None,
+        }
+    }
+
+    #[test]
+    fn test_group_by_support_level() {
+        let apps = vec![
+            app("WordPress", true, false),
+            app("Ollama", true, true),
+            app("docker", true, false),
+            app("Retired", false, false),
+        ];
+        let groups = group_by_support_level(&apps);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].level, "Stable");
+        let stable: Vec<&str> = groups[0].applications.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(stable, vec!["docker", "WordPress"]);
+        assert!(groups[1].is_experimental());
+        assert!(group_by_support_level(&[app("Only", true, true)])[0].is_experimental());
+    }
+}
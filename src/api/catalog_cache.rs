@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use serde_json::Value;
+use super::client::{api_get_conditional, ConditionalResponse};
+
+/// A catalog payload together with the `ETag` the upstream served it with.
+struct CachedCatalog {
+    etag: String,
+    payload: Value,
+}
+
+fn cache() -> &'static Mutex<HashMap<String, CachedCatalog>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, CachedCatalog>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Build the cache key for a catalog request (base URL, endpoint and query).
+pub fn cache_key(api_base_url: &str, endpoint: &str, params: &[(String, String)]) -> String {
+    let query = params
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("&");
+    format!("{}{}?{}", api_base_url, endpoint, query)
+}
+
+/// GET a rarely-changing catalog endpoint (regions, OS list, products) with
+/// conditional refresh.
+///
+/// The last successful payload is kept alongside its `ETag`; later calls send
+/// `If-None-Match` and reuse the cached payload on `304 Not Modified`. If the
+/// upstream does not send an `ETag`, nothing is cached and every call fetches
+/// the full payload as before.
+pub async fn cached_catalog_get(
+    client: &reqwest::Client,
+    api_base_url: &str,
+    api_token: &str,
+    endpoint: &str,
+    params: Vec<(String, String)>,
+) -> Value {
+    let key = cache_key(api_base_url, endpoint, &params);
+    let etag = cache().lock().unwrap().get(&key).map(|c| c.etag.clone());
+
+    let response = api_get_conditional(client, api_base_url, api_token, endpoint, Some(params.clone()), etag.as_deref()).await;
+    let response = match response {
+        ConditionalResponse::NotModified => {
+            if let Some(cached) = cache().lock().unwrap().get(&key) {
+                return cached.payload.clone();
+            }
+            // The entry vanished between the lookup and the response; refetch unconditionally.
+            api_get_conditional(client, api_base_url, api_token, endpoint, Some(params), None).await
+        }
+        fresh => fresh,
+    };
+
+    match response {
+        ConditionalResponse::Fresh { payload, etag } => {
+            let ok = payload.get("code").and_then(|c| c.as_str()) == Some("OKAY");
+            let mut cache = cache().lock().unwrap();
+            match etag {
+                Some(etag) if ok => {
+                    cache.insert(key, CachedCatalog { etag, payload: payload.clone() });
+                }
+                _ => {
+                    cache.remove(&key);
+                }
+            }
+            payload
+        }
+        ConditionalResponse::NotModified => serde_json::json!({"error": "Unexpected 304 Not Modified without a cached payload"}),
+    }
+}
+
+/// Drop every cached catalog payload (regions, OS list, products) so the next
+/// request fetches them in full.
+pub fn clear_catalog_cache() {
+    cache().lock().unwrap().clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_key_includes_query_params() {
+        let a = cache_key("https://api", "/v1/products", &[("regionId".into(), "us-1".into())]);
+        let b = cache_key("https://api", "/v1/products", &[("regionId".into(), "eu-1".into())]);
+        assert_ne!(a, b);
+        assert_eq!(a, "https://api/v1/products?regionId=us-1");
+    }
+}
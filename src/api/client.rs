@@ -1,33 +1,77 @@
 use serde_json::Value;
 use yansi::Paint;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+use std::time::Duration;
 
 static SILENT: AtomicBool = AtomicBool::new(false);
+static API_VERSION: RwLock<String> = RwLock::new(String::new());
 
 pub fn set_silent(silent: bool) {
     SILENT.store(silent, Ordering::Relaxed);
 }
 
+/// Set the upstream API version (`API_VERSION`) that replaces the `/v1/`
+/// prefix call sites are written with.
+pub fn set_api_version(version: &str) {
+    *API_VERSION.write().unwrap() = version.to_string();
+}
+
+/// Build the HTTP client for upstream API calls. `ca_cert` names a PEM file
+/// whose certificates are trusted alongside the system roots; `insecure`
+/// skips certificate verification altogether.
+pub fn build_api_client(ca_cert: Option<&str>, insecure: bool) -> Result<reqwest::Client, String> {
+    let mut builder = reqwest::Client::builder().user_agent(format!("Zy/{}", env!("CARGO_PKG_VERSION")));
+    if let Some(path) = ca_cert {
+        let pem = std::fs::read(path).map_err(|e| format!("Cannot read API_CA_CERT {}: {}", path, e))?;
+        let certs = reqwest::Certificate::from_pem_bundle(&pem)
+            .map_err(|e| format!("Invalid API_CA_CERT {}: {}", path, e))?;
+        if certs.is_empty() {
+            return Err(format!("API_CA_CERT {} contains no certificates", path));
+        }
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+    if insecure {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+    builder.build().map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+/// Rewrite a `/v1/...` endpoint to `version`. Empty or `v1` leaves it as is.
+fn endpoint_for_version(endpoint: &str, version: &str) -> String {
+    match endpoint.strip_prefix("/v1/") {
+        Some(rest) if !version.is_empty() && version != "v1" => format!("/{}/{}", version, rest),
+        _ => endpoint.to_string(),
+    }
+}
+
+fn versioned_endpoint(endpoint: &str) -> String {
+    endpoint_for_version(endpoint, &API_VERSION.read().unwrap())
+}
+
 fn log_output(msg: String) {
     if !SILENT.load(Ordering::Relaxed) {
         println!("{}", msg);
     }
 }
 
-/// Core HTTP client function for making API calls.
-/// Handles authentication, request building, and error responses.
-pub async fn api_call(
-    client: &reqwest::Client,
+/// Print the outgoing request as an equivalent (colourised) curl command.
+fn log_curl_request(
+    method: &str,
     api_base_url: &str,
     api_token: &str,
-    method: &str,
     endpoint: &str,
-    body: Option<Value>,
-    params: Option<Vec<(String, String)>>,
-) -> Value {
-    // --- Curl Logging ---
+    body: Option<&Value>,
+    params: Option<&Vec<(String, String)>>,
+    if_none_match: Option<&str>,
+) {
+    if SILENT.load(Ordering::Relaxed) {
+        return;
+    }
     let mut url_for_log = format!("{}{}", api_base_url, endpoint);
-    if let Some(ref p) = params {
+    if let Some(p) = params {
         if !p.is_empty() {
              let query_string = p.iter()
                 .map(|(k, v)| format!("{}={}", k, v))
@@ -53,6 +97,18 @@ pub async fn api_call(
             Paint::new(format!("'API-Token: {}'", masked_token)).fg(yansi::Color::Magenta)
         ));
     }
+    if let Some(tag) = if_none_match {
+        parts.push(format!("{} {}",
+            Paint::new("-H").fg(yansi::Color::Magenta),
+            Paint::new(format!("'If-None-Match: {}'", tag)).fg(yansi::Color::Magenta)
+        ));
+    }
+    if let Some(key) = super::idempotency::current() {
+        parts.push(format!("{} {}",
+            Paint::new("-H").fg(yansi::Color::Magenta),
+            Paint::new(format!("'{}: {}'", super::idempotency::IDEMPOTENCY_KEY_HEADER, key)).fg(yansi::Color::Magenta)
+        ));
+    }
     if body.is_some() {
         parts.push(format!("{} {}", 
             Paint::new("-H").fg(yansi::Color::Magenta), 
@@ -60,7 +116,7 @@ pub async fn api_call(
         ));
     }
 
-    if let Some(ref d) = body {
+    if let Some(d) = body {
         let json_str = serde_json::to_string_pretty(d).unwrap_or_default();
         let escaped_json = json_str.replace("'", "'\\''");
         parts.push(format!("{} {}", 
@@ -69,7 +125,139 @@ pub async fn api_call(
         ));
     }
     log_output(format!("Request:\n{}", parts.join(" ")));
-    // --------------------
+}
+
+fn log_response(result: &Value) {
+    if SILENT.load(Ordering::Relaxed) {
+        return;
+    }
+    // Colorize the response JSON for better readability in the terminal
+    let json_str = serde_json::to_string(result).unwrap_or_else(|_| format!("{:?}", result));
+    // Grayed out color (dimmed/dark gray)
+    let response_str = Paint::new(json_str).rgb(100, 100, 100).to_string();
+    log_output(format!("Response:\n{}", response_str));
+}
+
+/// Keep a 401/403 body but record the status in it (like the 429 payload),
+/// so callers can tell an authorization problem from an ordinary failure.
+fn tag_auth_failure(status: reqwest::StatusCode, body: Option<Value>) -> Value {
+    let mut payload = match body {
+        Some(Value::Object(map)) => Value::Object(map),
+        _ => serde_json::json!({"error": format!("Upstream returned {}", status)}),
+    };
+    payload["status"] = Value::from(status.as_u16());
+    payload
+}
+
+fn is_auth_failure(status: reqwest::StatusCode) -> bool {
+    status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN
+}
+
+/// `Some(401)` or `Some(403)` when the upstream refused the API token.
+pub fn auth_failure_status(payload: &Value) -> Option<u16> {
+    payload
+        .get("status")
+        .and_then(|s| s.as_u64())
+        .filter(|s| *s == 401 || *s == 403)
+        .map(|s| s as u16)
+}
+
+/// Explain an upstream 401/403 in terms of the API token, quoting the
+/// upstream's own detail; `None` for any other payload.
+pub fn auth_failure_message(payload: &Value) -> Option<String> {
+    let status = auth_failure_status(payload)?;
+    let mut message = if status == 401 {
+        "The API token was rejected (upstream 401). Check that API_TOKEN is set and hasn't expired or been revoked.".to_string()
+    } else {
+        "Your API token isn't authorized for this action (upstream 403). Grant the token this permission in the Cloudzy dashboard, or set API_TOKEN to a token with a wider scope.".to_string()
+    };
+    let detail = payload
+        .get("detail")
+        .and_then(|d| d.as_str())
+        .filter(|d| !d.trim().is_empty());
+    if let Some(detail) = detail {
+        message.push_str(&format!(" Upstream said: {}", detail));
+    }
+    Some(message)
+}
+
+/// Payload for a call that hit its [`super::timeout`] budget. A write may
+/// still be applied upstream, so the message says to check before retrying.
+fn timed_out_payload(method: &str) -> Value {
+    let secs = super::timeout::for_method(method).map(|t| t.as_secs()).unwrap_or_default();
+    let error = if method.eq_ignore_ascii_case("GET") {
+        format!("Upstream API did not answer within {}s", secs)
+    } else {
+        format!("Upstream API did not answer within {}s; the change may still be applied, so check the instance before retrying", secs)
+    };
+    serde_json::json!({"error": error, "status": 504})
+}
+
+/// How many times a request rejected with 429 is sent again.
+const RATE_LIMIT_RETRIES: u32 = 2;
+/// Longest `Retry-After` we are willing to wait out; longer values are capped.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(30);
+/// Wait before the first retry when a 429 carries no usable `Retry-After`;
+/// doubles for each later one.
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// Parse a `Retry-After` value, either delta-seconds or an HTTP date.
+fn parse_retry_after(value: &str, now: chrono::DateTime<chrono::Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some((at.with_timezone(&chrono::Utc) - now).to_std().unwrap_or(Duration::ZERO))
+}
+
+/// How long to wait before retry number `attempt` (0-based) of a 429.
+fn rate_limit_wait(retry_after: Option<&str>, attempt: u32, now: chrono::DateTime<chrono::Utc>) -> Duration {
+    retry_after
+        .and_then(|v| parse_retry_after(v, now))
+        .unwrap_or(DEFAULT_RETRY_AFTER * 2u32.pow(attempt))
+        .min(MAX_RETRY_AFTER)
+}
+
+/// Send `req` through the global rate limiter. A 429 is retried up to
+/// [`RATE_LIMIT_RETRIES`] times after the wait the upstream asks for.
+async fn send_with_rate_limit_retry(mut req: reqwest::RequestBuilder) -> Result<reqwest::Response, reqwest::Error> {
+    let mut attempt = 0;
+    loop {
+        let retry = if attempt < RATE_LIMIT_RETRIES { req.try_clone() } else { None };
+        super::rate_limit::acquire().await;
+        let outcome = req.send().await;
+        super::health::record(super::health::status_for(&outcome));
+        match (outcome, retry) {
+            (Ok(resp), Some(next)) if resp.status() == reqwest::StatusCode::TOO_MANY_REQUESTS => {
+                let retry_after = resp.headers().get(reqwest::header::RETRY_AFTER).and_then(|v| v.to_str().ok());
+                let wait = rate_limit_wait(retry_after, attempt, chrono::Utc::now());
+                tracing::warn!(wait_secs = wait.as_secs_f64(), attempt = attempt + 1, "Rate limited by upstream API, retrying");
+                if !SILENT.load(Ordering::Relaxed) {
+                    eprintln!("{}", Paint::yellow(&format!("Rate limited, retrying in {}s", wait.as_secs_f64().ceil())));
+                }
+                tokio::time::sleep(wait).await;
+                req = next;
+                attempt += 1;
+            }
+            (outcome, _) => return outcome,
+        }
+    }
+}
+
+/// Core HTTP client function for making API calls.
+/// Handles authentication, request building, and error responses.
+pub async fn api_call(
+    client: &reqwest::Client,
+    api_base_url: &str,
+    api_token: &str,
+    method: &str,
+    endpoint: &str,
+    body: Option<Value>,
+    params: Option<Vec<(String, String)>>,
+) -> Value {
+    let endpoint = &versioned_endpoint(endpoint);
+    log_curl_request(method, api_base_url, api_token, endpoint, body.as_ref(), params.as_ref(), None);
 
     let url = format!("{}{}", api_base_url, endpoint);
     let mut req = match method {
@@ -83,6 +271,12 @@ pub async fn api_call(
     if !api_token.is_empty() {
         req = req.header("API-Token", api_token);
     }
+    if let Some(id) = super::request_id::current() {
+        req = req.header(super::request_id::REQUEST_ID_HEADER, id);
+    }
+    if let Some(key) = super::idempotency::current() {
+        req = req.header(super::idempotency::IDEMPOTENCY_KEY_HEADER, key);
+    }
     
     if let Some(ref p) = params {
         req = req.query(p);
@@ -92,16 +286,151 @@ pub async fn api_call(
         req = req.json(b);
     }
     
-    let result = match req.send().await {
+    if let Some(timeout) = super::timeout::for_method(method) {
+        req = req.timeout(timeout);
+    }
+
+    let outcome = send_with_rate_limit_retry(req).await;
+    let result = match outcome {
+        Ok(resp) if resp.status() == reqwest::StatusCode::TOO_MANY_REQUESTS => serde_json::json!({
+            "error": "Rate limited by upstream API (429 Too Many Requests)",
+            "status": 429,
+        }),
+        Ok(resp) if is_auth_failure(resp.status()) => {
+            let status = resp.status();
+            tag_auth_failure(status, resp.json().await.ok())
+        }
         Ok(resp) => resp.json().await.unwrap_or_else(|_| serde_json::json!({"error": "Failed to parse response"})),
+        Err(e) if e.is_timeout() => timed_out_payload(method),
         Err(e) => serde_json::json!({"error": format!("Request failed: {}", e)}),
     };
 
-    // Colorize the response JSON for better readability in the terminal
-    let json_str = serde_json::to_string(&result).unwrap_or_else(|_| format!("{:?}", result));
-    // Grayed out color (dimmed/dark gray)
-    let response_str = Paint::new(json_str).rgb(100, 100, 100).to_string();
-    log_output(format!("Response:\n{}", response_str));
+    log_response(&result);
 
     result
 }
+
+/// Result of a conditional GET issued with `If-None-Match`.
+pub enum ConditionalResponse {
+    /// Upstream answered `304 Not Modified`; the caller's cached payload is still valid.
+    NotModified,
+    /// A full payload, along with the `ETag` the upstream sent for it (if any).
+    Fresh { payload: Value, etag: Option<String> },
+}
+
+/// GET an endpoint, sending `If-None-Match` when a previously seen `ETag` is supplied.
+/// A `304` short-circuits without reading or parsing a body.
+pub async fn api_get_conditional(
+    client: &reqwest::Client,
+    api_base_url: &str,
+    api_token: &str,
+    endpoint: &str,
+    params: Option<Vec<(String, String)>>,
+    etag: Option<&str>,
+) -> ConditionalResponse {
+    let endpoint = &versioned_endpoint(endpoint);
+    log_curl_request("GET", api_base_url, api_token, endpoint, None, params.as_ref(), etag);
+
+    let url = format!("{}{}", api_base_url, endpoint);
+    let mut req = client.get(&url);
+    if !api_token.is_empty() {
+        req = req.header("API-Token", api_token);
+    }
+    if let Some(id) = super::request_id::current() {
+        req = req.header(super::request_id::REQUEST_ID_HEADER, id);
+    }
+    if let Some(tag) = etag {
+        req = req.header(reqwest::header::IF_NONE_MATCH, tag);
+    }
+    if let Some(ref p) = params {
+        req = req.query(p);
+    }
+    if let Some(timeout) = super::timeout::for_method("GET") {
+        req = req.timeout(timeout);
+    }
+
+    let outcome = send_with_rate_limit_retry(req).await;
+    let (payload, new_etag) = match outcome {
+        Ok(resp) if resp.status() == reqwest::StatusCode::NOT_MODIFIED => {
+            log_output("Response:\n304 Not Modified (using cached payload)".to_string());
+            return ConditionalResponse::NotModified;
+        }
+        Ok(resp) if resp.status() == reqwest::StatusCode::TOO_MANY_REQUESTS => (
+            serde_json::json!({
+                "error": "Rate limited by upstream API (429 Too Many Requests)",
+                "status": 429,
+            }),
+            None,
+        ),
+        Ok(resp) if is_auth_failure(resp.status()) => {
+            let status = resp.status();
+            (tag_auth_failure(status, resp.json().await.ok()), None)
+        }
+        Ok(resp) => {
+            let new_etag = resp
+                .headers()
+                .get(reqwest::header::ETAG)
+                .and_then(|v| v.to_str().ok())
+                .map(|s| s.to_string());
+            let payload = resp.json().await.unwrap_or_else(|_| serde_json::json!({"error": "Failed to parse response"}));
+            (payload, new_etag)
+        }
+        Err(e) if e.is_timeout() => (timed_out_payload("GET"), None),
+        Err(e) => (serde_json::json!({"error": format!("Request failed: {}", e)}), None),
+    };
+
+    log_response(&payload);
+    ConditionalResponse::Fresh { payload, etag: new_etag }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_api_client_rejects_bad_ca() {
+        assert!(build_api_client(None, false).is_ok());
+        assert!(build_api_client(Some("/nonexistent/ca.pem"), false).is_err());
+        let path = std::env::temp_dir().join(format!("zy-empty-ca-{}.pem", std::process::id()));
+        std::fs::write(&path, "not a certificate\n").unwrap();
+        let result = build_api_client(path.to_str(), false);
+        std::fs::remove_file(&path).ok();
+        assert!(result.unwrap_err().contains("no certificates"));
+    }
+
+    #[test]
+    fn test_auth_failure_message() {
+        let forbidden = tag_auth_failure(
+            reqwest::StatusCode::FORBIDDEN,
+            Some(serde_json::json!({"code": "FORBIDDEN", "detail": "Token lacks instances:delete"})),
+        );
+        assert_eq!(auth_failure_status(&forbidden), Some(403));
+        let message = auth_failure_message(&forbidden).unwrap();
+        assert!(message.contains("isn't authorized"));
+        assert!(message.ends_with("Upstream said: Token lacks instances:delete"));
+
+        let unauthorized = tag_auth_failure(reqwest::StatusCode::UNAUTHORIZED, None);
+        assert!(auth_failure_message(&unauthorized).unwrap().contains("rejected"));
+        let rate_limited = serde_json::json!({"error": "Rate limited", "status": 429});
+        assert!(auth_failure_message(&rate_limited).is_none());
+    }
+
+    #[test]
+    fn test_endpoint_for_version() {
+        assert_eq!(endpoint_for_version("/v1/instances", ""), "/v1/instances");
+        assert_eq!(endpoint_for_version("/v1/instances", "v1"), "/v1/instances");
+        assert_eq!(endpoint_for_version("/v1/instances/abc/poweron", "v2"), "/v2/instances/abc/poweron");
+        assert_eq!(endpoint_for_version("/health", "v2"), "/health");
+    }
+
+    #[test]
+    fn test_rate_limit_wait() {
+        let now = chrono::DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z").unwrap().with_timezone(&chrono::Utc);
+        assert_eq!(rate_limit_wait(Some("5"), 0, now), Duration::from_secs(5));
+        assert_eq!(rate_limit_wait(Some("Thu, 01 Jan 2026 00:00:12 GMT"), 0, now), Duration::from_secs(12));
+        assert_eq!(rate_limit_wait(Some("Wed, 31 Dec 2025 23:59:00 GMT"), 0, now), Duration::ZERO);
+        assert_eq!(rate_limit_wait(Some("3600"), 0, now), MAX_RETRY_AFTER);
+        assert_eq!(rate_limit_wait(Some("soon"), 0, now), DEFAULT_RETRY_AFTER);
+        assert_eq!(rate_limit_wait(None, 1, now), DEFAULT_RETRY_AFTER * 2);
+    }
+}
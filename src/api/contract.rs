@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use serde_json::Value;
+
+/// JSON type a response field is expected to have.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FieldType {
+    String,
+    Number,
+    Bool,
+    Object,
+    Array,
+}
+
+impl FieldType {
+    fn matches(self, value: &Value) -> bool {
+        match self {
+            FieldType::String => value.is_string(),
+            FieldType::Number => value.is_number(),
+            FieldType::Bool => value.is_boolean(),
+            FieldType::Object => value.is_object(),
+            FieldType::Array => value.is_array(),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            FieldType::String => "string",
+            FieldType::Number => "number",
+            FieldType::Bool => "bool",
+            FieldType::Object => "object",
+            FieldType::Array => "array",
+        }
+    }
+}
+
+/// Fields the parsers rely on, as (dotted path, type) pairs. `null` is
+/// accepted for any field; only absent keys and wrong types count as drift.
+pub type Schema = &'static [(&'static str, FieldType)];
+
+pub const REGION_SCHEMA: Schema = &[
+    ("id", FieldType::String),
+    ("name", FieldType::String),
+    ("config", FieldType::Object),
+];
+
+pub const PRODUCT_SCHEMA: Schema = &[
+    ("id", FieldType::String),
+    ("regionId", FieldType::String),
+    ("planId", FieldType::String),
+    ("isActive", FieldType::Bool),
+    ("plan.specification.cpu", FieldType::Number),
+    ("plan.specification.ram", FieldType::Number),
+    ("plan.specification.storage", FieldType::Number),
+    ("priceItems", FieldType::Array),
+];
+
+pub const INSTANCE_SCHEMA: Schema = &[
+    ("id", FieldType::String),
+    ("hostname", FieldType::String),
+    ("status", FieldType::String),
+    ("region", FieldType::String),
+    ("vcpuCount", FieldType::Number),
+    ("ram", FieldType::Number),
+    ("disk", FieldType::Number),
+    ("mainIp", FieldType::String),
+];
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn lookup<'a>(item: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(item, |value, key| value.get(key))
+}
+
+/// Contract problems across `items`, one line per field and kind of problem
+/// with how many items it affects, e.g.
+/// `ram: string in 3 of 3 items (expected number)`.
+pub fn check_items<'a>(items: impl IntoIterator<Item = &'a Value>, schema: Schema) -> Vec<String> {
+    let mut total = 0;
+    let mut not_objects = 0;
+    // (path, found type) -> count; found is "missing" for absent keys
+    let mut counts: Vec<((&str, &str), usize)> = Vec::new();
+    for item in items {
+        total += 1;
+        if !item.is_object() {
+            not_objects += 1;
+            continue;
+        }
+        for &(path, expected) in schema {
+            let found = match lookup(item, path) {
+                None => "missing",
+                Some(v) if v.is_null() || expected.matches(v) => continue,
+                Some(v) => json_type(v),
+            };
+            match counts.iter_mut().find(|(key, _)| *key == (path, found)) {
+                Some((_, n)) => *n += 1,
+                None => counts.push(((path, found), 1)),
+            }
+        }
+    }
+    let mut problems = Vec::new();
+    if not_objects > 0 {
+        problems.push(format!("{} of {} items are not objects", not_objects, total));
+    }
+    for ((path, found), n) in counts {
+        let expected = schema.iter().find(|(p, _)| *p == path).map(|(_, t)| t.name()).unwrap_or("");
+        problems.push(format!("{}: {} in {} of {} items (expected {})", path, found, n, total, expected));
+    }
+    problems
+}
+
+fn last_reported() -> &'static Mutex<HashMap<String, Vec<String>>> {
+    static REPORTED: OnceLock<Mutex<HashMap<String, Vec<String>>>> = OnceLock::new();
+    REPORTED.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Check `items` from `endpoint` against `schema` and log a warning listing
+/// every problem found. Each endpoint logs only when its set of problems
+/// changes, so a steady drift is reported once rather than on every call.
+pub fn validate_items<'a>(endpoint: &str, items: impl IntoIterator<Item = &'a Value>, schema: Schema) {
+    let items: Vec<&Value> = items.into_iter().collect();
+    // An empty (or failed) response says nothing about the contract
+    if items.is_empty() {
+        return;
+    }
+    let problems = check_items(items, schema);
+    let mut reported = last_reported().lock().unwrap();
+    let previous = reported.get(endpoint).map(Vec::as_slice).unwrap_or_default();
+    if previous == problems.as_slice() {
+        return;
+    }
+    if problems.is_empty() {
+        tracing::info!(endpoint, "Upstream response matches the expected fields again");
+    } else {
+        tracing::warn!(endpoint, problems = %problems.join("; "), "Upstream response does not match the expected fields");
+    }
+    reported.insert(endpoint.to_string(), problems);
+}
+
+/// Check one item from a per-resource endpoint such as `/v1/instances/{id}`.
+/// One resource matching says little about the others, so a clean item never
+/// clears earlier reports; each distinct problem is warned about once.
+pub fn validate_item(endpoint: &str, item: &Value, schema: Schema) {
+    let problems = check_items([item], schema);
+    let mut reported = last_reported().lock().unwrap();
+    let seen = reported.entry(endpoint.to_string()).or_default();
+    let new: Vec<String> = problems.into_iter().filter(|p| !seen.contains(p)).collect();
+    if new.is_empty() {
+        return;
+    }
+    tracing::warn!(endpoint, problems = %new.join("; "), "Upstream response does not match the expected fields");
+    seen.extend(new);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_check_items_aggregates_drift_per_field() {
+        let items = [
+            json!({"id": "a", "hostname": "a", "status": "active", "region": "us", "vcpuCount": 2, "ram": "4096", "disk": 50, "mainIp": null}),
+            json!({"id": "b", "hostname": "b", "status": "active", "region": "us", "ram": "2048", "disk": 25, "mainIp": "10.0.0.2"}),
+            json!("garbage"),
+        ];
+        assert_eq!(
+            check_items(&items, INSTANCE_SCHEMA),
+            vec![
+                "1 of 3 items are not objects",
+                "ram: string in 2 of 3 items (expected number)",
+                "vcpuCount: missing in 1 of 3 items (expected number)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_items_follows_nested_paths() {
+        let product = json!({
+            "id": "p", "regionId": "us", "planId": "x", "isActive": true, "priceItems": [],
+            "plan": {"specification": {"cpu": 2, "ram": 4, "storage": "50"}},
+        });
+        assert_eq!(
+            check_items([&product], PRODUCT_SCHEMA),
+            vec!["plan.specification.storage: string in 1 of 1 items (expected number)"]
+        );
+        assert!(check_items([&json!({"id": "r", "name": "R", "config": {}})], REGION_SCHEMA).is_empty());
+    }
+}
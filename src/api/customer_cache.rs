@@ -0,0 +1,44 @@
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// The account's default customer ID, looked up once and reused for SSH-key
+/// calls until it is older than `CUSTOMER_CACHE_SECS` or explicitly cleared.
+#[derive(Clone, Debug, Default)]
+pub struct CustomerIdCache {
+    inner: Arc<RwLock<Option<(Instant, String)>>>,
+}
+
+impl CustomerIdCache {
+    /// The cached ID if it was stored less than `max_age` ago.
+    pub fn get(&self, max_age: Duration) -> Option<String> {
+        let guard = self.inner.read().unwrap();
+        guard
+            .as_ref()
+            .filter(|(stored_at, _)| stored_at.elapsed() < max_age)
+            .map(|(_, id)| id.clone())
+    }
+
+    pub fn store(&self, id: String) {
+        *self.inner.write().unwrap() = Some((Instant::now(), id));
+    }
+
+    pub fn invalidate(&self) {
+        *self.inner.write().unwrap() = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_customer_id_expires_and_invalidates() {
+        let cache = CustomerIdCache::default();
+        assert!(cache.get(Duration::from_secs(60)).is_none());
+        cache.store("cust-1".into());
+        assert_eq!(cache.get(Duration::from_secs(60)).as_deref(), Some("cust-1"));
+        assert!(cache.get(Duration::ZERO).is_none());
+        cache.invalidate();
+        assert!(cache.get(Duration::from_secs(60)).is_none());
+    }
+}
@@ -76,6 +76,54 @@ pub async fn load_floating_ips(
     }
 }
 
+/// Floating IP allocation limits and pricing, as far as the API reports them.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FloatingIpQuota {
+    /// Maximum number of floating IPs the account may hold, if reported.
+    pub limit: Option<u64>,
+    /// Floating IPs currently allocated to the account.
+    pub in_use: u64,
+    /// Monthly price per floating IP, if reported.
+    pub price_per_ip: Option<f64>,
+}
+
+impl FloatingIpQuota {
+    /// How many more floating IPs can be allocated, when a limit is known.
+    pub fn remaining(&self) -> Option<u64> {
+        self.limit.map(|l| l.saturating_sub(self.in_use))
+    }
+}
+
+/// Extract quota and pricing from a `/v1/floating-ips` response. Returns
+/// `None` when the call failed.
+pub fn parse_floating_ip_quota(payload: &Value) -> Option<FloatingIpQuota> {
+    if payload.get("code").and_then(|c| c.as_str()) != Some("OKAY") {
+        return None;
+    }
+    let data = payload.get("data")?.as_object()?;
+    let number = |keys: &[&str]| {
+        keys.iter().find_map(|k| {
+            data.get(*k).and_then(|v| v.as_f64().or_else(|| v.as_str().and_then(|s| s.trim().parse().ok())))
+        })
+    };
+    let listed = data.get("floatingIps").and_then(|f| f.as_array()).map(|a| a.len() as u64).unwrap_or(0);
+    Some(FloatingIpQuota {
+        limit: number(&["limit", "maxCount", "quota"]).map(|v| v as u64),
+        in_use: data.get("total").and_then(|t| t.as_u64()).unwrap_or(listed),
+        price_per_ip: number(&["pricePerIp", "price", "monthlyPrice"]),
+    })
+}
+
+/// Load the account's floating IP quota and per-IP price
+pub async fn load_floating_ip_quota(
+    client: &reqwest::Client,
+    api_base_url: &str,
+    api_token: &str,
+) -> Option<FloatingIpQuota> {
+    let payload = api_call(client, api_base_url, api_token, "GET", "/v1/floating-ips", None, None).await;
+    parse_floating_ip_quota(&payload)
+}
+
 /// Create floating IPs
 pub async fn create_floating_ips(
     client: &reqwest::Client,
@@ -124,3 +172,28 @@ pub async fn release_floating_ip(
     let endpoint = format!("/v1/floating-ips/{}/release", ip_id);
     api_call(client, api_base_url, api_token, "POST", &endpoint, None, None).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_quota_with_limit_and_price() {
+        let payload = serde_json::json!({
+            "code": "OKAY",
+            "data": { "floatingIps": [{"id": "a"}], "total": 3, "limit": 5, "pricePerIp": "2.5" }
+        });
+        let quota = parse_floating_ip_quota(&payload).unwrap();
+        assert_eq!(quota.remaining(), Some(2));
+        assert_eq!(quota.price_per_ip, Some(2.5));
+    }
+
+    #[test]
+    fn test_parse_quota_without_limit() {
+        let payload = serde_json::json!({"code": "OKAY", "data": { "floatingIps": [{"id": "a"}, {"id": "b"}] }});
+        let quota = parse_floating_ip_quota(&payload).unwrap();
+        assert_eq!(quota.in_use, 2);
+        assert_eq!(quota.remaining(), None);
+        assert!(parse_floating_ip_quota(&serde_json::json!({"error": "x"})).is_none());
+    }
+}
@@ -0,0 +1,96 @@
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+/// How often the server probes the upstream when no other call has
+/// reported on it in the meantime.
+pub const HEALTHCHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Upstream reachability as last observed, shown as a badge on every page.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum ApiStatus {
+    /// No upstream call has completed yet.
+    #[default]
+    Unknown,
+    Connected,
+    /// The last call failed to connect, timed out, was rate limited or got
+    /// a 5xx answer.
+    Degraded { error: String },
+}
+
+impl ApiStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ApiStatus::Unknown => "unknown",
+            ApiStatus::Connected => "connected",
+            ApiStatus::Degraded { .. } => "degraded",
+        }
+    }
+
+    pub fn is_known(&self) -> bool {
+        !matches!(self, ApiStatus::Unknown)
+    }
+
+    pub fn is_degraded(&self) -> bool {
+        matches!(self, ApiStatus::Degraded { .. })
+    }
+
+    /// The failure behind a degraded status; empty otherwise.
+    pub fn error(&self) -> &str {
+        match self {
+            ApiStatus::Degraded { error } => error,
+            _ => "",
+        }
+    }
+}
+
+static LAST_SAMPLE: RwLock<Option<(Instant, ApiStatus)>> = RwLock::new(None);
+
+/// Classify an upstream response: anything the API answered below 500
+/// (other than 429) counts as connected, even an error like 404 or 403.
+pub fn status_for(outcome: &Result<reqwest::Response, reqwest::Error>) -> ApiStatus {
+    match outcome {
+        Ok(resp) => status_for_code(resp.status()),
+        Err(e) if e.is_timeout() => ApiStatus::Degraded { error: "request timed out".into() },
+        Err(e) if e.is_connect() => ApiStatus::Degraded { error: "connection failed".into() },
+        Err(e) => ApiStatus::Degraded { error: e.to_string() },
+    }
+}
+
+fn status_for_code(code: reqwest::StatusCode) -> ApiStatus {
+    if code.is_server_error() || code == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        ApiStatus::Degraded { error: format!("upstream answered {}", code) }
+    } else {
+        ApiStatus::Connected
+    }
+}
+
+/// Remember the outcome of the latest upstream call.
+pub fn record(status: ApiStatus) {
+    *LAST_SAMPLE.write().unwrap() = Some((Instant::now(), status));
+}
+
+/// The last recorded status; costs no upstream call.
+pub fn current() -> ApiStatus {
+    LAST_SAMPLE.read().unwrap().as_ref().map(|(_, s)| s.clone()).unwrap_or_default()
+}
+
+/// Whether no call has been recorded within `max_age`.
+pub fn is_stale(max_age: Duration) -> bool {
+    LAST_SAMPLE.read().unwrap().as_ref().is_none_or(|(at, _)| at.elapsed() >= max_age)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_for_code() {
+        assert_eq!(status_for_code(reqwest::StatusCode::OK), ApiStatus::Connected);
+        assert_eq!(status_for_code(reqwest::StatusCode::FORBIDDEN), ApiStatus::Connected);
+        let degraded = status_for_code(reqwest::StatusCode::BAD_GATEWAY);
+        assert!(degraded.is_degraded());
+        assert_eq!(degraded.error(), "upstream answered 502 Bad Gateway");
+        assert!(status_for_code(reqwest::StatusCode::TOO_MANY_REQUESTS).is_degraded());
+        assert!(!ApiStatus::Unknown.is_known());
+    }
+}
@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Header that lets the upstream API deduplicate a retried create request.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// How long a retry of the same create request reuses its key.
+pub const KEY_REUSE_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+tokio::task_local! {
+    static IDEMPOTENCY_KEY: String;
+}
+
+/// Run `fut` with `key` sent as the `Idempotency-Key` of its API calls.
+pub async fn scope<F: std::future::Future>(key: String, fut: F) -> F::Output {
+    IDEMPOTENCY_KEY.scope(key, fut).await
+}
+
+/// The idempotency key in effect for the current task, if any.
+pub fn current() -> Option<String> {
+    IDEMPOTENCY_KEY.try_with(|key| key.clone()).ok()
+}
+
+/// Return the key issued for `fingerprint` within `window`, or issue a new one.
+/// Expired entries are dropped on the way.
+pub fn key_for(
+    keys: &mut HashMap<String, (String, Instant)>,
+    fingerprint: &str,
+    now: Instant,
+    window: Duration,
+) -> String {
+    keys.retain(|_, (_, issued)| now.saturating_duration_since(*issued) < window);
+    keys.entry(fingerprint.to_string())
+        .or_insert_with(|| (super::request_id::generate(), now))
+        .0
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_reused_within_window_only() {
+        let mut keys = HashMap::new();
+        let start = Instant::now();
+        let window = Duration::from_secs(60);
+        let first = key_for(&mut keys, "owner:{\"hostnames\":[\"a\"]}", start, window);
+        let retry = key_for(&mut keys, "owner:{\"hostnames\":[\"a\"]}", start + Duration::from_secs(30), window);
+        assert_eq!(first, retry);
+
+        let other = key_for(&mut keys, "owner:{\"hostnames\":[\"b\"]}", start, window);
+        assert_ne!(first, other);
+
+        let later = key_for(&mut keys, "owner:{\"hostnames\":[\"a\"]}", start + Duration::from_secs(61), window);
+        assert_ne!(first, later);
+    }
+}
@@ -65,6 +65,7 @@ pub async fn load_images(
 }
 
 /// Download and add a custom image
+#[allow(clippy::too_many_arguments)]
 pub async fn download_image(
     client: &reqwest::Client,
     api_base_url: &str,
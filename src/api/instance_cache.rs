@@ -0,0 +1,98 @@
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::models::InstanceView;
+
+/// The full upstream instance list, shared by every page load so filtering and
+/// pagination happen in memory. Refreshed in the background by the web server
+/// and cleared after any mutating instance call.
+///
+/// Every invalidation bumps a generation counter. A fetch records the
+/// generation before it starts and `store` drops its result if an
+/// invalidation happened meanwhile, so a slow fetch can't put back a list
+/// from before a mutation.
+#[derive(Clone, Debug, Default)]
+pub struct InstanceListCache {
+    inner: Arc<RwLock<CacheState>>,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    generation: u64,
+    list: Option<CachedList>,
+}
+
+#[derive(Debug)]
+struct CachedList {
+    stored_at: Instant,
+    instances: Vec<InstanceView>,
+}
+
+impl InstanceListCache {
+    /// The cached list if it was stored less than `max_age` ago.
+    pub fn get(&self, max_age: Duration) -> Option<Vec<InstanceView>> {
+        let guard = self.inner.read().unwrap();
+        guard
+            .list
+            .as_ref()
+            .filter(|cached| cached.stored_at.elapsed() < max_age)
+            .map(|cached| cached.instances.clone())
+    }
+
+    /// Current generation; take it before fetching and pass it to `store`.
+    pub fn generation(&self) -> u64 {
+        self.inner.read().unwrap().generation
+    }
+
+    /// Store `instances` fetched at `generation`. Returns false (and stores
+    /// nothing) if the cache was invalidated since.
+    pub fn store(&self, generation: u64, instances: Vec<InstanceView>) -> bool {
+        let mut guard = self.inner.write().unwrap();
+        if guard.generation != generation {
+            return false;
+        }
+        guard.list = Some(CachedList { stored_at: Instant::now(), instances });
+        true
+    }
+
+    pub fn invalidate(&self) {
+        let mut guard = self.inner.write().unwrap();
+        guard.generation += 1;
+        guard.list = None;
+    }
+
+    /// Run a mutating upstream `call`, invalidating before it (so pages stop
+    /// serving the old list) and again after it (so a fetch that overlapped
+    /// the call can't store a list from before the change).
+    pub async fn invalidate_around<T>(&self, call: impl std::future::Future<Output = T>) -> T {
+        self.invalidate();
+        let result = call.await;
+        self.invalidate();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_expires_and_invalidates() {
+        let cache = InstanceListCache::default();
+        assert!(cache.get(Duration::from_secs(60)).is_none());
+        assert!(cache.store(cache.generation(), vec![InstanceView::new_with_defaults("i-1".into())]));
+        assert_eq!(cache.get(Duration::from_secs(60)).unwrap().len(), 1);
+        assert!(cache.get(Duration::ZERO).is_none());
+        cache.invalidate();
+        assert!(cache.get(Duration::from_secs(60)).is_none());
+    }
+
+    #[test]
+    fn test_store_drops_a_fetch_that_raced_an_invalidation() {
+        let cache = InstanceListCache::default();
+        let generation = cache.generation();
+        cache.invalidate();
+        assert!(!cache.store(generation, vec![InstanceView::new_with_defaults("i-1".into())]));
+        assert!(cache.get(Duration::from_secs(60)).is_none());
+    }
+}
@@ -1,7 +1,9 @@
 use std::collections::HashMap;
+use serde_json::Value;
 use crate::models::{InstanceView, OsItem, UserRecord};
 use crate::models::workspace_record::WorkspaceRecord;
 use super::client::api_call;
+use crate::utils::paginate;
 
 /// Paginated result structure for instances
 #[derive(Clone, Debug)]
@@ -11,27 +13,40 @@ pub struct PaginatedInstances {
     pub current_page: usize,
     pub total_pages: usize,
     pub per_page: usize,
+    /// Instance count per status across all pages, most common first.
+    pub status_counts: Vec<(String, usize)>,
 }
 
-/// Load instances for a specific user from the API with pagination support.
-/// Filters instances based on the user's role, direct assignments, and any
-/// workspace memberships (workspace-centric access control).
-/// 
-/// # Parameters
-/// - `page`: Page number (1-indexed). Use 0 to disable pagination and return all instances.
-/// - `per_page`: Number of items per page. Default is 20.
-pub async fn load_instances_for_user(
-    client: &reqwest::Client,
-    api_base_url: &str,
-    api_token: &str,
-    users_map: &HashMap<String, UserRecord>,
-    workspaces_map: &HashMap<String, WorkspaceRecord>,
-    username: &str,
-    page: usize,
-    per_page: usize,
-) -> PaginatedInstances {
+/// Tally instances by status, most common first (ties ordered by name).
+pub fn summarize_statuses<'a>(statuses: impl IntoIterator<Item = &'a str>) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for status in statuses {
+        let status = if status.is_empty() { "unknown".to_string() } else { status.to_lowercase() };
+        *counts.entry(status).or_default() += 1;
+    }
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+/// Render status counts as e.g. "12 active, 3 stopped".
+pub fn format_status_summary(counts: &[(String, usize)]) -> String {
+    if counts.is_empty() {
+        return "No instances".into();
+    }
+    counts
+        .iter()
+        .map(|(status, n)| format!("{} {}", n, status))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// GET every page of `/v1/instances`. Returns `None` if a page request failed,
+/// so callers can avoid caching a partial list.
+pub async fn fetch_all_instances(client: &reqwest::Client, api_base_url: &str, api_token: &str) -> Option<Vec<InstanceView>> {
     let mut all_instances_data = Vec::new();
     let mut current_bookmark: Option<String> = None;
+    let mut complete = true;
 
     loop {
         let mut params = Vec::new();
@@ -57,7 +72,7 @@ pub async fn load_instances_for_user(
                 let next_bookmark = data.get("bookmark").and_then(|v| v.as_str()).map(|s| s.to_string());
                 
                 // If no bookmark, or it's the same as the one we just used, or we got no instances, break
-                if next_bookmark.is_none() || next_bookmark == current_bookmark || instances_arr.map_or(true, |a| a.is_empty()) {
+                if next_bookmark.is_none() || next_bookmark == current_bookmark || instances_arr.is_none_or(|a| a.is_empty()) {
                     break;
                 }
                 current_bookmark = next_bookmark;
@@ -69,6 +84,7 @@ pub async fn load_instances_for_user(
                 break;
             }
         } else {
+            complete = false;
             break;
         }
 
@@ -78,36 +94,38 @@ pub async fn load_instances_for_user(
         }
     }
 
+    super::contract::validate_items("/v1/instances", &all_instances_data, super::contract::INSTANCE_SCHEMA);
     let mut all_instances = Vec::new();
     for item in all_instances_data {
         if let Some(obj) = item.as_object() {
             let id = obj.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string();
-            let hostname = obj.get("hostname").and_then(|v| v.as_str()).unwrap_or("(no hostname)").to_string();
-            let vcpu_count = obj.get("vcpuCount").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
-            let ram = obj.get("ram").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
-            let disk = obj.get("disk").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
-            let inserted_at = obj.get("insertedAt").and_then(|v| v.as_str()).map(|s| s.to_string());
-            let os_id = obj.get("osId").and_then(|v| v.as_str()).map(|s| s.to_string());
-            let iso_id = obj.get("isoId").and_then(|v| v.as_str()).map(|s| s.to_string());
-            let from_image = obj.get("fromImage").and_then(|v| v.as_str()).map(|s| s.to_string());
-            let region = obj.get("region").and_then(|v| v.as_str()).unwrap_or("").to_string();
-            let user_id = obj.get("userId").and_then(|v| v.as_str()).map(|s| s.to_string());
-            let app_id = obj.get("appId").and_then(|v| v.as_str()).map(|s| s.to_string());
-            let status = obj.get("status").and_then(|v| v.as_str()).unwrap_or("").to_string();
-            let main_ip = obj.get("mainIp").and_then(|v| v.as_str()).map(|s| s.to_string());
-            let main_ipv6 = obj.get("mainIpv6").and_then(|v| v.as_str()).map(|s| s.to_string());
-            let product_id = obj.get("productId").and_then(|v| v.as_str()).map(|s| s.to_string());
-            let network_status = obj.get("networkStatus").and_then(|v| v.as_str()).map(|s| s.to_string());
-            let discount_percent = obj.get("discountPercent").and_then(|v| v.as_i64()).map(|i| i as i32);
-            let attach_iso = obj.get("attachIso").and_then(|v| v.as_bool());
-            let class = obj.get("class").and_then(|v| v.as_str()).unwrap_or("").to_string();
-            let oca_data = obj.get("ocaData").cloned();
-            let is_ddos_protected = obj.get("isDdosProtected").and_then(|v| v.as_bool());
-            let customer_note = obj.get("customerNote").and_then(|v| v.as_str()).map(|s| s.to_string());
-            let admin_note = obj.get("adminNote").and_then(|v| v.as_str()).map(|s| s.to_string());
+            let mut instance = InstanceView::new_with_defaults(id);
+            instance.hostname = obj.get("hostname").and_then(|v| v.as_str()).unwrap_or(&instance.hostname).to_string();
+            instance.vcpu_count = obj.get("vcpuCount").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
+            instance.ram = obj.get("ram").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
+            instance.disk = obj.get("disk").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
+            instance.inserted_at = obj.get("insertedAt").and_then(|v| v.as_str()).map(|s| s.to_string());
+            instance.os_id = obj.get("osId").and_then(|v| v.as_str()).map(|s| s.to_string());
+            instance.iso_id = obj.get("isoId").and_then(|v| v.as_str()).map(|s| s.to_string());
+            instance.from_image = obj.get("fromImage").and_then(|v| v.as_str()).map(|s| s.to_string());
+            instance.region = obj.get("region").and_then(|v| v.as_str()).unwrap_or("").to_string();
+            instance.user_id = obj.get("userId").and_then(|v| v.as_str()).map(|s| s.to_string());
+            instance.app_id = obj.get("appId").and_then(|v| v.as_str()).map(|s| s.to_string());
+            instance.status = obj.get("status").and_then(|v| v.as_str()).unwrap_or("").to_string();
+            instance.main_ip = obj.get("mainIp").and_then(|v| v.as_str()).map(|s| s.to_string());
+            instance.main_ipv6 = obj.get("mainIpv6").and_then(|v| v.as_str()).map(|s| s.to_string());
+            instance.product_id = obj.get("productId").and_then(|v| v.as_str()).map(|s| s.to_string());
+            instance.network_status = obj.get("networkStatus").and_then(|v| v.as_str()).map(|s| s.to_string());
+            instance.discount_percent = obj.get("discountPercent").and_then(|v| v.as_i64()).map(|i| i as i32);
+            instance.attach_iso = obj.get("attachIso").and_then(|v| v.as_bool());
+            instance.class = obj.get("class").and_then(|v| v.as_str()).unwrap_or("").to_string();
+            instance.oca_data = obj.get("ocaData").cloned();
+            instance.is_ddos_protected = obj.get("isDdosProtected").and_then(|v| v.as_bool());
+            instance.customer_note = obj.get("customerNote").and_then(|v| v.as_str()).map(|s| s.to_string());
+            instance.admin_note = obj.get("adminNote").and_then(|v| v.as_str()).map(|s| s.to_string());
 
             // Parse extra_resource if present
-            let extra_resource = obj.get("extraResource").and_then(|v| v.as_object()).map(|er| {
+            instance.extra_resource = obj.get("extraResource").and_then(|v| v.as_object()).map(|er| {
                 use crate::models::instance_view::ExtraResource;
                 ExtraResource {
                     cpu: er.get("cpu").and_then(|v| v.as_i64()).map(|i| i as i32),
@@ -117,8 +135,7 @@ pub async fn load_instances_for_user(
                 }
             });
 
-            let os = if let Some(os_obj) = obj.get("os").and_then(|v| v.as_object()) {
-                Some(OsItem {
+            instance.os = obj.get("os").and_then(|v| v.as_object()).map(|os_obj| OsItem {
                     id: os_obj.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
                     name: os_obj.get("name").and_then(|v| v.as_str()).unwrap_or("").to_string(),
                     family: os_obj.get("family").and_then(|v| v.as_str()).unwrap_or("").to_string(),
@@ -126,56 +143,27 @@ pub async fn load_instances_for_user(
                     min_ram: os_obj.get("minRam").and_then(|v| v.as_str()).map(|s| s.to_string()),
                     is_default: os_obj.get("isDefault").and_then(|v| v.as_bool()).unwrap_or(false),
                     is_active: os_obj.get("isActive").and_then(|v| v.as_bool()).unwrap_or(true),
-                })
-            } else {
-                None
-            };
-
-            // Build display fields
-            let status_display = crate::utils::format_status(&status);
-            let vcpu_count_display = if vcpu_count > 0 { vcpu_count.to_string() } else { "—".into() };
-            let ram_display = if ram > 0 { format!("{} MB", ram) } else { "—".into() };
-            let disk_display = if disk > 0 { format!("{} GB", disk) } else { "—".into() };
-            
-            all_instances.push(InstanceView {
-                id,
-                hostname,
-                vcpu_count,
-                ram,
-                disk,
-                inserted_at,
-                os_id,
-                iso_id,
-                from_image,
-                os,
-                region,
-                user_id,
-                app_id,
-                status,
-                main_ip,
-                main_ipv6,
-                product_id,
-                network_status,
-                discount_percent,
-                attach_iso,
-                extra_resource,
-                class,
-                oca_data,
-                is_ddos_protected,
-                customer_note,
-                admin_note,
-                status_display,
-                vcpu_count_display,
-                ram_display,
-                disk_display,
-            });
+                });
+
+            instance.fill_display_fields();
+            all_instances.push(instance);
         }
     }
-    
+    complete.then_some(all_instances)
+}
+
+/// Restrict `all_instances` to those `username` may access. An empty username
+/// (CLI without `--username`) sees everything.
+pub fn filter_instances_for_user(
+    all_instances: Vec<InstanceView>,
+    users_map: &HashMap<String, UserRecord>,
+    workspaces_map: &HashMap<String, WorkspaceRecord>,
+    username: &str,
+) -> Vec<InstanceView> {
     // Filter instances based on workspace-centric access control.
     // Owners see all instances; everyone else is limited to the union of
     // their direct assignments and instances from their workspaces.
-    let filtered_instances = if username.is_empty() {
+    if username.is_empty() {
         all_instances
     } else {
         use crate::services::get_accessible_instance_ids;
@@ -190,46 +178,479 @@ pub async fn load_instances_for_user(
                     .collect()
             }
         }
+    }
+}
+
+/// The instance whose ID is `key`, or failing that whose hostname matches it
+/// case-insensitively.
+pub fn find_instance<'a>(instances: &'a [InstanceView], key: &str) -> Option<&'a InstanceView> {
+    instances
+        .iter()
+        .find(|inst| inst.id == key)
+        .or_else(|| instances.iter().find(|inst| inst.hostname.eq_ignore_ascii_case(key)))
+}
+
+/// Instance IDs from a create response. The API may return the new instance
+/// as `data`, a list of them, or nest them under `data.instances`.
+pub fn parse_created_instance_ids(payload: &Value) -> Vec<String> {
+    fn collect(node: &Value, out: &mut Vec<String>) {
+        match node {
+            Value::String(id) if !id.is_empty() => out.push(id.clone()),
+            Value::Array(items) => items.iter().for_each(|item| collect(item, out)),
+            Value::Object(obj) => {
+                if let Some(id) = obj.get("id").and_then(|v| v.as_str()).filter(|id| !id.is_empty()) {
+                    out.push(id.to_string());
+                } else if let Some(nested) = obj.get("instances").or_else(|| obj.get("instanceIds")) {
+                    collect(nested, out);
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut ids = Vec::new();
+    if let Some(data) = payload.get("data") {
+        collect(data, &mut ids);
+    }
+    ids
+}
+
+/// The `mainIp` (or `mainIpv6` when `v6`) of an instance detail response,
+/// if set.
+pub fn parse_main_ip(payload: &Value, v6: bool) -> Option<String> {
+    let key = if v6 { "mainIpv6" } else { "mainIp" };
+    payload
+        .get("data")
+        .and_then(|d| d.get(key))
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|ip| !ip.is_empty())
+        .map(str::to_string)
+}
+
+/// Outcome of one hostname in a multi-host create request.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HostCreateResult {
+    pub hostname: String,
+    /// Set when the instance was created.
+    pub instance_id: Option<String>,
+    /// Why the host failed, when the API said it did. Neither this nor
+    /// `instance_id` is set when the response didn't mention the host.
+    pub error: Option<String>,
+}
+
+impl HostCreateResult {
+    pub fn succeeded(&self) -> bool {
+        self.instance_id.is_some()
+    }
+
+    /// The API reported an error for this host.
+    pub fn failed(&self) -> bool {
+        self.error.is_some()
+    }
+}
+
+/// Per-host results of a create response, in the order `hostnames` were
+/// requested. Entries are read from `data` (or its `instances` / `results`)
+/// and from top-level `results` / `errors` arrays; each needs a `hostname`,
+/// plus an `id` on success or an `error` / `detail` / `message` on failure.
+/// Requested hosts missing from a response that reports any host come back
+/// with neither an ID nor an error: their outcome is unknown (the API may have
+/// renamed them), so they must not be treated as failed and retried.
+/// Returns an empty list when the response carries no per-host entries.
+pub fn parse_host_results(payload: &Value, hostnames: &[String]) -> Vec<HostCreateResult> {
+    fn entries(node: Option<&Value>) -> Vec<&serde_json::Map<String, Value>> {
+        match node {
+            Some(Value::Array(items)) => items.iter().filter_map(|item| item.as_object()).collect(),
+            Some(Value::Object(obj)) if obj.contains_key("hostname") => vec![obj],
+            Some(Value::Object(obj)) => entries(obj.get("instances").or_else(|| obj.get("results"))),
+            _ => Vec::new(),
+        }
+    }
+    let text = |obj: &serde_json::Map<String, Value>, key: &str| {
+        obj.get(key).and_then(|v| v.as_str()).map(str::trim).filter(|s| !s.is_empty()).map(str::to_string)
     };
-    
-    let total_count = filtered_instances.len();
-    
-    // If page is 0 or per_page is 0, return all instances without pagination
-    if page == 0 || per_page == 0 {
-        return PaginatedInstances {
-            instances: filtered_instances,
-            total_count,
-            current_page: 0,
-            total_pages: 1,
-            per_page: total_count,
+    let mut reported: Vec<HostCreateResult> = Vec::new();
+    let all = entries(payload.get("data"))
+        .into_iter()
+        .chain(entries(payload.get("results")))
+        .chain(entries(payload.get("errors")));
+    for obj in all {
+        let Some(hostname) = text(obj, "hostname") else { continue };
+        if reported.iter().any(|r| r.hostname == hostname && r.succeeded()) {
+            continue;
+        }
+        let instance_id = text(obj, "id");
+        let error = if instance_id.is_some() {
+            None
+        } else {
+            Some(
+                text(obj, "error")
+                    .or_else(|| text(obj, "detail"))
+                    .or_else(|| text(obj, "message"))
+                    .unwrap_or_else(|| "Not created".to_string()),
+            )
         };
+        reported.retain(|r| r.hostname != hostname);
+        reported.push(HostCreateResult { hostname, instance_id, error });
     }
-    
-    // Calculate pagination
-    let total_pages = if total_count == 0 {
-        1
-    } else {
-        (total_count + per_page - 1) / per_page
+    if reported.is_empty() {
+        return reported;
+    }
+    let mut results: Vec<HostCreateResult> = hostnames
+        .iter()
+        .map(|host| {
+            reported.iter().find(|r| &r.hostname == host).cloned().unwrap_or_else(|| HostCreateResult {
+                hostname: host.clone(),
+                instance_id: None,
+                error: None,
+            })
+        })
+        .collect();
+    // Hosts the API reported that weren't requested (renamed, suffixed) still matter.
+    results.extend(reported.into_iter().filter(|r| !hostnames.contains(&r.hostname)));
+    results
+}
+
+/// Slice an already-filtered instance list into the requested page.
+/// A `page` or `per_page` of 0 returns everything on a single page.
+pub fn paginate_instances(instances: Vec<InstanceView>, page: usize, per_page: usize) -> PaginatedInstances {
+    let status_counts = summarize_statuses(instances.iter().map(|i| i.status.as_str()));
+    let page = paginate(instances, page, per_page);
+    PaginatedInstances {
+        instances: page.items,
+        total_count: page.total_count,
+        current_page: page.current_page,
+        total_pages: page.total_pages,
+        per_page: page.per_page,
+        status_counts,
+    }
+}
+
+/// A single entry in an instance's activity history.
+#[derive(Clone, Debug)]
+pub struct InstanceEvent {
+    pub action: String,
+    pub description: String,
+    /// Human-readable timestamp (UTC), or empty when the upstream omitted it.
+    pub occurred_at: String,
+    /// Relative form of `occurred_at` ("2 hours ago"), or empty.
+    pub occurred_ago: String,
+    /// Unix timestamp in seconds used for ordering (0 when unknown).
+    pub sort_key: i64,
+}
+
+/// Parse an event timestamp that may be an RFC 3339 string or a Unix epoch
+/// (seconds or milliseconds) into seconds since the epoch.
+fn parse_event_timestamp(value: Option<&serde_json::Value>) -> Option<i64> {
+    let value = value?;
+    if let Some(n) = value.as_i64() {
+        return Some(if n > 100_000_000_000 { n / 1000 } else { n });
+    }
+    let s = value.as_str()?;
+    if let Ok(n) = s.parse::<i64>() {
+        return Some(if n > 100_000_000_000 { n / 1000 } else { n });
+    }
+    chrono::DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.timestamp())
+        .or_else(|_| chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f").map(|dt| dt.and_utc().timestamp()))
+        .ok()
+}
+
+/// Load the activity/events timeline for an instance, newest first.
+///
+/// Tries `/v1/instances/{id}/events` and falls back to `/activity`. Returns an
+/// empty list when neither endpoint exists or the response cannot be parsed,
+/// so callers can simply render nothing.
+pub async fn load_instance_events(
+    client: &reqwest::Client,
+    api_base_url: &str,
+    api_token: &str,
+    instance_id: &str,
+) -> Vec<InstanceEvent> {
+    for suffix in ["events", "activity"] {
+        let endpoint = format!("/v1/instances/{}/{}", instance_id, suffix);
+        let payload = api_call(client, api_base_url, api_token, "GET", &endpoint, None, None).await;
+        if payload.get("code").and_then(|c| c.as_str()) != Some("OKAY") {
+            continue;
+        }
+        let data = payload.get("data");
+        let arr = data
+            .and_then(|d| d.as_array())
+            .or_else(|| data.and_then(|d| d.get("events")).and_then(|e| e.as_array()))
+            .or_else(|| data.and_then(|d| d.get("activities")).and_then(|e| e.as_array()));
+        let Some(arr) = arr else { continue };
+
+        let mut events: Vec<InstanceEvent> = arr
+            .iter()
+            .filter_map(|item| item.as_object())
+            .map(|obj| {
+                let action = ["action", "type", "event"]
+                    .iter()
+                    .find_map(|k| obj.get(*k).and_then(|v| v.as_str()))
+                    .unwrap_or("event")
+                    .to_string();
+                let description = ["description", "message", "detail"]
+                    .iter()
+                    .find_map(|k| obj.get(*k).and_then(|v| v.as_str()))
+                    .unwrap_or("")
+                    .to_string();
+                let ts = ["createdAt", "insertedAt", "timestamp", "time"]
+                    .iter()
+                    .find_map(|k| parse_event_timestamp(obj.get(*k)));
+                let when = ts.and_then(|t| chrono::DateTime::from_timestamp(t, 0));
+                let occurred_at = when
+                    .map(|dt| dt.format("%Y-%m-%d %H:%M UTC").to_string())
+                    .unwrap_or_default();
+                let occurred_ago = when
+                    .map(|dt| crate::utils::relative_time(dt, chrono::Utc::now()))
+                    .unwrap_or_default();
+                InstanceEvent {
+                    action,
+                    description,
+                    occurred_at,
+                    occurred_ago,
+                    sort_key: ts.unwrap_or(0),
+                }
+            })
+            .collect();
+        events.sort_by_key(|e| std::cmp::Reverse(e.sort_key));
+        return events;
+    }
+    Vec::new()
+}
+
+/// Billing state of an instance, from `/v1/instances/{id}/subscription`.
+#[derive(Clone, Debug, Default, serde::Serialize)]
+pub struct InstanceSubscription {
+    pub plan: String,
+    pub status: Option<String>,
+    pub price: Option<f64>,
+    pub currency: Option<String>,
+    /// e.g. `monthly`, `hourly`.
+    pub billing_cycle: Option<String>,
+    /// Next renewal (or expiry) as sent by the API.
+    pub renews_at: Option<String>,
+    /// Whether a refund can be requested now; `None` when the API doesn't say.
+    pub refundable: Option<bool>,
+    pub refund_amount: Option<f64>,
+}
+
+impl InstanceSubscription {
+    /// `"12.50 USD / monthly"`, or `"—"` without a price.
+    pub fn price_display(&self) -> String {
+        let Some(price) = self.price else { return "—".into() };
+        let mut out = format!("{:.2}", price);
+        if let Some(currency) = &self.currency {
+            out = format!("{} {}", out, currency);
+        }
+        if let Some(cycle) = &self.billing_cycle {
+            out = format!("{} / {}", out, cycle);
+        }
+        out
+    }
+
+    /// `"Yes (4.20 USD)"`, `"No"` or `"Unknown"`.
+    pub fn refund_display(&self) -> String {
+        match (self.refundable, self.refund_amount) {
+            (Some(true), Some(amount)) => match &self.currency {
+                Some(currency) => format!("Yes ({:.2} {})", amount, currency),
+                None => format!("Yes ({:.2})", amount),
+            },
+            (Some(true), None) => "Yes".into(),
+            (Some(false), _) => "No".into(),
+            (None, _) => "Unknown".into(),
+        }
+    }
+}
+
+/// Parse the `data` object of a subscription response, accepting the key
+/// spellings the billing API has used.
+pub fn parse_subscription(data: &serde_json::Value) -> Option<InstanceSubscription> {
+    let obj = data.as_object()?;
+    let text = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|k| obj.get(*k).and_then(|v| v.as_str()))
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
     };
-    
-    // Clamp page to valid range
-    let current_page = page.max(1).min(total_pages);
-    
-    // Calculate slice range
-    let start_idx = (current_page - 1) * per_page;
-    let end_idx = (start_idx + per_page).min(total_count);
-    
-    let paginated_instances = if start_idx < total_count {
-        filtered_instances[start_idx..end_idx].to_vec()
-    } else {
-        vec![]
+    let number = |keys: &[&str]| {
+        keys.iter().find_map(|k| {
+            let v = obj.get(*k)?;
+            v.as_f64().or_else(|| v.as_str().and_then(|s| s.trim().parse().ok()))
+        })
     };
-    
-    PaginatedInstances {
-        instances: paginated_instances,
-        total_count,
-        current_page,
-        total_pages,
-        per_page,
+    let flag = |keys: &[&str]| keys.iter().find_map(|k| obj.get(*k).and_then(|v| v.as_bool()));
+    Some(InstanceSubscription {
+        plan: text(&["plan", "planName", "productName", "productId"]).unwrap_or_else(|| "—".into()),
+        status: text(&["status", "state"]),
+        price: number(&["price", "amount", "monthlyPrice"]),
+        currency: text(&["currency"]),
+        billing_cycle: text(&["billingCycle", "cycle", "period"]),
+        renews_at: text(&["renewsAt", "renewalDate", "nextRenewal", "nextBillingDate", "expiresAt"]),
+        refundable: flag(&["refundable", "isRefundable", "refundEligible"]),
+        refund_amount: number(&["refundAmount", "refundableAmount"]),
+    })
+}
+
+/// Load an instance's subscription; `None` when the endpoint is missing or
+/// returns no usable data.
+pub async fn load_subscription(
+    client: &reqwest::Client,
+    api_base_url: &str,
+    api_token: &str,
+    instance_id: &str,
+) -> Option<InstanceSubscription> {
+    let endpoint = format!("/v1/instances/{}/subscription", instance_id);
+    let payload = api_call(client, api_base_url, api_token, "GET", &endpoint, None, None).await;
+    if payload.get("code").and_then(|c| c.as_str()) != Some("OKAY") {
+        return None;
+    }
+    payload.get("data").and_then(parse_subscription)
+}
+
+/// Default number of console lines kept by [`load_instance_console`].
+pub const DEFAULT_CONSOLE_LINES: usize = 500;
+
+/// Serial/console output of an instance, capped to the most recent lines.
+#[derive(Clone, Debug, Default)]
+pub struct InstanceConsole {
+    pub lines: Vec<String>,
+    /// Total number of lines the upstream returned before capping.
+    pub total_lines: usize,
+    pub truncated: bool,
+}
+
+/// Keep only the last `max_lines` lines of `text` (0 keeps everything).
+pub fn cap_console_output(text: &str, max_lines: usize) -> InstanceConsole {
+    let all: Vec<&str> = text.lines().collect();
+    let total_lines = all.len();
+    let skip = if max_lines > 0 && total_lines > max_lines { total_lines - max_lines } else { 0 };
+    InstanceConsole {
+        lines: all[skip..].iter().map(|l| l.to_string()).collect(),
+        total_lines,
+        truncated: skip > 0,
+    }
+}
+
+/// Load the serial console output for an instance.
+///
+/// Tries `/v1/instances/{id}/console` and falls back to `/serial-log`. Returns
+/// `None` when neither endpoint yields any output. Only the last `max_lines`
+/// lines are kept; `truncated` records whether anything was dropped.
+pub async fn load_instance_console(
+    client: &reqwest::Client,
+    api_base_url: &str,
+    api_token: &str,
+    instance_id: &str,
+    max_lines: usize,
+) -> Option<InstanceConsole> {
+    for suffix in ["console", "serial-log"] {
+        let endpoint = format!("/v1/instances/{}/{}", instance_id, suffix);
+        let payload = api_call(client, api_base_url, api_token, "GET", &endpoint, None, None).await;
+        if payload.get("code").and_then(|c| c.as_str()) != Some("OKAY") {
+            continue;
+        }
+        let data = payload.get("data");
+        let text = data
+            .and_then(|d| d.as_str())
+            .or_else(|| {
+                ["output", "log", "console", "content"]
+                    .iter()
+                    .find_map(|k| data.and_then(|d| d.get(*k)).and_then(|v| v.as_str()))
+            });
+        if let Some(text) = text {
+            return Some(cap_console_output(text, max_lines));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_subscription() {
+        let sub = parse_subscription(&serde_json::json!({
+            "planName": "Standard 2GB", "status": "active", "price": "12.5", "currency": "USD",
+            "billingCycle": "monthly", "renewalDate": "2026-11-01T00:00:00Z",
+            "isRefundable": true, "refundAmount": 4.2
+        }))
+        .unwrap();
+        assert_eq!(sub.plan, "Standard 2GB");
+        assert_eq!(sub.price_display(), "12.50 USD / monthly");
+        assert_eq!(sub.renews_at.as_deref(), Some("2026-11-01T00:00:00Z"));
+        assert_eq!(sub.refund_display(), "Yes (4.20 USD)");
+
+        let bare = parse_subscription(&serde_json::json!({"productId": "p-1"})).unwrap();
+        assert_eq!(bare.price_display(), "—");
+        assert_eq!(bare.refund_display(), "Unknown");
+        assert!(parse_subscription(&serde_json::json!([])).is_none());
+    }
+
+    #[test]
+    fn test_status_summary_orders_by_count() {
+        let counts = summarize_statuses(["active", "Active", "stopped", "", "active"]);
+        assert_eq!(counts[0], ("active".to_string(), 3));
+        assert_eq!(format_status_summary(&counts), "3 active, 1 stopped, 1 unknown");
+        assert_eq!(format_status_summary(&[]), "No instances");
+    }
+
+    #[test]
+    fn test_parse_created_instance_ids_shapes() {
+        let single = serde_json::json!({"code": "CREATED", "data": {"id": "i-1", "hostname": "web"}});
+        assert_eq!(parse_created_instance_ids(&single), vec!["i-1"]);
+        let list = serde_json::json!({"data": [{"id": "i-1"}, {"id": "i-2"}]});
+        assert_eq!(parse_created_instance_ids(&list), vec!["i-1", "i-2"]);
+        let nested = serde_json::json!({"data": {"instances": [{"id": "i-3"}], "instanceIds": ["ignored"]}});
+        assert_eq!(parse_created_instance_ids(&nested), vec!["i-3"]);
+        let ids = serde_json::json!({"data": {"instanceIds": ["i-4", ""]}});
+        assert_eq!(parse_created_instance_ids(&ids), vec!["i-4"]);
+        assert!(parse_created_instance_ids(&serde_json::json!({"code": "OKAY"})).is_empty());
+    }
+
+    #[test]
+    fn test_find_instance_by_id_or_hostname() {
+        let mut web = InstanceView::new_with_defaults("i-1".into());
+        web.hostname = "web-prod-1".into();
+        let mut db = InstanceView::new_with_defaults("web-prod-1".into());
+        db.hostname = "db".into();
+        let instances = vec![web, db];
+        assert_eq!(find_instance(&instances, "i-1").unwrap().hostname, "web-prod-1");
+        // An exact ID wins over a hostname
+        assert_eq!(find_instance(&instances, "web-prod-1").unwrap().id, "web-prod-1");
+        assert_eq!(find_instance(&instances, "DB").unwrap().id, "web-prod-1");
+        assert!(find_instance(&instances, "missing").is_none());
+    }
+
+    #[test]
+    fn test_parse_main_ip() {
+        let payload = serde_json::json!({"code": "OKAY", "data": {"mainIp": "203.0.113.7", "mainIpv6": ""}});
+        assert_eq!(parse_main_ip(&payload, false).as_deref(), Some("203.0.113.7"));
+        assert_eq!(parse_main_ip(&payload, true), None);
+        assert_eq!(parse_main_ip(&serde_json::json!({"code": "OKAY"}), false), None);
+    }
+
+    #[test]
+    fn test_parse_host_results_reports_partial_failures() {
+        let hosts: Vec<String> = ["web1", "web2", "web3"].iter().map(|h| h.to_string()).collect();
+        let payload = serde_json::json!({
+            "code": "BAD_REQUEST",
+            "data": [{"id": "i-1", "hostname": "web1"}],
+            "errors": [{"hostname": "web2", "detail": "Quota exceeded"}]
+        });
+        let results = parse_host_results(&payload, &hosts);
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].instance_id.as_deref(), Some("i-1"));
+        assert_eq!(results[1].error.as_deref(), Some("Quota exceeded"));
+        assert!(results[1].failed());
+        assert!(!results[2].succeeded() && !results[2].failed());
+
+        let nested = serde_json::json!({"data": {"results": [{"hostname": "db", "id": "i-9"}]}});
+        let results = parse_host_results(&nested, &["db".to_string()]);
+        assert!(results[0].succeeded());
+
+        // No per-host entries: callers fall back to the top-level code
+        assert!(parse_host_results(&serde_json::json!({"data": {"instanceIds": ["i-4"]}}), &hosts).is_empty());
     }
 }
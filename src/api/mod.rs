@@ -1,9 +1,19 @@
 // Atomic API modules
 pub mod client;
+pub mod rate_limit;
+pub mod request_id;
+pub mod idempotency;
+pub mod timeout;
+pub mod health;
+pub mod catalog_cache;
+pub mod contract;
 pub mod regions;
 pub mod products;
 pub mod operating_systems;
 pub mod instances;
+pub mod instance_cache;
+pub mod customer_cache;
+pub mod product_name_cache;
 pub mod ssh_keys;
 pub mod snapshots;
 pub mod applications;
@@ -13,20 +23,27 @@ pub mod images;
 pub mod backups;
 
 // Re-export commonly used functions
-pub use client::api_call;
+pub use client::{api_call, auth_failure_message};
+pub use health::ApiStatus;
 pub use regions::load_regions;
-pub use products::load_products;
+pub use products::{load_products, try_load_products};
 pub use operating_systems::load_os_list;
-pub use instances::{load_instances_for_user, PaginatedInstances};
+pub use instances::{
+    fetch_all_instances, filter_instances_for_user, find_instance, paginate_instances, parse_created_instance_ids, parse_host_results, HostCreateResult, parse_main_ip, format_status_summary, load_instance_events, load_instance_console, InstanceEvent,
+    InstanceConsole, PaginatedInstances, DEFAULT_CONSOLE_LINES, load_subscription, InstanceSubscription,
+};
+pub use instance_cache::InstanceListCache;
+pub use customer_cache::CustomerIdCache;
+pub use product_name_cache::{ProductNameCache, PRODUCT_NAME_CACHE_TTL};
 pub use ssh_keys::{load_ssh_keys, load_ssh_keys_paginated, get_ssh_key, PaginatedSshKeys};
 pub use snapshots::{
     load_snapshots, create_snapshot, get_snapshot, delete_snapshot, restore_snapshot,
     SnapshotView,
 };
-pub use applications::{load_applications, Application};
+pub use applications::{load_applications, group_by_support_level, Application, ApplicationGroup};
 pub use floating_ips::{
     load_floating_ips, create_floating_ips, update_floating_ip, release_floating_ip,
-    FloatingIpView,
+    load_floating_ip_quota, FloatingIpView, FloatingIpQuota,
 };
 pub use iso::{load_isos, download_iso, IsoView};
 pub use images::{load_images, download_image, ImageView};
@@ -1,5 +1,5 @@
 use crate::models::OsItem;
-use super::client::api_call;
+use super::catalog_cache::cached_catalog_get;
 
 /// Load operating system catalog from the API.
 /// Returns a list of available OS images with their details.
@@ -9,7 +9,7 @@ pub async fn load_os_list(
     api_token: &str,
 ) -> Vec<OsItem> {
     let params = vec![("per_page".to_string(), "1000".to_string())];
-    let payload = api_call(client, api_base_url, api_token, "GET", "/v1/os", None, Some(params)).await;
+    let payload = cached_catalog_get(client, api_base_url, api_token, "/v1/os", params).await;
     let mut out = vec![];
     
     if payload.get("code").and_then(|c| c.as_str()) == Some("OKAY") {
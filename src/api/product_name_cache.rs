@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// How long a scan of every region's products is reused before rescanning.
+pub const PRODUCT_NAME_CACHE_TTL: Duration = Duration::from_secs(3600);
+
+type ProductNames = HashMap<String, String>;
+
+/// Product ID → display name across all regions. Filled by one full scan when
+/// an instance reports a `productId` without a region, so later lookups (and
+/// IDs no region lists) don't trigger another scan until the map expires or
+/// the upstream caches are cleared.
+#[derive(Clone, Debug, Default)]
+pub struct ProductNameCache {
+    inner: Arc<RwLock<Option<(Instant, ProductNames)>>>,
+}
+
+impl ProductNameCache {
+    /// The cached map if it was stored less than `max_age` ago.
+    pub fn get(&self, max_age: Duration) -> Option<ProductNames> {
+        let guard = self.inner.read().unwrap();
+        guard
+            .as_ref()
+            .filter(|(stored_at, _)| stored_at.elapsed() < max_age)
+            .map(|(_, names)| names.clone())
+    }
+
+    pub fn store(&self, names: ProductNames) {
+        *self.inner.write().unwrap() = Some((Instant::now(), names));
+    }
+
+    pub fn invalidate(&self) {
+        *self.inner.write().unwrap() = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_product_names_expire_and_invalidate() {
+        let cache = ProductNameCache::default();
+        assert!(cache.get(PRODUCT_NAME_CACHE_TTL).is_none());
+        cache.store(HashMap::from([("p-1".to_string(), "2 vCPU / 4 GB".to_string())]));
+        let names = cache.get(PRODUCT_NAME_CACHE_TTL).unwrap();
+        assert_eq!(names.get("p-1").map(String::as_str), Some("2 vCPU / 4 GB"));
+        assert!(cache.get(Duration::ZERO).is_none());
+        cache.invalidate();
+        assert!(cache.get(PRODUCT_NAME_CACHE_TTL).is_none());
+    }
+}
@@ -1,5 +1,5 @@
 use crate::models::{ProductView, ProductEntry, product_view::{Plan, PlanSpecification, PriceItem}};
-use super::client::api_call;
+use super::catalog_cache::cached_catalog_get;
 
 /// Load products/plans for a specific region.
 /// Returns a list of product offerings with specifications and pricing.
@@ -9,15 +9,30 @@ pub async fn load_products(
     api_token: &str,
     region_id: &str,
 ) -> Vec<ProductView> {
+    try_load_products(client, api_base_url, api_token, region_id).await.unwrap_or_default()
+}
+
+/// Like [`load_products`], but `None` when the request failed rather than an
+/// empty list, so a region without products can be told from an outage.
+pub async fn try_load_products(
+    client: &reqwest::Client,
+    api_base_url: &str,
+    api_token: &str,
+    region_id: &str,
+) -> Option<Vec<ProductView>> {
     let params = vec![
         ("regionId".into(), region_id.to_string()),
         ("per_page".into(), "1000".into()),
     ];
-    let payload = api_call(client, api_base_url, api_token, "GET", "/v1/products", None, Some(params)).await;
+    let payload = cached_catalog_get(client, api_base_url, api_token, "/v1/products", params).await;
+    if payload.get("code").and_then(|c| c.as_str()) != Some("OKAY") || !payload.get("data").is_some_and(|d| d.is_array()) {
+        return None;
+    }
     let mut out = vec![];
     
     if payload.get("code").and_then(|c| c.as_str()) == Some("OKAY") {
         if let Some(arr) = payload.get("data").and_then(|d| d.as_array()) {
+            super::contract::validate_items("/v1/products", arr, super::contract::PRODUCT_SCHEMA);
             for item in arr {
                 if let Some(obj) = item.as_object() {
                     let id = obj
@@ -64,6 +79,11 @@ pub async fn load_products(
                             plan_type: p.get("type").and_then(|v| v.as_str()).map(|s| s.to_string()),
                             gpu_name: p.get("gpuName").and_then(|v| v.as_str()).map(|s| s.to_string()),
                             gpu_quantity: p.get("gpuQuantity").and_then(|v| v.as_i64()).map(|i| i as i32),
+                            arch: ["arch", "architecture"]
+                                .iter()
+                                .find_map(|k| p.get(*k).or_else(|| spec_obj.and_then(|s| s.get(*k))))
+                                .and_then(|v| v.as_str())
+                                .map(|s| s.to_string()),
                             specification,
                             is_active: p.get("isActive").and_then(|v| v.as_bool()).unwrap_or(false),
                         }
@@ -73,6 +93,7 @@ pub async fn load_products(
                             plan_type: None,
                             gpu_name: None,
                             gpu_quantity: None,
+                            arch: None,
                             specification: PlanSpecification {
                                 cpu: 0.0,
                                 ram: 0.0,
@@ -160,5 +181,5 @@ pub async fn load_products(
             }
         }
     }
-    out
+    Some(out)
 }
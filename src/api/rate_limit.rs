@@ -0,0 +1,109 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Token bucket used to throttle outgoing upstream API requests.
+///
+/// The bucket holds up to `capacity` tokens and refills at `rate` tokens per
+/// second. Each request consumes one token; when the bucket is empty the
+/// caller waits until the next token becomes available.
+pub struct TokenBucket {
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    last_refill: Option<Instant>,
+}
+
+impl TokenBucket {
+    pub const fn new(rate: f64) -> Self {
+        let capacity = if rate < 1.0 { 1.0 } else { rate };
+        Self {
+            rate,
+            capacity,
+            tokens: capacity,
+            last_refill: None,
+        }
+    }
+
+    /// Change the refill rate. A rate of `0` (or less) disables throttling.
+    pub fn set_rate(&mut self, rate: f64) {
+        self.rate = rate;
+        self.capacity = rate.max(1.0);
+        self.tokens = self.tokens.min(self.capacity);
+    }
+
+    fn refill(&mut self, now: Instant) {
+        if let Some(last) = self.last_refill {
+            let elapsed = now.saturating_duration_since(last).as_secs_f64();
+            self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        } else {
+            self.tokens = self.capacity;
+        }
+        self.last_refill = Some(now);
+    }
+
+    /// Try to take a token at `now`. Returns `None` when a token was consumed,
+    /// or the duration to wait before a token will be available.
+    pub fn try_acquire(&mut self, now: Instant) -> Option<Duration> {
+        if self.rate <= 0.0 {
+            return None;
+        }
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - self.tokens) / self.rate))
+        }
+    }
+}
+
+static LIMITER: Mutex<TokenBucket> = Mutex::new(TokenBucket::new(zy::config::DEFAULT_API_RATE_LIMIT));
+
+/// Configure the global upstream request rate (requests per second).
+pub fn set_rate_limit(rate: f64) {
+    LIMITER.lock().unwrap().set_rate(rate);
+}
+
+/// Wait until the global limiter grants a token for one upstream request.
+pub async fn acquire() {
+    loop {
+        let wait = LIMITER.lock().unwrap().try_acquire(Instant::now());
+        match wait {
+            None => return,
+            Some(d) => tokio::time::sleep(d).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burst_up_to_capacity_then_wait() {
+        let mut bucket = TokenBucket::new(2.0);
+        let now = Instant::now();
+        assert!(bucket.try_acquire(now).is_none());
+        assert!(bucket.try_acquire(now).is_none());
+        let wait = bucket.try_acquire(now).expect("bucket should be empty");
+        assert!(wait > Duration::ZERO && wait <= Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_refills_over_time() {
+        let mut bucket = TokenBucket::new(1.0);
+        let now = Instant::now();
+        assert!(bucket.try_acquire(now).is_none());
+        assert!(bucket.try_acquire(now).is_some());
+        assert!(bucket.try_acquire(now + Duration::from_secs(1)).is_none());
+    }
+
+    #[test]
+    fn test_zero_rate_disables_throttling() {
+        let mut bucket = TokenBucket::new(0.0);
+        let now = Instant::now();
+        for _ in 0..100 {
+            assert!(bucket.try_acquire(now).is_none());
+        }
+    }
+}
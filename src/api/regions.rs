@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 use crate::models::{Region, region::RegionConfig};
-use super::client::api_call;
+use super::catalog_cache::cached_catalog_get;
 
 /// Parse RegionConfig from JSON object
 fn parse_region_config(config_value: Option<&serde_json::Value>) -> RegionConfig {
@@ -36,6 +36,45 @@ fn parse_region_config(config_value: Option<&serde_json::Value>) -> RegionConfig
     }
 }
 
+/// Read a boolean flag under its camelCase or snake_case key. Accepts JSON
+/// booleans, `0`/`1`, and `"true"`/`"false"` strings.
+fn flag(obj: &serde_json::Map<String, serde_json::Value>, camel: &str, snake: &str) -> Option<bool> {
+    let value = obj.get(camel).or_else(|| obj.get(snake))?;
+    value
+        .as_bool()
+        .or_else(|| value.as_i64().map(|n| n != 0))
+        .or_else(|| match value.as_str()?.trim().to_ascii_lowercase().as_str() {
+            "true" | "1" | "yes" => Some(true),
+            "false" | "0" | "no" => Some(false),
+            _ => None,
+        })
+}
+
+/// Build a [`Region`] from one entry of `GET /v1/regions`. A region without
+/// `isActive` / `isHidden` counts as active and visible, so only regions the
+/// API explicitly retires or hides drop out of the wizard.
+pub fn parse_region(value: &serde_json::Value) -> Option<Region> {
+    let obj = value.as_object()?;
+    let id = obj.get("id").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+    let name = obj.get("name").and_then(|v| v.as_str()).unwrap_or(&id).to_string();
+    Some(Region {
+        name,
+        abbr: obj.get("abbr").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+        image: obj.get("image").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+        is_active: flag(obj, "isActive", "is_active").unwrap_or(true),
+        is_out_of_stock: flag(obj, "isOutOfStock", "is_out_of_stock").unwrap_or(false),
+        overall_activeness: flag(obj, "overallActiveness", "overall_activeness").unwrap_or(false),
+        ddos_activeness: flag(obj, "ddosActiveness", "ddos_activeness"),
+        is_premium: flag(obj, "isPremium", "is_premium").unwrap_or(false),
+        is_hidden: flag(obj, "isHidden", "is_hidden").unwrap_or(false),
+        has_offset_price: flag(obj, "hasOffsetPrice", "has_offset_price").unwrap_or(false),
+        max_discount_percent: obj.get("maxDiscountPercent").and_then(|v| v.as_i64()).map(|i| i as i32),
+        position: obj.get("position").cloned().unwrap_or(serde_json::json!({})),
+        config: parse_region_config(obj.get("config")),
+        id,
+    })
+}
+
 /// Load all available regions from the API.
 /// Returns a vector of regions and a hashmap for quick lookup by ID.
 pub async fn load_regions(
@@ -44,46 +83,47 @@ pub async fn load_regions(
     api_token: &str,
 ) -> (Vec<Region>, HashMap<String, Region>) {
     let params = vec![("per_page".to_string(), "1000".to_string())];
-    let payload = api_call(client, api_base_url, api_token, "GET", "/v1/regions", None, Some(params)).await;
+    let payload = cached_catalog_get(client, api_base_url, api_token, "/v1/regions", params).await;
     let mut regions = Vec::new();
     let mut map = HashMap::new();
     
     if payload.get("code").and_then(|c| c.as_str()) == Some("OKAY") {
         if let Some(arr) = payload.get("data").and_then(|d| d.as_array()) {
+            super::contract::validate_items("/v1/regions", arr, super::contract::REGION_SCHEMA);
             for r in arr {
-                if let Some(obj) = r.as_object() {
-                    let id = obj
-                        .get("id")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or_default()
-                        .to_string();
-                    let name = obj
-                        .get("name")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or(&id)
-                        .to_string();
-
-                    let region = Region {
-                        id: id.clone(),
-                        name,
-                        abbr: obj.get("abbr").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                        image: obj.get("image").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                        is_active: obj.get("isActive").and_then(|v| v.as_bool()).unwrap_or(false),
-                        is_out_of_stock: obj.get("isOutOfStock").and_then(|v| v.as_bool()).unwrap_or(false),
-                        overall_activeness: obj.get("overallActiveness").and_then(|v| v.as_bool()).unwrap_or(false),
-                        ddos_activeness: obj.get("ddosActiveness").and_then(|v| v.as_bool()),
-                        is_premium: obj.get("isPremium").and_then(|v| v.as_bool()).unwrap_or(false),
-                        is_hidden: obj.get("isHidden").and_then(|v| v.as_bool()).unwrap_or(false),
-                        has_offset_price: obj.get("hasOffsetPrice").and_then(|v| v.as_bool()).unwrap_or(false),
-                        max_discount_percent: obj.get("maxDiscountPercent").and_then(|v| v.as_i64()).map(|i| i as i32),
-                        position: obj.get("position").cloned().unwrap_or(serde_json::json!({})),
-                        config: parse_region_config(obj.get("config")),
-                    };
+                if let Some(region) = parse_region(r) {
                     regions.push(region.clone());
-                    map.insert(id, region);
+                    map.insert(region.id.clone(), region);
                 }
             }
         }
     }
     (regions, map)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_region_flags() {
+        let retired = parse_region(&serde_json::json!({
+            "id": "lon", "name": "London", "abbr": "LON", "isActive": false, "isHidden": false,
+            "isOutOfStock": true, "position": {"lat": 51.5, "lon": -0.12}
+        }))
+        .unwrap();
+        assert!(!retired.is_active && !retired.is_hidden && retired.is_out_of_stock);
+        assert!(!retired.is_available());
+
+        let hidden = parse_region(&serde_json::json!({"id": "tst", "is_active": "true", "is_hidden": 1})).unwrap();
+        assert!(hidden.is_active && hidden.is_hidden);
+        assert!(!hidden.is_available());
+        assert_eq!(hidden.name, "tst");
+
+        let bare = parse_region(&serde_json::json!({"id": "ams", "name": "Amsterdam"})).unwrap();
+        assert!(bare.is_active && !bare.is_hidden);
+        assert!(bare.is_available());
+
+        assert!(parse_region(&serde_json::json!("ams")).is_none());
+    }
+}
@@ -0,0 +1,63 @@
+use rand::RngCore;
+
+/// Header used to correlate a web request with the upstream API calls it makes.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Generate a random (version 4) UUID string.
+pub fn generate() -> String {
+    let mut b = [0u8; 16];
+    rand::rngs::OsRng.fill_bytes(&mut b);
+    b[6] = (b[6] & 0x0f) | 0x40;
+    b[8] = (b[8] & 0x3f) | 0x80;
+    let hex: String = b.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("{}-{}-{}-{}-{}", &hex[0..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..32])
+}
+
+/// Accept a client-supplied request ID only if it is short and printable, so
+/// it is safe to echo into logs and headers.
+pub fn is_valid(id: &str) -> bool {
+    !id.is_empty() && id.len() <= 128 && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Run `fut` with `id` as the current request ID.
+pub async fn scope<F: std::future::Future>(id: String, fut: F) -> F::Output {
+    REQUEST_ID.scope(id, fut).await
+}
+
+/// The request ID of the web request currently being handled, if any.
+/// CLI commands run outside a request scope and get `None`.
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_is_uuid_v4() {
+        let id = generate();
+        assert_eq!(id.len(), 36);
+        assert_eq!(id.as_bytes()[14], b'4');
+        assert!(is_valid(&id));
+        assert_ne!(id, generate());
+    }
+
+    #[test]
+    fn test_rejects_unsafe_ids() {
+        assert!(!is_valid(""));
+        assert!(!is_valid("abc\ndef"));
+        assert!(!is_valid(&"a".repeat(129)));
+    }
+
+    #[tokio::test]
+    async fn test_current_is_scoped() {
+        assert_eq!(current(), None);
+        let inside = scope("req-1".to_string(), async { current() }).await;
+        assert_eq!(inside.as_deref(), Some("req-1"));
+    }
+}
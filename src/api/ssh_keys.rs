@@ -114,40 +114,13 @@ pub async fn load_ssh_keys_paginated(
     per_page: usize,
 ) -> PaginatedSshKeys {
     let all_keys = load_ssh_keys(client, api_base_url, api_token, customer_id).await;
-    let total_count = all_keys.len();
-    
-    if page == 0 || per_page == 0 {
-        return PaginatedSshKeys {
-            ssh_keys: all_keys,
-            total_count,
-            current_page: 0,
-            total_pages: 1,
-            per_page: total_count,
-        };
-    }
-    
-    let total_pages = if total_count == 0 {
-        1
-    } else {
-        (total_count + per_page - 1) / per_page
-    };
-    
-    let current_page = page.max(1).min(total_pages);
-    let start_idx = (current_page - 1) * per_page;
-    let end_idx = (start_idx + per_page).min(total_count);
-    
-    let paginated_keys = if start_idx < total_count {
-        all_keys[start_idx..end_idx].to_vec()
-    } else {
-        vec![]
-    };
-    
+    let page = crate::utils::paginate(all_keys, page, per_page);
     PaginatedSshKeys {
-        ssh_keys: paginated_keys,
-        total_count,
-        current_page,
-        total_pages,
-        per_page,
+        ssh_keys: page.items,
+        total_count: page.total_count,
+        current_page: page.current_page,
+        total_pages: page.total_pages,
+        per_page: page.per_page,
     }
 }
 
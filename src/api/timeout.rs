@@ -0,0 +1,65 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Minimum budget for resize and OS rebuild calls, which can keep the
+/// upstream busy for minutes before it answers.
+pub const LONG_OPERATION_TIMEOUT: Duration = Duration::from_secs(600);
+
+static READ_TIMEOUT_SECS: AtomicU64 = AtomicU64::new(0);
+static WRITE_TIMEOUT_SECS: AtomicU64 = AtomicU64::new(0);
+
+tokio::task_local! {
+    static TIMEOUT_OVERRIDE: Duration;
+}
+
+/// Set the per-request budgets for reads (`GET`) and writes (everything
+/// else). Zero disables the timeout.
+pub fn set_timeouts(read_secs: u64, write_secs: u64) {
+    READ_TIMEOUT_SECS.store(read_secs, Ordering::Relaxed);
+    WRITE_TIMEOUT_SECS.store(write_secs, Ordering::Relaxed);
+}
+
+/// Run `fut` with each of its API calls allowed `timeout` instead of the
+/// method's default budget.
+pub async fn scope<F: std::future::Future>(timeout: Duration, fut: F) -> F::Output {
+    TIMEOUT_OVERRIDE.scope(timeout, fut).await
+}
+
+/// Budget for a resize or rebuild: [`LONG_OPERATION_TIMEOUT`], or the write
+/// budget when that is configured longer.
+pub fn long_operation_timeout() -> Duration {
+    LONG_OPERATION_TIMEOUT.max(Duration::from_secs(WRITE_TIMEOUT_SECS.load(Ordering::Relaxed)))
+}
+
+fn resolve(method: &str, read_secs: u64, write_secs: u64, scoped: Option<Duration>) -> Option<Duration> {
+    let secs = if method.eq_ignore_ascii_case("GET") { read_secs } else { write_secs };
+    if secs == 0 {
+        return None;
+    }
+    Some(scoped.unwrap_or(Duration::from_secs(secs)))
+}
+
+/// Timeout for an API call: the [`scope`] override if any, else the read or
+/// write budget for `method`. `None` when that budget is disabled.
+pub fn for_method(method: &str) -> Option<Duration> {
+    resolve(
+        method,
+        READ_TIMEOUT_SECS.load(Ordering::Relaxed),
+        WRITE_TIMEOUT_SECS.load(Ordering::Relaxed),
+        TIMEOUT_OVERRIDE.try_with(|t| *t).ok(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_by_method_and_override() {
+        assert_eq!(resolve("GET", 30, 120, None), Some(Duration::from_secs(30)));
+        assert_eq!(resolve("POST", 30, 120, None), Some(Duration::from_secs(120)));
+        assert_eq!(resolve("DELETE", 30, 120, Some(LONG_OPERATION_TIMEOUT)), Some(LONG_OPERATION_TIMEOUT));
+        assert_eq!(resolve("POST", 30, 0, Some(LONG_OPERATION_TIMEOUT)), None);
+        assert_eq!(resolve("get", 0, 120, None), None);
+    }
+}
@@ -1,4 +1,5 @@
 use std::env;
+use std::collections::HashMap;
 use std::path::Path;
 
 // Default configuration constants
@@ -13,6 +14,34 @@ pub const DEFAULT_OWNER_ROLE: &str = "owner";
 #[allow(dead_code)]
 pub const DEFAULT_ADMIN_ROLE: &str = "admin";
 pub const DEFAULT_PBKDF2_ITERATIONS: u32 = 100_000;
+/// Maximum upstream API requests per second (0 disables throttling).
+pub const DEFAULT_API_RATE_LIMIT: f64 = 10.0;
+/// Seconds an upstream read (GET) may take (0 disables the timeout).
+pub const DEFAULT_API_TIMEOUT_SECS: u64 = 30;
+/// Seconds an upstream write may take (0 disables the timeout). Resize and
+/// OS rebuild calls get at least ten minutes.
+pub const DEFAULT_API_WRITE_TIMEOUT_SECS: u64 = 120;
+/// Upper bound on parallel upstream calls in bulk actions and detail fan-outs.
+pub const DEFAULT_MAX_CONCURRENCY: usize = 8;
+/// Seconds during which a repeated instance action is rejected (0 disables).
+pub const DEFAULT_ACTION_COOLDOWN_SECS: u64 = 5;
+
+pub const DEFAULT_API_VERSION: &str = "v1";
+
+/// Default seconds the shared instance list is served from cache.
+pub const DEFAULT_INSTANCE_CACHE_SECS: u64 = 15;
+
+/// Default seconds the looked-up default customer ID is reused.
+pub const DEFAULT_CUSTOMER_CACHE_SECS: u64 = 3600;
+
+/// Default seconds between upstream polls for the live instance status stream.
+pub const DEFAULT_LIVE_STATUS_INTERVAL_SECS: u64 = 10;
+
+/// Default cap on a web request body, in bytes (256 KiB).
+pub const DEFAULT_MAX_BODY_BYTES: usize = 256 * 1024;
+
+/// Default login user for `zy instances ssh`.
+pub const DEFAULT_SSH_USER: &str = "root";
 
 pub fn load_env_file(env_file: Option<&str>) {
     if let Some(path) = env_file {
@@ -22,30 +51,326 @@ pub fn load_env_file(env_file: Option<&str>) {
     }
 }
 
+/// Read the env file (`--env-file`, else `.env`) into a map without touching
+/// the process environment, for reloading settings while the server runs.
+pub fn read_env_file_vars(env_file: Option<&str>) -> Result<HashMap<String, String>, String> {
+    let iter = match env_file {
+        Some(path) => dotenvy::from_path_iter(Path::new(path)),
+        None => dotenvy::dotenv_iter(),
+    };
+    let source = env_file.unwrap_or(".env");
+    iter.map_err(|e| format!("Cannot read {}: {}", source, e))?
+        .map(|item| item.map_err(|e| format!("Cannot parse {}: {}", source, e)))
+        .collect()
+}
+
+/// `key` from `file_vars` (see [`read_env_file_vars`]), falling back to the
+/// process environment for keys the file doesn't set, so settings passed by
+/// docker, systemd or the shell survive a reload. Also returns where the
+/// value came from: `"env file"`, `"environment"` or `"unset"`.
+pub fn env_file_or_process_var(file_vars: &HashMap<String, String>, key: &str) -> (String, &'static str) {
+    if let Some(value) = file_vars.get(key) {
+        return (value.clone(), "env file");
+    }
+    match env::var(key) {
+        Ok(value) => (value, "environment"),
+        Err(_) => (String::new(), "unset"),
+    }
+}
+
 pub fn get_api_base_url() -> String {
     sanitize_base_url(&env::var("API_BASE_URL").unwrap_or_else(|_| DEFAULT_API_BASE_URL.to_string()))
 }
 
+/// Upstream API version used in place of the `/v1/` endpoint prefix
+/// (`API_VERSION`, e.g. `v2`). Surrounding slashes are ignored.
+pub fn get_api_version() -> String {
+    env::var("API_VERSION")
+        .ok()
+        .map(|v| v.trim().trim_matches('/').to_string())
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| DEFAULT_API_VERSION.to_string())
+}
+
 pub fn get_api_token() -> String {
     env::var("API_TOKEN").unwrap_or_else(|_| DEFAULT_API_TOKEN.to_string())
 }
 
+/// Read an API token from a file (`--api-token-file`), ignoring surrounding
+/// whitespace and the trailing newline most editors add.
+pub fn read_api_token_file(path: &str) -> Result<String, String> {
+    let raw = std::fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path, e))?;
+    let token = raw.trim();
+    if token.is_empty() {
+        return Err(format!("{} is empty", path));
+    }
+    Ok(token.to_string())
+}
+
 pub fn get_public_base_url() -> String {
     sanitize_base_url(&env::var("PUBLIC_BASE_URL").unwrap_or_else(|_| DEFAULT_PUBLIC_BASE_URL.to_string()))
 }
 
-pub fn get_disabled_instance_ids() -> std::collections::HashSet<String> {
-    let raw = env::var("DISABLED_INSTANCE_IDS").unwrap_or_default();
-    let mut set = std::collections::HashSet::new();
-    if !raw.trim().is_empty() {
-        for id in raw.split(',') {
-            let t = id.trim();
-            if !t.is_empty() {
-                set.insert(t.to_string());
-            }
+/// PEM file of extra root certificates trusted for the upstream API
+/// (`API_CA_CERT`), for APIs behind an internal CA.
+pub fn get_api_ca_cert() -> Option<String> {
+    non_empty_env("API_CA_CERT")
+}
+
+/// Whether `API_TLS_INSECURE` turns off certificate verification for the
+/// upstream API. Development only.
+pub fn get_api_tls_insecure() -> bool {
+    env::var("API_TLS_INSECURE")
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+pub fn get_api_rate_limit() -> f64 {
+    env::var("API_RATE_LIMIT")
+        .ok()
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .filter(|v| v.is_finite() && *v >= 0.0)
+        .unwrap_or(DEFAULT_API_RATE_LIMIT)
+}
+
+pub fn get_api_timeout_secs() -> u64 {
+    env::var("API_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_API_TIMEOUT_SECS)
+}
+
+pub fn get_api_write_timeout_secs() -> u64 {
+    env::var("API_WRITE_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_API_WRITE_TIMEOUT_SECS)
+}
+
+/// Most upstream calls any bulk action or detail fan-out runs at once
+/// (`ZY_MAX_CONCURRENCY`); at least 1.
+pub fn get_max_concurrency() -> usize {
+    env::var("ZY_MAX_CONCURRENCY")
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(DEFAULT_MAX_CONCURRENCY)
+}
+
+pub fn get_action_cooldown_secs() -> u64 {
+    env::var("ACTION_COOLDOWN_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_ACTION_COOLDOWN_SECS)
+}
+
+fn non_empty_env(key: &str) -> Option<String> {
+    env::var(key).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// Operator location (`ZY_HOME_LAT`, `ZY_HOME_LON`) used to order wizard
+/// regions by distance. Both must be set and in range.
+pub fn get_home_location() -> Option<(f64, f64)> {
+    let lat: f64 = non_empty_env("ZY_HOME_LAT")?.parse().ok()?;
+    let lon: f64 = non_empty_env("ZY_HOME_LON")?.parse().ok()?;
+    ((-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon)).then_some((lat, lon))
+}
+
+/// Region the creation wizard preselects (`ZY_DEFAULT_REGION`).
+pub fn get_default_region() -> Option<String> {
+    non_empty_env("ZY_DEFAULT_REGION")
+}
+
+/// Instance class the creation wizard preselects (`ZY_DEFAULT_INSTANCE_CLASS`).
+pub fn get_default_instance_class() -> Option<String> {
+    non_empty_env("ZY_DEFAULT_INSTANCE_CLASS").map(|v| v.to_lowercase())
+}
+
+/// Plan type the creation wizard preselects (`ZY_DEFAULT_PLAN_TYPE`); only
+/// `fixed` and `custom` are accepted.
+pub fn get_default_plan_type() -> Option<String> {
+    non_empty_env("ZY_DEFAULT_PLAN_TYPE")
+        .map(|v| v.to_lowercase())
+        .filter(|v| matches!(v.as_str(), "fixed" | "custom"))
+}
+
+/// Comma-separated instance columns for the web table and CLI output
+/// (`ZY_INSTANCE_COLUMNS`, e.g. `hostname,ip,status,region,ram`).
+pub fn get_instance_columns() -> Option<String> {
+    non_empty_env("ZY_INSTANCE_COLUMNS")
+}
+
+/// Local user the CLI acts as when a command has no `--username` (`ZY_CLI_USER`).
+pub fn get_cli_user() -> Option<String> {
+    non_empty_env("ZY_CLI_USER").map(|v| v.to_lowercase())
+}
+
+/// Whether `ZY_MAINTENANCE_MODE` is set to a truthy value (`1`, `true`, `yes`, `on`).
+pub fn get_maintenance_mode() -> bool {
+    env::var("ZY_MAINTENANCE_MODE")
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+/// Whether web deletes must be confirmed by typing the instance hostname
+/// (`ZY_DELETE_REQUIRE_HOSTNAME` set to `1`, `true`, `yes` or `on`).
+pub fn get_delete_require_hostname() -> bool {
+    env::var("ZY_DELETE_REQUIRE_HOSTNAME")
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+/// Algorithm used to store new local passwords.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PasswordScheme {
+    /// PBKDF2-HMAC-SHA256 with `DEFAULT_PBKDF2_ITERATIONS` rounds.
+    #[default]
+    Pbkdf2,
+    /// Argon2id with the argon2 crate's default (OWASP) cost parameters.
+    Argon2id,
+}
+
+/// Password hashing scheme from `ZY_PASSWORD_HASH` (`pbkdf2` or `argon2id`);
+/// unknown values keep the PBKDF2 default. Stored hashes of either scheme
+/// keep verifying, and are upgraded on the next successful login.
+pub fn get_password_scheme() -> PasswordScheme {
+    match env::var("ZY_PASSWORD_HASH").unwrap_or_default().trim().to_ascii_lowercase().as_str() {
+        "argon2id" | "argon2" => PasswordScheme::Argon2id,
+        _ => PasswordScheme::Pbkdf2,
+    }
+}
+
+/// Whether `ZY_HIDE_PRICES_FOR_NONOWNERS` hides plan, add-on and
+/// subscription prices from admins and viewers.
+pub fn get_hide_prices_for_nonowners() -> bool {
+    env::var("ZY_HIDE_PRICES_FOR_NONOWNERS")
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+/// Whether `ZY_REDIRECT_ON_DENY` sends browsers back to `/` instead of
+/// showing the 403 page when they lack permission.
+pub fn get_redirect_on_deny() -> bool {
+    env::var("ZY_REDIRECT_ON_DENY")
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+/// Seconds between background refreshes of the cached instance list
+/// (`INSTANCE_CACHE_SECS`); 0 disables the cache.
+pub fn get_instance_cache_secs() -> u64 {
+    env::var("INSTANCE_CACHE_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_INSTANCE_CACHE_SECS)
+}
+
+/// Seconds the default customer ID is reused before it is looked up again
+/// (`CUSTOMER_CACHE_SECS`); 0 looks it up on every use.
+pub fn get_customer_cache_secs() -> u64 {
+    env::var("CUSTOMER_CACHE_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_CUSTOMER_CACHE_SECS)
+}
+
+/// Shared secret for signed admin requests such as `zy cache clear`
+/// (`ZY_ADMIN_SECRET`); the admin endpoints are disabled without it.
+pub fn get_admin_secret() -> Option<String> {
+    non_empty_env("ZY_ADMIN_SECRET")
+}
+
+/// Whether `ZY_LIVE_STATUS` enables the `/events/instances` stream.
+pub fn get_live_status_enabled() -> bool {
+    env::var("ZY_LIVE_STATUS")
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+/// Seconds between upstream polls for the live status stream (minimum 1).
+pub fn get_live_status_interval_secs() -> u64 {
+    env::var("LIVE_STATUS_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_LIVE_STATUS_INTERVAL_SECS)
+        .max(1)
+}
+
+/// Largest request body the web server accepts (`ZY_MAX_BODY_BYTES`, minimum
+/// 1 KiB); bigger bodies get 413.
+pub fn get_max_body_bytes() -> usize {
+    env::var("ZY_MAX_BODY_BYTES")
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .unwrap_or(DEFAULT_MAX_BODY_BYTES)
+        .max(1024)
+}
+
+/// URL that instance status transitions seen by the background refresh are
+/// POSTed to (`ZY_WEBHOOK_URL`); unset disables the notifier.
+pub fn get_webhook_url() -> Option<String> {
+    non_empty_env("ZY_WEBHOOK_URL")
+}
+
+/// Login user for `zy instances ssh` (`ZY_SSH_USER`, default `root`).
+pub fn get_ssh_user() -> String {
+    non_empty_env("ZY_SSH_USER").unwrap_or_else(|| DEFAULT_SSH_USER.to_string())
+}
+
+/// SSH key IDs every new instance gets (`ZY_REQUIRED_SSH_KEY_IDS`,
+/// comma-separated). Entries that aren't integers are ignored.
+pub fn get_required_ssh_key_ids() -> Vec<i64> {
+    let mut ids: Vec<i64> = Vec::new();
+    for id in env::var("ZY_REQUIRED_SSH_KEY_IDS").unwrap_or_default().split(',').filter_map(|p| p.trim().parse().ok()) {
+        if !ids.contains(&id) {
+            ids.push(id);
         }
     }
-    set
+    ids
+}
+
+/// Hostname patterns whose instances refuse actions
+/// (`ZY_BLOCKED_HOSTNAME_PATTERNS`, comma-separated, e.g. `*-prod-*,payroll-*`).
+pub fn get_blocked_hostname_patterns() -> Vec<String> {
+    parse_blocked_hostname_patterns(&env::var("ZY_BLOCKED_HOSTNAME_PATTERNS").unwrap_or_default())
+}
+
+/// Split a comma-separated `ZY_BLOCKED_HOSTNAME_PATTERNS` value.
+pub fn parse_blocked_hostname_patterns(raw: &str) -> Vec<String> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+pub fn get_disabled_instance_ids() -> std::collections::HashSet<String> {
+    parse_disabled_instance_ids(&env::var("DISABLED_INSTANCE_IDS").unwrap_or_default())
+}
+
+/// Split a comma-separated `DISABLED_INSTANCE_IDS` value.
+pub fn parse_disabled_instance_ids(raw: &str) -> std::collections::HashSet<String> {
+    raw.split(',').map(str::trim).filter(|id| !id.is_empty()).map(str::to_string).collect()
+}
+
+/// Quote an env file value when dotenv would otherwise misread it.
+fn env_value(raw: &str) -> String {
+    if raw.is_empty() || raw.chars().any(|c| c.is_whitespace() || matches!(c, '#' | '"' | '\'' | '\\')) {
+        format!("\"{}\"", raw.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        raw.to_string()
+    }
+}
+
+/// Contents of a `.env` file with the settings `zy config init` asks for.
+pub fn render_env_file(api_base_url: &str, api_token: &str, public_base_url: &str) -> String {
+    let mut out = String::from("# Generated by `zy config init`; see .env.example for more options\n\n");
+    out.push_str(&format!("API_BASE_URL={}\n", env_value(api_base_url)));
+    out.push_str(&format!("API_TOKEN={}\n", env_value(api_token)));
+    if !public_base_url.is_empty() {
+        out.push_str(&format!("PUBLIC_BASE_URL={}\n", env_value(public_base_url)));
+    }
+    out
 }
 
 pub fn sanitize_base_url(raw: &str) -> String {
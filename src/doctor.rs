@@ -0,0 +1,186 @@
+//! `zy doctor`: run a set of deployment diagnostics and print a checklist.
+
+use std::path::Path;
+use std::time::Instant;
+
+use crate::api;
+use crate::models::AppState;
+use crate::update;
+
+/// Local files the panel reads at startup and rewrites on every change.
+const PERSISTENCE_FILES: &[&str] = &["users.json", "workspaces.json", "clocked_instances.json", "notes.json", "labels.json"];
+
+/// Upstream round trips slower than this are flagged.
+const SLOW_UPSTREAM_MS: u128 = 2000;
+
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    Pass,
+    /// Something to look at that doesn't stop the panel working.
+    Warn,
+    /// The panel will misbehave until this is fixed.
+    Fail,
+}
+
+pub struct Check {
+    pub name: String,
+    pub outcome: Outcome,
+    pub detail: String,
+    /// What to do about a warning or failure.
+    pub hint: Option<&'static str>,
+}
+
+impl Check {
+    fn pass(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Check { name: name.into(), outcome: Outcome::Pass, detail: detail.into(), hint: None }
+    }
+
+    fn warn(name: impl Into<String>, detail: impl Into<String>, hint: &'static str) -> Self {
+        Check { name: name.into(), outcome: Outcome::Warn, detail: detail.into(), hint: Some(hint) }
+    }
+
+    fn fail(name: impl Into<String>, detail: impl Into<String>, hint: &'static str) -> Self {
+        Check { name: name.into(), outcome: Outcome::Fail, detail: detail.into(), hint: Some(hint) }
+    }
+}
+
+fn check_config(state: &AppState) -> Vec<Check> {
+    let mut checks = Vec::new();
+    checks.push(if state.api_base_url.trim().is_empty() {
+        Check::fail("API_BASE_URL", "not set", "Set API_BASE_URL in .env (run `zy config init`).")
+    } else {
+        Check::pass("API_BASE_URL", state.api_base_url.clone())
+    });
+    checks.push(if state.api_token.trim().is_empty() {
+        Check::fail("API_TOKEN", "not set", "Set API_TOKEN in .env (run `zy config init`).")
+    } else {
+        Check::pass("API_TOKEN", "set")
+    });
+    checks.push(if state.public_base_url.trim().is_empty() {
+        Check::warn("PUBLIC_BASE_URL", "not set", "Set PUBLIC_BASE_URL if the panel runs behind a proxy or under a sub-path.")
+    } else {
+        Check::pass("PUBLIC_BASE_URL", state.public_base_url.clone())
+    });
+    checks
+}
+
+async fn check_upstream(state: &AppState) -> Vec<Check> {
+    if state.api_token.trim().is_empty() {
+        return vec![Check::fail("API token", "skipped: no token configured", "Set API_TOKEN first.")];
+    }
+    let started = Instant::now();
+    let resp = api::api_call(&state.client, &state.api_base_url, &state.api_token, "GET", "/v1/regions", None, None).await;
+    let elapsed_ms = started.elapsed().as_millis();
+    let error = resp.get("error").and_then(|e| e.as_str()).unwrap_or_default();
+    if error.starts_with("Request failed") {
+        return vec![Check::fail("Upstream reachable", error.to_string(), "Check API_BASE_URL, DNS and outbound firewall rules.")];
+    }
+    let latency = format!("{} ms", elapsed_ms);
+    let reachability = if elapsed_ms > SLOW_UPSTREAM_MS {
+        Check::warn("Upstream latency", latency, "Pages will load slowly; check the network path to the API.")
+    } else {
+        Check::pass("Upstream latency", latency)
+    };
+    let token = if resp.get("code").and_then(|c| c.as_str()) == Some("OKAY") {
+        Check::pass("API token", "accepted")
+    } else {
+        let detail = resp
+            .get("detail")
+            .and_then(|d| d.as_str())
+            .or_else(|| (!error.is_empty()).then_some(error))
+            .unwrap_or("rejected");
+        Check::fail("API token", detail.to_string(), "Generate a new token in the Cloudzy dashboard and update API_TOKEN.")
+    };
+    vec![Check::pass("Upstream reachable", state.api_base_url.clone()), reachability, token]
+}
+
+/// Whether `path` holds valid JSON (a missing file is fine; it gets created).
+pub fn check_json_file(path: &Path) -> Check {
+    let name = format!("{} parses", path.display());
+    match std::fs::read_to_string(path) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Check::pass(name, "not created yet"),
+        Err(e) => Check::fail(name, e.to_string(), "Make the file readable by the user running zy."),
+        Ok(text) => match serde_json::from_str::<serde_json::Value>(&text) {
+            Ok(_) => Check::pass(name, "valid JSON"),
+            Err(e) => Check::fail(name, e.to_string(), "Fix or restore the file; the panel ignores it while it is invalid."),
+        },
+    }
+}
+
+/// Whether `path` (or its directory, if it doesn't exist yet) can be written.
+pub fn check_writable(path: &Path) -> Check {
+    let name = format!("{} writable", path.display());
+    let result = if path.exists() {
+        std::fs::OpenOptions::new().append(true).open(path).map(|_| ())
+    } else {
+        let dir = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        let probe = dir.join(".zy-doctor-probe");
+        std::fs::write(&probe, b"").and_then(|_| std::fs::remove_file(&probe))
+    };
+    match result {
+        Ok(()) => Check::pass(name, "ok"),
+        Err(e) => Check::fail(name, e.to_string(), "Run zy from a directory it can write to, or fix the file's ownership."),
+    }
+}
+
+async fn check_update() -> Check {
+    update::set_quiet(true);
+    match update::check_for_update(update::Channel::for_current_version()).await {
+        Ok(Some(release)) => Check::warn("Up to date", format!("{} is available", release.version), "Run `zy update`."),
+        Ok(None) => Check::pass("Up to date", update::Version::current().to_string()),
+        Err(e) => Check::warn("Up to date", format!("could not check: {}", e), "Check outbound access to GitHub."),
+    }
+}
+
+pub async fn run_checks(state: &AppState) -> Vec<Check> {
+    let mut checks = check_config(state);
+    checks.extend(check_upstream(state).await);
+    for file in PERSISTENCE_FILES {
+        checks.push(check_json_file(Path::new(file)));
+        checks.push(check_writable(Path::new(file)));
+    }
+    checks.push(check_update().await);
+    checks
+}
+
+/// Print the checklist; returns false when any check failed.
+pub fn print_report(checks: &[Check]) -> bool {
+    for check in checks {
+        let mark = match check.outcome {
+            Outcome::Pass => yansi::Paint::green("✓"),
+            Outcome::Warn => yansi::Paint::yellow("!"),
+            Outcome::Fail => yansi::Paint::red("✗"),
+        };
+        println!("{} {}: {}", mark, yansi::Paint::new(&check.name).bold(), check.detail);
+        if let Some(hint) = check.hint {
+            println!("    {}", yansi::Paint::new(hint).dim());
+        }
+    }
+    let failed = checks.iter().filter(|c| c.outcome == Outcome::Fail).count();
+    let warned = checks.iter().filter(|c| c.outcome == Outcome::Warn).count();
+    println!("\n{} failed, {} warning(s), {} checks", failed, warned, checks.len());
+    failed == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_json_file() {
+        let dir = std::env::temp_dir().join(format!("zy-doctor-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let good = dir.join("good.json");
+        let bad = dir.join("bad.json");
+        std::fs::write(&good, "{\"a\": 1}").unwrap();
+        std::fs::write(&bad, "{\"a\": ").unwrap();
+
+        assert_eq!(check_json_file(&good).outcome, Outcome::Pass);
+        assert_eq!(check_json_file(&bad).outcome, Outcome::Fail);
+        assert_eq!(check_json_file(&dir.join("missing.json")).outcome, Outcome::Pass);
+        assert_eq!(check_writable(&good).outcome, Outcome::Pass);
+        assert_eq!(check_writable(&dir.join("missing.json")).outcome, Outcome::Pass);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
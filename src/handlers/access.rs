@@ -1,25 +1,20 @@
 use axum::{
-    extract::{Path, State, Form},
-    response::{IntoResponse, Redirect},
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
 };
 use axum_extra::extract::cookie::CookieJar;
-use std::collections::HashSet;
-use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
 
-use crate::models::{AppState, AdminView, InstanceCheckbox, InstanceView};
+use crate::models::{AppState, AdminView, InstanceCheckbox, InstanceView, RegionGroup};
 use crate::templates::AccessTemplate;
 use crate::handlers::helpers::{
+    redirect_from_state,
     build_template_globals, ensure_owner, render_template, TemplateGlobals,
-    api_call_wrapper, plain_html,
+    api_call_wrapper, render_error, load_all_instances, push_flash,
 };
-use crate::services::persist_users_file;
-
-#[derive(Deserialize)]
-pub struct UpdateAccessForm {
-    #[serde(default)]
-    #[serde(rename = "instances")]
-    instances: Vec<String>,
-}
+use crate::services::{save_users, unsaved_message, resolve_access_selection};
+use crate::utils::parse_urlencoded_body;
 
 // Access management (owner only): list admins and assign instances
 
@@ -51,63 +46,17 @@ pub async fn access_get(State(state): State<AppState>, jar: CookieJar) -> impl I
                     .map(|s| s.to_string())
                     .or_else(|| obj.get("id").and_then(|v| v.as_i64()).map(|n| n.to_string()))
                     .unwrap_or("?".into());
-                let hostname = obj
-                    .get("hostname")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("(no hostname)")
-                    .to_string();
-                let status = obj
-                    .get("status")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("?")
-                    .to_string();
-                let status_display = crate::utils::format_status(&status);
-                let region = obj
-                    .get("region")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or_default()
-                    .to_string();
-                let main_ip = obj.get("mainIp").and_then(|v| v.as_str()).map(|s| s.to_string());
-                let main_ipv6 = obj.get("mainIpv6").and_then(|v| v.as_str()).map(|s| s.to_string());
-                let vcpu_count = obj.get("vcpuCount").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
-                let ram = obj.get("ram").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
-                let disk = obj.get("disk").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
-                let vcpu_count_display = if vcpu_count > 0 { vcpu_count.to_string() } else { "—".into() };
-                let ram_display = if ram > 0 { format!("{} MB", ram) } else { "—".into() };
-                let disk_display = if disk > 0 { format!("{} GB", disk) } else { "—".into() };
-                
-                list.push(InstanceView { 
-                    id, 
-                    hostname, 
-                    vcpu_count,
-                    ram,
-                    disk,
-                    inserted_at: None,
-                    os_id: None,
-                    iso_id: None,
-                    from_image: None,
-                    os: None,
-                    region,
-                    user_id: None,
-                    app_id: None,
-                    status,
-                    main_ip,
-                    main_ipv6,
-                    product_id: None,
-                    network_status: None,
-                    discount_percent: None,
-                    attach_iso: None,
-                    extra_resource: None,
-                    class: "".into(),
-                    oca_data: None,
-                    is_ddos_protected: None,
-                    customer_note: None,
-                    admin_note: None,
-                    status_display,
-                    vcpu_count_display,
-                    ram_display,
-                    disk_display,
-                });
+                let mut instance = InstanceView::new_with_defaults(id);
+                instance.hostname = obj.get("hostname").and_then(|v| v.as_str()).unwrap_or(&instance.hostname).to_string();
+                instance.status = obj.get("status").and_then(|v| v.as_str()).unwrap_or("?").to_string();
+                instance.region = obj.get("region").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+                instance.main_ip = obj.get("mainIp").and_then(|v| v.as_str()).map(|s| s.to_string());
+                instance.main_ipv6 = obj.get("mainIpv6").and_then(|v| v.as_str()).map(|s| s.to_string());
+                instance.vcpu_count = obj.get("vcpuCount").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
+                instance.ram = obj.get("ram").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
+                instance.disk = obj.get("disk").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
+                instance.fill_display_fields();
+                list.push(instance);
             }
         }
     }
@@ -119,20 +68,25 @@ pub async fn access_get(State(state): State<AppState>, jar: CookieJar) -> impl I
         .map(|(u, rec)| {
             let assigned: HashSet<&str> =
                 rec.assigned_instances.iter().map(|s| s.as_str()).collect();
-            let rows = list
-                .iter()
-                .map(|inst| {
-                    let checked = assigned.contains(inst.id.as_str());
-                    InstanceCheckbox {
-                        id: inst.id.clone(),
-                        hostname: inst.hostname.clone(),
-                        checked,
-                    }
+            let mut regions: BTreeMap<&str, Vec<InstanceCheckbox>> = BTreeMap::new();
+            for inst in &list {
+                regions.entry(inst.region.as_str()).or_default().push(InstanceCheckbox {
+                    id: inst.id.clone(),
+                    hostname: inst.hostname.clone(),
+                    checked: assigned.contains(inst.id.as_str()),
+                });
+            }
+            let regions = regions
+                .into_iter()
+                .map(|(region, instances)| RegionGroup {
+                    region: region.to_string(),
+                    all_checked: instances.iter().all(|i| i.checked),
+                    instances,
                 })
                 .collect();
             AdminView {
                 username: u.clone(),
-                instances: rows,
+                regions,
             }
         })
         .collect();
@@ -143,45 +97,52 @@ pub async fn access_get(State(state): State<AppState>, jar: CookieJar) -> impl I
         base_url,
         flash_messages,
         has_flash_messages,
+        maintenance_mode,
+        api_status,
     } = build_template_globals(&state, &jar);
-    render_template(&state, &jar, AccessTemplate { current_user, api_hostname, base_url, flash_messages, has_flash_messages, admins: &admins })
+    render_template(&state, &jar, AccessTemplate { current_user, api_hostname, base_url, flash_messages, has_flash_messages, maintenance_mode, api_status, admins: &admins })
 }
 
 pub async fn update_access(
     State(state): State<AppState>,
     jar: CookieJar,
     Path(username): Path<String>,
-    Form(form): Form<UpdateAccessForm>,
+    body: axum::body::Bytes,
 ) -> impl IntoResponse {
     if let Some(r) = ensure_owner(&state, &jar) {
         return r.into_response();
     }
     let uname = username.to_lowercase();
+    // Repeated `instances` fields (one per checkbox) need the multi-value parser
+    let form = match parse_urlencoded_body(&body) {
+        Ok(form) => form,
+        Err(e) => return render_error(&state, &jar, StatusCode::PAYLOAD_TOO_LARGE, e),
+    };
+    let selected = form.get("instances").cloned().unwrap_or_default();
+    let bulk = form.get("bulk").and_then(|v| v.first()).map(|s| s.as_str());
+    let all_instances: Vec<(String, String)> = if bulk.is_some() {
+        load_all_instances(&state).await.into_iter().map(|i| (i.id, i.region)).collect()
+    } else {
+        Vec::new()
+    };
     {
         let mut users = state.users.lock().unwrap();
         if let Some(rec) = users.get_mut(&uname) {
             if rec.role != "admin" && rec.role != "viewer" {
-                return plain_html("Target user is not an admin or viewer");
+                drop(users);
+                return render_error(&state, &jar, StatusCode::BAD_REQUEST, "Target user is not an admin or viewer");
             }
-            // Normalize and dedupe
-            let mut normalized: Vec<String> = form
-                .instances
-                .iter()
-                .map(|s| s.trim().to_string())
-                .filter(|s| !s.is_empty())
-                .collect();
-            normalized.sort();
-            normalized.dedup();
-            rec.assigned_instances = normalized;
+            rec.assigned_instances = resolve_access_selection(&selected, bulk, &all_instances);
         } else {
-            return plain_html("Admin not found");
+            drop(users);
+            return render_error(&state, &jar, StatusCode::NOT_FOUND, "Admin not found");
         }
     }
     
-    if let Err(e) = persist_users_file(&state.users).await {
+    if let Err(e) = save_users(&state).await {
         tracing::error!(%e, "Failed to persist users");
-        return plain_html("Failed to persist users");
+        push_flash(&state, &jar, unsaved_message("instance access", &e));
     }
 
-    Redirect::to("/access").into_response()
+    redirect_from_state(&state, "/access").into_response()
 }
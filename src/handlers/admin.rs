@@ -0,0 +1,62 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use crate::handlers::helpers::clear_upstream_caches;
+use crate::models::AppState;
+use crate::services::admin_service::{reload_block_lists, verify_admin_request, ADMIN_SIGNATURE_HEADER, ADMIN_TIMESTAMP_HEADER};
+
+/// Path of the cache-clear endpoint; also the signed path.
+pub const CACHE_CLEAR_PATH: &str = "/admin/cache/clear";
+
+/// Path of the config-reload endpoint; also the signed path.
+pub const RELOAD_CONFIG_PATH: &str = "/admin/reload-config";
+
+/// The rejection for a request to `path` not signed with `ZY_ADMIN_SECRET`:
+/// 404 when no secret is set, 401 when the signature doesn't verify.
+fn reject_unsigned(state: &AppState, headers: &HeaderMap, path: &str) -> Option<Response> {
+    let Some(secret) = state.admin_secret.as_deref() else {
+        return Some(StatusCode::NOT_FOUND.into_response());
+    };
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).unwrap_or_default();
+    let now = chrono::Utc::now().timestamp();
+    let e = verify_admin_request(secret, "POST", path, header(ADMIN_TIMESTAMP_HEADER), header(ADMIN_SIGNATURE_HEADER), now).err()?;
+    tracing::warn!(error = %e, %path, "Rejected admin request");
+    Some((StatusCode::UNAUTHORIZED, Json(serde_json::json!({"ok": false, "message": e}))).into_response())
+}
+
+/// `POST /admin/cache/clear`: reset the customer, catalog and instance caches.
+/// Requires a request signed with `ZY_ADMIN_SECRET`; 404 when no secret is set.
+pub async fn cache_clear_post(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    if let Some(rejection) = reject_unsigned(&state, &headers, CACHE_CLEAR_PATH) {
+        return rejection;
+    }
+    clear_upstream_caches(&state);
+    tracing::info!("Cleared customer, catalog and instance caches");
+    Json(serde_json::json!({"ok": true, "message": "Caches cleared"})).into_response()
+}
+
+/// `POST /admin/reload-config`: re-read the disabled-instance list and
+/// blocked-hostname patterns without a restart (sessions are kept). Signed
+/// like [`cache_clear_post`].
+pub async fn reload_config_post(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    if let Some(rejection) = reject_unsigned(&state, &headers, RELOAD_CONFIG_PATH) {
+        return rejection;
+    }
+    let reload = match reload_block_lists(&state).await {
+        Ok(reload) => reload,
+        Err(e) => {
+            tracing::error!(error = %e, "Config reload failed");
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"ok": false, "message": e}))).into_response();
+        }
+    };
+    tracing::info!(?reload, "Reloaded instance block lists");
+    let message = format!(
+        "Reloaded {} disabled instance(s) from {} and {} blocked hostname pattern(s) from {}",
+        reload.disabled_instances, reload.disabled_instances_source, reload.blocked_hostname_patterns, reload.blocked_hostname_patterns_source
+    );
+    Json(serde_json::json!({"ok": true, "message": message})).into_response()
+}
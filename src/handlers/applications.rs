@@ -0,0 +1,40 @@
+use axum::{
+    extract::State,
+    response::IntoResponse,
+};
+use axum_extra::extract::cookie::CookieJar;
+
+use crate::models::AppState;
+use crate::handlers::helpers::{
+    build_template_globals, render_template, TemplateGlobals, ensure_admin_or_owner, prices_visible,
+};
+use crate::api::{load_applications, group_by_support_level};
+
+/// GET /applications — the one-click application catalog, grouped by support level.
+pub async fn applications_get(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> impl IntoResponse {
+    if let Some(r) = ensure_admin_or_owner(&state, &jar) {
+        return r.into_response();
+    }
+    let applications = load_applications(&state.client, &state.api_base_url, &state.api_token).await;
+    let groups = group_by_support_level(&applications);
+    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages, maintenance_mode, api_status } =
+        build_template_globals(&state, &jar);
+    render_template(
+        &state,
+        &jar,
+        crate::templates::ApplicationsTemplate {
+            current_user,
+            api_hostname,
+            base_url,
+            flash_messages,
+            has_flash_messages,
+            maintenance_mode,
+            api_status,
+            groups,
+            show_prices: prices_visible(&state, &jar),
+        },
+    )
+}
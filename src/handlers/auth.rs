@@ -1,15 +1,15 @@
 use axum::{
     extract::{Form, State},
-    response::{IntoResponse, Redirect},
+    response::IntoResponse,
 };
 use axum_extra::extract::cookie::{Cookie, CookieJar};
 use serde::Deserialize;
 
-use crate::models::AppState;
-use crate::services::{verify_password, random_session_id};
+use crate::models::{AppState, Session};
+use crate::services::{generate_password_hash, password_needs_rehash, verify_password, random_session_id, save_users, now_iso8601};
 use crate::templates::LoginTemplate;
 
-use super::helpers::{build_template_globals, current_username_from_jar, resolve_default_endpoint, TemplateGlobals, render_template};
+use super::helpers::{redirect_from_state, build_template_globals, current_username_from_jar, resolve_default_endpoint, TemplateGlobals, render_template};
 
 #[derive(Deserialize)]
 pub struct LoginForm {
@@ -21,7 +21,7 @@ pub async fn login_get(State(state): State<AppState>, jar: CookieJar) -> impl In
     if let Some(_username) = current_username_from_jar(&state, &jar) {
         // If already logged in, redirect to `/` which will then send the
         // user to the correct default landing (instances or create).
-        return Redirect::to("/").into_response();
+        return redirect_from_state(&state, "/").into_response();
     }
     let TemplateGlobals {
         current_user,
@@ -29,6 +29,8 @@ pub async fn login_get(State(state): State<AppState>, jar: CookieJar) -> impl In
         base_url,
         flash_messages,
         has_flash_messages,
+        maintenance_mode,
+        api_status,
     } = build_template_globals(&state, &jar);
     render_template(&state, &jar, LoginTemplate {
             current_user,
@@ -36,6 +38,8 @@ pub async fn login_get(State(state): State<AppState>, jar: CookieJar) -> impl In
             base_url: base_url.clone(),
             flash_messages,
             has_flash_messages,
+            maintenance_mode,
+            api_status,
             error: None,
         },
     )
@@ -47,30 +51,43 @@ pub async fn login_post(
     Form(form): Form<LoginForm>,
 ) -> impl IntoResponse {
     let uname = form.username.trim().to_lowercase();
-    let users = state.users.lock().unwrap();
-    if let Some(record) = users.get(&uname) {
-        if verify_password(&record.password, &form.password) {
-            drop(users);
-            let sid = random_session_id();
-            state
-                .sessions
-                .lock()
-                .unwrap()
-                .insert(sid.clone(), uname.clone());
-            let mut cookie = Cookie::new("session_id", sid);
-            cookie.set_path("/");
-            cookie.set_http_only(true);
-            let target = resolve_default_endpoint(&state, &uname);
-            return (jar.add(cookie), Redirect::to(&target)).into_response();
+    let verified = state
+        .users
+        .lock()
+        .unwrap()
+        .get(&uname)
+        .is_some_and(|record| verify_password(&record.password, &form.password));
+    if verified {
+        if let Some(rec) = state.users.lock().unwrap().get_mut(&uname) {
+            rec.last_login = Some(now_iso8601());
+            if password_needs_rehash(&rec.password, state.password_scheme) {
+                rec.password = generate_password_hash(&form.password, state.password_scheme);
+            }
         }
+        if let Err(e) = save_users(&state).await {
+            tracing::error!(%e, "Failed to record last login");
+        }
+        let sid = random_session_id();
+        state
+            .sessions
+            .lock()
+            .unwrap()
+            .insert(sid.clone(), Session::new(uname.clone()));
+        let mut cookie = Cookie::new("session_id", sid);
+        cookie.set_path("/");
+        cookie.set_http_only(true);
+        cookie.set_secure(state.secure_cookies);
+        let target = resolve_default_endpoint(&state, &uname);
+        return (jar.add(cookie), redirect_from_state(&state, &target)).into_response();
     }
-    drop(users);
     let TemplateGlobals {
         current_user,
         api_hostname,
         base_url,
         flash_messages,
         has_flash_messages,
+        maintenance_mode,
+        api_status,
     } = build_template_globals(&state, &jar);
     render_template(&state, &jar, LoginTemplate {
             current_user,
@@ -78,6 +95,8 @@ pub async fn login_post(
             base_url,
             flash_messages,
             has_flash_messages,
+            maintenance_mode,
+            api_status,
             error: Some("Invalid credentials".into()),
         },
     )
@@ -88,13 +107,13 @@ pub async fn logout_post(State(state): State<AppState>, jar: CookieJar) -> impl
         state.sessions.lock().unwrap().remove(&sid);
     }
     let cleared = jar.remove(Cookie::new("session_id", ""));
-    (cleared, Redirect::to("/login")).into_response()
+    (cleared, redirect_from_state(&state, "/login")).into_response()
 }
 
 pub async fn root_get(State(state): State<AppState>, jar: CookieJar) -> impl IntoResponse {
     if let Some(username) = current_username_from_jar(&state, &jar) {
         let target = resolve_default_endpoint(&state, &username);
-        return Redirect::to(&target).into_response();
+        return redirect_from_state(&state, &target).into_response();
     }
-    Redirect::to("/login").into_response()
+    redirect_from_state(&state, "/login").into_response()
 }
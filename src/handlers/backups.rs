@@ -1,13 +1,14 @@
 use axum::{
     extract::{State, Form},
-    response::{IntoResponse, Redirect},
+    response::IntoResponse,
 };
 use axum_extra::extract::cookie::CookieJar;
 use serde::Deserialize;
 
 use crate::models::AppState;
 use crate::handlers::helpers::{
-    build_template_globals, render_template, TemplateGlobals, ensure_owner,
+    redirect_from_state,
+    build_template_globals, render_template, TemplateGlobals, ensure_owner, api_failure_message,
 };
 use crate::api::load_backups;
 
@@ -33,7 +34,7 @@ pub async fn backups_list_get(
     )
     .await;
     
-    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages } = 
+    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages, maintenance_mode, api_status } = 
         build_template_globals(&state, &jar);
     
     render_template(
@@ -45,6 +46,8 @@ pub async fn backups_list_get(
             base_url,
             flash_messages,
             has_flash_messages,
+            maintenance_mode,
+            api_status,
             backups: &backups,
         },
     )
@@ -78,10 +81,9 @@ pub async fn backup_create_post(
         if resp.get("code").and_then(|c| c.as_str()) == Some("OKAY") {
             entry.push("Backup profile created successfully.".into());
         } else {
-            let detail = resp.get("detail").and_then(|d| d.as_str()).unwrap_or("Unknown error");
-            entry.push(format!("Failed to create backup profile: {}", detail));
+            entry.push(api_failure_message(&resp, "Failed to create backup profile"));
         }
     }
     
-    Redirect::to("/backups").into_response()
+    redirect_from_state(&state, "/backups").into_response()
 }
@@ -1,6 +1,6 @@
 use axum::{
     extract::{Form, State},
-    response::{IntoResponse, Redirect},
+    response::IntoResponse,
 };
 use axum_extra::extract::cookie::CookieJar;
 use serde::Deserialize;
@@ -8,6 +8,7 @@ use serde::Deserialize;
 use crate::models::AppState;
 use crate::templates::ClockedInstancesTemplate;
 use crate::handlers::helpers::{
+    redirect_from_state,
     build_template_globals, ensure_owner, render_template, TemplateGlobals,
 };
 use crate::services::persist_clocked_instances_file;
@@ -32,7 +33,7 @@ pub async fn clocked_instances_get(
         v.sort();
         v
     };
-    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages } =
+    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages, maintenance_mode, api_status } =
         build_template_globals(&state, &jar);
     render_template(
         &state,
@@ -43,6 +44,8 @@ pub async fn clocked_instances_get(
             base_url,
             flash_messages,
             has_flash_messages,
+            maintenance_mode,
+            api_status,
             clocked_ids: &ids,
         },
     )
@@ -78,5 +81,5 @@ pub async fn clocked_instances_post(
         entry.push("Clocked instance IDs updated successfully.".into());
     }
 
-    Redirect::to("/clocked-instances").into_response()
+    redirect_from_state(&state, "/clocked-instances").into_response()
 }
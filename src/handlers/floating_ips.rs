@@ -1,13 +1,14 @@
 use axum::{
     extract::{State, Path, Query, Form},
-    response::{IntoResponse, Redirect},
+    response::IntoResponse,
 };
 use axum_extra::extract::cookie::CookieJar;
 use serde::Deserialize;
 
 use crate::models::AppState;
 use crate::handlers::helpers::{
-    build_template_globals, render_template, TemplateGlobals, ensure_owner, load_active_regions,
+    redirect_from_state,
+    build_template_globals, render_template, TemplateGlobals, ensure_owner, load_active_regions, api_failure_message,
 };
 use crate::api::{load_floating_ips, create_floating_ips, update_floating_ip, release_floating_ip};
 
@@ -58,7 +59,7 @@ pub async fn floating_ips_list_get(
     .await;
     let regions = load_active_regions(&state).await;
     
-    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages } = 
+    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages, maintenance_mode, api_status } = 
         build_template_globals(&state, &jar);
     
     render_template(
@@ -70,6 +71,8 @@ pub async fn floating_ips_list_get(
             base_url,
             flash_messages,
             has_flash_messages,
+            maintenance_mode,
+            api_status,
             floating_ips: &paginated.floating_ips,
             current_page: paginated.current_page,
             total_pages: paginated.total_pages,
@@ -89,7 +92,7 @@ pub async fn floating_ip_create_post(
         return r.into_response();
     }
     
-    let count = form.count.parse::<i32>().unwrap_or(1).max(1).min(5);
+    let count = form.count.parse::<i32>().unwrap_or(1).clamp(1, 5);
     
     let resp = create_floating_ips(
         &state.client,
@@ -106,12 +109,11 @@ pub async fn floating_ip_create_post(
         if resp.get("code").and_then(|c| c.as_str()) == Some("OKAY") {
             entry.push(format!("{} floating IP(s) created successfully.", count));
         } else {
-            let detail = resp.get("detail").and_then(|d| d.as_str()).unwrap_or("Unknown error");
-            entry.push(format!("Failed to create floating IPs: {}", detail));
+            entry.push(api_failure_message(&resp, "Failed to create floating IPs"));
         }
     }
     
-    Redirect::to("/floating-ips").into_response()
+    redirect_from_state(&state, "/floating-ips").into_response()
 }
 
 pub async fn floating_ip_update_post(
@@ -142,12 +144,11 @@ pub async fn floating_ip_update_post(
         if resp.get("code").and_then(|c| c.as_str()) == Some("OKAY") {
             entry.push("Floating IP updated successfully.".into());
         } else {
-            let detail = resp.get("detail").and_then(|d| d.as_str()).unwrap_or("Unknown error");
-            entry.push(format!("Failed to update floating IP: {}", detail));
+            entry.push(api_failure_message(&resp, "Failed to update floating IP"));
         }
     }
     
-    Redirect::to("/floating-ips").into_response()
+    redirect_from_state(&state, "/floating-ips").into_response()
 }
 
 pub async fn floating_ip_release_post(
@@ -173,10 +174,9 @@ pub async fn floating_ip_release_post(
         if resp.get("code").and_then(|c| c.as_str()) == Some("OKAY") {
             entry.push("Floating IP released successfully.".into());
         } else {
-            let detail = resp.get("detail").and_then(|d| d.as_str()).unwrap_or("Unknown error");
-            entry.push(format!("Failed to release floating IP: {}", detail));
+            entry.push(api_failure_message(&resp, "Failed to release floating IP"));
         }
     }
     
-    Redirect::to("/floating-ips").into_response()
+    redirect_from_state(&state, "/floating-ips").into_response()
 }
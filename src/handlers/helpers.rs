@@ -1,14 +1,18 @@
 use axum::response::{Html, IntoResponse, Redirect, Response};
 use axum::http::StatusCode;
 use axum_extra::extract::cookie::CookieJar;
+use futures_util::stream::{self, StreamExt};
 use serde::Deserialize;
 use serde_json::Value;
+use tracing::Instrument;
 
 use crate::api::{
     api_call, load_ssh_keys, load_ssh_keys_paginated, load_regions, load_products, 
-    load_instances_for_user, PaginatedInstances, PaginatedSshKeys
+    fetch_all_instances, filter_instances_for_user, paginate_instances, PaginatedInstances, PaginatedSshKeys
 };
-use crate::models::{AppState, CurrentUser, SshKeyView, Region, ProductView, InstanceView};
+use crate::models::{AppState, CurrentUser, SshKeyView, Region, ProductView, InstanceView, InstanceListDiff, RecentInstance};
+use crate::templates::ErrorTemplate;
+use askama::Template;
 use std::collections::HashMap;
 
 #[derive(Deserialize, Debug)]
@@ -19,7 +23,7 @@ pub enum OneOrMany {
 }
 
 impl OneOrMany {
-    pub fn to_csv(self) -> String {
+    pub fn into_csv(self) -> String {
         match self {
             OneOrMany::One(s) => s,
             OneOrMany::Many(v) => v.join(","),
@@ -33,7 +37,77 @@ pub fn session_id_from_jar(jar: &CookieJar) -> Option<String> {
 
 pub fn current_username_from_jar(state: &AppState, jar: &CookieJar) -> Option<String> {
     let sid = session_id_from_jar(jar)?;
-    state.sessions.lock().unwrap().get(&sid).cloned()
+    state.sessions.lock().unwrap().get(&sid).map(|s| s.username.clone())
+}
+
+/// Sign `username` out everywhere except the caller's own session, so an
+/// owner resetting their own password stays logged in. Returns the count.
+pub fn revoke_user_sessions(state: &AppState, jar: &CookieJar, username: &str) -> usize {
+    let keep = session_id_from_jar(jar);
+    crate::models::revoke_sessions(&mut state.sessions.lock().unwrap(), username, keep.as_deref())
+}
+
+/// Sessions currently open for `username`.
+pub fn active_session_count(state: &AppState, username: &str) -> usize {
+    state.sessions.lock().unwrap().values().filter(|s| s.username == username).count()
+}
+
+/// Remember that the session opened `instance_id`, for the recent-instances links.
+pub fn record_recent_instance(state: &AppState, jar: &CookieJar, instance_id: &str, hostname: &str) {
+    let Some(sid) = session_id_from_jar(jar) else { return };
+    if let Some(session) = state.sessions.lock().unwrap().get_mut(&sid) {
+        session.record_instance(instance_id, hostname);
+    }
+}
+
+/// What changed in `instances` since this session last loaded the instances
+/// page; also remembers `instances` for the next load.
+pub fn diff_since_last_view(state: &AppState, jar: &CookieJar, instances: &[InstanceView]) -> InstanceListDiff {
+    let Some(sid) = session_id_from_jar(jar) else { return InstanceListDiff::default() };
+    state
+        .sessions
+        .lock()
+        .unwrap()
+        .get_mut(&sid)
+        .map(|s| s.diff_seen_instances(instances))
+        .unwrap_or_default()
+}
+
+/// Instance IDs ticked on the instances list, across all pages.
+pub fn selected_instances(state: &AppState, jar: &CookieJar) -> Vec<String> {
+    let Some(sid) = session_id_from_jar(jar) else { return Vec::new() };
+    state
+        .sessions
+        .lock()
+        .unwrap()
+        .get(&sid)
+        .map(|s| s.selected_instances.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Apply one page's checkboxes (or clear everything) and return the new count.
+pub fn update_selected_instances(state: &AppState, jar: &CookieJar, page_ids: &[String], checked: &[String], clear: bool) -> usize {
+    let Some(sid) = session_id_from_jar(jar) else { return 0 };
+    let mut sessions = state.sessions.lock().unwrap();
+    let Some(session) = sessions.get_mut(&sid) else { return 0 };
+    if clear {
+        session.selected_instances.clear();
+    } else {
+        session.update_selection(page_ids, checked);
+    }
+    session.selected_instances.len()
+}
+
+/// Instances this session viewed, most recent first.
+pub fn recent_instances(state: &AppState, jar: &CookieJar) -> Vec<RecentInstance> {
+    let Some(sid) = session_id_from_jar(jar) else { return Vec::new() };
+    state
+        .sessions
+        .lock()
+        .unwrap()
+        .get(&sid)
+        .map(|s| s.recent_instances.iter().cloned().collect())
+        .unwrap_or_default()
 }
 
 pub fn take_flash_messages(state: &AppState, jar: &CookieJar) -> Vec<String> {
@@ -43,7 +117,43 @@ pub fn take_flash_messages(state: &AppState, jar: &CookieJar) -> Vec<String> {
     }
     let sid = sid.unwrap();
     let mut fs = state.flash_store.lock().unwrap();
-    fs.remove(&sid).unwrap_or_else(Vec::new)
+    fs.remove(&sid).unwrap_or_default()
+}
+
+/// Queue a flash message for the current session (no-op without a session).
+pub fn push_flash(state: &AppState, jar: &CookieJar, message: impl Into<String>) {
+    if let Some(sid) = session_id_from_jar(jar) {
+        let mut flashes = state.flash_store.lock().unwrap();
+        flashes.entry(sid).or_default().push(message.into());
+    }
+}
+
+/// Whether an upstream payload reports success (`OKAY` or `CREATED`).
+pub fn is_api_success(payload: &Value) -> bool {
+    matches!(payload.get("code").and_then(|c| c.as_str()), Some("OKAY") | Some("CREATED"))
+}
+
+/// Whether an upstream call succeeded, and the message to show for it:
+/// `success_msg`, or the failure from [`api_failure_message`].
+pub fn api_result_message(payload: &Value, success_msg: &str, failure_prefix: &str) -> (bool, String) {
+    if is_api_success(payload) {
+        return (true, success_msg.to_string());
+    }
+    (false, api_failure_message(payload, failure_prefix))
+}
+
+/// `"{failure_prefix}: {detail}"` from the payload's `detail` (or `error`),
+/// or an explanation of the API token's permissions for upstream 401/403.
+pub fn api_failure_message(payload: &Value, failure_prefix: &str) -> String {
+    if let Some(message) = crate::api::auth_failure_message(payload) {
+        return message;
+    }
+    let detail = payload
+        .get("detail")
+        .and_then(|d| d.as_str())
+        .or_else(|| payload.get("error").and_then(|e| e.as_str()))
+        .unwrap_or("Unknown error");
+    format!("{}: {}", failure_prefix, detail)
 }
 
 pub fn resolve_default_endpoint(_state: &AppState, _username: &str) -> String {
@@ -67,6 +177,11 @@ pub struct TemplateGlobals {
     pub base_url: String,
     pub flash_messages: Vec<String>,
     pub has_flash_messages: bool,
+    /// True while `ZY_MAINTENANCE_MODE` is on; base.html shows a banner.
+    pub maintenance_mode: bool,
+    /// Upstream reachability from the last API call; base.html shows a badge,
+    /// plus a banner while degraded.
+    pub api_status: crate::api::ApiStatus,
 }
 
 pub fn build_template_globals(state: &AppState, jar: &CookieJar) -> TemplateGlobals {
@@ -79,6 +194,8 @@ pub fn build_template_globals(state: &AppState, jar: &CookieJar) -> TemplateGlob
         base_url: state.public_base_url.clone(),
         flash_messages,
         has_flash_messages,
+        maintenance_mode: state.maintenance_mode,
+        api_status: crate::api::health::current(),
     }
 }
 
@@ -86,46 +203,95 @@ pub fn absolute_url_from_state(state: &AppState, path: &str) -> String {
     crate::utils::absolute_url(&state.public_base_url, path)
 }
 
-pub fn ensure_owner(state: &AppState, jar: &CookieJar) -> Option<Redirect> {
-    let username = current_username_from_jar(state, jar)?;
+/// Redirect to a panel path under `PUBLIC_BASE_URL`, so redirects keep the
+/// proxy's host and sub-path.
+pub fn redirect_from_state(state: &AppState, path: &str) -> Redirect {
+    Redirect::to(&absolute_url_from_state(state, path))
+}
+
+/// 403 response for a logged-in user who lacks permission.
+///
+/// `auth_middleware` turns this into JSON for API clients, or a redirect when
+/// `redirect_on_deny` is set.
+pub fn forbidden(state: &AppState, jar: &CookieJar) -> Response {
+    render_error(state, jar, StatusCode::FORBIDDEN, "You don't have permission to access this page.")
+}
+
+fn has_role(state: &AppState, username: &str, roles: &[&str]) -> bool {
     let users = state.users.lock().unwrap();
-    if let Some(rec) = users.get(&username) {
-        if rec.role == "owner" {
-            return None;
-        }
+    users.get(username).is_some_and(|rec| roles.contains(&rec.role.as_str()))
+}
+
+/// Whether the current user may see prices: always for owners, and for
+/// everyone unless `ZY_HIDE_PRICES_FOR_NONOWNERS` is set.
+pub fn prices_visible(state: &AppState, jar: &CookieJar) -> bool {
+    !state.hide_prices_for_nonowners
+        || current_username_from_jar(state, jar).is_some_and(|u| has_role(state, &u, &["owner"]))
+}
+
+pub fn ensure_owner(state: &AppState, jar: &CookieJar) -> Option<Response> {
+    let username = current_username_from_jar(state, jar)?;
+    if has_role(state, &username, &["owner"]) {
+        return None;
     }
-    Some(Redirect::to("/"))
+    Some(forbidden(state, jar))
 }
 
 pub async fn load_active_regions(state: &AppState) -> Vec<Region> {
     let (regions, _) = load_regions(&state.client, &state.api_base_url, &state.api_token).await;
     regions
         .into_iter()
-        .filter(|region| region.is_active && !region.is_hidden)
+        .filter(Region::is_available)
         .collect()
 }
 
 #[allow(dead_code)]
 pub fn ensure_logged_in(state: &AppState, jar: &CookieJar) -> Option<Redirect> {
     if current_username_from_jar(state, jar).is_none() {
-        return Some(Redirect::to("/login"));
+        return Some(redirect_from_state(state, "/login"));
     }
     None
 }
 
-pub fn ensure_admin_or_owner(state: &AppState, jar: &CookieJar) -> Option<Redirect> {
+pub fn ensure_admin_or_owner(state: &AppState, jar: &CookieJar) -> Option<Response> {
     let username = current_username_from_jar(state, jar)?;
-    let users = state.users.lock().unwrap();
-    if let Some(rec) = users.get(&username) {
-        if rec.role == "owner" || rec.role == "admin" {
-            return None;
-        }
+    if has_role(state, &username, &["owner", "admin"]) {
+        return None;
     }
-    Some(Redirect::to("/"))
+    Some(forbidden(state, jar))
 }
 
-pub fn plain_html<S: AsRef<str>>(s: S) -> Response {
-    Html(format!("<!DOCTYPE html><html><body><p>{}</p></body></html>", s.as_ref())).into_response()
+/// The workspace `slug` when `username` may create instances into it: panel
+/// owners always, admins when they are a manager or editor of the workspace.
+pub fn workspace_for_create(state: &AppState, username: &str, slug: &str) -> Option<crate::models::WorkspaceRecord> {
+    let workspace = state.workspaces.lock().unwrap().get(slug).cloned()?;
+    let allowed = has_role(state, username, &["owner"])
+        || (has_role(state, username, &["admin"]) && workspace.can_create_instances(username));
+    allowed.then_some(workspace)
+}
+
+/// Render the shared error page with `status` and a user-facing `message`.
+pub fn render_error(state: &AppState, jar: &CookieJar, status: StatusCode, message: impl Into<String>) -> Response {
+    let globals = build_template_globals(state, jar);
+    let template = ErrorTemplate {
+        current_user: globals.current_user,
+        api_hostname: globals.api_hostname,
+        base_url: globals.base_url,
+        flash_messages: globals.flash_messages,
+        has_flash_messages: globals.has_flash_messages,
+        maintenance_mode: globals.maintenance_mode,
+        api_status: globals.api_status,
+        status_code: status.as_u16(),
+        reason: status.canonical_reason().unwrap_or("Error").to_string(),
+        message: message.into(),
+    };
+    match template.render() {
+        Ok(body) => (status, Html(body)).into_response(),
+        Err(e) => {
+            tracing::error!(%e, "Template render error");
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
 }
 
 pub fn render_template<T: askama::Template>(_state: &AppState, _jar: &CookieJar, t: T) -> Response {
@@ -148,14 +314,23 @@ pub async fn api_call_wrapper(
     params: Option<Vec<(String, String)>>,
 ) -> Value {
     let should_log = !LOGGING_IGNORE_ENDPOINTS.contains(&endpoint);
-    if should_log {
-        tracing::info!(method, endpoint, ?data, ?params, "API Request");
-    }
-    let result = api_call(&state.client, &state.api_base_url, &state.api_token, method, endpoint, data, params).await;
-    if should_log {
-        tracing::info!(response=?result, "API Response");
+    // Power, delete, create, ... change the list; don't serve it stale
+    let invalidates = method != "GET" && endpoint.starts_with("/v1/instances");
+    let request_id = crate::api::request_id::current();
+    let span = tracing::info_span!("api_call", request_id = request_id.as_deref().unwrap_or("-"), method, endpoint);
+    async move {
+        if should_log {
+            tracing::info!(?data, ?params, "API Request");
+        }
+        let call = api_call(&state.client, &state.api_base_url, &state.api_token, method, endpoint, data, params);
+        let result = if invalidates { state.instance_cache.invalidate_around(call).await } else { call.await };
+        if should_log {
+            tracing::info!(response=?result, "API Response");
+        }
+        result
     }
-    result
+    .instrument(span)
+    .await
 }
 
 pub fn detail_requires_customer(detail: &str) -> bool {
@@ -208,21 +383,29 @@ pub fn extract_customer_id_from_value(value: &Value) -> Option<String> {
 }
 
 pub async fn fetch_default_customer_id(state: &AppState) -> Option<String> {
-    if let Some(existing) = state.default_customer_cache.lock().unwrap().clone() {
+    if let Some(existing) = state.default_customer_cache.get(state.customer_cache_ttl) {
         return Some(existing);
     }
     let endpoints = ["/v1/customers", "/v1/profile"];
     for endpoint in endpoints {
         let payload = api_call_wrapper(state, "GET", endpoint, None, None).await;
         if let Some(id) = extract_customer_id_from_value(&payload) {
-            let mut cache = state.default_customer_cache.lock().unwrap();
-            *cache = Some(id.clone());
+            state.default_customer_cache.store(id.clone());
             return Some(id);
         }
     }
     None
 }
 
+/// Forget the cached customer ID, catalogs (regions, OS list, products) and
+/// instance list so the next requests refetch them from the API.
+pub fn clear_upstream_caches(state: &AppState) {
+    state.default_customer_cache.invalidate();
+    state.instance_cache.invalidate();
+    crate::api::catalog_cache::clear_catalog_cache();
+    state.product_name_cache.invalidate();
+}
+
 pub async fn load_ssh_keys_api(state: &AppState, customer_id: Option<String>) -> Vec<SshKeyView> {
     load_ssh_keys(&state.client, &state.api_base_url, &state.api_token, customer_id).await
 }
@@ -244,12 +427,75 @@ pub async fn load_products_wrapper(state: &AppState, region_id: &str) -> Vec<Pro
     load_products(&state.client, &state.api_base_url, &state.api_token, region_id).await
 }
 
+/// Display name of `product_id`, or the raw ID when no product matches. With
+/// a `region` only that region's products are searched; without one every
+/// region is scanned and, if every region loaded, the result kept in
+/// `state.product_name_cache`.
+pub async fn resolve_product_name(state: &AppState, region: &str, product_id: &str) -> String {
+    if !region.is_empty() {
+        return load_products_wrapper(state, region)
+            .await
+            .into_iter()
+            .find(|p| p.id == product_id)
+            .map(|p| p.display_name())
+            .unwrap_or_else(|| product_id.to_string());
+    }
+    let names = match state.product_name_cache.get(crate::api::PRODUCT_NAME_CACHE_TTL) {
+        Some(names) => names,
+        None => {
+            let (regions, _) = load_regions_wrapper(state).await;
+            let fan_out = state.fan_out(regions.len());
+            let scans: Vec<Option<Vec<ProductView>>> = stream::iter(regions)
+                .map(|r| async move {
+                    crate::api::try_load_products(&state.client, &state.api_base_url, &state.api_token, &r.id).await
+                })
+                .buffer_unordered(fan_out)
+                .collect()
+                .await;
+            // A scan missing some regions would pin raw IDs for an hour; only
+            // a complete one is cached, a partial one is retried next time
+            let complete = !scans.is_empty() && scans.iter().all(Option::is_some);
+            let names: HashMap<String, String> = scans
+                .into_iter()
+                .flatten()
+                .flatten()
+                .map(|p| (p.id.clone(), p.display_name()))
+                .collect();
+            if complete {
+                state.product_name_cache.store(names.clone());
+            }
+            names
+        }
+    };
+    names.get(product_id).cloned().unwrap_or_else(|| product_id.to_string())
+}
+
+/// The full upstream instance list, served from `state.instance_cache` while
+/// fresh. The background refresher keeps it warm; a miss (first load, or right
+/// after a mutating call) fetches inline and repopulates it.
+pub async fn load_all_instances(state: &AppState) -> Vec<InstanceView> {
+    try_load_all_instances(state).await.unwrap_or_default()
+}
+
+/// Like [`load_all_instances`], but `None` when the upstream fetch failed
+/// rather than an empty list.
+pub async fn try_load_all_instances(state: &AppState) -> Option<Vec<InstanceView>> {
+    if state.instance_cache_ttl.is_zero() {
+        return fetch_all_instances(&state.client, &state.api_base_url, &state.api_token).await;
+    }
+    // Allow one missed refresh before treating the cache as stale
+    if let Some(cached) = state.instance_cache.get(state.instance_cache_ttl * 2) {
+        return Some(cached);
+    }
+    let generation = state.instance_cache.generation();
+    let instances = fetch_all_instances(&state.client, &state.api_base_url, &state.api_token).await?;
+    state.instance_cache.store(generation, instances.clone());
+    Some(instances)
+}
+
 #[allow(dead_code)]
 pub async fn load_instances_for_user_wrapper(state: &AppState, username: &str) -> Vec<InstanceView> {
-    let users_map = state.users.lock().unwrap().clone();
-    let workspaces_map = state.workspaces.lock().unwrap().clone();
-    let result = load_instances_for_user(&state.client, &state.api_base_url, &state.api_token, &users_map, &workspaces_map, username, 0, 0).await;
-    result.instances
+    load_instances_for_user_paginated(state, username, 0, 0).await.instances
 }
 
 pub async fn load_instances_for_user_paginated(
@@ -258,7 +504,13 @@ pub async fn load_instances_for_user_paginated(
     page: usize,
     per_page: usize,
 ) -> PaginatedInstances {
+    let all_instances = load_all_instances(state).await;
+    paginate_instances(instances_for_user(state, all_instances, username), page, per_page)
+}
+
+/// `instances` narrowed to what `username` may see, by role and workspace.
+pub fn instances_for_user(state: &AppState, instances: Vec<InstanceView>, username: &str) -> Vec<InstanceView> {
     let users_map = state.users.lock().unwrap().clone();
     let workspaces_map = state.workspaces.lock().unwrap().clone();
-    load_instances_for_user(&state.client, &state.api_base_url, &state.api_token, &users_map, &workspaces_map, username, page, per_page).await
+    filter_instances_for_user(instances, &users_map, &workspaces_map, username)
 }
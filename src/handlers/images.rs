@@ -1,13 +1,14 @@
 use axum::{
     extract::{State, Query, Form},
-    response::{IntoResponse, Redirect},
+    response::IntoResponse,
 };
 use axum_extra::extract::cookie::CookieJar;
 use serde::Deserialize;
 
 use crate::models::AppState;
 use crate::handlers::helpers::{
-    build_template_globals, render_template, TemplateGlobals, ensure_owner, load_active_regions,
+    redirect_from_state,
+    build_template_globals, render_template, TemplateGlobals, ensure_owner, load_active_regions, api_failure_message,
 };
 use crate::api::{load_images, download_image};
 
@@ -55,7 +56,7 @@ pub async fn images_list_get(
     .await;
     let regions = load_active_regions(&state).await;
     
-    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages } = 
+    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages, maintenance_mode, api_status } = 
         build_template_globals(&state, &jar);
     
     render_template(
@@ -67,6 +68,8 @@ pub async fn images_list_get(
             base_url,
             flash_messages,
             has_flash_messages,
+            maintenance_mode,
+            api_status,
             images: &paginated.images,
             regions: &regions,
             total_count: paginated.total_count,
@@ -101,10 +104,9 @@ pub async fn image_download_post(
         if resp.get("code").and_then(|c| c.as_str()) == Some("OKAY") {
             entry.push("Image download initiated successfully.".into());
         } else {
-            let detail = resp.get("detail").and_then(|d| d.as_str()).unwrap_or("Unknown error");
-            entry.push(format!("Failed to download image: {}", detail));
+            entry.push(api_failure_message(&resp, "Failed to download image"));
         }
     }
     
-    Redirect::to("/images").into_response()
+    redirect_from_state(&state, "/images").into_response()
 }
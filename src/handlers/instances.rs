@@ -1,7 +1,16 @@
 use axum::{
     extract::{State, Path, Form, Query},
-    response::{IntoResponse, Redirect},
+    Extension,
+    http::StatusCode,
+    Json,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
 };
+use futures_util::stream::{self, StreamExt};
+use std::collections::HashMap;
+use std::convert::Infallible;
 use axum_extra::extract::cookie::CookieJar;
 use serde::Deserialize;
 use serde_json::Value;
@@ -10,18 +19,31 @@ use crate::models::{
     AppState, InstanceView, AddTrafficForm, ResizeForm, OsItem,
 };
 use crate::templates::{
-    InstancesTemplate, InstanceDetailTemplate,
-    ChangePassInstanceTemplate, ChangeOsInstanceTemplate, ResizeTemplate,
+    InstancesTemplate, InstanceDetailTemplate, InstanceConsoleTemplate,
+    ChangePassInstanceTemplate, ChangeOsInstanceTemplate, ChangeOsOption, ResizeTemplate, InstancesBulkTemplate,
 };
 use crate::handlers::helpers::{
+    redirect_from_state,
     build_template_globals, current_username_from_jar,
-    render_template, api_call_wrapper, TemplateGlobals,
-    load_regions_wrapper, load_products_wrapper,
-    load_instances_for_user_paginated,
+    render_template, api_call_wrapper, TemplateGlobals, forbidden, render_error, ensure_admin_or_owner,
+    load_regions_wrapper, load_products_wrapper, resolve_product_name,
+    load_instances_for_user_paginated, try_load_all_instances, instances_for_user, push_flash, is_api_success,
+    fetch_default_customer_id, load_ssh_keys_api, OneOrMany,
+    record_recent_instance, recent_instances, selected_instances, diff_since_last_view, update_selected_instances, prices_visible,
 };
-use crate::api::load_os_list;
-use crate::services::instance_service::{enforce_instance_access, simple_instance_action};
-use crate::services::persist_users_file;
+use crate::utils::{parse_urlencoded_body, power_transition_problem};
+use crate::api::{timeout, paginate_instances, load_os_list, load_instance_events, load_instance_console, load_subscription, DEFAULT_CONSOLE_LINES};
+use crate::services::instance_service::{
+    enforce_instance_access, simple_instance_action, check_action_cooldown, check_instance_block,
+    get_instance_for_action, confirmation_hostname, custom_resize_downsizes, hostname_confirmed, status_changes, resize_preview, provisioned_ssh_keys, ssh_key_labels, BlockReason,
+};
+use crate::services::bulk_service::{
+    parse_instance_ids, run_bulk_action, summarize, BulkAction, BulkOutcome, PowerAction, ResizeSpec, DEFAULT_BULK_CONCURRENCY,
+};
+use crate::models::resize_form::string_or_number;
+use crate::handlers::negotiate::{ActionResponder, FormOrJson};
+use crate::services::{save_users, unsaved_message, persist_notes_file, apply_note, now_iso8601};
+use crate::handlers::middleware::RequestId;
 
 #[derive(Deserialize)]
 pub struct PaginationParams {
@@ -29,6 +51,9 @@ pub struct PaginationParams {
     page: usize,
     #[serde(default = "default_per_page")]
     per_page: usize,
+    /// Comma-separated instance IDs to restrict the list to (e.g. just-created ones).
+    #[serde(default)]
+    ids: Option<String>,
 }
 
 fn default_page() -> usize {
@@ -45,38 +70,190 @@ pub async fn instances_real(
     Query(params): Query<PaginationParams>,
 ) -> impl IntoResponse {
     let username = current_username_from_jar(&state, &jar).expect("Middleware ensures user is logged in");
-    let paginated = load_instances_for_user_paginated(&state, &username, params.page, params.per_page).await;
-    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages } = build_template_globals(&state, &jar);
+    let only_ids: Vec<String> = params
+        .ids
+        .as_deref()
+        .unwrap_or("")
+        .split(',')
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .collect();
+    let loaded = try_load_all_instances(&state).await;
+    let fetched = loaded.is_some();
+    let all = instances_for_user(&state, loaded.unwrap_or_default(), &username);
+    let (paginated, changes) = if only_ids.is_empty() {
+        // Only the full list is a meaningful baseline for "since last view".
+        // A failed fetch keeps the previous snapshot, and so do later pages,
+        // so rows highlighted on page 1 are not consumed by paging through.
+        let changes = if fetched && params.page <= 1 {
+            diff_since_last_view(&state, &jar, &all)
+        } else {
+            Default::default()
+        };
+        (paginate_instances(all, params.page, params.per_page), changes)
+    } else {
+        let matching = all.into_iter().filter(|i| only_ids.contains(&i.id)).collect();
+        (paginate_instances(matching, params.page, params.per_page), Default::default())
+    };
+    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages, maintenance_mode, api_status } = build_template_globals(&state, &jar);
+    let can_select = current_user.as_ref().is_some_and(|u| u.role == "owner" || u.role == "admin");
     render_template(&state, &jar, InstancesTemplate {
             current_user,
             api_hostname,
             base_url,
             flash_messages,
             has_flash_messages,
+            maintenance_mode,
+            api_status,
             instances: &paginated.instances,
+            columns: state.web_instance_columns(),
             current_page: paginated.current_page,
             total_pages: paginated.total_pages,
             per_page: paginated.per_page,
             total_count: paginated.total_count,
+            status_counts: &paginated.status_counts,
+            filtered: !only_ids.is_empty(),
+            live_status: state.live_status_interval.is_some(),
+            recent_instances: recent_instances(&state, &jar),
+            can_select,
+            selected_instances: selected_instances(&state, &jar),
+            changes,
         },
     )
 }
 
+/// `GET /api/instances`: the current user's accessible instances as a JSON
+/// array, filtered by role and workspace like the instances page.
+pub async fn instances_api_get(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Json<Vec<InstanceView>> {
+    let username = current_username_from_jar(&state, &jar).expect("Middleware ensures user is logged in");
+    Json(load_instances_for_user_paginated(&state, &username, 0, 0).await.instances)
+}
+
+/// POST /instances/selection — save the bulk-selection checkboxes of one list
+/// page into the session, so ticks survive moving between pages. Submitting
+/// with `bulk` continues to the bulk actions form; `clear` empties the selection.
+pub async fn instances_selection_post(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    responder: ActionResponder,
+    body: axum::body::Bytes,
+) -> impl IntoResponse {
+    if let Some(r) = ensure_admin_or_owner(&state, &jar) {
+        return r;
+    }
+    // Repeated `selected` / `page_ids` fields need the multi-value parser
+    let form = match parse_urlencoded_body(&body) {
+        Ok(form) => form,
+        Err(e) if responder.json => {
+            return (StatusCode::PAYLOAD_TOO_LARGE, Json(serde_json::json!({"ok": false, "message": e}))).into_response();
+        }
+        Err(e) => return render_error(&state, &jar, StatusCode::PAYLOAD_TOO_LARGE, e),
+    };
+    let field = |name: &str| form.get(name).cloned().unwrap_or_default();
+    let first = |name: &str| form.get(name).and_then(|v| v.first()).cloned();
+    let count = update_selected_instances(&state, &jar, &field("page_ids"), &field("selected"), first("clear").is_some());
+    if responder.json {
+        return Json(serde_json::json!({"ok": true, "selected": count})).into_response();
+    }
+    if first("bulk").is_some() {
+        return redirect_from_state(&state, "/instances/bulk").into_response();
+    }
+    let page = first("page").and_then(|p| p.parse::<usize>().ok()).unwrap_or_else(default_page);
+    let per_page = first("per_page").and_then(|p| p.parse::<usize>().ok()).unwrap_or_else(default_per_page);
+    redirect_from_state(&state, &format!("/instances?page={}&per_page={}", page, per_page)).into_response()
+}
+
+/// `GET /events/instances`: server-sent `status` events for the current user's
+/// instances. Polls the API every `LIVE_STATUS_INTERVAL_SECS` and sends only
+/// rows whose status changed; the first poll sends every row. The stream ends
+/// once the session is logged out or revoked.
+pub async fn instances_events(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Response {
+    let Some(interval) = state.live_status_interval else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let username = current_username_from_jar(&state, &jar).expect("Middleware ensures user is logged in");
+    let polls = stream::unfold(
+        (state, jar, username, HashMap::new(), true),
+        move |(state, jar, username, previous, first)| async move {
+            if !first {
+                tokio::time::sleep(interval).await;
+                if current_username_from_jar(&state, &jar).as_deref() != Some(username.as_str()) {
+                    return None;
+                }
+            }
+            let current = load_instances_for_user_paginated(&state, &username, 0, 0).await.instances;
+            let events: Vec<Result<Event, Infallible>> = status_changes(&previous, &current)
+                .into_iter()
+                .map(|inst| {
+                    let data = serde_json::json!({
+                        "id": inst.id,
+                        "status": inst.status,
+                        "status_display": inst.status_display,
+                        "status_class": inst.status_class(),
+                    });
+                    Ok(Event::default().event("status").data(data.to_string()))
+                })
+                .collect();
+            let previous = current.into_iter().map(|inst| (inst.id, inst.status)).collect();
+            Some((stream::iter(events), (state, jar, username, previous, false)))
+        },
+    );
+    Sse::new(polls.flatten()).keep_alive(KeepAlive::default()).into_response()
+}
+
 pub async fn instance_detail(
     State(state): State<AppState>,
     jar: CookieJar,
     Path(instance_id): Path<String>,
 ) -> impl IntoResponse {
     if !enforce_instance_access(&state, current_username_from_jar(&state, &jar).as_deref(), &instance_id).await {
-        return Redirect::to("/instances").into_response();
+        return forbidden(&state, &jar);
     }
     let endpoint = format!("/v1/instances/{}", instance_id);
-    let payload = api_call_wrapper(&state, "GET", &endpoint, None, None).await;
+    // Independent upstream calls run together; the console loads on expand
+    // from `instance_console_get`
+    let (payload, events, mut subscription) = tokio::join!(
+        api_call_wrapper(&state, "GET", &endpoint, None, None),
+        load_instance_events(&state.client, &state.api_base_url, &state.api_token, &instance_id),
+        load_subscription(&state.client, &state.api_base_url, &state.api_token, &instance_id),
+    );
+    if let Some(message) = crate::api::auth_failure_message(&payload) {
+        return render_error(&state, &jar, StatusCode::FORBIDDEN, message);
+    }
+    // Lookups that need the instance itself, also run together
+    let data = payload.get("data").filter(|d| d.is_object());
+    let field = |key: &str| data.and_then(|d| d.get(key)).and_then(|v| v.as_str()).unwrap_or("");
+    let ssh_keys = data.map(provisioned_ssh_keys).unwrap_or_default();
+    let (product_name, known_ssh_keys) = tokio::join!(
+        async {
+            let pid = field("productId");
+            if pid.is_empty() { pid.to_string() } else { resolve_product_name(&state, field("region"), pid).await }
+        },
+        async {
+            // Only look up the account's keys when some ID came without a name
+            if ssh_keys.iter().any(|(_, name)| name.is_none()) {
+                let customer_id = fetch_default_customer_id(&state).await;
+                load_ssh_keys_api(&state, customer_id).await
+            } else {
+                Vec::new()
+            }
+        },
+    );
     
     let mut details: Vec<(String, String)> = Vec::new();
     let mut hostname = "(no hostname)".to_string();
     let mut status = "".to_string();
+    let mut created = None;
     if let Some(obj) = payload.as_object() {
+        if let Some(data) = obj.get("data").filter(|d| d.is_object()) {
+            crate::api::contract::validate_item("/v1/instances/{id}", data, crate::api::contract::INSTANCE_SCHEMA);
+        }
         if let Some(data) = obj.get("data").and_then(|d| d.as_object()) {
             hostname = data
                 .get("hostname")
@@ -84,6 +261,7 @@ pub async fn instance_detail(
                 .unwrap_or("(no hostname)")
                 .to_string();
             details.push(("Hostname".into(), hostname.clone()));
+            record_recent_instance(&state, &jar, &instance_id, &hostname);
             status = data
                 .get("status")
                 .and_then(|v| v.as_str())
@@ -103,21 +281,7 @@ pub async fn instance_detail(
                 .unwrap_or("")
                 .to_string();
             details.push(("Instance class".into(), class));
-            let product_id = data
-                .get("productId")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string());
-            if let Some(pid) = product_id.clone() {
-                let product_name = if !region.is_empty() && !pid.is_empty() {
-                    let products = load_products_wrapper(&state, &region).await;
-                    products
-                        .into_iter()
-                        .find(|p| p.id == pid)
-                        .map(|p| p.id.clone())
-                        .unwrap_or(pid.clone())
-                } else {
-                    pid.clone()
-                };
+            if data.get("productId").is_some_and(|v| v.is_string()) {
                 details.push(("Product".into(), product_name));
             }
             let vcpu = data.get("vcpuCount").and_then(|v| v.as_i64()).map(|v| v.to_string());
@@ -139,8 +303,13 @@ pub async fn instance_detail(
                     .to_string();
                 if !os_name.is_empty() { details.push(("OS".into(), os_name)); }
             }
-            if let Some(inserted) = data.get("insertedAt").and_then(|v| v.as_str()).map(|s| s.to_string()) {
-                details.push(("Created".into(), inserted));
+            if let Some(inserted) = data.get("insertedAt").and_then(|v| v.as_str()) {
+                let shown = crate::utils::display_time(inserted, chrono::Utc::now());
+                details.push(("Created".into(), shown.relative.clone()));
+                created = Some(shown);
+            }
+            if !ssh_keys.is_empty() {
+                details.push(("SSH keys".into(), ssh_key_labels(&ssh_keys, &known_ssh_keys).join(", ")));
             }
             if let Some(features) = data.get("features").and_then(|v| v.as_array()) {
                 let mut features_list = Vec::new();
@@ -149,9 +318,24 @@ pub async fn instance_detail(
             }
         }
     }
-    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages } = build_template_globals(&state, &jar);
+    if !prices_visible(&state, &jar) {
+        if let Some(sub) = subscription.as_mut() {
+            sub.price = None;
+            sub.refund_amount = None;
+        }
+    }
+    let renews = subscription
+        .as_ref()
+        .and_then(|s| s.renews_at.as_deref())
+        .map(|raw| crate::utils::display_time(raw, chrono::Utc::now()));
+    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages, maintenance_mode, api_status } = build_template_globals(&state, &jar);
     let disabled_by_env = state.is_instance_disabled(&instance_id);
     let disabled_by_host = state.is_hostname_blocked(&hostname);
+    let note = state.notes.lock().unwrap().get(&instance_id).cloned();
+    let labels = crate::services::label_service::label_strings(state.labels.lock().unwrap().get(&instance_id));
+    let can_edit_note = current_user.as_ref().is_some_and(|u| u.role == "owner" || u.role == "admin");
+    let can_power_on = power_transition_problem(&status, "poweron").is_none();
+    let can_power_off = power_transition_problem(&status, "poweroff").is_none();
     
     render_template(&state, &jar, InstanceDetailTemplate {
             current_user,
@@ -159,74 +343,134 @@ pub async fn instance_detail(
             base_url,
             flash_messages,
             has_flash_messages,
+            maintenance_mode,
+            api_status,
             instance_id: instance_id.clone(),
             hostname,
+            status_class: crate::utils::status_class(&status),
             status,
             details,
+            created,
+            events,
+            subscription,
+            renews,
+            note,
+            labels,
+            can_edit_note,
+            can_power_on,
+            can_power_off,
             disabled_by_env,
             disabled_by_host,
         },
     )
 }
 
-pub async fn instance_poweron_post(
+/// `GET /instance/:instance_id/console`: the serial console panel as an HTML
+/// fragment, fetched by the detail page when the panel is first expanded.
+pub async fn instance_console_get(
     State(state): State<AppState>,
     jar: CookieJar,
     Path(instance_id): Path<String>,
 ) -> impl IntoResponse {
     if !enforce_instance_access(&state, current_username_from_jar(&state, &jar).as_deref(), &instance_id).await {
-        return Redirect::to("/instances").into_response();
-    }
-    if let Some(reason) = crate::services::instance_service::check_instance_block(&state, &instance_id, None).await {
-        if let Some(sid) = jar.get("session_id") {
-            let mut flashes = state.flash_store.lock().unwrap();
-            let entry = flashes.entry(sid.value().to_string()).or_default();
-            entry.push(reason.message());
-        }
-        return Redirect::to(&format!("/instance/{}", instance_id)).into_response();
+        return forbidden(&state, &jar);
     }
-    let _ = simple_instance_action(&state, "poweron", &instance_id).await;
-    Redirect::to(&format!("/instance/{}", instance_id)).into_response()
+    let console = load_instance_console(&state.client, &state.api_base_url, &state.api_token, &instance_id, DEFAULT_CONSOLE_LINES).await;
+    render_template(&state, &jar, InstanceConsoleTemplate { instance_id, console })
 }
 
-pub async fn instance_poweroff_post(
+#[derive(Deserialize)]
+pub struct NoteForm {
+    #[serde(default)]
+    pub note: String,
+}
+
+/// POST /notes/:instance_id — set or clear the local note (admins and owners).
+pub async fn instance_note_post(
     State(state): State<AppState>,
     jar: CookieJar,
     Path(instance_id): Path<String>,
+    Form(form): Form<NoteForm>,
 ) -> impl IntoResponse {
-    if !enforce_instance_access(&state, current_username_from_jar(&state, &jar).as_deref(), &instance_id).await {
-        return Redirect::to("/instances").into_response();
+    if let Some(r) = ensure_admin_or_owner(&state, &jar) {
+        return r;
     }
-    if let Some(reason) = crate::services::instance_service::check_instance_block(&state, &instance_id, None).await {
-        if let Some(sid) = jar.get("session_id") {
-            let mut flashes = state.flash_store.lock().unwrap();
-            let entry = flashes.entry(sid.value().to_string()).or_default();
-            entry.push(reason.message());
-        }
-        return Redirect::to(&format!("/instance/{}", instance_id)).into_response();
+    let username = current_username_from_jar(&state, &jar).unwrap_or_default();
+    if !enforce_instance_access(&state, Some(&username), &instance_id).await {
+        return forbidden(&state, &jar);
+    }
+    {
+        let mut notes = state.notes.lock().unwrap();
+        apply_note(&mut notes, &instance_id, &form.note, &username, now_iso8601());
+    }
+    if let Err(e) = persist_notes_file(&state.notes).await {
+        tracing::error!(%e, "Failed to persist notes");
+        return render_error(&state, &jar, StatusCode::INTERNAL_SERVER_ERROR, "Failed to save note");
     }
-    let _ = simple_instance_action(&state, "poweroff", &instance_id).await;
-    Redirect::to(&format!("/instance/{}", instance_id)).into_response()
+    push_flash(&state, &jar, "Note saved.");
+    redirect_from_state(&state, &format!("/instance/{}", instance_id)).into_response()
+}
+
+/// Status for an action refused by [`check_instance_block`] or the cooldown.
+fn block_status(reason: &BlockReason) -> StatusCode {
+    match reason {
+        BlockReason::Cooldown(_) => StatusCode::TOO_MANY_REQUESTS,
+        _ => StatusCode::CONFLICT,
+    }
+}
+
+async fn power_action(
+    state: &AppState,
+    jar: &CookieJar,
+    responder: &ActionResponder,
+    instance_id: &str,
+    action: &str,
+    label: &str,
+) -> Response {
+    if !enforce_instance_access(state, current_username_from_jar(state, jar).as_deref(), instance_id).await {
+        return forbidden(state, jar);
+    }
+    let back = format!("/instance/{}", instance_id);
+    let instance = get_instance_for_action(state, instance_id).await;
+    if let Some(problem) = power_transition_problem(&instance.status, action) {
+        return responder.reject(state, jar, StatusCode::CONFLICT, problem, &back);
+    }
+    let blocked = match check_instance_block(state, instance_id, Some(&instance.hostname)).await {
+        Some(reason) => Some(reason),
+        None => check_action_cooldown(state, instance_id, action),
+    };
+    if let Some(reason) = blocked {
+        return responder.reject(state, jar, block_status(&reason), reason.message(), &back);
+    }
+    let resp = simple_instance_action(state, action, instance_id).await;
+    responder.outcome(state, jar, &resp, &format!("{} requested.", label), &format!("{} failed", label), &back)
+}
+
+pub async fn instance_poweron_post(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    responder: ActionResponder,
+    Path(instance_id): Path<String>,
+) -> impl IntoResponse {
+    power_action(&state, &jar, &responder, &instance_id, "poweron", "Power on").await
+}
+
+pub async fn instance_poweroff_post(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    responder: ActionResponder,
+    Path(instance_id): Path<String>,
+) -> impl IntoResponse {
+    power_action(&state, &jar, &responder, &instance_id, "poweroff", "Power off").await
 }
 
 pub async fn instance_reset_post(
     State(state): State<AppState>,
     jar: CookieJar,
+    responder: ActionResponder,
     Path(instance_id): Path<String>,
 ) -> impl IntoResponse {
-    if !enforce_instance_access(&state, current_username_from_jar(&state, &jar).as_deref(), &instance_id).await {
-        return Redirect::to("/instances").into_response();
-    }
-    if let Some(reason) = crate::services::instance_service::check_instance_block(&state, &instance_id, None).await {
-        if let Some(sid) = jar.get("session_id") {
-            let mut flashes = state.flash_store.lock().unwrap();
-            let entry = flashes.entry(sid.value().to_string()).or_default();
-            entry.push(reason.message());
-        }
-        return Redirect::to(&format!("/instance/{}", instance_id)).into_response();
-    }
-    let _ = simple_instance_action(&state, "reset", &instance_id).await;
-    Redirect::to(&format!("/instance/{}", instance_id)).into_response()
+    power_action(&state, &jar, &responder, &instance_id, "reset", "Reset").await
 }
 
 pub async fn instance_change_pass_get(
@@ -235,7 +479,7 @@ pub async fn instance_change_pass_get(
     Path(instance_id): Path<String>,
 ) -> impl IntoResponse {
     if !enforce_instance_access(&state, current_username_from_jar(&state, &jar).as_deref(), &instance_id).await {
-        return Redirect::to("/instances").into_response();
+        return forbidden(&state, &jar);
     }
     let endpoint = format!("/v1/instances/{}", instance_id);
     let payload = api_call_wrapper(&state, "GET", &endpoint, None, None).await;
@@ -247,13 +491,13 @@ pub async fn instance_change_pass_get(
             instance.main_ip = data.get("mainIp").and_then(|v| v.as_str()).map(|s| s.to_string());
             instance.main_ipv6 = data.get("mainIpv6").and_then(|v| v.as_str()).map(|s| s.to_string());
             instance.status = data.get("status").and_then(|v| v.as_str()).unwrap_or("").to_string();
-            instance.status_display = crate::utils::format_status(&instance.status);
+            instance.fill_display_fields();
         }
     }
-    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages } = build_template_globals(&state, &jar);
+    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages, maintenance_mode, api_status } = build_template_globals(&state, &jar);
     let disabled_by_env = state.is_instance_disabled(&instance_id);
     let disabled_by_host = state.is_hostname_blocked(&instance.hostname);
-    render_template(&state, &jar, ChangePassInstanceTemplate { current_user, api_hostname, base_url, flash_messages, has_flash_messages, instance, new_password: None, disabled_by_env, disabled_by_host })
+    render_template(&state, &jar, ChangePassInstanceTemplate { current_user, api_hostname, base_url, flash_messages, has_flash_messages, maintenance_mode, api_status, instance, new_password: None, disabled_by_env, disabled_by_host })
 }
 
 pub async fn instance_change_pass_post(
@@ -262,7 +506,7 @@ pub async fn instance_change_pass_post(
     Path(instance_id): Path<String>,
 ) -> impl IntoResponse {
     if !enforce_instance_access(&state, current_username_from_jar(&state, &jar).as_deref(), &instance_id).await {
-        return Redirect::to("/instances").into_response();
+        return forbidden(&state, &jar);
     }
     if let Some(reason) = crate::services::instance_service::check_instance_block(&state, &instance_id, None).await {
         if let Some(sid) = jar.get("session_id") {
@@ -270,7 +514,11 @@ pub async fn instance_change_pass_post(
             let entry = flashes.entry(sid.value().to_string()).or_default();
             entry.push(reason.message());
         }
-        return Redirect::to(&format!("/instance/{}/change-pass", instance_id)).into_response();
+        return redirect_from_state(&state, &format!("/instance/{}/change-pass", instance_id)).into_response();
+    }
+    if let Some(reason) = check_action_cooldown(&state, &instance_id, "change-pass") {
+        push_flash(&state, &jar, reason.message());
+        return redirect_from_state(&state, &format!("/instance/{}/change-pass", instance_id)).into_response();
     }
     let endpoint = format!("/v1/instances/{}/change-pass", instance_id);
     let payload = api_call_wrapper(&state, "POST", &endpoint, None, None).await;
@@ -285,35 +533,62 @@ pub async fn instance_change_pass_post(
             instance.main_ip = data.get("mainIp").and_then(|v| v.as_str()).map(|s| s.to_string());
             instance.main_ipv6 = data.get("mainIpv6").and_then(|v| v.as_str()).map(|s| s.to_string());
             instance.status = data.get("status").and_then(|v| v.as_str()).unwrap_or("").to_string();
-            instance.status_display = crate::utils::format_status(&instance.status);
+            instance.fill_display_fields();
         }
     }
-    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages } = build_template_globals(&state, &jar);
+    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages, maintenance_mode, api_status } = build_template_globals(&state, &jar);
     let disabled_by_env = state.is_instance_disabled(&instance_id);
     let disabled_by_host = state.is_hostname_blocked(&instance.hostname);
-    render_template(&state, &jar, ChangePassInstanceTemplate { current_user, api_hostname, base_url, flash_messages, has_flash_messages, instance, new_password, disabled_by_env, disabled_by_host })
+    render_template(&state, &jar, ChangePassInstanceTemplate { current_user, api_hostname, base_url, flash_messages, has_flash_messages, maintenance_mode, api_status, instance, new_password, disabled_by_env, disabled_by_host })
+}
+
+#[derive(Deserialize, Default)]
+pub struct DeleteInstanceForm {
+    /// Hostname typed on the confirm page (`ZY_DELETE_REQUIRE_HOSTNAME`).
+    #[serde(default)]
+    pub confirm_hostname: String,
 }
 
 pub async fn instance_delete(
     State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
     jar: CookieJar,
+    responder: ActionResponder,
     Path(instance_id): Path<String>,
+    form: Option<FormOrJson<DeleteInstanceForm>>,
 ) -> impl IntoResponse {
     if !enforce_instance_access(&state, current_username_from_jar(&state, &jar).as_deref(), &instance_id).await {
-        return Redirect::to("/instances").into_response();
+        return forbidden(&state, &jar);
     }
-    if let Some(reason) = crate::services::instance_service::check_instance_block(&state, &instance_id, None).await {
-        if let Some(sid) = jar.get("session_id") {
-            let mut flashes = state.flash_store.lock().unwrap();
-            let entry = flashes.entry(sid.value().to_string()).or_default();
-            entry.push(reason.message());
+    let back = format!("/instance/{}", instance_id);
+    let mut hostname = None;
+    if state.delete_require_hostname {
+        let Some(expected) = confirmation_hostname(&state, &instance_id).await else {
+            let message = format!("Could not load instance {} to confirm its hostname; try again later.", instance_id);
+            return responder.reject(&state, &jar, StatusCode::BAD_GATEWAY, message, &back);
+        };
+        let typed = form.map(|FormOrJson(f)| f.confirm_hostname).unwrap_or_default();
+        if !hostname_confirmed(&expected, &typed) {
+            let confirm = format!("/confirm/delete-instance/{}", instance_id);
+            let message = format!("Type the hostname '{}' to confirm deleting this instance.", expected);
+            return responder.reject(&state, &jar, StatusCode::BAD_REQUEST, message, &confirm);
         }
-        return Redirect::to(&format!("/instance/{}", instance_id)).into_response();
+        hostname = Some(expected);
+    }
+    let blocked = match check_instance_block(&state, &instance_id, hostname.as_deref()).await {
+        Some(reason) => Some(reason),
+        None => check_action_cooldown(&state, &instance_id, "delete"),
+    };
+    if let Some(reason) = blocked {
+        return responder.reject(&state, &jar, block_status(&reason), reason.message(), &back);
     }
     let endpoint = format!("/v1/instances/{}", instance_id);
     let payload = api_call_wrapper(&state, "DELETE", &endpoint, None, None).await;
     
-    let success = payload.get("code").and_then(|c| c.as_str()) == Some("OKAY");
+    let success = is_api_success(&payload);
+    if !success {
+        tracing::warn!(request_id = %request_id.0, %instance_id, "Instance delete failed");
+    }
     
     if success {
         {
@@ -324,29 +599,26 @@ pub async fn instance_delete(
                 }
             }
         }
-        if let Err(e) = persist_users_file(&state.users).await {
+        if let Err(e) = save_users(&state).await {
             tracing::error!(%e, "Failed to persist users after instance deletion");
+            push_flash(&state, &jar, unsaved_message("instance assignments", &e));
         }
-    }
-
-    if let Some(sid) = jar.get("session_id") {
-        let mut flashes = state.flash_store.lock().unwrap();
-        let entry = flashes.entry(sid.value().to_string()).or_default();
-        if success {
-            entry.push("Instance deleted successfully.".into());
-            return Redirect::to("/instances").into_response();
-        } else {
-            let detail = payload.get("detail").and_then(|d| d.as_str()).unwrap_or("Unknown error");
-            entry.push(format!("Delete failed: {}", detail));
-            return Redirect::to(&format!("/instance/{}", instance_id)).into_response();
+        let had_note = state.notes.lock().unwrap().remove(&instance_id).is_some();
+        if had_note {
+            if let Err(e) = persist_notes_file(&state.notes).await {
+                tracing::error!(%e, "Failed to persist notes after instance deletion");
+            }
+        }
+        let had_labels = state.labels.lock().unwrap().remove(&instance_id).is_some();
+        if had_labels {
+            if let Err(e) = crate::services::persist_labels_file(&state.labels).await {
+                tracing::error!(%e, "Failed to persist labels after instance deletion");
+            }
         }
     }
-    
-    if success {
-        Redirect::to("/instances").into_response()
-    } else {
-        Redirect::to(&format!("/instance/{}", instance_id)).into_response()
-    }
+
+    let redirect = if success { "/instances" } else { back.as_str() };
+    responder.outcome(&state, &jar, &payload, "Instance deleted successfully.", "Delete failed", redirect)
 }
 
 pub async fn instance_add_traffic(
@@ -356,7 +628,7 @@ pub async fn instance_add_traffic(
     Form(form): Form<AddTrafficForm>,
 ) -> impl IntoResponse {
     if !enforce_instance_access(&state, current_username_from_jar(&state, &jar).as_deref(), &instance_id).await {
-        return Redirect::to("/instances").into_response();
+        return forbidden(&state, &jar);
     }
     if let Some(reason) = crate::services::instance_service::check_instance_block(&state, &instance_id, None).await {
         if let Some(sid) = jar.get("session_id") {
@@ -364,7 +636,11 @@ pub async fn instance_add_traffic(
             let entry = flashes.entry(sid.value().to_string()).or_default();
             entry.push(reason.message());
         }
-        return Redirect::to(&format!("/instance/{}", instance_id)).into_response();
+        return redirect_from_state(&state, &format!("/instance/{}", instance_id)).into_response();
+    }
+    if let Some(reason) = check_action_cooldown(&state, &instance_id, "add-traffic") {
+        push_flash(&state, &jar, reason.message());
+        return redirect_from_state(&state, &format!("/instance/{}", instance_id)).into_response();
     }
     if let Ok(amount) = form.traffic_amount.parse::<f64>() {
         if amount > 0.0 {
@@ -373,16 +649,17 @@ pub async fn instance_add_traffic(
             let _ = api_call_wrapper(&state, "POST", &endpoint, Some(payload), None).await;
         }
     }
-    Redirect::to(&format!("/instance/{}", instance_id)).into_response()
+    redirect_from_state(&state, &format!("/instance/{}", instance_id)).into_response()
 }
 
 pub async fn instance_resize_get(
     State(state): State<AppState>,
     jar: CookieJar,
     Path(instance_id): Path<String>,
+    Query(q): Query<HashMap<String, String>>,
 ) -> impl IntoResponse {
     if !enforce_instance_access(&state, current_username_from_jar(&state, &jar).as_deref(), &instance_id).await {
-        return Redirect::to("/instances").into_response();
+        return forbidden(&state, &jar);
     }
     let endpoint = format!("/v1/instances/{}", instance_id);
     let payload = api_call_wrapper(&state, "GET", &endpoint, None, None).await;
@@ -394,32 +671,57 @@ pub async fn instance_resize_get(
             instance.main_ip = data.get("mainIp").and_then(|v| v.as_str()).map(|s| s.to_string());
             instance.main_ipv6 = data.get("mainIpv6").and_then(|v| v.as_str()).map(|s| s.to_string());
             instance.status = data.get("status").and_then(|v| v.as_str()).unwrap_or("").to_string();
-            instance.status_display = crate::utils::format_status(&instance.status);
+            instance.fill_display_fields();
+            instance.product_id = data.get("productId").and_then(|v| v.as_str()).map(|s| s.to_string());
         }
     }
+    // "Preview price" submits the form here with GET instead of resizing
+    let product_id = q.get("product_id").map(|p| p.trim().to_string()).unwrap_or_default();
+    let preview = if product_id.is_empty() || !prices_visible(&state, &jar) {
+        None
+    } else {
+        let products = load_products_wrapper(&state, &instance.region).await;
+        let prices: Vec<(String, f64)> = products.iter().map(|p| (p.id.clone(), p.monthly_price())).collect();
+        Some(resize_preview(instance.product_id.as_deref(), &product_id, &prices))
+    };
     let (regions, _map) = load_regions_wrapper(&state).await;
-    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages } = build_template_globals(&state, &jar);
+    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages, maintenance_mode, api_status } = build_template_globals(&state, &jar);
     let disabled_by_env = state.is_instance_disabled(&instance_id);
     let disabled_by_host = state.is_hostname_blocked(&instance.hostname);
-    render_template(&state, &jar, ResizeTemplate { current_user, api_hostname, base_url, flash_messages, has_flash_messages, instance, regions: &regions, disabled_by_env, disabled_by_host })
+    render_template(&state, &jar, ResizeTemplate { current_user, api_hostname, base_url, flash_messages, has_flash_messages, maintenance_mode, api_status, instance, regions: &regions, product_id, preview, can_preview: prices_visible(&state, &jar), disabled_by_env, disabled_by_host })
 }
 
 pub async fn instance_resize_post(
     State(state): State<AppState>,
     jar: CookieJar,
+    responder: ActionResponder,
     Path(instance_id): Path<String>,
-    Form(form): Form<ResizeForm>,
+    FormOrJson(form): FormOrJson<ResizeForm>,
 ) -> impl IntoResponse {
     if !enforce_instance_access(&state, current_username_from_jar(&state, &jar).as_deref(), &instance_id).await {
-        return Redirect::to("/instances").into_response();
+        return forbidden(&state, &jar);
     }
-    if let Some(reason) = crate::services::instance_service::check_instance_block(&state, &instance_id, None).await {
-        if let Some(sid) = jar.get("session_id") {
-            let mut flashes = state.flash_store.lock().unwrap();
-            let entry = flashes.entry(sid.value().to_string()).or_default();
-            entry.push(reason.message());
+    let back = format!("/instance/{}/resize", instance_id);
+    if let Some(reason) = check_instance_block(&state, &instance_id, None).await {
+        return responder.reject(&state, &jar, block_status(&reason), reason.message(), &back);
+    }
+    if form.r#type.eq_ignore_ascii_case("CUSTOM") && form.confirm_downsize.as_deref() != Some("1") {
+        let parse = |v: &Option<String>| v.as_deref().and_then(|s| s.trim().parse::<i64>().ok());
+        let changes = match custom_resize_downsizes(&state, &instance_id, parse(&form.cpu), parse(&form.ram_in_gb), parse(&form.disk_in_gb)).await {
+            Ok(changes) => changes,
+            Err(e) => return responder.reject(&state, &jar, StatusCode::BAD_GATEWAY, format!("{}; try again later.", e), &back),
+        };
+        if !changes.is_empty() {
+            let message = format!(
+                "This resize shrinks {}. Tick \"Allow downsize\" (or send confirm_downsize=1) to proceed.",
+                changes.join(", ")
+            );
+            return responder.reject(&state, &jar, StatusCode::PRECONDITION_REQUIRED, message, &back);
         }
-        return Redirect::to(&format!("/instance/{}/resize", instance_id)).into_response();
+    }
+    // Only a request that is about to go upstream starts the cooldown
+    if let Some(reason) = check_action_cooldown(&state, &instance_id, "resize") {
+        return responder.reject(&state, &jar, block_status(&reason), reason.message(), &back);
     }
     let endpoint = format!("/v1/instances/{}/resize", instance_id);
     let mut payload = serde_json::json!({"type": form.r#type});
@@ -482,20 +784,9 @@ pub async fn instance_resize_post(
     if !extra_resource.is_empty() {
         payload["extraResource"] = Value::Object(extra_resource);
     }
-    let resp = api_call_wrapper(&state, "POST", &endpoint, Some(payload), None).await;
-    
-    if let Some(sid) = jar.get("session_id") {
-        let mut flashes = state.flash_store.lock().unwrap();
-        let entry = flashes.entry(sid.value().to_string()).or_default();
-        if resp.get("code").and_then(|c| c.as_str()) == Some("OKAY") {
-            entry.push("Instance resize initiated successfully.".into());
-        } else {
-            let detail = resp.get("detail").and_then(|d| d.as_str()).unwrap_or("Unknown error");
-            entry.push(format!("Resize failed: {}", detail));
-        }
-    }
-
-    Redirect::to(&format!("/instance/{}", instance_id)).into_response()
+    let resp = timeout::scope(timeout::long_operation_timeout(), api_call_wrapper(&state, "POST", &endpoint, Some(payload), None)).await;
+    let back = format!("/instance/{}", instance_id);
+    responder.outcome(&state, &jar, &resp, "Instance resize initiated successfully.", "Resize failed", &back)
 }
 
 #[derive(Deserialize)]
@@ -509,7 +800,7 @@ pub async fn instance_change_os_get(
     Path(instance_id): Path<String>,
 ) -> impl IntoResponse {
     if !enforce_instance_access(&state, current_username_from_jar(&state, &jar).as_deref(), &instance_id).await {
-        return Redirect::to("/instances").into_response();
+        return forbidden(&state, &jar);
     }
     let endpoint = format!("/v1/instances/{}", instance_id);
     let payload = api_call_wrapper(&state, "GET", &endpoint, None, None).await;
@@ -524,7 +815,7 @@ pub async fn instance_change_os_get(
             instance.main_ip = data.get("mainIp").and_then(|v| v.as_str()).map(|s| s.to_string());
             instance.main_ipv6 = data.get("mainIpv6").and_then(|v| v.as_str()).map(|s| s.to_string());
             instance.status = data.get("status").and_then(|v| v.as_str()).unwrap_or("").to_string();
-            instance.status_display = crate::utils::format_status(&instance.status);
+            instance.fill_display_fields();
             if let Some(os_obj) = data.get("os").and_then(|v| v.as_object()) {
                 instance.os = Some(OsItem {
                     id: os_obj.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
@@ -539,8 +830,16 @@ pub async fn instance_change_os_get(
         }
     }
     
-    let os_list = load_os_list(&state.client, &state.api_base_url, &state.api_token).await;
-    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages } = build_template_globals(&state, &jar);
+    let instance_arch = instance.os.as_ref().and_then(|os| os.arch.clone());
+    let os_options = load_os_list(&state.client, &state.api_base_url, &state.api_token)
+        .await
+        .into_iter()
+        .map(|os| ChangeOsOption {
+            disabled_reason: os.incompatibility(instance.ram, instance_arch.as_deref()),
+            os,
+        })
+        .collect();
+    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages, maintenance_mode, api_status } = build_template_globals(&state, &jar);
     let disabled_by_env = state.is_instance_disabled(&instance_id);
     let disabled_by_host = state.is_hostname_blocked(&instance.hostname);
     render_template(&state, &jar, ChangeOsInstanceTemplate { 
@@ -549,8 +848,10 @@ pub async fn instance_change_os_get(
         base_url, 
         flash_messages, 
         has_flash_messages, 
+        maintenance_mode,
+        api_status,
         instance, 
-        os_list, 
+        os_options, 
         disabled_by_env, 
         disabled_by_host 
     })
@@ -559,35 +860,177 @@ pub async fn instance_change_os_get(
 pub async fn instance_change_os_post(
     State(state): State<AppState>,
     jar: CookieJar,
+    responder: ActionResponder,
     Path(instance_id): Path<String>,
-    Form(form): Form<ChangeOsForm>,
+    FormOrJson(form): FormOrJson<ChangeOsForm>,
 ) -> impl IntoResponse {
     if !enforce_instance_access(&state, current_username_from_jar(&state, &jar).as_deref(), &instance_id).await {
-        return Redirect::to("/instances").into_response();
+        return forbidden(&state, &jar);
     }
-    if let Some(reason) = crate::services::instance_service::check_instance_block(&state, &instance_id, None).await {
-        if let Some(sid) = jar.get("session_id") {
-            let mut flashes = state.flash_store.lock().unwrap();
-            let entry = flashes.entry(sid.value().to_string()).or_default();
-            entry.push(reason.message());
+    let change_os_page = format!("/instance/{}/change-os", instance_id);
+    let instance = get_instance_for_action(&state, &instance_id).await;
+    if let Some(reason) = check_instance_block(&state, &instance_id, Some(&instance.hostname)).await {
+        return responder.reject(&state, &jar, block_status(&reason), reason.message(), &change_os_page);
+    }
+
+    // Reject images that don't fit before the API fails with a vaguer error
+    let os_list = load_os_list(&state.client, &state.api_base_url, &state.api_token).await;
+    let rejection = match os_list.iter().find(|os| os.id == form.os_id) {
+        // Without the catalog there is nothing to check against; let the API decide
+        None if os_list.is_empty() => {
+            tracing::warn!(%instance_id, os_id = %form.os_id, "OS list unavailable; skipping change-os compatibility check");
+            None
         }
-        return Redirect::to(&format!("/instance/{}/change-os", instance_id)).into_response();
+        None => Some(format!("Unknown operating system '{}'.", form.os_id)),
+        Some(os) => os
+            .incompatibility(instance.ram, instance.os.as_ref().and_then(|o| o.arch.as_deref()))
+            .map(|reason| format!("Cannot install {}: {}.", os.name, reason)),
+    };
+    if let Some(message) = rejection {
+        return responder.reject(&state, &jar, StatusCode::UNPROCESSABLE_ENTITY, message, &change_os_page);
     }
-    
+    // A rejected image must not use up the cooldown for the corrected retry
+    if let Some(reason) = check_action_cooldown(&state, &instance_id, "change-os") {
+        return responder.reject(&state, &jar, block_status(&reason), reason.message(), &change_os_page);
+    }
+
     let endpoint = format!("/v1/instances/{}/change-os", instance_id);
     let payload = serde_json::json!({"osId": form.os_id});
-    let resp = api_call_wrapper(&state, "POST", &endpoint, Some(payload), None).await;
-    
-    if let Some(sid) = jar.get("session_id") {
-        let mut flashes = state.flash_store.lock().unwrap();
-        let entry = flashes.entry(sid.value().to_string()).or_default();
-        if resp.get("code").and_then(|c| c.as_str()) == Some("OKAY") {
-            entry.push("OS change initiated successfully.".into());
-        } else {
-            let detail = resp.get("detail").and_then(|d| d.as_str()).unwrap_or("Unknown error");
-            entry.push(format!("OS change failed: {}", detail));
+    let resp = timeout::scope(timeout::long_operation_timeout(), api_call_wrapper(&state, "POST", &endpoint, Some(payload), None)).await;
+    let back = format!("/instance/{}", instance_id);
+    responder.outcome(&state, &jar, &resp, "OS change initiated successfully.", "OS change failed", &back)
+}
+
+#[derive(Deserialize)]
+pub struct BulkActionForm {
+    /// Comma- or newline-separated IDs from the form, or a JSON array.
+    pub instance_ids: OneOrMany,
+    /// `change-os`, `resize`, `poweron`, `poweroff` or `reset`.
+    pub action: String,
+    pub os_id: Option<String>,
+    #[serde(rename = "type")]
+    pub resize_type: Option<String>,
+    pub product_id: Option<String>,
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub cpu: Option<String>,
+    #[serde(rename = "ramInGB", default, deserialize_with = "string_or_number")]
+    pub ram_in_gb: Option<String>,
+    #[serde(rename = "diskInGB", default, deserialize_with = "string_or_number")]
+    pub disk_in_gb: Option<String>,
+    #[serde(rename = "bandwidthInTB", default, deserialize_with = "string_or_number")]
+    pub bandwidth_in_tb: Option<String>,
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub confirm_downsize: Option<String>,
+}
+
+impl BulkActionForm {
+    fn bulk_action(&self) -> Result<BulkAction, String> {
+        let parse = |v: &Option<String>| v.as_deref().and_then(|s| s.trim().parse::<i64>().ok());
+        match self.action.as_str() {
+            "change-os" => match self.os_id.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+                Some(os_id) => Ok(BulkAction::ChangeOs { os_id: os_id.to_string() }),
+                None => Err("Select an operating system.".into()),
+            },
+            "resize" => {
+                let resize_type = self.resize_type.as_deref().unwrap_or("").trim().to_uppercase();
+                if resize_type != "FIXED" && resize_type != "CUSTOM" {
+                    return Err("Resize type must be FIXED or CUSTOM.".into());
+                }
+                Ok(BulkAction::Resize(ResizeSpec {
+                    resize_type,
+                    product_id: self.product_id.clone(),
+                    cpu: parse(&self.cpu),
+                    ram_in_gb: parse(&self.ram_in_gb),
+                    disk_in_gb: parse(&self.disk_in_gb),
+                    bandwidth_in_tb: parse(&self.bandwidth_in_tb),
+                    allow_downsize: self.confirm_downsize.as_deref() == Some("1"),
+                }))
+            }
+            other => match PowerAction::parse(other) {
+                Some(power) => Ok(BulkAction::Power(power)),
+                None => Err(format!("Unknown bulk action '{}'.", other)),
+            },
         }
     }
-    
-    Redirect::to(&format!("/instance/{}", instance_id)).into_response()
+}
+
+async fn render_bulk_page(state: &AppState, jar: &CookieJar, instance_ids: String, results: Vec<BulkOutcome>, summary: String) -> Response {
+    let os_options = load_os_list(&state.client, &state.api_base_url, &state.api_token)
+        .await
+        .into_iter()
+        .filter(|os| os.is_active)
+        .collect();
+    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages, maintenance_mode, api_status } = build_template_globals(state, jar);
+    render_template(state, jar, InstancesBulkTemplate {
+        current_user,
+        api_hostname,
+        base_url,
+        flash_messages,
+        has_flash_messages,
+        maintenance_mode,
+        api_status,
+        os_options,
+        instance_ids,
+        results,
+        summary,
+    })
+}
+
+/// GET /instances/bulk — form for a bulk power, change-os or resize action
+/// (admins and owners), pre-filled with the instances ticked on the list.
+pub async fn instances_bulk_get(State(state): State<AppState>, jar: CookieJar) -> impl IntoResponse {
+    if let Some(r) = ensure_admin_or_owner(&state, &jar) {
+        return r;
+    }
+    let selection = selected_instances(&state, &jar).join("\n");
+    render_bulk_page(&state, &jar, selection, Vec::new(), String::new()).await
+}
+
+/// POST /instances/bulk — apply one change-os or resize to many instances and
+/// report a result per instance (a results table, or JSON for API clients).
+pub async fn instances_bulk_post(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    responder: ActionResponder,
+    FormOrJson(form): FormOrJson<BulkActionForm>,
+) -> impl IntoResponse {
+    if let Some(r) = ensure_admin_or_owner(&state, &jar) {
+        return r;
+    }
+    let action = form.bulk_action();
+    let ids = parse_instance_ids(&form.instance_ids.into_csv());
+    let action = match action {
+        Ok(_) if ids.is_empty() => Err("Enter at least one instance ID.".to_string()),
+        other => other,
+    };
+    let action = match action {
+        Ok(action) => action,
+        Err(message) => return responder.reject(&state, &jar, StatusCode::UNPROCESSABLE_ENTITY, message, "/instances/bulk"),
+    };
+
+    let username = current_username_from_jar(&state, &jar);
+    let mut accessible = Vec::new();
+    for id in &ids {
+        if enforce_instance_access(&state, username.as_deref(), id).await {
+            accessible.push(id.clone());
+        }
+    }
+    let mut ran = run_bulk_action(&state, &accessible, &action, DEFAULT_BULK_CONCURRENCY).await.into_iter();
+    let results: Vec<BulkOutcome> = ids
+        .iter()
+        .map(|id| {
+            if accessible.contains(id) {
+                ran.next().expect("one outcome per accessible instance")
+            } else {
+                BulkOutcome { instance_id: id.clone(), ok: false, message: "Skipped: you don't have access to this instance.".into() }
+            }
+        })
+        .collect();
+    let summary = summarize(&action, &results);
+
+    if responder.json {
+        let ok = results.iter().all(|r| r.ok);
+        return Json(serde_json::json!({"ok": ok, "message": summary, "results": results})).into_response();
+    }
+    render_bulk_page(&state, &jar, ids.join("\n"), results, summary).await
 }
@@ -1,13 +1,14 @@
 use axum::{
     extract::{State, Query, Form},
-    response::{IntoResponse, Redirect},
+    response::IntoResponse,
 };
 use axum_extra::extract::cookie::CookieJar;
 use serde::Deserialize;
 
 use crate::models::AppState;
 use crate::handlers::helpers::{
-    build_template_globals, render_template, TemplateGlobals, ensure_owner, load_active_regions,
+    redirect_from_state,
+    build_template_globals, render_template, TemplateGlobals, ensure_owner, load_active_regions, api_failure_message,
 };
 use crate::api::{load_isos, download_iso};
 
@@ -55,7 +56,7 @@ pub async fn isos_list_get(
     .await;
     let regions = load_active_regions(&state).await;
     
-    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages } = 
+    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages, maintenance_mode, api_status } = 
         build_template_globals(&state, &jar);
     
     render_template(
@@ -67,6 +68,8 @@ pub async fn isos_list_get(
             base_url,
             flash_messages,
             has_flash_messages,
+            maintenance_mode,
+            api_status,
             isos: &paginated.isos,
             regions: &regions,
             total_count: paginated.total_count,
@@ -102,10 +105,9 @@ pub async fn iso_download_post(
         if resp.get("code").and_then(|c| c.as_str()) == Some("OKAY") {
             entry.push("ISO download initiated successfully.".into());
         } else {
-            let detail = resp.get("detail").and_then(|d| d.as_str()).unwrap_or("Unknown error");
-            entry.push(format!("Failed to download ISO: {}", detail));
+            entry.push(api_failure_message(&resp, "Failed to download ISO"));
         }
     }
     
-    Redirect::to("/isos").into_response()
+    redirect_from_state(&state, "/isos").into_response()
 }
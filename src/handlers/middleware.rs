@@ -1,22 +1,234 @@
 use axum::{
     extract::{State, Request},
     middleware::Next,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
     response::{IntoResponse, Redirect, Response},
+    Json,
 };
 use axum_extra::extract::cookie::CookieJar;
+use tracing::Instrument;
 
 use crate::models::AppState;
-use crate::handlers::helpers::current_username_from_jar;
+use crate::handlers::helpers::{redirect_from_state, current_username_from_jar, push_flash, render_error};
+use crate::handlers::negotiate::has_json_body;
+use crate::api::request_id::{self, REQUEST_ID_HEADER};
 
+/// Per-request correlation ID, stored as a request extension by
+/// [`request_id_middleware`].
+#[derive(Clone, Debug)]
+pub struct RequestId(pub String);
+
+/// Whether the client asked for JSON (`Accept: application/json`), i.e. is
+/// a script or monitor rather than a browser.
+pub fn wants_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|accept| accept.split(',').any(|t| t.trim().starts_with("application/json")))
+}
+
+/// Require a session for protected routes.
+///
+/// Browsers without a session are redirected to `/login`; JSON clients and
+/// `/api/` routes get 401. A 403 from the handler is passed through as the
+/// error page, or as JSON for API clients, or as a redirect to `/` when
+/// `redirect_on_deny` is set.
 pub async fn auth_middleware(
     State(state): State<AppState>,
     jar: CookieJar,
     request: Request,
     next: Next,
 ) -> Response {
-    if current_username_from_jar(&state, &jar).is_some() {
+    let json = wants_json(request.headers()) || request.uri().path().starts_with("/api/");
+    if current_username_from_jar(&state, &jar).is_none() {
+        if json {
+            return (StatusCode::UNAUTHORIZED, Json(serde_json::json!({"error": "unauthorized"}))).into_response();
+        }
+        return redirect_from_state(&state, "/login").into_response();
+    }
+    let response = next.run(request).await;
+    if response.status() != StatusCode::FORBIDDEN {
+        return response;
+    }
+    if json {
+        (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "forbidden"}))).into_response()
+    } else if state.redirect_on_deny {
+        redirect_from_state(&state, "/").into_response()
+    } else {
+        response
+    }
+}
+
+/// POST routes that only touch local panel data (users, access, workspaces,
+/// instance notes, clocked instances, the list selection, update checks) and
+/// stay available during maintenance.
+const MAINTENANCE_EXEMPT_PREFIXES: &[&str] = &[
+    "/users",
+    "/access",
+    "/workspaces",
+    "/notes",
+    "/clocked-instances",
+    "/instances/selection",
+    "/about/check-update",
+];
+
+/// Whether a request would change upstream resources and must be refused
+/// while maintenance mode is on.
+pub fn is_blocked_by_maintenance(method: &axum::http::Method, path: &str) -> bool {
+    *method != axum::http::Method::GET
+        && *method != axum::http::Method::HEAD
+        && !MAINTENANCE_EXEMPT_PREFIXES.iter().any(|p| path == *p || path.starts_with(&format!("{}/", p)))
+}
+
+/// Short-circuit mutating actions while `AppState::maintenance_mode` is on,
+/// redirecting back to the referring page with a flash message. JSON clients
+/// get `503 {"ok": false, "message": ...}` instead.
+pub async fn maintenance_middleware(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !state.maintenance_mode || !is_blocked_by_maintenance(request.method(), request.uri().path()) {
+        return next.run(request).await;
+    }
+    tracing::info!(path = %request.uri().path(), "Blocked action during maintenance mode");
+    let message = "Maintenance in progress: actions are temporarily disabled.";
+    if wants_json(request.headers()) || has_json_body(request.headers()) {
+        return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"ok": false, "message": message}))).into_response();
+    }
+    push_flash(&state, &jar, message);
+    let back = request
+        .headers()
+        .get(axum::http::header::REFERER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|r| r.parse::<axum::http::Uri>().ok())
+        .map(|u| u.path().to_string())
+        .unwrap_or_else(|| "/".to_string());
+    Redirect::to(&back).into_response()
+}
+
+/// After a request that succeeded, write any state file an earlier request
+/// failed to save (see [`UnsavedChanges`](crate::services::UnsavedChanges)).
+pub async fn retry_unsaved_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let response = next.run(request).await;
+    if state.unsaved.any() && !response.status().is_client_error() && !response.status().is_server_error() {
+        crate::services::persistence::retry_unsaved(&state).await;
+    }
+    response
+}
+
+/// Whether a declared `Content-Length` exceeds `limit` bytes.
+pub fn exceeds_body_limit(headers: &HeaderMap, limit: usize) -> bool {
+    headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .is_some_and(|len| len > limit as u64)
+}
+
+/// Answer oversized request bodies (`ZY_MAX_BODY_BYTES`) with a 413 error
+/// page, or JSON for API clients.
+///
+/// Bodies that declare their length are refused before being read. Chunked
+/// bodies are cut off by `DefaultBodyLimit` while buffering; its plain-text
+/// rejection is replaced here with the same response.
+pub async fn body_limit_middleware(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    request: Request,
+    next: Next,
+) -> Response {
+    let json = wants_json(request.headers()) || request.uri().path().starts_with("/api/");
+    let path = request.uri().path().to_string();
+    let response = if exceeds_body_limit(request.headers(), state.max_body_bytes) {
+        StatusCode::PAYLOAD_TOO_LARGE.into_response()
+    } else {
         next.run(request).await
+    };
+    if response.status() != StatusCode::PAYLOAD_TOO_LARGE {
+        return response;
+    }
+    tracing::warn!(%path, limit = state.max_body_bytes, "Rejected oversized request body");
+    let message = format!("The submitted form is larger than the {} KiB limit.", state.max_body_bytes / 1024);
+    if json {
+        (StatusCode::PAYLOAD_TOO_LARGE, Json(serde_json::json!({"error": message}))).into_response()
     } else {
-        Redirect::to("/login").into_response()
+        render_error(&state, &jar, StatusCode::PAYLOAD_TOO_LARGE, message)
+    }
+}
+
+/// Tag every request with a correlation ID.
+///
+/// A valid incoming `X-Request-Id` header is reused, otherwise a new UUID is
+/// generated. The ID is stored as a [`RequestId`] extension, attached to the
+/// request's tracing span (so handler and `api_call_wrapper` log lines carry
+/// it), forwarded to the upstream API and echoed back in the response.
+pub async fn request_id_middleware(mut request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| request_id::is_valid(v))
+        .map(str::to_string)
+        .unwrap_or_else(request_id::generate);
+    request.extensions_mut().insert(RequestId(id.clone()));
+
+    let span = tracing::info_span!("request", request_id = %id, method = %request.method(), path = %request.uri().path());
+    let started = std::time::Instant::now();
+    let mut response = request_id::scope(id.clone(), next.run(request))
+        .instrument(span.clone())
+        .await;
+    span.in_scope(|| {
+        tracing::info!(status = response.status().as_u16(), elapsed_ms = started.elapsed().as_millis() as u64, "Request completed");
+    });
+
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Method;
+
+    #[test]
+    fn test_maintenance_blocks_upstream_actions_only() {
+        assert!(is_blocked_by_maintenance(&Method::POST, "/instance/abc/delete"));
+        assert!(is_blocked_by_maintenance(&Method::POST, "/create/step-7"));
+        assert!(!is_blocked_by_maintenance(&Method::GET, "/instance/abc"));
+        assert!(!is_blocked_by_maintenance(&Method::POST, "/users/bob/role"));
+        assert!(!is_blocked_by_maintenance(&Method::POST, "/workspaces"));
+        assert!(is_blocked_by_maintenance(&Method::POST, "/usersx"));
+        assert!(!is_blocked_by_maintenance(&Method::POST, "/instances/selection"));
+        assert!(is_blocked_by_maintenance(&Method::POST, "/instances/bulk"));
+    }
+
+    #[test]
+    fn test_wants_json() {
+        let mut headers = HeaderMap::new();
+        assert!(!wants_json(&headers));
+        headers.insert(header::ACCEPT, HeaderValue::from_static("text/html,application/xhtml+xml,*/*;q=0.8"));
+        assert!(!wants_json(&headers));
+        headers.insert(header::ACCEPT, HeaderValue::from_static("application/json"));
+        assert!(wants_json(&headers));
+        headers.insert(header::ACCEPT, HeaderValue::from_static("text/plain, application/json;q=0.9"));
+        assert!(wants_json(&headers));
+    }
+
+    #[test]
+    fn test_exceeds_body_limit() {
+        let mut headers = HeaderMap::new();
+        assert!(!exceeds_body_limit(&headers, 1024));
+        headers.insert(header::CONTENT_LENGTH, HeaderValue::from_static("1024"));
+        assert!(!exceeds_body_limit(&headers, 1024));
+        headers.insert(header::CONTENT_LENGTH, HeaderValue::from_static("1025"));
+        assert!(exceeds_body_limit(&headers, 1024));
     }
 }
@@ -5,10 +5,12 @@ pub mod wizard;
 pub mod access;
 pub mod ssh_keys;
 pub mod middleware;
+pub mod negotiate;
 pub mod system;
 pub mod workspaces;
 pub mod clocked_instances;
 pub mod mcp_docs;
+pub mod admin;
 
 // Temporary - these will be fully implemented
 pub mod instances;
@@ -16,4 +18,5 @@ pub mod snapshots;
 pub mod floating_ips;
 pub mod iso;
 pub mod images;
+pub mod applications;
 pub mod backups;
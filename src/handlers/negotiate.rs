@@ -0,0 +1,101 @@
+//! Content negotiation for instance actions, so scripts can drive the same
+//! endpoints as the browser (and reuse its session and permission checks).
+
+use axum::{
+    async_trait,
+    extract::{Form, FromRequest, FromRequestParts, Request},
+    http::{header, request::Parts, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::extract::cookie::CookieJar;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::handlers::helpers::{redirect_from_state, api_result_message, push_flash};
+use crate::handlers::middleware::wants_json;
+use crate::models::AppState;
+
+pub fn has_json_body(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.trim_start().starts_with("application/json"))
+}
+
+/// Request body parsed as JSON when `Content-Type: application/json`,
+/// otherwise as a urlencoded form.
+pub struct FormOrJson<T>(pub T);
+
+#[async_trait]
+impl<S, T> FromRequest<S> for FormOrJson<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        if has_json_body(req.headers()) {
+            let Json(value) = Json::<T>::from_request(req, state).await.map_err(IntoResponse::into_response)?;
+            Ok(FormOrJson(value))
+        } else {
+            let Form(value) = Form::<T>::from_request(req, state).await.map_err(IntoResponse::into_response)?;
+            Ok(FormOrJson(value))
+        }
+    }
+}
+
+/// How to report an action's outcome: a flash plus redirect for browsers, or
+/// a JSON body with a matching status for clients that send or accept JSON.
+pub struct ActionResponder {
+    pub json: bool,
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ActionResponder {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(ActionResponder { json: wants_json(&parts.headers) || has_json_body(&parts.headers) })
+    }
+}
+
+impl ActionResponder {
+    /// Refuse the action before it reaches the API.
+    pub fn reject(&self, state: &AppState, jar: &CookieJar, status: StatusCode, message: impl Into<String>, redirect: &str) -> Response {
+        let message = message.into();
+        if self.json {
+            return (status, Json(serde_json::json!({"ok": false, "message": message}))).into_response();
+        }
+        push_flash(state, jar, message);
+        redirect_from_state(state, redirect).into_response()
+    }
+
+    /// Report an upstream result; failures map to 502 for JSON clients.
+    pub fn outcome(&self, state: &AppState, jar: &CookieJar, payload: &Value, success_msg: &str, failure_prefix: &str, redirect: &str) -> Response {
+        let (success, message) = api_result_message(payload, success_msg, failure_prefix);
+        if self.json {
+            let status = if success { StatusCode::OK } else { StatusCode::BAD_GATEWAY };
+            return (status, Json(serde_json::json!({"ok": success, "message": message}))).into_response();
+        }
+        push_flash(state, jar, message);
+        redirect_from_state(state, redirect).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_has_json_body() {
+        let mut headers = HeaderMap::new();
+        assert!(!has_json_body(&headers));
+        headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/x-www-form-urlencoded"));
+        assert!(!has_json_body(&headers));
+        headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json; charset=utf-8"));
+        assert!(has_json_body(&headers));
+    }
+}
@@ -1,14 +1,15 @@
 use axum::{
     extract::{State, Path, Query, Form},
-    response::{IntoResponse, Redirect},
+    response::IntoResponse,
 };
 use axum_extra::extract::cookie::CookieJar;
 use serde::Deserialize;
 
 use crate::models::AppState;
 use crate::handlers::helpers::{
+    redirect_from_state,
     build_template_globals, current_username_from_jar,
-    render_template, TemplateGlobals, ensure_owner,
+    render_template, TemplateGlobals, ensure_owner, forbidden, api_failure_message,
 };
 use crate::api::{load_snapshots, create_snapshot, get_snapshot, delete_snapshot, restore_snapshot};
 use crate::services::instance_service::enforce_instance_access;
@@ -54,7 +55,7 @@ pub async fn snapshots_list_get(
     )
     .await;
     
-    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages } = 
+    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages, maintenance_mode, api_status } = 
         build_template_globals(&state, &jar);
     
     render_template(
@@ -66,6 +67,8 @@ pub async fn snapshots_list_get(
             base_url,
             flash_messages,
             has_flash_messages,
+            maintenance_mode,
+            api_status,
             snapshots: &paginated.snapshots,
             current_page: paginated.current_page,
             total_pages: paginated.total_pages,
@@ -94,7 +97,7 @@ pub async fn snapshot_detail_get(
         }
     }
     
-    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages } = 
+    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages, maintenance_mode, api_status } = 
         build_template_globals(&state, &jar);
     
     render_template(
@@ -106,6 +109,8 @@ pub async fn snapshot_detail_get(
             base_url,
             flash_messages,
             has_flash_messages,
+            maintenance_mode,
+            api_status,
             snapshot_id,
             snapshot_data,
         },
@@ -123,7 +128,7 @@ pub async fn snapshot_create_post(
     
     // Check access to instance
     if !enforce_instance_access(&state, current_username_from_jar(&state, &jar).as_deref(), &form.instance_id).await {
-        return Redirect::to("/instances").into_response();
+        return forbidden(&state, &jar);
     }
     
     let resp = create_snapshot(
@@ -140,12 +145,11 @@ pub async fn snapshot_create_post(
         if resp.get("code").and_then(|c| c.as_str()) == Some("OKAY") {
             entry.push("Snapshot creation initiated successfully.".into());
         } else {
-            let detail = resp.get("detail").and_then(|d| d.as_str()).unwrap_or("Unknown error");
-            entry.push(format!("Snapshot creation failed: {}", detail));
+            entry.push(api_failure_message(&resp, "Snapshot creation failed"));
         }
     }
     
-    Redirect::to("/snapshots").into_response()
+    redirect_from_state(&state, "/snapshots").into_response()
 }
 
 pub async fn snapshot_delete_post(
@@ -170,15 +174,14 @@ pub async fn snapshot_delete_post(
         let entry = flashes.entry(sid.value().to_string()).or_default();
         if resp.get("code").and_then(|c| c.as_str()) == Some("OKAY") {
             entry.push("Snapshot deleted successfully.".into());
-            return Redirect::to("/snapshots").into_response();
+            return redirect_from_state(&state, "/snapshots").into_response();
         } else {
-            let detail = resp.get("detail").and_then(|d| d.as_str()).unwrap_or("Unknown error");
-            entry.push(format!("Snapshot deletion failed: {}", detail));
-            return Redirect::to(&format!("/snapshots/{}", snapshot_id)).into_response();
+            entry.push(api_failure_message(&resp, "Snapshot deletion failed"));
+            return redirect_from_state(&state, &format!("/snapshots/{}", snapshot_id)).into_response();
         }
     }
     
-    Redirect::to("/snapshots").into_response()
+    redirect_from_state(&state, "/snapshots").into_response()
 }
 
 pub async fn snapshot_restore_post(
@@ -204,10 +207,9 @@ pub async fn snapshot_restore_post(
         if resp.get("code").and_then(|c| c.as_str()) == Some("OKAY") {
             entry.push("Snapshot restore initiated successfully.".into());
         } else {
-            let detail = resp.get("detail").and_then(|d| d.as_str()).unwrap_or("Unknown error");
-            entry.push(format!("Snapshot restore failed: {}", detail));
+            entry.push(api_failure_message(&resp, "Snapshot restore failed"));
         }
     }
     
-    Redirect::to(&format!("/snapshots/{}", snapshot_id)).into_response()
+    redirect_from_state(&state, &format!("/snapshots/{}", snapshot_id)).into_response()
 }
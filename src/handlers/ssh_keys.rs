@@ -1,6 +1,7 @@
 use axum::{
     extract::{State, Form, Query},
-    response::{IntoResponse, Redirect},
+    http::StatusCode,
+    response::IntoResponse,
 };
 use axum_extra::extract::cookie::CookieJar;
 use serde::Deserialize;
@@ -9,10 +10,11 @@ use serde_json::Value;
 use crate::models::AppState;
 use crate::templates::SshKeysTemplate;
 use crate::handlers::helpers::{
+    redirect_from_state,
     build_template_globals, ensure_owner,
     fetch_default_customer_id, render_template, TemplateGlobals,
     detail_requires_customer, api_call_wrapper, load_ssh_keys_paginated_wrapper,
-    plain_html,
+    render_error,
 };
 
 #[derive(Deserialize)]
@@ -55,13 +57,15 @@ pub async fn ssh_keys_get(
     };
     let paginated = load_ssh_keys_paginated_wrapper(&state, customer_id.clone(), q.page, q.per_page).await;
     
-    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages } = build_template_globals(&state, &jar);
+    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages, maintenance_mode, api_status } = build_template_globals(&state, &jar);
     render_template(&state, &jar, SshKeysTemplate {
             current_user,
             api_hostname,
             base_url,
             flash_messages,
             has_flash_messages,
+            maintenance_mode,
+            api_status,
             ssh_keys: &paginated.ssh_keys,
             customer_id,
             current_page: paginated.current_page,
@@ -84,7 +88,7 @@ pub async fn ssh_keys_post(
     if action == "delete" {
         let key_id_raw = form.ssh_key_id.clone().unwrap_or_default();
         if !key_id_raw.chars().all(|c| c.is_ascii_digit()) {
-            return plain_html("Invalid key id");
+            return render_error(&state, &jar, StatusCode::BAD_REQUEST, "Invalid key id");
         }
         let endpoint = format!("/v1/ssh-keys/{}", key_id_raw);
         let payload = api_call_wrapper(&state, "DELETE", &endpoint, None, None).await;
@@ -104,7 +108,7 @@ pub async fn ssh_keys_post(
                 }
             }
         }
-        return Redirect::to("/ssh-keys").into_response();
+        return redirect_from_state(&state, "/ssh-keys").into_response();
     }
     let name = form.name.clone().unwrap_or_default().trim().to_string();
     let public_key = form
@@ -114,7 +118,7 @@ pub async fn ssh_keys_post(
         .trim()
         .to_string();
     if name.is_empty() || public_key.is_empty() {
-        return plain_html("Provide name and public key");
+        return render_error(&state, &jar, StatusCode::BAD_REQUEST, "Provide name and public key");
     }
     let mut body = serde_json::json!({"name": name, "publicKey": public_key});
     let payload = api_call_wrapper(&state, "POST", "/v1/ssh-keys", Some(body.clone()), None).await;
@@ -128,7 +132,7 @@ pub async fn ssh_keys_post(
             }
         }
     }
-    Redirect::to("/ssh-keys").into_response()
+    redirect_from_state(&state, "/ssh-keys").into_response()
 }
 
 pub async fn ssh_key_detail_get(
@@ -148,6 +152,8 @@ pub async fn ssh_key_detail_get(
         base_url,
         flash_messages,
         has_flash_messages,
+        maintenance_mode,
+        api_status,
     } = build_template_globals(&state, &jar);
     
     render_template(
@@ -159,6 +165,8 @@ pub async fn ssh_key_detail_get(
             base_url,
             flash_messages,
             has_flash_messages,
+            maintenance_mode,
+            api_status,
             ssh_key,
             key_id,
         },
@@ -1,12 +1,14 @@
 use axum::{
     extract::{Form, Path, State},
-    response::{IntoResponse, Redirect},
+    http::StatusCode,
+    response::IntoResponse,
 };
 use axum_extra::extract::cookie::CookieJar;
 
 use crate::models::{AppState, ConfirmationAction};
 use crate::templates::{AboutTemplate, ConfirmationTemplate, ComingSoonTemplate, PermissionsTemplate};
-use super::helpers::{build_template_globals, render_template, TemplateGlobals};
+use crate::services::instance_service::confirmation_hostname;
+use super::helpers::{redirect_from_state, build_template_globals, render_error, render_template, TemplateGlobals};
 
 pub async fn permissions_get(
     State(state): State<AppState>,
@@ -18,6 +20,8 @@ pub async fn permissions_get(
         base_url,
         flash_messages,
         has_flash_messages,
+        maintenance_mode,
+        api_status,
     } = build_template_globals(&state, &jar);
     render_template(
         &state,
@@ -28,6 +32,8 @@ pub async fn permissions_get(
             base_url,
             flash_messages,
             has_flash_messages,
+            maintenance_mode,
+            api_status,
         ),
     )
 }
@@ -51,6 +57,8 @@ pub async fn coming_soon(
         base_url,
         flash_messages,
         has_flash_messages,
+        maintenance_mode,
+        api_status,
     } = build_template_globals(&state, &jar);
 
     render_template(&state, &jar, ComingSoonTemplate {
@@ -59,10 +67,18 @@ pub async fn coming_soon(
         base_url,
         flash_messages,
         has_flash_messages,
+        maintenance_mode,
+        api_status,
         feature_name,
     })
 }
 
+/// `GET /version`: the running version, git commit and build date, plus
+/// whether a newer release exists (checked against GitHub every few hours).
+pub async fn version_get(State(state): State<AppState>) -> impl IntoResponse {
+    axum::Json(crate::services::version_service::version_report(&state.update_check).await)
+}
+
 pub async fn about_get(
     State(state): State<AppState>,
     jar: CookieJar,
@@ -73,6 +89,8 @@ pub async fn about_get(
         base_url,
         flash_messages,
         has_flash_messages,
+        maintenance_mode,
+        api_status,
     } = build_template_globals(&state, &jar);
 
     // We don't check for update on every GET to avoid rate limiting
@@ -82,6 +100,8 @@ pub async fn about_get(
         base_url,
         flash_messages,
         has_flash_messages,
+        maintenance_mode,
+        api_status,
         version: env!("CARGO_PKG_VERSION"),
         latest_version: None,
         all_releases: vec![],
@@ -118,6 +138,8 @@ pub async fn about_check_update(
         base_url,
         flash_messages,
         has_flash_messages,
+        maintenance_mode,
+        api_status,
     } = build_template_globals(&state, &jar);
 
     render_template(&state, &jar, AboutTemplate {
@@ -126,6 +148,8 @@ pub async fn about_check_update(
         base_url,
         flash_messages,
         has_flash_messages,
+        maintenance_mode,
+        api_status,
         version: env!("CARGO_PKG_VERSION"),
         latest_version: latest,
         all_releases,
@@ -138,7 +162,7 @@ pub struct SwitchVersionForm {
 }
 
 pub async fn about_switch_version(
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
     _jar: CookieJar,
     Form(form): Form<SwitchVersionForm>,
 ) -> impl IntoResponse {
@@ -150,7 +174,7 @@ pub async fn about_switch_version(
     // In a real implementation, this would trigger the background update process
     // and potentially restart the server.
     
-    axum::response::Redirect::to("/about")
+    redirect_from_state(&state, "/about")
 }
 
 pub async fn confirmation_get(
@@ -160,7 +184,7 @@ pub async fn confirmation_get(
 ) -> impl IntoResponse {
     let action = match ConfirmationAction::from_str(&action_str) {
         Some(a) => a,
-        None => return Redirect::to("/").into_response(),
+        None => return redirect_from_state(&state, "/").into_response(),
     };
 
     let TemplateGlobals {
@@ -169,6 +193,8 @@ pub async fn confirmation_get(
         base_url,
         flash_messages,
         has_flash_messages,
+        maintenance_mode,
+        api_status,
     } = build_template_globals(&state, &jar);
 
     let mut title = "Confirm Action".to_string();
@@ -178,6 +204,7 @@ pub async fn confirmation_get(
     let mut cancel_url = "/".to_string();
     let mut button_class = "btn-primary".to_string();
     let mut hidden_fields = vec![];
+    let mut confirm_hostname = None;
 
     match action {
         ConfirmationAction::DeleteUser => {
@@ -191,6 +218,14 @@ pub async fn confirmation_get(
         ConfirmationAction::DeleteInstance => {
             title = "Delete Instance".into();
             message = format!("Are you sure you want to permanently delete instance '{}'?", id);
+            if state.delete_require_hostname {
+                let Some(hostname) = confirmation_hostname(&state, &id).await else {
+                    let message = format!("Could not load instance {} to confirm its hostname; try again later.", id);
+                    return render_error(&state, &jar, StatusCode::BAD_GATEWAY, message);
+                };
+                message = format!("This permanently deletes instance '{}' ({}). Type its hostname to confirm.", hostname, id);
+                confirm_hostname = Some(hostname);
+            }
             target_url = format!("{}/instance/{}/delete", base_url, id);
             confirm_label = "Delete Instance".into();
             cancel_url = format!("{}/instance/{}", base_url, id);
@@ -281,6 +316,8 @@ pub async fn confirmation_get(
         base_url,
         flash_messages,
         has_flash_messages,
+        maintenance_mode,
+        api_status,
         title,
         message,
         target_url,
@@ -288,5 +325,6 @@ pub async fn confirmation_get(
         cancel_url,
         button_class,
         hidden_fields,
+        confirm_hostname,
     })
 }
@@ -1,37 +1,23 @@
 use axum::{
     extract::{Form, Path, State},
+    http::StatusCode,
     response::IntoResponse,
 };
 use axum_extra::extract::cookie::CookieJar;
 use serde::Deserialize;
 
-use crate::models::{AppState, UserRecord, UserRow};
-use crate::services::{generate_password_hash, persist_users_file};
+use crate::models::{AppState, UserRecord, UserView};
+use crate::services::{generate_password_hash, save_users, unsaved_message};
 use crate::templates::{UsersTemplate, UserDetailTemplate};
 
-use super::helpers::{build_template_globals, ensure_owner, plain_html, TemplateGlobals, render_template};
+use super::helpers::{redirect_from_state, build_template_globals, ensure_owner, render_error, push_flash, TemplateGlobals, render_template, revoke_user_sessions, active_session_count};
 
 pub async fn users_list(State(state): State<AppState>, jar: CookieJar) -> impl IntoResponse {
     if let Some(r) = ensure_owner(&state, &jar) {
         return r.into_response();
     }
     let users = state.users.lock().unwrap();
-    let mut rows: Vec<UserRow> = users
-        .iter()
-        .map(|(k, v)| {
-            let assigned = if v.assigned_instances.is_empty() {
-                String::new()
-            } else {
-                v.assigned_instances.join(", ")
-            };
-            UserRow {
-                username: k.clone(),
-                role: v.role.clone(),
-                assigned,
-                about: v.about.clone(),
-            }
-        })
-        .collect();
+    let mut rows: Vec<UserView> = users.iter().map(|(k, v)| UserView::from_record(k, v)).collect();
     rows.sort_by(|a, b| a.username.cmp(&b.username));
     drop(users);
     let TemplateGlobals {
@@ -40,6 +26,8 @@ pub async fn users_list(State(state): State<AppState>, jar: CookieJar) -> impl I
         base_url,
         flash_messages,
         has_flash_messages,
+        maintenance_mode,
+        api_status,
     } = build_template_globals(&state, &jar);
     render_template(&state, &jar, UsersTemplate {
             current_user,
@@ -47,6 +35,8 @@ pub async fn users_list(State(state): State<AppState>, jar: CookieJar) -> impl I
             base_url,
             flash_messages,
             has_flash_messages,
+            maintenance_mode,
+            api_status,
             rows: &rows,
         }
     )
@@ -62,20 +52,11 @@ pub async fn user_detail(
     }
     let uname = username.to_lowercase();
     let users = state.users.lock().unwrap();
-    let user_row = if let Some(rec) = users.get(&uname) {
-        let assigned = if rec.assigned_instances.is_empty() {
-            String::new()
-        } else {
-            rec.assigned_instances.join(", ")
-        };
-        UserRow {
-            username: uname.clone(),
-            role: rec.role.clone(),
-            assigned,
-            about: rec.about.clone(),
-        }
+    let user_view = if let Some(rec) = users.get(&uname) {
+        UserView::from_record(&uname, rec)
     } else {
-        return plain_html("User not found");
+        drop(users);
+        return render_error(&state, &jar, StatusCode::NOT_FOUND, "User not found");
     };
     drop(users);
 
@@ -85,6 +66,8 @@ pub async fn user_detail(
         base_url,
         flash_messages,
         has_flash_messages,
+        maintenance_mode,
+        api_status,
     } = build_template_globals(&state, &jar);
 
     render_template(&state, &jar, UserDetailTemplate {
@@ -93,7 +76,10 @@ pub async fn user_detail(
         base_url,
         flash_messages,
         has_flash_messages,
-        user: user_row,
+        maintenance_mode,
+        api_status,
+        active_sessions: active_session_count(&state, &uname),
+        user: user_view,
     })
 }
 
@@ -116,17 +102,18 @@ pub async fn users_create(
     }
     let uname = form.username.trim().to_lowercase();
     if uname.is_empty() || form.password.is_empty() {
-        return plain_html("Missing username/password");
+        return render_error(&state, &jar, StatusCode::BAD_REQUEST, "Missing username/password");
     }
     if !UserRecord::is_valid_role(&form.role) {
-        return plain_html("Invalid role. Must be one of: owner, admin, viewer");
+        return render_error(&state, &jar, StatusCode::BAD_REQUEST, "Invalid role. Must be one of: owner, admin, viewer");
     }
     {
         let mut users = state.users.lock().unwrap();
         if users.contains_key(&uname) {
-            return plain_html("Username exists");
+            drop(users);
+            return render_error(&state, &jar, StatusCode::CONFLICT, "Username exists");
         }
-        let hash = generate_password_hash(&form.password);
+        let hash = generate_password_hash(&form.password, state.password_scheme);
         users.insert(
             uname.clone(),
             UserRecord {
@@ -134,17 +121,15 @@ pub async fn users_create(
                 role: form.role.clone(),
                 assigned_instances: vec![],
                 about: form.about.unwrap_or_default(),
+                last_login: None,
             },
         );
     }
-    match persist_users_file(&state.users).await {
-        Ok(_) => (),
-        Err(e) => {
-            tracing::error!(%e, "Failed to persist users");
-            return plain_html("Failed to persist users");
-        }
+    if let Err(e) = save_users(&state).await {
+        tracing::error!(%e, "Failed to persist users");
+        push_flash(&state, &jar, unsaved_message("users", &e));
     }
-    axum::response::Redirect::to("/users").into_response()
+    redirect_from_state(&state, "/users").into_response()
 }
 
 #[derive(Deserialize)]
@@ -162,25 +147,47 @@ pub async fn reset_password(
         return r.into_response();
     }
     if form.new_password.trim().is_empty() {
-        return plain_html("Password cannot be empty");
+        return render_error(&state, &jar, StatusCode::BAD_REQUEST, "Password cannot be empty");
     }
     let uname = username.to_lowercase();
     {
         let mut users = state.users.lock().unwrap();
         if let Some(rec) = users.get_mut(&uname) {
-            rec.password = generate_password_hash(&form.new_password);
+            rec.password = generate_password_hash(&form.new_password, state.password_scheme);
         } else {
-            return plain_html("User not found");
+            drop(users);
+            return render_error(&state, &jar, StatusCode::NOT_FOUND, "User not found");
         }
     }
-    match persist_users_file(&state.users).await {
-        Ok(_) => (),
-        Err(e) => {
-            tracing::error!(%e, "Failed to persist users");
-            return plain_html("Failed to persist users");
-        }
+    // Sessions opened with the old password must not outlive it
+    let revoked = revoke_user_sessions(&state, &jar, &uname);
+    if revoked > 0 {
+        push_flash(&state, &jar, format!("Password reset; signed {} out of {} session(s).", uname, revoked));
+    }
+    if let Err(e) = save_users(&state).await {
+        tracing::error!(%e, "Failed to persist users");
+        push_flash(&state, &jar, unsaved_message("users", &e));
+    }
+    redirect_from_state(&state, &format!("/users/{}", uname)).into_response()
+}
+
+/// POST /users/:username/revoke-sessions — sign the user out of every session
+/// (the owner's own current session is kept).
+pub async fn revoke_sessions(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(username): Path<String>,
+) -> impl IntoResponse {
+    if let Some(r) = ensure_owner(&state, &jar) {
+        return r.into_response();
+    }
+    let uname = username.to_lowercase();
+    if !state.users.lock().unwrap().contains_key(&uname) {
+        return render_error(&state, &jar, StatusCode::NOT_FOUND, "User not found");
     }
-    axum::response::Redirect::to(&format!("/users/{}", uname)).into_response()
+    let revoked = revoke_user_sessions(&state, &jar, &uname);
+    push_flash(&state, &jar, format!("Signed {} out of {} session(s).", uname, revoked));
+    redirect_from_state(&state, &format!("/users/{}", uname)).into_response()
 }
 
 #[derive(Deserialize)]
@@ -199,13 +206,16 @@ pub async fn update_role(
     }
     let uname = username.to_lowercase();
     if !UserRecord::is_valid_role(&form.role) {
-        return plain_html("Invalid role. Must be one of: owner, admin, viewer");
+        return render_error(&state, &jar, StatusCode::BAD_REQUEST, "Invalid role. Must be one of: owner, admin, viewer");
     }
     {
         let mut users = state.users.lock().unwrap();
         let current_role = match users.get(&uname) {
             Some(r) => r.role.clone(),
-            None => return plain_html("User not found"),
+            None => {
+                drop(users);
+                return render_error(&state, &jar, StatusCode::NOT_FOUND, "User not found");
+            }
         };
         if current_role == "owner" && form.role != "owner" {
             let remaining_owners = users
@@ -213,21 +223,19 @@ pub async fn update_role(
                 .filter(|(n, r)| r.role == "owner" && n.as_str() != uname)
                 .count();
             if remaining_owners == 0 {
-                return plain_html("At least one owner required");
+                drop(users);
+                return render_error(&state, &jar, StatusCode::CONFLICT, "At least one owner required");
             }
         }
         if let Some(rec) = users.get_mut(&uname) {
             rec.role = form.role.clone();
         }
     }
-    match persist_users_file(&state.users).await {
-        Ok(_) => (),
-        Err(e) => {
-            tracing::error!(%e, "Failed to persist users");
-            return plain_html("Failed to persist users");
-        }
+    if let Err(e) = save_users(&state).await {
+        tracing::error!(%e, "Failed to persist users");
+        push_flash(&state, &jar, unsaved_message("users", &e));
     }
-    axum::response::Redirect::to(&format!("/users/{}", uname)).into_response()
+    redirect_from_state(&state, &format!("/users/{}", uname)).into_response()
 }
 
 #[derive(Deserialize)]
@@ -250,17 +258,15 @@ pub async fn update_about(
         if let Some(rec) = users.get_mut(&uname) {
             rec.about = form.about;
         } else {
-            return plain_html("User not found");
+            drop(users);
+            return render_error(&state, &jar, StatusCode::NOT_FOUND, "User not found");
         }
     }
-    match persist_users_file(&state.users).await {
-        Ok(_) => (),
-        Err(e) => {
-            tracing::error!(%e, "Failed to persist users");
-            return plain_html("Failed to persist users");
-        }
+    if let Err(e) = save_users(&state).await {
+        tracing::error!(%e, "Failed to persist users");
+        push_flash(&state, &jar, unsaved_message("users", &e));
     }
-    axum::response::Redirect::to(&format!("/users/{}", uname)).into_response()
+    redirect_from_state(&state, &format!("/users/{}", uname)).into_response()
 }
 
 pub async fn delete_user(
@@ -273,11 +279,11 @@ pub async fn delete_user(
     }
     let current = super::helpers::current_username_from_jar(&state, &jar).unwrap_or_default();
     let uname = username.to_lowercase();
+    if uname == current {
+        return render_error(&state, &jar, StatusCode::FORBIDDEN, "Cannot delete own account");
+    }
     {
         let mut users = state.users.lock().unwrap();
-        if uname == current {
-            return plain_html("Cannot delete own account");
-        }
         if let Some(rec) = users.get(&uname) {
             if rec.role == "owner" {
                 let owners = users
@@ -285,20 +291,20 @@ pub async fn delete_user(
                     .filter(|(name, r)| r.role == "owner" && name.as_str() != uname)
                     .count();
                 if owners == 0 {
-                    return plain_html("At least one owner required");
+                    drop(users);
+                    return render_error(&state, &jar, StatusCode::CONFLICT, "At least one owner required");
                 }
             }
         }
         if users.remove(&uname).is_none() {
-            return plain_html("User not found");
+            drop(users);
+            return render_error(&state, &jar, StatusCode::NOT_FOUND, "User not found");
         }
     }
-    match persist_users_file(&state.users).await {
-        Ok(_) => (),
-        Err(e) => {
-            tracing::error!(%e, "Failed to persist users");
-            return plain_html("Failed to persist users");
-        }
+    revoke_user_sessions(&state, &jar, &uname);
+    if let Err(e) = save_users(&state).await {
+        tracing::error!(%e, "Failed to persist users");
+        push_flash(&state, &jar, unsaved_message("users", &e));
     }
-    axum::response::Redirect::to("/users").into_response()
+    redirect_from_state(&state, "/users").into_response()
 }
@@ -1,24 +1,27 @@
 use axum::{
     extract::State,
-    response::{IntoResponse, Redirect},
+    response::{IntoResponse, Response},
 };
 use axum_extra::extract::cookie::CookieJar;
 use serde_json::Value;
 use std::collections::{HashMap, HashSet};
+use std::time::Instant;
 
 use crate::models::{
     AppState, Step1FormData, Step2FormData,
-    CustomPlanFormValues, Region, ProductView, ProductEntry, OsItem,
-    SshKeyDisplay, Extras, PlanState,
+    CustomPlanFormValues, Region, ProductView, ProductEntry, OsItem, group_os_by_family, pick_default_os,
+    SshKeyDisplay, Extras, PlanState, BaseState, WorkspaceRecord,
 };
-use crate::services::{parse_wizard_base, build_base_query_pairs};
-use crate::utils::{build_query_string, parse_urlencoded_body};
-use crate::api::{load_regions, load_products, load_os_list, load_applications};
+use crate::services::{parse_wizard_base, build_base_query_pairs, pick_default_region, order_regions_by_distance, merge_required_ssh_keys, missing_required_ssh_keys, save_workspaces, unsaved_message, now_iso8601, INSTANCE_CLASSES};
+use crate::utils::{build_query_string, parse_urlencoded_body, validate_hostnames};
+use crate::api::{idempotency, parse_created_instance_ids, parse_host_results, load_regions, load_products, load_os_list, load_applications, load_floating_ip_quota, FloatingIpQuota};
 use crate::templates::*;
 use crate::handlers::helpers::{
+    redirect_from_state,
     build_template_globals, absolute_url_from_state,
     ensure_admin_or_owner, TemplateGlobals, OneOrMany, render_template,
-    api_call_wrapper, fetch_default_customer_id, load_ssh_keys_api,
+    api_call_wrapper, fetch_default_customer_id, load_ssh_keys_api, current_username_from_jar, push_flash,
+    prices_visible, workspace_for_create, render_error,
 };
 
 fn value_to_short_string(value: &Value) -> String {
@@ -72,23 +75,46 @@ pub async fn create_step_1(
     let (all_regions, _lookup) = load_regions_wrapper(&state).await;
     // Filter to only show active, non-hidden regions
     let regions: Vec<Region> = all_regions.into_iter()
-        .filter(|r| r.is_active && !r.is_hidden)
+        .filter(Region::is_available)
         .collect();
+    let region_options = order_regions_by_distance(&regions, zy::config::get_home_location());
+    // A workspace the user can't create into is ignored rather than refused
+    let workspace = base.workspace.as_deref().and_then(|slug| {
+        let username = current_username_from_jar(&state, &jar)?;
+        workspace_for_create(&state, &username, slug)
+    });
+    let workspace_default = |value: fn(&WorkspaceRecord) -> &String| {
+        workspace.as_ref().map(value).filter(|v| !v.is_empty()).cloned()
+    };
     let mut region_sel = base.region.clone();
-    if region_sel.is_empty() && !regions.is_empty() {
-        region_sel = regions[0].id.clone();
+    if region_sel.is_empty() {
+        let ordered: Vec<&Region> = region_options.iter().map(|(r, _)| *r).collect();
+        let configured = workspace_default(|w| &w.default_region).or_else(zy::config::get_default_region);
+        region_sel = pick_default_region(&ordered, configured.as_deref());
     }
+    // Workspace, then organisation defaults only apply when the query did not choose a value
+    let query_has = |key: &str| q.get(key).is_some_and(|v| !v.trim().is_empty());
+    let instance_class = match zy::config::get_default_instance_class() {
+        Some(class) if !query_has("instance_class") && INSTANCE_CLASSES.contains(&class.as_str()) => class,
+        _ => base.instance_class.clone(),
+    };
+    let plan_type = match workspace_default(|w| &w.default_plan_type).or_else(zy::config::get_default_plan_type) {
+        Some(plan) if !query_has("plan_type") => plan,
+        _ => base.plan_type.clone(),
+    };
     let TemplateGlobals {
         current_user,
         api_hostname,
         base_url,
         flash_messages,
         has_flash_messages,
+        maintenance_mode,
+        api_status,
     } = build_template_globals(&state, &jar);
     let form_data = Step1FormData {
         region: region_sel,
-        instance_class: base.instance_class.clone(),
-        plan_type: base.plan_type.clone(),
+        instance_class,
+        plan_type,
     };
     render_template(&state, &jar, Step1Template {
             current_user,
@@ -96,8 +122,11 @@ pub async fn create_step_1(
             base_url,
             flash_messages,
             has_flash_messages,
-            regions: &regions,
+            maintenance_mode,
+            api_status,
+            regions: region_options,
             form_data,
+            workspace,
         },
     )
 }
@@ -114,7 +143,7 @@ pub async fn create_step_2(
     }
     let mut base = parse_wizard_base(&q);
     if base.region.is_empty() {
-        return Redirect::to("/create/step-1").into_response();
+        return redirect_from_state(&state, "/create/step-1").into_response();
     }
     // If hostnames passed as comma separated in textarea update parsing
     if let Some(raw_hosts) = q.get("hostnames") {
@@ -124,12 +153,17 @@ pub async fn create_step_2(
             .filter(|s| !s.is_empty())
             .collect();
     }
-    let back_pairs = build_base_query_pairs(&base);
+    render_step_2(&state, &jar, &base, Vec::new())
+}
+
+/// Render step 2 for `base`, showing `hostname_errors` under the hostnames field.
+fn render_step_2(state: &AppState, jar: &CookieJar, base: &BaseState, hostname_errors: Vec<String>) -> Response {
+    let back_pairs = build_base_query_pairs(base);
     let back_q = build_query_string(&back_pairs);
     let back_url = if back_q.is_empty() {
-        absolute_url_from_state(&state, "/create/step-1")
+        absolute_url_from_state(state, "/create/step-1")
     } else {
-        absolute_url_from_state(&state, &format!("/create/step-1?{}", back_q))
+        absolute_url_from_state(state, &format!("/create/step-1?{}", back_q))
     };
     let hostnames_text = base.hostnames.join(", ");
     let TemplateGlobals {
@@ -138,25 +172,31 @@ pub async fn create_step_2(
         base_url,
         flash_messages,
         has_flash_messages,
-    } = build_template_globals(&state, &jar);
+        maintenance_mode,
+        api_status,
+    } = build_template_globals(state, jar);
     let form_data = Step2FormData {
         hostnames_text,
         assign_ipv4: base.assign_ipv4,
         assign_ipv6: base.assign_ipv6,
         floating_ip_count: base.floating_ip_count.to_string(),
     };
-    render_template(&state, &jar, Step2Template {
+    render_template(state, jar, Step2Template {
             current_user,
             api_hostname,
             base_url,
             flash_messages,
             has_flash_messages,
-            base_state: &base,
+            maintenance_mode,
+            api_status,
+            base_state: base,
             form_data,
+            hostname_errors,
             back_url,
-            submit_url: absolute_url_from_state(&state, "/create/step-3"),
+            submit_url: absolute_url_from_state(state, "/create/step-3"),
         },
     )
+    .into_response()
 }
 
 // ---------- Wizard Step 3 (Product selection or custom resources) ----------
@@ -169,9 +209,13 @@ pub async fn create_step_3(
     if let Some(r) = ensure_admin_or_owner(&state, &jar) {
         return r.into_response();
     }
-    let base = parse_wizard_base(&q);
-    if base.hostnames.is_empty() || base.region.is_empty() {
-        return Redirect::to("/create/step-1").into_response();
+    let mut base = parse_wizard_base(&q);
+    if base.region.is_empty() {
+        return redirect_from_state(&state, "/create/step-1").into_response();
+    }
+    match validate_hostnames(&base.hostnames) {
+        Ok(names) => base.hostnames = names,
+        Err(errors) => return render_step_2(&state, &jar, &base, errors),
     }
     let back_pairs = build_base_query_pairs(&base);
     let back_q = build_query_string(&back_pairs);
@@ -185,7 +229,10 @@ pub async fn create_step_3(
     let ssh_key_ids_csv = base.ssh_key_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(",");
 
     if base.plan_type == "fixed" {
-        let products = load_products_wrapper(&state, &base.region).await;
+        let mut products = load_products_wrapper(&state, &base.region).await;
+        if !prices_visible(&state, &jar) {
+            products.iter_mut().for_each(|p| p.price_entries.clear());
+        }
         let selected_product_id = q.get("product_id").cloned().unwrap_or_default();
         let TemplateGlobals {
             current_user,
@@ -193,6 +240,8 @@ pub async fn create_step_3(
             base_url,
             flash_messages,
             has_flash_messages,
+            maintenance_mode,
+            api_status,
         } = build_template_globals(&state, &jar);
         // Use the outer variables defined above
         return render_template(&state, &jar, Step3FixedTemplate {
@@ -201,6 +250,8 @@ pub async fn create_step_3(
                 base_url,
                 flash_messages,
                 has_flash_messages,
+                maintenance_mode,
+                api_status,
                 base_state: &base,
                 products: &products,
                 has_products: !products.is_empty(),
@@ -230,6 +281,8 @@ pub async fn create_step_3(
         base_url,
         flash_messages,
         has_flash_messages,
+        maintenance_mode,
+        api_status,
     } = build_template_globals(&state, &jar);
     let form_values = CustomPlanFormValues {
         cpu,
@@ -243,6 +296,8 @@ pub async fn create_step_3(
             base_url,
             flash_messages,
             has_flash_messages,
+            maintenance_mode,
+            api_status,
             base_state: &base,
             region_name: base.region.clone(),
             floating_ip_count: base.floating_ip_count.to_string(),
@@ -253,7 +308,7 @@ pub async fn create_step_3(
             minimum_disk: 1,
             form_values,
             ssh_key_ids_csv: ssh_key_ids_csv.clone(),
-            hostnames_csv: hostnames_csv,
+            hostnames_csv,
         },
     )
 }
@@ -270,7 +325,7 @@ pub async fn create_step_4(
     }
     let base = parse_wizard_base(&q);
     if base.hostnames.is_empty() || base.region.is_empty() {
-        return Redirect::to("/create/step-1").into_response();
+        return redirect_from_state(&state, "/create/step-1").into_response();
     }
     let ssh_key_ids_csv = base.ssh_key_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(",");
     let hostnames_csv = base.hostnames.join(",");
@@ -289,11 +344,11 @@ pub async fn create_step_4(
         } else {
             format!("/create/step-5?{}", next_q)
         };
-        return Redirect::to(&next_url).into_response();
+        return redirect_from_state(&state, &next_url).into_response();
     }
     let product_id = q.get("product_id").cloned().unwrap_or_default();
     if product_id.is_empty() {
-        return Redirect::to("/create/step-3").into_response();
+        return redirect_from_state(&state, "/create/step-3").into_response();
     }
     let TemplateGlobals {
         current_user,
@@ -301,6 +356,8 @@ pub async fn create_step_4(
         base_url,
         flash_messages,
         has_flash_messages,
+        maintenance_mode,
+        api_status,
     } = build_template_globals(&state, &jar);
     let extras = Extras {
         extra_disk: q.get("extra_disk").cloned().unwrap_or_else(|| "0".into()),
@@ -315,11 +372,13 @@ pub async fn create_step_4(
             base_url,
             flash_messages,
             has_flash_messages,
+            maintenance_mode,
+            api_status,
             base_state: &base,
             floating_ip_count: base.floating_ip_count.to_string(),
             product_id,
-            ssh_key_ids_csv: ssh_key_ids_csv,
-            hostnames_csv: hostnames_csv,
+            ssh_key_ids_csv,
+            hostnames_csv,
             extras,
             back_url,
             submit_url: absolute_url_from_state(&state, "/create/step-5"),
@@ -339,7 +398,7 @@ pub async fn create_step_5(
     }
     let base = parse_wizard_base(&q);
     if base.hostnames.is_empty() || base.region.is_empty() {
-        return Redirect::to("/create/step-1").into_response();
+        return redirect_from_state(&state, "/create/step-1").into_response();
     }
     let TemplateGlobals {
         current_user,
@@ -347,10 +406,12 @@ pub async fn create_step_5(
         base_url,
         flash_messages,
         has_flash_messages,
+        maintenance_mode,
+        api_status,
     } = build_template_globals(&state, &jar);
     let product_id = q.get("product_id").cloned().unwrap_or_default();
     if base.plan_type == "fixed" && product_id.is_empty() {
-        return Redirect::to("/create/step-3").into_response();
+        return redirect_from_state(&state, "/create/step-3").into_response();
     }
     let extra_disk = q.get("extra_disk").cloned().unwrap_or_else(|| "0".into());
     let extra_bandwidth = q
@@ -372,14 +433,46 @@ pub async fn create_step_5(
     if selected_os_id.is_empty() {
         selected_os_id = q.get("os_id").cloned().unwrap_or_default();
     }
+    // Preselect an image the chosen plan can actually boot
+    let (plan_ram_mb, plan_arch) = if base.plan_type == "fixed" {
+        load_products_wrapper(&state, &base.region)
+            .await
+            .into_iter()
+            .find(|p| p.id == product_id)
+            .map(|p| {
+                let spec = &p.plan.specification;
+                let ram_mb = if spec.ram_in_mb > 0.0 { spec.ram_in_mb } else { spec.ram * 1024.0 };
+                (ram_mb as i32, p.plan.arch)
+            })
+            .unwrap_or((0, None))
+    } else {
+        let ram_gb: f64 = custom_plan.ram_in_gb.trim().parse().unwrap_or(0.0);
+        ((ram_gb * 1024.0) as i32, None)
+    };
     if selected_os_id.is_empty() {
-        selected_os_id = os_list
-            .iter()
-            .find(|o| o.is_default)
+        selected_os_id = pick_default_os(&os_list, plan_ram_mb, plan_arch.as_deref())
             .map(|o| o.id.clone())
-            .or_else(|| os_list.first().map(|o| o.id.clone()))
             .unwrap_or_default();
     }
+    // Group by family; an `os_family` filter narrows the list and moves the
+    // selection to that family's default image.
+    let mut os_groups = group_os_by_family(&os_list);
+    let os_family = q
+        .get("os_family")
+        .map(|f| f.trim().to_lowercase())
+        .filter(|f| os_groups.iter().any(|g| &g.family == f))
+        .unwrap_or_default();
+    if !os_family.is_empty() {
+        os_groups.retain(|g| g.family == os_family);
+        if let Some(group) = os_groups.first() {
+            if !group.contains(&selected_os_id) {
+                selected_os_id = group
+                    .default_item_for(plan_ram_mb, plan_arch.as_deref())
+                    .map(|o| o.id.clone())
+                    .unwrap_or_default();
+            }
+        }
+    }
     let selected_app_id = base.app_id.clone().or_else(|| q.get("app_id").cloned()).unwrap_or_default();
     let mut back_pairs = build_base_query_pairs(&base);
     let back_target = if base.plan_type == "fixed" {
@@ -402,6 +495,22 @@ pub async fn create_step_5(
     } else {
         absolute_url_from_state(&state, &format!("{}?{}", back_target, back_q))
     };
+    // Step 5 takes the same parameters as the previous step, plus the family filter
+    let family_url = |family: &str| {
+        let mut pairs = back_pairs.clone();
+        if !family.is_empty() {
+            pairs.push(("os_family".into(), family.to_string()));
+        }
+        absolute_url_from_state(&state, &format!("/create/step-5?{}", build_query_string(&pairs)))
+    };
+    let mut family_filters = vec![OsFamilyFilter { label: "All".into(), url: family_url(""), active: os_family.is_empty() }];
+    for group in group_os_by_family(&os_list) {
+        family_filters.push(OsFamilyFilter {
+            url: family_url(&group.family),
+            active: group.family == os_family,
+            label: group.label,
+        });
+    }
     let hostnames_csv = base.hostnames.join(",");
     let ssh_key_ids_csv = base.ssh_key_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(",");
     render_template(&state, &jar, Step5Template {
@@ -410,10 +519,14 @@ pub async fn create_step_5(
             base_url,
             flash_messages,
             has_flash_messages,
+            maintenance_mode,
+            api_status,
             base_state: &base,
-            os_list: &os_list,
+            os_groups,
+            family_filters,
             selected_os_id,
             applications: &applications,
+            show_prices: prices_visible(&state, &jar),
             selected_app_id,
             product_id,
             extra_disk,
@@ -422,8 +535,8 @@ pub async fn create_step_5(
             floating_ip_count: base.floating_ip_count.to_string(),
             back_url,
             submit_url: absolute_url_from_state(&state, "/create/step-6"),
-            hostnames_csv: hostnames_csv,
-            ssh_key_ids_csv: ssh_key_ids_csv,
+            hostnames_csv,
+            ssh_key_ids_csv,
         },
     )
 }
@@ -440,10 +553,10 @@ pub async fn create_step_6(
     }
     let base = parse_wizard_base(&q);
     if base.hostnames.is_empty() || base.region.is_empty() {
-        return Redirect::to("/create/step-1").into_response();
+        return redirect_from_state(&state, "/create/step-1").into_response();
     }
     if base.os_id.is_empty() {
-        return Redirect::to("/create/step-5").into_response();
+        return redirect_from_state(&state, "/create/step-5").into_response();
     }
     let TemplateGlobals {
         current_user,
@@ -451,10 +564,12 @@ pub async fn create_step_6(
         base_url,
         flash_messages,
         has_flash_messages,
+        maintenance_mode,
+        api_status,
     } = build_template_globals(&state, &jar);
     let product_id = q.get("product_id").cloned().unwrap_or_default();
     if base.plan_type == "fixed" && product_id.is_empty() {
-        return Redirect::to("/create/step-3").into_response();
+        return redirect_from_state(&state, "/create/step-3").into_response();
     }
     let extra_disk = q.get("extra_disk").cloned().unwrap_or_else(|| "0".into());
     let extra_bandwidth = q
@@ -498,11 +613,13 @@ pub async fn create_step_6(
     let selectable: Vec<SshKeyDisplay> = ssh_keys
         .into_iter()
         .map(|key| {
-            let is_selected = selected_ids.contains(&key.id);
+            let required = state.required_ssh_key_ids.iter().any(|id| id.to_string() == key.id);
+            let is_selected = required || selected_ids.contains(&key.id);
             SshKeyDisplay {
                 id: key.id,
                 name: key.name,
                 selected: is_selected,
+                required,
             }
         })
         .collect();
@@ -514,6 +631,8 @@ pub async fn create_step_6(
             base_url,
             flash_messages,
             has_flash_messages,
+            maintenance_mode,
+            api_status,
             base_state: &base,
             floating_ip_count: base.floating_ip_count.to_string(),
             ssh_keys: &selectable,
@@ -531,6 +650,20 @@ pub async fn create_step_6(
 
 // ---------- Wizard Step 7 (Review & Create) ----------
 
+/// Explain why `requested` floating IPs cannot be allocated under `quota`,
+/// or `None` when they fit (or the API reports no limit).
+fn floating_ip_limit_message(quota: Option<&FloatingIpQuota>, requested: i32) -> Option<String> {
+    let remaining = quota?.remaining()?;
+    if requested > 0 && requested as u64 > remaining {
+        Some(format!(
+            "Requested {} floating IP(s) but the account can only allocate {} more.",
+            requested, remaining
+        ))
+    } else {
+        None
+    }
+}
+
 async fn create_step_7_core(
     state: AppState,
     jar: CookieJar,
@@ -546,18 +679,26 @@ async fn create_step_7_core(
     } else {
         &query
     };
-    let base = parse_wizard_base(source);
-    if base.hostnames.is_empty() || base.region.is_empty() {
-        return Redirect::to("/create/step-1").into_response();
+    let mut base = parse_wizard_base(source);
+    if base.region.is_empty() {
+        return redirect_from_state(&state, "/create/step-1").into_response();
+    }
+    // Mandated keys can't be dropped by editing the hidden field
+    base.ssh_key_ids = merge_required_ssh_keys(&base.ssh_key_ids, &state.required_ssh_key_ids);
+    // Re-check here too: the hidden fields carrying hostnames can be edited
+    // between step 3 and the final submit.
+    match validate_hostnames(&base.hostnames) {
+        Ok(names) => base.hostnames = names,
+        Err(errors) => return render_step_2(&state, &jar, &base, errors),
     }
     if base.os_id.is_empty() {
-        return Redirect::to("/create/step-5").into_response();
+        return redirect_from_state(&state, "/create/step-5").into_response();
     }
     let mut plan_state = PlanState::default();
     if base.plan_type == "fixed" {
         plan_state.product_id = source.get("product_id").cloned().unwrap_or_default();
         if plan_state.product_id.is_empty() {
-            return Redirect::to("/create/step-3").into_response();
+            return redirect_from_state(&state, "/create/step-3").into_response();
         }
         plan_state.extra_disk = source
             .get("extra_disk")
@@ -579,7 +720,59 @@ async fn create_step_7_core(
             .cloned()
             .unwrap_or_else(|| "1".into());
     }
+    let floating_ip_quota = if base.floating_ip_count > 0 {
+        load_floating_ip_quota(&state.client, &state.api_base_url, &state.api_token).await
+    } else {
+        None
+    };
+    let floating_ip_shortfall = floating_ip_limit_message(floating_ip_quota.as_ref(), base.floating_ip_count);
     if method == axum::http::Method::POST {
+        if let Some(message) = floating_ip_shortfall {
+            let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages, maintenance_mode, api_status } = build_template_globals(&state, &jar);
+            return render_template(&state, &jar, Step8Template {
+                current_user,
+                api_hostname,
+                base_url,
+                flash_messages,
+                has_flash_messages,
+                maintenance_mode,
+                api_status,
+                back_url: absolute_url_from_state(&state, "/create/step-6"),
+                status_label: "Failed".into(),
+                code: None,
+                detail: Some("Floating IP limit exceeded".into()),
+                errors: vec![message],
+                hosts: Vec::new(),
+            });
+        }
+        if !state.required_ssh_key_ids.is_empty() {
+            let customer_id = fetch_default_customer_id(&state).await;
+            let available = load_ssh_keys_api(&state, customer_id).await;
+            let missing = missing_required_ssh_keys(&state.required_ssh_key_ids, &available);
+            if !missing.is_empty() {
+                let ids = missing.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ");
+                tracing::warn!(%ids, "Required SSH keys not found; refusing to create instance");
+                let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages, maintenance_mode, api_status } = build_template_globals(&state, &jar);
+                return render_template(&state, &jar, Step8Template {
+                    current_user,
+                    api_hostname,
+                    base_url,
+                    flash_messages,
+                    has_flash_messages,
+                    maintenance_mode,
+                    api_status,
+                    back_url: absolute_url_from_state(&state, "/create/step-6"),
+                    status_label: "Failed".into(),
+                    code: None,
+                    detail: Some("Required SSH key missing".into()),
+                    errors: vec![format!(
+                        "SSH key(s) {} are required on every new instance (ZY_REQUIRED_SSH_KEY_IDS) but don't exist in this account.",
+                        ids
+                    )],
+                    hosts: Vec::new(),
+                });
+            }
+        }
         let mut payload = serde_json::json!({
             "hostnames": base.hostnames,
             "region": base.region,
@@ -625,31 +818,69 @@ async fn create_step_7_core(
             }
         } else {
             let mut extras = serde_json::Map::new();
-            if let Some(cpu) = plan_state.cpu.trim().parse::<i64>().ok() {
+            if let Ok(cpu) = plan_state.cpu.trim().parse::<i64>() {
                 extras.insert("cpu".into(), Value::from(cpu));
             }
-            if let Some(ram) = plan_state.ram_in_gb.trim().parse::<i64>().ok() {
+            if let Ok(ram) = plan_state.ram_in_gb.trim().parse::<i64>() {
                 extras.insert("ramInGB".into(), Value::from(ram));
             }
-            if let Some(disk) = plan_state.disk_in_gb.trim().parse::<i64>().ok() {
+            if let Ok(disk) = plan_state.disk_in_gb.trim().parse::<i64>() {
                 extras.insert("diskInGB".into(), Value::from(disk));
             }
-            if let Some(bw) = plan_state.bandwidth_in_tb.trim().parse::<i64>().ok() {
+            if let Ok(bw) = plan_state.bandwidth_in_tb.trim().parse::<i64>() {
                 extras.insert("bandwidthInTB".into(), Value::from(bw));
             }
             if !extras.is_empty() {
                 payload["extraResource"] = Value::Object(extras);
             }
         }
-        let resp = api_call_wrapper(&state, "POST", "/v1/instances", Some(payload.clone()), None).await;
+        // A retry of the same request (e.g. after a timeout) reuses the key, so
+        // the API won't create the instances twice.
+        let fingerprint = format!(
+            "{}:{}",
+            current_username_from_jar(&state, &jar).unwrap_or_default(),
+            payload
+        );
+        let idempotency_key = {
+            let mut keys = state.idempotency_keys.lock().unwrap();
+            idempotency::key_for(&mut keys, &fingerprint, Instant::now(), idempotency::KEY_REUSE_WINDOW)
+        };
+        let resp = idempotency::scope(
+            idempotency_key,
+            api_call_wrapper(&state, "POST", "/v1/instances", Some(payload.clone()), None),
+        )
+        .await;
         
         // Debug logging for creation failure
         tracing::info!(?payload, ?resp, "Create Instance Attempt");
 
-        if resp.get("code").and_then(|c| c.as_str()) == Some("OKAY")
-            || resp.get("code").and_then(|c| c.as_str()) == Some("CREATED")
+        // A multi-host request can create some hosts and fail others, whatever the top-level code
+        let hosts = parse_host_results(&resp, &base.hostnames);
+        let any_host_failed = hosts.iter().any(|h| h.failed());
+        if (resp.get("code").and_then(|c| c.as_str()) == Some("OKAY")
+            || resp.get("code").and_then(|c| c.as_str()) == Some("CREATED"))
+            && !any_host_failed
         {
-            return Redirect::to("/instances").into_response();
+            let created = parse_created_instance_ids(&resp);
+            assign_to_workspace(&state, &jar, &base, &created).await;
+            // Hosts the response didn't name (e.g. returned as an FQDN) were most
+            // likely created too; say so rather than inviting a duplicate retry
+            let unknown: Vec<&str> = hosts.iter().filter(|h| !h.succeeded() && !h.failed()).map(|h| h.hostname.as_str()).collect();
+            if !unknown.is_empty() {
+                push_flash(&state, &jar, format!("No per-host result for {}; check the instance list before retrying.", unknown.join(", ")));
+            }
+            return match created.as_slice() {
+                [] => redirect_from_state(&state, "/instances").into_response(),
+                [id] => {
+                    push_flash(&state, &jar, format!("Instance {} created.", id));
+                    redirect_from_state(&state, &format!("/instance/{}", id)).into_response()
+                }
+                ids => {
+                    push_flash(&state, &jar, format!("Created {} instances: {}.", ids.len(), ids.join(", ")));
+                    let query = build_query_string(&[("ids".to_string(), ids.join(","))]);
+                    redirect_from_state(&state, &format!("/instances?{}", query)).into_response()
+                }
+            };
         } else {
             // Build error / result page
             let mut errors: Vec<String> = Vec::new();
@@ -661,6 +892,10 @@ async fn create_step_7_core(
             // Some APIs return 'errors' as array or map
             if let Some(arr) = resp.get("errors").and_then(|e| e.as_array()) {
                 for entry in arr {
+                    // Per-host failures are listed in the hosts table instead
+                    if !hosts.is_empty() && entry.get("hostname").is_some() {
+                        continue;
+                    }
                     if let Some(s) = entry.as_str() {
                         errors.push(s.to_string());
                     } else if let Some(obj) = entry.as_object() {
@@ -686,19 +921,28 @@ async fn create_step_7_core(
             }
             let code = resp.get("code").and_then(|c| c.as_str()).map(|s| s.to_string());
             let detail = resp.get("detail").and_then(|d| d.as_str()).map(|s| s.to_string());
+            let created: Vec<String> = hosts.iter().filter_map(|h| h.instance_id.clone()).collect();
+            assign_to_workspace(&state, &jar, &base, &created).await;
             // Do not expose raw JSON to rendered templates - keep UI friendly.
-            let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages } = build_template_globals(&state, &jar);
+            let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages, maintenance_mode, api_status } = build_template_globals(&state, &jar);
                 return render_template(&state, &jar, Step8Template {
                     current_user,
                     api_hostname,
                     base_url,
                     flash_messages,
                     has_flash_messages,
+                    maintenance_mode,
+                    api_status,
                     back_url: absolute_url_from_state(&state, "/create/step-6"),
-                    status_label: "Failed".into(),
+                    status_label: if hosts.iter().any(|h| h.succeeded()) {
+                        "Partially created".into()
+                    } else {
+                        "Failed".into()
+                    },
                     code,
                     detail,
                     errors,
+                    hosts,
                 });
         }
     }
@@ -708,6 +952,8 @@ async fn create_step_7_core(
         base_url,
         flash_messages,
         has_flash_messages,
+        maintenance_mode,
+        api_status,
     } = build_template_globals(&state, &jar);
     let mut plan_summary = Vec::new();
     let mut price_entries = Vec::new();
@@ -766,7 +1012,12 @@ async fn create_step_7_core(
         let ssh_keys = load_ssh_keys_api(&state, customer_id).await;
         let mut names = Vec::new();
         for key in ssh_keys {
-            if id_set.contains(&key.id) {
+            if !id_set.contains(&key.id) {
+                continue;
+            }
+            if state.required_ssh_key_ids.iter().any(|id| id.to_string() == key.id) {
+                names.push(format!("{} (required)", key.name));
+            } else {
                 names.push(key.name);
             }
         }
@@ -810,6 +1061,10 @@ async fn create_step_7_core(
         absolute_url_from_state(&state, &format!("/create/step-6?{}", back_q))
     };
     let has_plan_summary = !plan_summary.is_empty();
+    let show_prices = prices_visible(&state, &jar);
+    if !show_prices {
+        price_entries.clear();
+    }
     let has_price_entries = !price_entries.is_empty();
     let footnote_text = footnote.unwrap_or_default();
     let has_footnote = !footnote_text.is_empty();
@@ -819,8 +1074,16 @@ async fn create_step_7_core(
             base_url,
             flash_messages,
             has_flash_messages,
+            maintenance_mode,
+            api_status,
             base_state: &base,
             floating_ip_count: base.floating_ip_count.to_string(),
+            floating_ip_price: floating_ip_quota
+                .as_ref()
+                .and_then(|q| q.price_per_ip)
+                .filter(|_| show_prices && base.floating_ip_count > 0)
+                .map(|p| format!("{:.2} / IP per month ({:.2} total)", p, p * base.floating_ip_count as f64)),
+            floating_ip_warning: floating_ip_shortfall,
             plan_state,
             plan_type_label,
             region_name: base.region.clone(),
@@ -844,6 +1107,36 @@ async fn create_step_7_core(
     )
 }
 
+/// Add newly created instances to the workspace the wizard was started from,
+/// when the user may still create into it.
+async fn assign_to_workspace(state: &AppState, jar: &CookieJar, base: &BaseState, created: &[String]) {
+    let Some(slug) = base.workspace.as_deref() else { return };
+    if created.is_empty() {
+        return;
+    }
+    let Some(username) = current_username_from_jar(state, jar) else { return };
+    let Some(workspace) = workspace_for_create(state, &username, slug) else {
+        push_flash(state, jar, format!("New instances were not added to workspace '{}': you can't create instances there.", slug));
+        return;
+    };
+    {
+        let mut workspaces = state.workspaces.lock().unwrap();
+        if let Some(rec) = workspaces.get_mut(slug) {
+            rec.assigned_instances.extend(created.iter().cloned());
+            rec.assigned_instances.sort();
+            rec.assigned_instances.dedup();
+            rec.updated_at = now_iso8601();
+        }
+    }
+    match save_workspaces(state).await {
+        Ok(()) => push_flash(state, jar, format!("Added {} instance(s) to workspace {}.", created.len(), workspace.name)),
+        Err(e) => {
+            tracing::error!(%e, "Failed to persist workspaces");
+            push_flash(state, jar, unsaved_message("workspaces", &e));
+        }
+    }
+}
+
 pub async fn create_step_7_get(
     State(state): State<AppState>,
     jar: CookieJar,
@@ -852,7 +1145,7 @@ pub async fn create_step_7_get(
     // For GET requests, query params may have single or multiple values; flatten to CSV strings.
     let mut q_flat: HashMap<String, String> = HashMap::new();
     for (k, v) in q {
-        q_flat.insert(k, v.to_csv());
+        q_flat.insert(k, v.into_csv());
     }
     create_step_7_core(state, jar, axum::http::Method::GET, q_flat, HashMap::new()).await
 }
@@ -862,7 +1155,7 @@ pub async fn create_step_8(
     jar: CookieJar,
     axum::extract::Query(q): axum::extract::Query<HashMap<String, String>>,
 ) -> impl IntoResponse {
-    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages } = build_template_globals(&state, &jar);
+    let TemplateGlobals { current_user, api_hostname, base_url, flash_messages, has_flash_messages, maintenance_mode, api_status } = build_template_globals(&state, &jar);
     let code = q.get("code").cloned();
     let detail = q.get("detail").cloned();
     // Raw JSON is no longer rendered in the UI; any raw response can be logged by server
@@ -873,12 +1166,14 @@ pub async fn create_step_8(
         base_url,
         flash_messages,
         has_flash_messages,
+        maintenance_mode,
+        api_status,
         back_url: q.get("back_url").cloned().unwrap_or_else(|| absolute_url_from_state(&state, "/create/step-1")),
         status_label: q.get("status_label").cloned().unwrap_or_else(|| "Result".into()),
         code,
         detail,
         errors,
-        
+        hosts: Vec::new(),
     })
 }
 
@@ -890,13 +1185,16 @@ pub async fn create_step_7_post(
 ) -> impl IntoResponse {
     let mut q_flat: HashMap<String, String> = HashMap::new();
     for (k, v) in q {
-        q_flat.insert(k, v.to_csv());
+        q_flat.insert(k, v.into_csv());
     }
     // Try to parse as HashMap<String, Vec<String>> first
     let mut f_flat: HashMap<String, String> = HashMap::new();
-    let parsed_map = parse_urlencoded_body(&body);
+    let parsed_map = match parse_urlencoded_body(&body) {
+        Ok(map) => map,
+        Err(e) => return render_error(&state, &jar, axum::http::StatusCode::PAYLOAD_TOO_LARGE, e),
+    };
     for (k, v) in parsed_map {
         f_flat.insert(k, v.join(","));
     }
-    create_step_7_core(state, jar, axum::http::Method::POST, q_flat, f_flat).await
+    create_step_7_core(state, jar, axum::http::Method::POST, q_flat, f_flat).await.into_response()
 }
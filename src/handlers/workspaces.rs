@@ -1,35 +1,41 @@
 use axum::{
     extract::{Form, Path, State, Query},
-    response::{IntoResponse, Redirect},
+    http::StatusCode,
+    response::IntoResponse,
 };
 use axum_extra::extract::cookie::CookieJar;
 use serde::Deserialize;
 
 use crate::models::{AppState, WorkspaceMember, WorkspaceRecord, WorkspaceRole};
-use crate::services::{persist_workspaces_file, slugify, now_iso8601};
+use crate::services::{save_workspaces, unsaved_message, slugify, now_iso8601, is_last_manager, sort_workspaces, WorkspaceSort};
 use crate::templates::{WorkspacesTemplate, WorkspaceDetailTemplate, WorkspaceInstancesTemplate};
+use crate::utils::paginate;
 
 use super::helpers::{
-    build_template_globals, ensure_owner, plain_html,
+    redirect_from_state,
+    build_template_globals, ensure_owner, render_error, workspace_for_create,
     render_template, TemplateGlobals, current_username_from_jar,
-    load_instances_for_user_paginated,
+    load_instances_for_user_paginated, recent_instances, push_flash,
 };
 
 // ── List ─────────────────────────────────────────────────────────────────────
 
-/// GET /workspaces — list all workspaces the current user belongs to (or all if owner).
+/// GET /workspaces — list all workspaces the current user belongs to (or all
+/// if owner), ordered by `?sort=name|created|updated`.
 pub async fn workspaces_list(
     State(state): State<AppState>,
     jar: CookieJar,
+    Query(params): Query<WsPaginationParams>,
 ) -> impl IntoResponse {
     let username = match current_username_from_jar(&state, &jar) {
         Some(u) => u,
-        None => return Redirect::to("/login").into_response(),
+        None => return redirect_from_state(&state, "/login").into_response(),
     };
     let is_owner = {
         let users = state.users.lock().unwrap();
         users.get(&username).map(|r| r.role == "owner").unwrap_or(false)
     };
+    let sort = WorkspaceSort::parse(&params.sort);
     let workspaces = {
         let ws = state.workspaces.lock().unwrap();
         let mut list: Vec<WorkspaceRecord> = ws
@@ -37,15 +43,18 @@ pub async fn workspaces_list(
             .filter(|w| is_owner || w.members.iter().any(|m| m.username == username))
             .cloned()
             .collect();
-        list.sort_by(|a, b| a.name.cmp(&b.name));
+        sort_workspaces(&mut list, sort);
         list
     };
+    let page = paginate(workspaces, params.page.max(1), params.per_page.max(1));
     let TemplateGlobals {
         current_user,
         api_hostname,
         base_url,
         flash_messages,
         has_flash_messages,
+        maintenance_mode,
+        api_status,
     } = build_template_globals(&state, &jar);
     render_template(
         &state,
@@ -56,7 +65,15 @@ pub async fn workspaces_list(
             base_url,
             flash_messages,
             has_flash_messages,
-            workspaces: &workspaces,
+            maintenance_mode,
+            api_status,
+            workspaces: &page.items,
+            current_page: page.current_page,
+            total_pages: page.total_pages,
+            per_page: page.per_page,
+            total_count: page.total_count,
+            sort: sort.as_str(),
+            recent_instances: recent_instances(&state, &jar),
         },
     )
 }
@@ -81,34 +98,38 @@ pub async fn workspace_create(
     }
     let name = form.name.trim().to_string();
     if name.is_empty() {
-        return plain_html("Workspace name cannot be empty");
+        return render_error(&state, &jar, StatusCode::BAD_REQUEST, "Workspace name cannot be empty");
     }
     let slug = slugify(&name);
     if slug.is_empty() {
-        return plain_html("Could not generate a valid slug from that name");
+        return render_error(&state, &jar, StatusCode::BAD_REQUEST, "Could not generate a valid slug from that name");
     }
     {
         let mut ws = state.workspaces.lock().unwrap();
         if ws.contains_key(&slug) {
-            return plain_html("A workspace with that name already exists");
+            return render_error(&state, &jar, StatusCode::CONFLICT, "A workspace with that name already exists");
         }
+        let now = now_iso8601();
         ws.insert(
             slug.clone(),
             WorkspaceRecord {
                 name,
                 description: form.description.trim().to_string(),
                 slug: slug.clone(),
-                created_at: now_iso8601(),
+                created_at: now.clone(),
+                updated_at: now,
                 members: vec![],
                 assigned_instances: vec![],
+                default_region: String::new(),
+                default_plan_type: String::new(),
             },
         );
     }
-    if let Err(e) = persist_workspaces_file(&state.workspaces).await {
+    if let Err(e) = save_workspaces(&state).await {
         tracing::error!(%e, "Failed to persist workspaces");
-        return plain_html("Failed to save workspace");
+        push_flash(&state, &jar, unsaved_message("workspaces", &e));
     }
-    Redirect::to(&format!("/workspaces/{}", slug)).into_response()
+    redirect_from_state(&state, &format!("/workspaces/{}", slug)).into_response()
 }
 
 // ── Detail ────────────────────────────────────────────────────────────────────
@@ -121,7 +142,7 @@ pub async fn workspace_detail(
 ) -> impl IntoResponse {
     let username = match current_username_from_jar(&state, &jar) {
         Some(u) => u,
-        None => return Redirect::to("/login").into_response(),
+        None => return redirect_from_state(&state, "/login").into_response(),
     };
     let workspace = {
         let ws = state.workspaces.lock().unwrap();
@@ -129,7 +150,7 @@ pub async fn workspace_detail(
     };
     let workspace = match workspace {
         Some(w) => w,
-        None => return plain_html("Workspace not found"),
+        None => return render_error(&state, &jar, StatusCode::NOT_FOUND, "Workspace not found"),
     };
 
     // Allow owners and workspace members to view the detail page.
@@ -138,7 +159,7 @@ pub async fn workspace_detail(
         let is_owner = users.get(&username).map(|r| r.role == "owner").unwrap_or(false);
         let is_member = workspace.members.iter().any(|m| m.username == username);
         if !is_owner && !is_member {
-            return Redirect::to("/workspaces").into_response();
+            return redirect_from_state(&state, "/workspaces").into_response();
         }
     }
 
@@ -159,6 +180,8 @@ pub async fn workspace_detail(
         base_url,
         flash_messages,
         has_flash_messages,
+        maintenance_mode,
+        api_status,
     } = build_template_globals(&state, &jar);
     render_template(
         &state,
@@ -169,7 +192,10 @@ pub async fn workspace_detail(
             base_url,
             flash_messages,
             has_flash_messages,
+            maintenance_mode,
+            api_status,
             workspace: &workspace,
+            can_create: workspace_for_create(&state, &username, &workspace.slug).is_some(),
             all_users: &all_users,
             all_instances: &all_instances.instances,
         },
@@ -183,9 +209,14 @@ pub struct EditWorkspaceForm {
     pub name: String,
     #[serde(default)]
     pub description: String,
+    #[serde(default)]
+    pub default_region: String,
+    #[serde(default)]
+    pub default_plan_type: String,
 }
 
-/// POST /workspaces/:slug/edit — rename / redescribe a workspace (owner only).
+/// POST /workspaces/:slug/edit — rename / redescribe a workspace and set its
+/// creation wizard defaults (owner only).
 pub async fn workspace_edit(
     State(state): State<AppState>,
     jar: CookieJar,
@@ -197,22 +228,29 @@ pub async fn workspace_edit(
     }
     let name = form.name.trim().to_string();
     if name.is_empty() {
-        return plain_html("Workspace name cannot be empty");
+        return render_error(&state, &jar, StatusCode::BAD_REQUEST, "Workspace name cannot be empty");
+    }
+    let default_plan_type = form.default_plan_type.trim().to_lowercase();
+    if !matches!(default_plan_type.as_str(), "" | "fixed" | "custom") {
+        return render_error(&state, &jar, StatusCode::BAD_REQUEST, "Default plan type must be fixed or custom");
     }
     {
         let mut ws = state.workspaces.lock().unwrap();
         if let Some(rec) = ws.get_mut(&slug) {
             rec.name = name;
             rec.description = form.description.trim().to_string();
+            rec.default_region = form.default_region.trim().to_string();
+            rec.default_plan_type = default_plan_type;
+            rec.updated_at = now_iso8601();
         } else {
-            return plain_html("Workspace not found");
+            return render_error(&state, &jar, StatusCode::NOT_FOUND, "Workspace not found");
         }
     }
-    if let Err(e) = persist_workspaces_file(&state.workspaces).await {
+    if let Err(e) = save_workspaces(&state).await {
         tracing::error!(%e, "Failed to persist workspaces");
-        return plain_html("Failed to save workspace");
+        push_flash(&state, &jar, unsaved_message("workspaces", &e));
     }
-    Redirect::to(&format!("/workspaces/{}", slug)).into_response()
+    redirect_from_state(&state, &format!("/workspaces/{}", slug)).into_response()
 }
 
 // ── Add member ────────────────────────────────────────────────────────────────
@@ -236,31 +274,36 @@ pub async fn workspace_add_member(
     let username = form.username.trim().to_lowercase();
     let role = match WorkspaceRole::from_str(form.role.trim()) {
         Some(r) => r,
-        None => return plain_html("Invalid workspace role"),
+        None => return render_error(&state, &jar, StatusCode::BAD_REQUEST, "Invalid workspace role"),
     };
     // Verify the user exists.
     {
         let users = state.users.lock().unwrap();
         if !users.contains_key(&username) {
-            return plain_html("User not found");
+            drop(users);
+            return render_error(&state, &jar, StatusCode::NOT_FOUND, "User not found");
         }
     }
     {
         let mut ws = state.workspaces.lock().unwrap();
         if let Some(rec) = ws.get_mut(&slug) {
+            if role != WorkspaceRole::Manager && is_last_manager(&rec.members, &username) {
+                return render_error(&state, &jar, StatusCode::CONFLICT, "At least one workspace manager required");
+            }
             // Remove any existing membership for this user then re-add.
             rec.members.retain(|m| m.username != username);
             rec.members.push(WorkspaceMember { username, role });
             rec.members.sort_by(|a, b| a.username.cmp(&b.username));
+            rec.updated_at = now_iso8601();
         } else {
-            return plain_html("Workspace not found");
+            return render_error(&state, &jar, StatusCode::NOT_FOUND, "Workspace not found");
         }
     }
-    if let Err(e) = persist_workspaces_file(&state.workspaces).await {
+    if let Err(e) = save_workspaces(&state).await {
         tracing::error!(%e, "Failed to persist workspaces");
-        return plain_html("Failed to save workspace");
+        push_flash(&state, &jar, unsaved_message("workspaces", &e));
     }
-    Redirect::to(&format!("/workspaces/{}", slug)).into_response()
+    redirect_from_state(&state, &format!("/workspaces/{}", slug)).into_response()
 }
 
 // ── Remove member ─────────────────────────────────────────────────────────────
@@ -278,16 +321,20 @@ pub async fn workspace_remove_member(
     {
         let mut ws = state.workspaces.lock().unwrap();
         if let Some(rec) = ws.get_mut(&slug) {
+            if is_last_manager(&rec.members, &uname) {
+                return render_error(&state, &jar, StatusCode::CONFLICT, "At least one workspace manager required");
+            }
             rec.members.retain(|m| m.username != uname);
+            rec.updated_at = now_iso8601();
         } else {
-            return plain_html("Workspace not found");
+            return render_error(&state, &jar, StatusCode::NOT_FOUND, "Workspace not found");
         }
     }
-    if let Err(e) = persist_workspaces_file(&state.workspaces).await {
+    if let Err(e) = save_workspaces(&state).await {
         tracing::error!(%e, "Failed to persist workspaces");
-        return plain_html("Failed to save workspace");
+        push_flash(&state, &jar, unsaved_message("workspaces", &e));
     }
-    Redirect::to(&format!("/workspaces/{}", slug)).into_response()
+    redirect_from_state(&state, &format!("/workspaces/{}", slug)).into_response()
 }
 
 // ── Delete workspace ──────────────────────────────────────────────────────────
@@ -307,21 +354,22 @@ pub async fn workspace_delete(
         let users = state.users.lock().unwrap();
         if let Some(rec) = users.get(&current) {
             if rec.role != "owner" {
-                return plain_html("Only owners can delete workspaces");
+                drop(users);
+                return render_error(&state, &jar, StatusCode::FORBIDDEN, "Only owners can delete workspaces");
             }
         }
     }
     {
         let mut ws = state.workspaces.lock().unwrap();
         if ws.remove(&slug).is_none() {
-            return plain_html("Workspace not found");
+            return render_error(&state, &jar, StatusCode::NOT_FOUND, "Workspace not found");
         }
     }
-    if let Err(e) = persist_workspaces_file(&state.workspaces).await {
+    if let Err(e) = save_workspaces(&state).await {
         tracing::error!(%e, "Failed to persist workspaces");
-        return plain_html("Failed to save workspace");
+        push_flash(&state, &jar, unsaved_message("workspaces", &e));
     }
-    Redirect::to("/workspaces").into_response()
+    redirect_from_state(&state, "/workspaces").into_response()
 }
 
 // ── Assign instances to workspace ─────────────────────────────────────────────
@@ -354,15 +402,16 @@ pub async fn workspace_assign_instances(
             ids.sort();
             ids.dedup();
             rec.assigned_instances = ids;
+            rec.updated_at = now_iso8601();
         } else {
-            return plain_html("Workspace not found");
+            return render_error(&state, &jar, StatusCode::NOT_FOUND, "Workspace not found");
         }
     }
-    if let Err(e) = persist_workspaces_file(&state.workspaces).await {
+    if let Err(e) = save_workspaces(&state).await {
         tracing::error!(%e, "Failed to persist workspaces");
-        return plain_html("Failed to save workspace");
+        push_flash(&state, &jar, unsaved_message("workspaces", &e));
     }
-    Redirect::to(&format!("/workspaces/{}", slug)).into_response()
+    redirect_from_state(&state, &format!("/workspaces/{}", slug)).into_response()
 }
 
 // ── Workspace instances view ──────────────────────────────────────────────────
@@ -373,6 +422,9 @@ pub struct WsPaginationParams {
     page: usize,
     #[serde(default = "default_per_page")]
     per_page: usize,
+    /// List order (`name`, `created` or `updated`); only the list page uses it.
+    #[serde(default)]
+    sort: String,
 }
 
 fn default_page() -> usize { 1 }
@@ -388,7 +440,7 @@ pub async fn workspace_instances(
 ) -> impl IntoResponse {
     let username = match current_username_from_jar(&state, &jar) {
         Some(u) => u,
-        None => return Redirect::to("/login").into_response(),
+        None => return redirect_from_state(&state, "/login").into_response(),
     };
 
     let workspace = {
@@ -397,7 +449,7 @@ pub async fn workspace_instances(
     };
     let workspace = match workspace {
         Some(w) => w,
-        None => return plain_html("Workspace not found"),
+        None => return render_error(&state, &jar, StatusCode::NOT_FOUND, "Workspace not found"),
     };
 
     // Only workspace members (or owners) can view workspace instances.
@@ -409,7 +461,7 @@ pub async fn workspace_instances(
             .unwrap_or(false);
         let is_member = workspace.members.iter().any(|m| m.username == username);
         if !is_owner && !is_member {
-            return Redirect::to("/workspaces").into_response();
+            return redirect_from_state(&state, "/workspaces").into_response();
         }
     }
 
@@ -425,13 +477,7 @@ pub async fn workspace_instances(
         .filter(|inst| ws_instance_ids.contains(inst.id.as_str()))
         .collect();
 
-    let total_count = ws_instances.len();
-    let per_page = params.per_page.max(1);
-    let total_pages = (total_count + per_page - 1).max(1) / per_page;
-    let current_page = params.page.max(1).min(total_pages.max(1));
-    let start = (current_page - 1) * per_page;
-    let end = (start + per_page).min(total_count);
-    let page_instances = ws_instances[start..end].to_vec();
+    let page = paginate(ws_instances, params.page.max(1), params.per_page.max(1));
 
     let TemplateGlobals {
         current_user,
@@ -439,6 +485,8 @@ pub async fn workspace_instances(
         base_url,
         flash_messages,
         has_flash_messages,
+        maintenance_mode,
+        api_status,
     } = build_template_globals(&state, &jar);
     render_template(
         &state,
@@ -449,12 +497,14 @@ pub async fn workspace_instances(
             base_url,
             flash_messages,
             has_flash_messages,
+            maintenance_mode,
+            api_status,
             workspace: &workspace,
-            instances: &page_instances,
-            current_page,
-            total_pages,
-            per_page,
-            total_count,
+            instances: &page.items,
+            current_page: page.current_page,
+            total_pages: page.total_pages,
+            per_page: page.per_page,
+            total_count: page.total_count,
         },
     )
 }
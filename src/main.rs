@@ -6,6 +6,8 @@ mod templates;
 mod handlers;
 mod update;
 mod mcp;
+mod tls;
+mod doctor;
 
 use zy::config;
 
@@ -23,6 +25,7 @@ use std::sync::{Arc, Mutex};
 use std::collections::HashMap;
 use std::process;
 use clap::{Parser, Subcommand};
+use futures_util::stream::{self, StreamExt};
 use tracing_subscriber::{fmt, EnvFilter};
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
@@ -37,11 +40,34 @@ use handlers::helpers::api_call_wrapper;
 // Embed the default stylesheet in the binary
 const DEFAULT_STYLESHEET: &str = include_str!("../static/styles.css");
 
+/// Upstream API client honouring `API_CA_CERT` and `API_TLS_INSECURE`.
+/// Exits when the CA file can't be used.
+fn api_client_from_env() -> reqwest::Client {
+    let insecure = config::get_api_tls_insecure();
+    if insecure {
+        eprintln!(
+            "{}",
+            yansi::Paint::yellow("WARNING: API_TLS_INSECURE is set; upstream TLS certificates are NOT verified. Use only for development.").bold()
+        );
+    }
+    match api::client::build_api_client(config::get_api_ca_cert().as_deref(), insecure) {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("{}", yansi::Paint::red(&e));
+            process::exit(1);
+        }
+    }
+}
+
 async fn build_state_from_env(env_file: Option<&str>) -> AppState {
     config::load_env_file(env_file);
+    api::rate_limit::set_rate_limit(config::get_api_rate_limit());
+    api::timeout::set_timeouts(config::get_api_timeout_secs(), config::get_api_write_timeout_secs());
+    api::client::set_api_version(&config::get_api_version());
     let users = load_users_from_file().await;
     let workspaces = load_workspaces_from_file().await;
-    let disabled_instances = std::sync::Arc::new(config::get_disabled_instance_ids());
+    let notes = services::load_notes_from_file().await;
+    let labels = services::load_labels_from_file().await;
     // Load clocked instances: file-based overrides take precedence over env var
     let env_ids = config::get_disabled_instance_ids();
     let initial_ids = load_clocked_instances_from_file().await.unwrap_or(env_ids);
@@ -52,24 +78,58 @@ async fn build_state_from_env(env_file: Option<&str>) -> AppState {
         .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
         .unwrap_or_default();
 
-    let client = reqwest::Client::builder()
-        .user_agent(format!("Zy/{}", env!("CARGO_PKG_VERSION")))
-        .build()
-        .expect("Failed to create HTTP client");
+    let client = api_client_from_env();
+    let instance_columns = match config::get_instance_columns().map(|raw| models::InstanceColumn::parse_list(&raw)) {
+        None => None,
+        Some(Ok(columns)) => Some(columns),
+        Some(Err(e)) => {
+            eprintln!("{}", yansi::Paint::red(&format!("ZY_INSTANCE_COLUMNS: {}", e)));
+            process::exit(1);
+        }
+    };
     
     AppState {
         users,
         sessions: Arc::new(Mutex::new(HashMap::new())),
         flash_store: Arc::new(Mutex::new(HashMap::new())),
-        default_customer_cache: Arc::new(Mutex::new(None)),
+        default_customer_cache: api::CustomerIdCache::default(),
+        customer_cache_ttl: std::time::Duration::from_secs(config::get_customer_cache_secs()),
+        product_name_cache: api::ProductNameCache::default(),
+        admin_secret: config::get_admin_secret(),
         api_base_url: config::get_api_base_url(),
         api_token: config::get_api_token(),
         public_base_url: config::get_public_base_url(),
         client,
         disabled_instances,
         current_hostname,
+        blocked_hostname_patterns: Arc::new(Mutex::new(
+            config::get_blocked_hostname_patterns().iter().map(|p| utils::GlobPattern::new(p)).collect(),
+        )),
+        env_file: env_file.map(str::to_string),
         custom_css: None,
+        secure_cookies: false,
+        maintenance_mode: config::get_maintenance_mode(),
+        required_ssh_key_ids: config::get_required_ssh_key_ids(),
+        delete_require_hostname: config::get_delete_require_hostname(),
+        redirect_on_deny: config::get_redirect_on_deny(),
+        hide_prices_for_nonowners: config::get_hide_prices_for_nonowners(),
+        max_concurrency: config::get_max_concurrency(),
+        max_body_bytes: config::get_max_body_bytes(),
+        password_scheme: config::get_password_scheme(),
+        action_cooldowns: Arc::new(Mutex::new(HashMap::new())),
+        idempotency_keys: Arc::new(Mutex::new(HashMap::new())),
+        instance_cache: api::InstanceListCache::default(),
+        instance_cache_ttl: std::time::Duration::from_secs(config::get_instance_cache_secs()),
+        webhook_url: config::get_webhook_url(),
+        live_status_interval: config::get_live_status_enabled()
+            .then(|| std::time::Duration::from_secs(config::get_live_status_interval_secs())),
+        action_cooldown: std::time::Duration::from_secs(config::get_action_cooldown_secs()),
+        notes,
+        labels,
         workspaces,
+        instance_columns,
+        update_check: services::version_service::UpdateCheckCache::default(),
+        unsaved: services::UnsavedChanges::default(),
         mcp_log_store: mcp::log::McpLogStore::new(),
     }
 }
@@ -81,6 +141,7 @@ fn build_app(state: AppState) -> Router {
         .route("/users", get(handlers::users::users_list).post(handlers::users::users_create))
         .route("/users/:username", get(handlers::users::user_detail))
         .route("/users/:username/reset-password", post(handlers::users::reset_password))
+        .route("/users/:username/revoke-sessions", post(handlers::users::revoke_sessions))
         .route("/users/:username/role", post(handlers::users::update_role))
         .route("/users/:username/about", post(handlers::users::update_about))
         .route("/users/:username/delete", post(handlers::users::delete_user))
@@ -105,9 +166,12 @@ fn build_app(state: AppState) -> Router {
         .route("/isos/download", post(handlers::iso::iso_download_post))
         .route("/images", get(handlers::images::images_list_get))
         .route("/images/download", post(handlers::images::image_download_post))
+        .route("/applications", get(handlers::applications::applications_get))
         .route("/backups", get(handlers::backups::backups_list_get))
         .route("/backups/create", post(handlers::backups::backup_create_post))
         .route("/instances", get(handlers::instances::instances_real))
+        .route("/events/instances", get(handlers::instances::instances_events))
+        .route("/api/instances", get(handlers::instances::instances_api_get))
         .route("/create/step-1", get(handlers::wizard::create_step_1))
         .route("/create/step-2", get(handlers::wizard::create_step_2))
         .route("/create/step-3", get(handlers::wizard::create_step_3))
@@ -120,6 +184,8 @@ fn build_app(state: AppState) -> Router {
         )
         .route("/create/result", get(handlers::wizard::create_step_8))
         .route("/instance/:instance_id", get(handlers::instances::instance_detail))
+        .route("/instance/:instance_id/console", get(handlers::instances::instance_console_get))
+        .route("/notes/:instance_id", post(handlers::instances::instance_note_post))
         .route("/instance/:instance_id/delete", post(handlers::instances::instance_delete))
         .route("/instance/:instance_id/poweron", post(handlers::instances::instance_poweron_post))
         .route("/instance/:instance_id/poweroff", post(handlers::instances::instance_poweroff_post))
@@ -136,6 +202,8 @@ fn build_app(state: AppState) -> Router {
             "/instance/:instance_id/change-os",
             get(handlers::instances::instance_change_os_get).post(handlers::instances::instance_change_os_post),
         )
+        .route("/instances/bulk", get(handlers::instances::instances_bulk_get).post(handlers::instances::instances_bulk_post))
+        .route("/instances/selection", post(handlers::instances::instances_selection_post))
         .route("/instance/:instance_id/resize", get(handlers::instances::instance_resize_get).post(handlers::instances::instance_resize_post))
         .route("/coming-soon/:feature", get(handlers::system::coming_soon))
         .route(
@@ -153,6 +221,8 @@ fn build_app(state: AppState) -> Router {
         .route("/workspaces/:slug/delete", post(handlers::workspaces::workspace_delete))
         // ── Permissions reference page ───────────────────────────────────
         .route("/permissions", get(handlers::system::permissions_get))
+        .route_layer(axum::middleware::from_fn_with_state(state.clone(), handlers::middleware::maintenance_middleware))
+        .route_layer(axum::middleware::from_fn_with_state(state.clone(), handlers::middleware::retry_unsaved_middleware))
         .route_layer(axum::middleware::from_fn_with_state(state.clone(), handlers::middleware::auth_middleware));
 
     // Always serve styles.css - use custom if provided, otherwise use embedded default
@@ -162,6 +232,9 @@ fn build_app(state: AppState) -> Router {
         .route("/", get(handlers::auth::root_get))
         .route("/login", get(handlers::auth::login_get).post(handlers::auth::login_post))
         .route("/logout", post(handlers::auth::logout_post))
+        .route(handlers::admin::CACHE_CLEAR_PATH, post(handlers::admin::cache_clear_post))
+        .route(handlers::admin::RELOAD_CONFIG_PATH, post(handlers::admin::reload_config_post))
+        .route("/version", get(handlers::system::version_get))
         .route("/mcp", get(handlers::mcp_docs::mcp_docs_page))
         .route("/mcp/tools", get(handlers::mcp_docs::mcp_tools_json))
         .route("/mcp/logs", get(handlers::mcp_docs::mcp_logs_json))
@@ -178,7 +251,10 @@ fn build_app(state: AppState) -> Router {
         }))
         .merge(protected_routes);
 
-    app.nest_service(
+    app.layer(axum::extract::DefaultBodyLimit::max(state.max_body_bytes))
+        .layer(axum::middleware::from_fn_with_state(state.clone(), handlers::middleware::body_limit_middleware))
+        .layer(axum::middleware::from_fn(handlers::middleware::request_id_middleware))
+        .nest_service(
             "/static",
             ServiceBuilder::new()
                 .layer(SetResponseHeaderLayer::if_not_present(
@@ -190,7 +266,148 @@ fn build_app(state: AppState) -> Router {
         .with_state(state)
 }
 
-async fn start_server(mut state: AppState, host: &str, port: u16, stylesheet: Option<String>) {
+/// POST a request signed with `ZY_ADMIN_SECRET` to `path` on a running
+/// server, exiting with an error unless it succeeds. Returns the server's
+/// `message`, if any.
+async fn send_admin_request(env_file: Option<&str>, server: Option<String>, path: &str, failure_label: &str) -> Option<String> {
+    config::load_env_file(env_file);
+    let Some(secret) = config::get_admin_secret() else {
+        eprintln!("{}", yansi::Paint::new("ZY_ADMIN_SECRET is not set").red());
+        process::exit(1);
+    };
+    let base = server.map(|s| config::sanitize_base_url(&s)).unwrap_or_else(config::get_public_base_url);
+    if base.is_empty() {
+        eprintln!("{}", yansi::Paint::new("Pass --server <url> or set PUBLIC_BASE_URL").red());
+        process::exit(1);
+    }
+    let timestamp = chrono::Utc::now().timestamp();
+    let signature = services::admin_service::sign_admin_request(&secret, "POST", path, timestamp);
+    let resp = reqwest::Client::new()
+        .post(utils::absolute_url(&base, path))
+        .header(services::admin_service::ADMIN_TIMESTAMP_HEADER, timestamp.to_string())
+        .header(services::admin_service::ADMIN_SIGNATURE_HEADER, signature)
+        .send()
+        .await;
+    match resp {
+        Ok(r) if r.status().is_success() => {
+            let body: serde_json::Value = r.json().await.unwrap_or_default();
+            body.get("message").and_then(|m| m.as_str()).map(str::to_string)
+        }
+        Ok(r) if r.status() == reqwest::StatusCode::NOT_FOUND => {
+            eprintln!("{}", yansi::Paint::new("The server has no ZY_ADMIN_SECRET set (or is not a zy server)").red());
+            process::exit(1);
+        }
+        Ok(r) => {
+            let status = r.status();
+            let body: serde_json::Value = r.json().await.unwrap_or_default();
+            let message = body.get("message").and_then(|m| m.as_str()).unwrap_or("request rejected");
+            eprintln!("{}: {} ({})", yansi::Paint::new(failure_label).red(), message, status);
+            process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("{}: {}", yansi::Paint::new("Could not reach the server").red(), e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Read one line from the terminal, falling back to `default` when left empty.
+fn prompt(term: &console::Term, label: &str, default: &str, secret: bool) -> String {
+    let hint = if default.is_empty() { String::new() } else { format!(" [{}]", default) };
+    let _ = term.write_str(&format!("{}{}: ", label, hint));
+    let line = if secret { term.read_secure_line() } else { term.read_line() };
+    match line {
+        Ok(value) if !value.trim().is_empty() => value.trim().to_string(),
+        Ok(_) => default.to_string(),
+        Err(e) => {
+            eprintln!("{}: {}", yansi::Paint::red("Failed to read input"), e);
+            process::exit(1);
+        }
+    }
+}
+
+async fn config_init(path: &str, force: bool) {
+    if std::path::Path::new(path).exists() && !force {
+        eprintln!("{} {} (use --force to overwrite)", yansi::Paint::red("Refusing to overwrite existing"), path);
+        process::exit(1);
+    }
+    let term = console::Term::stderr();
+    let api_base_url = prompt(&term, "API base URL", "https://api.cloudzy.com/developers", false);
+    let api_base_url = api_base_url.trim_end_matches('/').to_string();
+    let api_token = prompt(&term, "API token", "", true);
+    if api_token.is_empty() {
+        eprintln!("{}", yansi::Paint::red("An API token is required"));
+        process::exit(1);
+    }
+    let public_base_url = prompt(&term, "Public base URL (optional)", "", false);
+
+    let client = api_client_from_env();
+    api::client::set_silent(true);
+    let resp = api::api_call(&client, &api_base_url, &api_token, "GET", "/v1/regions", None, None).await;
+    if resp.get("code").and_then(|c| c.as_str()) == Some("OKAY") {
+        println!("{}", yansi::Paint::green("Credentials verified (regions returned)"));
+    } else {
+        let detail = resp.get("detail").and_then(|d| d.as_str()).unwrap_or("unexpected response");
+        eprintln!("{}: {}", yansi::Paint::yellow("Could not verify credentials"), detail);
+        let answer = prompt(&term, "Write the file anyway? (y/N)", "n", false);
+        if !answer.eq_ignore_ascii_case("y") && !answer.eq_ignore_ascii_case("yes") {
+            process::exit(1);
+        }
+    }
+
+    let contents = config::render_env_file(&api_base_url, &api_token, public_base_url.trim_end_matches('/'));
+    if let Err(e) = write_private_file(path, &contents) {
+        eprintln!("{} {}: {}", yansi::Paint::red("Failed to write"), path, e);
+        process::exit(1);
+    }
+    println!("{} {}", yansi::Paint::green("Wrote"), path);
+}
+
+/// Write `contents` to `path`, readable only by the owner on Unix since the
+/// file holds the API token. An existing file's permissions are tightened too.
+fn write_private_file(path: &str, contents: &str) -> std::io::Result<()> {
+    use std::io::Write;
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    #[cfg(unix)]
+    file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))?;
+    file.write_all(contents.as_bytes())
+}
+
+/// Fetch `/v1/regions` once to confirm the configured token is accepted.
+async fn probe_api_token(state: &AppState) -> Result<(), String> {
+    if state.api_token.trim().is_empty() {
+        return Err("API_TOKEN is not configured".into());
+    }
+    let resp = api::api_call(&state.client, &state.api_base_url, &state.api_token, "GET", "/v1/regions", None, None).await;
+    if resp.get("code").and_then(|c| c.as_str()) == Some("OKAY") {
+        return Ok(());
+    }
+    let detail = resp
+        .get("detail")
+        .and_then(|d| d.as_str())
+        .or_else(|| resp.get("error").and_then(|e| e.as_str()))
+        .unwrap_or("unexpected response");
+    Err(format!("API token check against {} failed: {}", state.api_base_url, detail))
+}
+
+async fn start_server(mut state: AppState, host: &str, port: u16, stylesheet: Option<String>, tls_paths: Option<(String, String)>, require_valid_token: bool) {
+    match probe_api_token(&state).await {
+        Ok(()) => tracing::info!("API token accepted"),
+        Err(reason) if require_valid_token => {
+            tracing::error!(%reason, "Refusing to start with an invalid API token");
+            eprintln!("{}: {}", yansi::Paint::red("Refusing to start"), reason);
+            process::exit(1);
+        }
+        Err(reason) => {
+            tracing::warn!(%reason, "API token check failed; lists will be empty until it is fixed");
+            eprintln!("{} {}", yansi::Paint::new("WARNING:").yellow().bold(), yansi::Paint::yellow(&reason));
+            eprintln!("{}", yansi::Paint::yellow("The panel will start, but instances, regions and plans will not load. Use --require-valid-token to fail instead."));
+        }
+    }
     if let Some(path) = stylesheet {
         match std::fs::read_to_string(&path) {
             Ok(css) => {
@@ -213,13 +430,75 @@ async fn start_server(mut state: AppState, host: &str, port: u16, stylesheet: Op
             process::exit(1);
         }
     };
+    let tls_config = match tls_paths {
+        Some((cert, key)) => match tls::load_server_config(std::path::Path::new(&cert), std::path::Path::new(&key)) {
+            Ok(config) => {
+                // Session cookies are only worth marking Secure when we terminate TLS ourselves
+                state.secure_cookies = true;
+                Some(config)
+            }
+            Err(e) => {
+                tracing::error!(%e, "Failed to load TLS certificate");
+                eprintln!("{}: {}", yansi::Paint::red("Failed to load TLS certificate"), e);
+                process::exit(1);
+            }
+        },
+        None => None,
+    };
+    let scheme = if tls_config.is_some() { "https" } else { "http" };
+    if state.webhook_url.is_some() && state.instance_cache_ttl.is_zero() {
+        tracing::warn!("ZY_WEBHOOK_URL is set but INSTANCE_CACHE_SECS=0; no status webhooks will be sent");
+    }
+    if !state.instance_cache_ttl.is_zero() {
+        let refresh_state = state.clone();
+        // The API client may trust a private CA or skip verification; the
+        // webhook receiver is a different host and gets default TLS settings
+        let webhook_client = reqwest::Client::new();
+        tokio::spawn(async move {
+            let mut last_statuses = None;
+            loop {
+                let generation = refresh_state.instance_cache.generation();
+                if let Some(instances) = api::fetch_all_instances(&refresh_state.client, &refresh_state.api_base_url, &refresh_state.api_token).await {
+                    if let Some(url) = &refresh_state.webhook_url {
+                        let current = services::webhook_service::status_snapshot(&instances);
+                        if let Some(previous) = last_statuses.replace(current) {
+                            let changes = services::webhook_service::detect_status_changes(&previous, &instances, &services::now_iso8601());
+                            if !changes.is_empty() {
+                                let (client, url) = (webhook_client.clone(), url.clone());
+                                // Don't hold up the next refresh while retrying a slow receiver
+                                tokio::spawn(async move {
+                                    services::webhook_service::notify_status_changes(&client, &url, &changes).await;
+                                });
+                            }
+                        }
+                    }
+                    refresh_state.instance_cache.store(generation, instances);
+                }
+                tokio::time::sleep(refresh_state.instance_cache_ttl).await;
+            }
+        });
+    }
+    let health_state = state.clone();
+    tokio::spawn(async move {
+        loop {
+            // Any API call refreshes the status; only probe when the panel is idle
+            if api::health::is_stale(api::health::HEALTHCHECK_INTERVAL) {
+                api::api_call(&health_state.client, &health_state.api_base_url, &health_state.api_token, "GET", "/v1/regions", None, None).await;
+            }
+            tokio::time::sleep(api::health::HEALTHCHECK_INTERVAL).await;
+        }
+    });
     let app = build_app(state.clone());
-    tracing::info!(%addr, "Starting Zy Rust server");
-    println!("{} {}", yansi::Paint::new("Web server running on").green(), yansi::Paint::new(format!("http://{}", addr)).cyan());
+    tracing::info!(%addr, scheme, "Starting Zy Rust server");
+    println!("{} {}", yansi::Paint::new("Web server running on").green(), yansi::Paint::new(format!("{}://{}", scheme, addr)).cyan());
     match tokio::net::TcpListener::bind(addr).await {
         Ok(listener) => {
             // Run the server and log any errors (do not panic with unwrap()).
-            if let Err(e) = axum::serve(listener, app).await {
+            let result = match tls_config {
+                Some(config) => tls::serve(listener, app, config).await,
+                None => axum::serve(listener, app).await,
+            };
+            if let Err(e) = result {
                 tracing::error!(%e, "Server encountered an error while running");
                 eprintln!("{}: {}", yansi::Paint::new("Server error").red(), e);
                 process::exit(1);
@@ -299,6 +578,87 @@ fn print_table(value: &serde_json::Value) {
     println!("\n{table}\n");
 }
 
+/// Print a list command's output. `rows` back the table, plain and CSV
+/// formats; `json` holds one serialized item per row for `--format json`.
+fn print_list(format: ListFormat, header: &[&str], rows: Vec<Vec<String>>, json: Vec<serde_json::Value>) {
+    match format {
+        ListFormat::Table => {
+            let mut table = Table::new();
+            table.load_preset(presets::UTF8_FULL);
+            table.apply_modifier(modifiers::UTF8_ROUND_CORNERS);
+            table.set_content_arrangement(ContentArrangement::Dynamic);
+            if let Some((Width(w), _)) = terminal_size() {
+                table.set_width(w - 4);
+            }
+            table.set_header(header.to_vec());
+            for row in rows {
+                table.add_row(row);
+            }
+            println!("\n{table}\n");
+        }
+        ListFormat::Plain => {
+            println!("{}", yansi::Paint::new(header.join("\t")).bold().underline());
+            for row in rows {
+                println!("{}", row.join("\t"));
+            }
+        }
+        ListFormat::Csv => {
+            println!("{}", utils::csv_line(header));
+            for row in rows {
+                println!("{}", utils::csv_line(&row));
+            }
+        }
+        ListFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&json).unwrap_or_else(|_| "[]".into()));
+        }
+    }
+}
+
+/// Run a bulk action and print one row per instance; exits non-zero if any failed.
+async fn print_bulk_results(state: &AppState, instance_ids: &[String], action: &services::bulk_service::BulkAction, concurrency: usize) {
+    let outcomes = services::bulk_service::run_bulk_action(state, instance_ids, action, concurrency).await;
+    let mut table = Table::new();
+    table.load_preset(presets::UTF8_FULL);
+    table.apply_modifier(modifiers::UTF8_ROUND_CORNERS);
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+    table.set_header(vec!["Instance", "Result", "Message"]);
+    for o in &outcomes {
+        table.add_row(vec![o.instance_id.clone(), if o.ok { "OK".into() } else { "FAILED".into() }, o.message.clone()]);
+    }
+    println!("\n{table}");
+    let summary = services::bulk_service::summarize(action, &outcomes);
+    if outcomes.iter().all(|o| o.ok) {
+        println!("\n{}\n", yansi::Paint::new(summary).green());
+    } else {
+        eprintln!("\n{}\n", yansi::Paint::new(summary).red());
+        process::exit(1);
+    }
+}
+
+/// Print an API response and exit non-zero (with a stderr message) when the
+/// upstream did not report success, so scripts can rely on `$?`.
+fn print_api_result(value: &serde_json::Value, action: &str) {
+    print_api_response(value);
+    exit_on_api_failure(value, action);
+}
+
+/// Print why `action` failed and exit non-zero, unless `value` is a success.
+fn exit_on_api_failure(value: &serde_json::Value, action: &str) {
+    if !handlers::helpers::is_api_success(value) {
+        if let Some(message) = api::auth_failure_message(value) {
+            eprintln!("{}", yansi::Paint::new(message).red());
+            process::exit(1);
+        }
+        let detail = value
+            .get("detail")
+            .and_then(|d| d.as_str())
+            .or_else(|| value.get("error").and_then(|e| e.as_str()))
+            .unwrap_or("Unknown error");
+        eprintln!("{}: {}", yansi::Paint::new(format!("{} failed", action)).red(), detail);
+        process::exit(1);
+    }
+}
+
 fn print_api_response(value: &serde_json::Value) {
     if let Some(obj) = value.as_object() {
         // Check for standard envelope
@@ -321,6 +681,7 @@ fn print_api_response(value: &serde_json::Value) {
     name = "zy",
      author,
      version,
+    long_version = update::LONG_VERSION,
     about = "Zy command-line tool",
     long_about = r#"Zy — control and manage your Cloudzy services right from home.
 
@@ -340,12 +701,33 @@ Examples:
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
-    /// Disable colorized output
+    /// Disable colorized output (also off with NO_COLOR set or when stdout isn't a terminal)
     #[arg(long, global = true)]
     no_color: bool,
     /// Disable request/response logging
     #[arg(long, global = true)]
     silent: bool,
+    /// Path to .env file for every subcommand (a subcommand's own --env-file wins)
+    #[arg(long)]
+    env_file: Option<String>,
+    /// PEM file of extra root certificates for the upstream API (same as API_CA_CERT)
+    #[arg(long, global = true)]
+    ca_cert: Option<String>,
+    /// Skip upstream TLS certificate verification; development only (same as API_TLS_INSECURE=1)
+    #[arg(long, global = true)]
+    insecure: bool,
+    /// Upstream API base URL, overriding API_BASE_URL from the environment and .env files
+    #[arg(long, global = true)]
+    api_base_url: Option<String>,
+    /// Upstream API token, overriding API_TOKEN; visible in process listings, prefer --api-token-file
+    #[arg(long, global = true, conflicts_with = "api_token_file")]
+    api_token: Option<String>,
+    /// File holding the upstream API token, overriding API_TOKEN
+    #[arg(long, global = true)]
+    api_token_file: Option<String>,
+    /// Restore the binary from the backup an interrupted `zy update` left behind
+    #[arg(long, global = true)]
+    recover_update: bool,
 }
 
 #[derive(Subcommand)]
@@ -364,29 +746,68 @@ enum Commands {
         /// Path to a custom stylesheet to serve instead of the default
         #[arg(long)]
         stylesheet: Option<String>,
+        /// Block mutating actions (same as ZY_MAINTENANCE_MODE=1)
+        #[arg(long)]
+        maintenance: bool,
+        /// PEM certificate chain; serve HTTPS instead of HTTP (requires --tls-key)
+        #[arg(long, requires = "tls_key")]
+        tls_cert: Option<String>,
+        /// PEM private key for --tls-cert
+        #[arg(long, requires = "tls_cert")]
+        tls_key: Option<String>,
+        /// Exit instead of warning when the API token is rejected at startup
+        #[arg(long)]
+        require_valid_token: bool,
     },
     /// Validate configuration (env vars / API credentials)
     #[command(about = "Validate configuration and ensure API connectivity.", long_about = "Validate environment variables required for the Zy server, and optionally validate the configured API token by attempting to fetch regions from the remote API.")]
     CheckConfig { env_file: Option<String> },
+    /// Run deployment diagnostics
+    #[command(about = "Diagnose configuration, connectivity and local files.", long_about = "Check config presence, API token validity, upstream reachability and latency, that users.json/workspaces.json/clocked_instances.json/notes.json/labels.json parse and are writable, and whether an update is available. Prints a checklist with remediation hints and exits non-zero if any critical check fails.")]
+    Doctor,
+    /// Create configuration files
+    Config {
+        #[command(subcommand)]
+        sub: ConfigCommands,
+    },
+    /// Browse the region catalog
+    Regions {
+        #[command(subcommand)]
+        sub: RegionCommands,
+    },
+    /// Manage the zy installation itself
+    #[command(name = "self")]
+    SelfCmd {
+        #[command(subcommand)]
+        sub: SelfCommands,
+    },
+    /// Reset a running server's caches
+    Cache {
+        #[command(subcommand)]
+        sub: CacheCommands,
+    },
     /// Manage local users (users.json)
     Users {
         #[command(subcommand)]
         sub: UserCommands,
     },
     /// Manage instances via the configured API
-    #[command(about = "Manage compute instances via the API (list, show, power, delete, etc.)", long_about = "These commands perform the same actions that the web UI's instance actions perform; they make API requests using the current API configuration and token. Be careful with commands that mutate state (delete, reset). Use `--help` on a subcommand for detailed examples.")]
+    #[command(about = "Manage compute instances via the API (list, show, power, delete, etc.)", long_about = "These commands perform the same actions that the web UI's instance actions perform; they make API requests using the current API configuration and token. Be careful with commands that mutate state (delete, reset). Each command exits with a non-zero status when the API does not report success. Use `--help` on a subcommand for detailed examples.")]
     Instances {
         #[command(subcommand)]
         sub: InstanceCommands,
     },
     /// Update the Zy CLI to the latest version
     Update {
-        /// Release channel to check (stable, beta, alpha, rc)
-        #[arg(long, default_value = "stable")]
-        channel: String,
+        /// Release channel to check (stable, beta, alpha, rc); defaults to the channel of the running build
+        #[arg(long)]
+        channel: Option<String>,
         /// Skip confirmation prompt and update immediately
         #[arg(long)]
         force: bool,
+        /// Suppress progress output (also enabled by setting ZY_QUIET=1)
+        #[arg(long, short = 'q')]
+        quiet: bool,
     },
     /// Start the Model Context Protocol (MCP) server over stdio
     #[command(about = "Start an MCP server (JSON-RPC over stdio) for AI assistant integration", long_about = "Launch a Model Context Protocol server that communicates via JSON-RPC 2.0 over stdin/stdout. This allows AI assistants such as Claude Desktop or VS Code Copilot to manage Cloudzy instances through the standard MCP interface.\n\nThe server reads newline-delimited JSON-RPC messages from stdin and writes responses to stdout. Configure API credentials via environment variables or --env-file.")]
@@ -397,10 +818,69 @@ enum Commands {
     },
 }
 
+#[derive(Subcommand)]
+enum RegionCommands {
+    #[command(about = "List regions", long_about = "List the regions offered by the API (active, visible ones unless `--all`). `--format json` prints the full region objects, including their config and position.")]
+    List {
+        /// Include inactive and hidden regions
+        #[arg(long)]
+        all: bool,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = ListFormat::Table)]
+        format: ListFormat,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheCommands {
+    #[command(about = "Clear a running server's caches", long_about = "Ask a running `zy serve` to forget its cached default customer ID, regions, OS list, products and instance list, so changes upstream (e.g. a rotated account) take effect without a restart. The request is signed with ZY_ADMIN_SECRET, which must match the server's.")]
+    Clear {
+        /// Base URL of the running server (defaults to PUBLIC_BASE_URL)
+        #[arg(long)]
+        server: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SelfCommands {
+    #[command(about = "Remove update backups (and optionally zy itself)", long_about = "Delete the `.bak`, `.old` and `.previous` copies the updater can leave next to the zy executable. With `--binary`, also remove the executable after a confirmation prompt (skip it with `--yes`); on Windows the running executable is moved aside and deleted once zy exits. users.json, .env and other data files are never touched.")]
+    Uninstall {
+        /// Also remove the zy executable
+        #[arg(long)]
+        binary: bool,
+        /// Don't ask before removing the executable
+        #[arg(long, short = 'y')]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    #[command(about = "Interactively write a .env file", long_about = "Prompt for the API base URL, API token (input hidden) and public base URL, check them by fetching regions from the API, and write them to a .env file. An existing file is only replaced with `--force`.")]
+    Init {
+        /// File to write
+        #[arg(long, default_value = ".env")]
+        path: String,
+        /// Overwrite the file if it already exists
+        #[arg(long)]
+        force: bool,
+    },
+    #[command(about = "Reload a running server's block lists", long_about = "Ask a running `zy serve` to re-read its env file and swap in the disabled instance IDs (clocked_instances.json, else DISABLED_INSTANCE_IDS) and ZY_BLOCKED_HOSTNAME_PATTERNS, without a restart or dropping sessions. The request is signed with ZY_ADMIN_SECRET, which must match the server's.")]
+    Reload {
+        /// Base URL of the running server (defaults to PUBLIC_BASE_URL)
+        #[arg(long)]
+        server: Option<String>,
+    },
+}
+
 #[derive(Subcommand)]
 enum UserCommands {
-    #[command(about = "List current users", long_about = "Enumerate users stored in users.json (username, role, assigned_instances).")]
-    List,
+    #[command(about = "List current users", long_about = "Enumerate users stored in users.json (username, role, assigned_instances), sorted by username. `--format json` prints an array of {username, role, assigned_instances, about, last_login} objects; password hashes are never printed.")]
+    List {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = ListFormat::Plain)]
+        format: ListFormat,
+    },
     #[command(about = "Add a new user", long_about = "Add a user with a role (owner|admin). The password will be hashed and saved to users.json.")]
     Add {
         username: String,
@@ -422,24 +902,65 @@ enum UserCommands {
     },
 }
 
+/// Output format shared by the `list` commands.
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum ListFormat {
+    Table,
+    /// Tab-separated with a header line
+    Plain,
+    Csv,
+    Json,
+}
+
+impl ListFormat {
+    /// CSV and JSON are parsed by other tools, so nothing else may reach stdout.
+    fn is_machine_readable(self) -> bool {
+        matches!(self, ListFormat::Csv | ListFormat::Json)
+    }
+}
+
 #[derive(Subcommand)]
 enum InstanceCommands {
     /// List instances (optional --username to filter)
-    #[command(about = "List instances", long_about = "List instances the configured API user may access. Provide `--username` to filter instances assigned to a local user. Use `--page` and `--per-page` for pagination.")]
+    #[command(about = "List instances", long_about = "List instances a local user may access. Scope precedence: `--all` lists the whole account (refused when ZY_CLI_USER is set to a non-owner), otherwise `--username` (which a non-owner ZY_CLI_USER may only set to themselves), otherwise the ZY_CLI_USER user; with none of these the command fails instead of listing everything. `--workspace` narrows to instances assigned to a workspace and `--label key=value` (repeatable) to instances carrying local labels. Use `--page` and `--per-page` for pagination.")]
     List {
-        /// Optional username to filter instances by assigned user (use empty to list all)
-        #[arg(long)]
+        /// List the instances this local user can access (defaults to ZY_CLI_USER)
+        #[arg(long, conflicts_with = "all")]
         username: Option<String>,
+        /// List every instance on the account (owner-only)
+        #[arg(long)]
+        all: bool,
+        /// Only list instances assigned to this workspace (by slug)
+        #[arg(long)]
+        workspace: Option<String>,
+        /// Only list instances carrying this local label (key=value; repeat to require several)
+        #[arg(long = "label", value_name = "KEY=VALUE")]
+        labels: Vec<String>,
         /// Page number to display (1-indexed). Use 0 to show all instances without pagination.
         #[arg(long, short = 'p', default_value = "0")]
         page: usize,
         /// Number of instances per page (default: 20, only used when page > 0)
         #[arg(long, default_value = "20")]
         per_page: usize,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = ListFormat::Table)]
+        format: ListFormat,
     },
     /// Show instance details
-    #[command(about = "Show instance details", long_about = "Show the raw JSON payload returned by the API for an instance ID.")]
-    Show { instance_id: String },
+    #[command(about = "Show instance details", long_about = "Show the raw JSON payload returned by the API for an instance ID. With `--all`, fetch the detail of every instance the local user may access (`--username`, else ZY_CLI_USER, else the whole account) and print them as one JSON array; instances whose detail could not be fetched appear as `{\"id\", \"error\"}` entries and the command exits non-zero.")]
+    Show {
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        instance_id: Option<String>,
+        /// Dump the detail of every accessible instance as a JSON array
+        #[arg(long)]
+        all: bool,
+        /// With --all, dump the instances this local user can access (defaults to ZY_CLI_USER)
+        #[arg(long, requires = "all")]
+        username: Option<String>,
+        /// With --all, how many details to fetch at once (capped by ZY_MAX_CONCURRENCY)
+        #[arg(long, default_value = "8", value_parser = clap::value_parser!(u16).range(1..=64))]
+        concurrency: u16,
+    },
     /// Power on an instance
     #[command(about = "Power on an instance", long_about = "Request an asynchronous power-on operation for an instance; the API may perform the action asynchronously.")]
     PowerOn { instance_id: String },
@@ -458,41 +979,278 @@ enum InstanceCommands {
     /// Change the instance OS
     #[command(about = "Change the instance OS", long_about = "Trigger an OS distribution and image change. Provide a valid `os_id` from the remote API.")]
     ChangeOs { instance_id: String, os_id: String },
+    /// Power many instances on, off or reset, optionally picked by state
+    #[command(about = "Power many instances on/off/reset", long_about = "Apply a power action to several instances, `--concurrency` at a time. Targets are the listed IDs, the instances matching every `--where key=value` condition (keys are ZY_INSTANCE_COLUMNS names such as status, region or hostname; values compare case-insensitively), or both. `--where` is checked against a fresh instance list for `--username`, else ZY_CLI_USER, else the whole account. Locked instances are skipped. Prints a result per instance and exits non-zero if any failed.")]
+    Power {
+        #[arg(value_enum)]
+        action: PowerArg,
+        #[arg(required_unless_present = "filters")]
+        instance_ids: Vec<String>,
+        /// Only act on instances where a column has this value, e.g. status=stopped (repeatable)
+        #[arg(long = "where", value_name = "KEY=VALUE")]
+        filters: Vec<String>,
+        /// With --where, consider the instances this local user can access (defaults to ZY_CLI_USER)
+        #[arg(long, requires = "filters")]
+        username: Option<String>,
+        /// How many instances to change at once (capped by ZY_MAX_CONCURRENCY)
+        #[arg(long, default_value_t = services::bulk_service::DEFAULT_BULK_CONCURRENCY)]
+        concurrency: usize,
+    },
+    /// Reinstall the same OS on many instances
+    #[command(about = "Change the OS of many instances", long_about = "Reinstall `--os-id` on every listed instance, erasing their data. Instances are processed `--concurrency` at a time; locked instances (DISABLED_INSTANCE_IDS, hostname rules, cooldown) and images that don't fit an instance are skipped. Prints a result per instance and exits non-zero if any failed.")]
+    BulkChangeOs {
+        #[arg(required = true)]
+        instance_ids: Vec<String>,
+        #[arg(long)]
+        os_id: String,
+        /// How many instances to change at once (capped by ZY_MAX_CONCURRENCY)
+        #[arg(long, default_value_t = services::bulk_service::DEFAULT_BULK_CONCURRENCY)]
+        concurrency: usize,
+    },
+    /// Resize many instances to the same target
+    #[command(about = "Resize many instances", long_about = "Apply one resize (same flags as `resize`) to every listed instance, `--concurrency` at a time. Locked instances are skipped, as are CUSTOM resizes that would shrink an instance unless `--allow-downsize` is given. Prints a result per instance and exits non-zero if any failed.")]
+    BulkResize {
+        #[arg(required = true)]
+        instance_ids: Vec<String>,
+        #[arg(long)]
+        r#type: String,
+        #[arg(long)]
+        product_id: Option<String>,
+        #[arg(long)]
+        cpu: Option<i64>,
+        #[arg(long)]
+        ram_in_gb: Option<i64>,
+        #[arg(long)]
+        disk_in_gb: Option<i64>,
+        #[arg(long)]
+        bandwidth_in_tb: Option<i64>,
+        /// Go ahead with CUSTOM resizes that shrink CPU, RAM or disk
+        #[arg(long)]
+        allow_downsize: bool,
+        /// How many instances to resize at once (capped by ZY_MAX_CONCURRENCY)
+        #[arg(long, default_value_t = services::bulk_service::DEFAULT_BULK_CONCURRENCY)]
+        concurrency: usize,
+    },
     /// Resize the instance (type: FIXED|CUSTOM — for CUSTOM specify cpu,ram,disk etc.)
-    #[command(about = "Resize an instance", long_about = "Change a plan; specify `--type FIXED` with `--product-id` or `--type CUSTOM` with specific resource values (cpu, ram-in-gb, disk-in-gb, bandwidth-in-tb).")]
-    Resize { instance_id: String, #[arg(long)] r#type: String, #[arg(long)] product_id: Option<String>, #[arg(long)] cpu: Option<i64>, #[arg(long)] ram_in_gb: Option<i64>, #[arg(long)] disk_in_gb: Option<i64>, #[arg(long)] bandwidth_in_tb: Option<i64> },
+    #[command(about = "Resize an instance", long_about = "Change a plan; specify `--type FIXED` with `--product-id` or `--type CUSTOM` with specific resource values (cpu, ram-in-gb, disk-in-gb, bandwidth-in-tb). The type and product are checked against the catalog for the instance's region first; use `--no-validate` to skip the check. `--preview` prints the current and target monthly price of a FIXED resize and the difference, without resizing.")]
+    Resize { instance_id: String, #[arg(long)] r#type: String, #[arg(long)] product_id: Option<String>, #[arg(long)] cpu: Option<i64>, #[arg(long)] ram_in_gb: Option<i64>, #[arg(long)] disk_in_gb: Option<i64>, #[arg(long)] bandwidth_in_tb: Option<i64>,
+        /// Skip checking the type and product against the catalog before sending
+        #[arg(long)]
+        no_validate: bool,
+        /// Allow a CUSTOM resize that shrinks CPU, RAM or disk
+        #[arg(long)]
+        allow_downsize: bool,
+        /// Show the monthly price change of a FIXED resize without resizing
+        #[arg(long)]
+        preview: bool,
+    },
+    /// Count instances by status
+    #[command(about = "Summarize instances by status", long_about = "Print how many instances are in each status (e.g. `12 active, 3 stopped`). Provide `--username` to count only instances a local user can access.")]
+    Summary {
+        /// Optional username to restrict the count to instances the user can access
+        #[arg(long)]
+        username: Option<String>,
+    },
+    /// Assign instances to (or remove them from) a local admin/viewer
+    #[command(about = "Reassign instances to a user", long_about = "Add instance IDs to a local admin or viewer's assigned_instances in users.json, like the /access page. Use `--from <username>` to move them off another user in the same step, or `--remove` to unassign them from `--to` instead.")]
+    Reassign {
+        /// One or more instance IDs
+        #[arg(required = true)]
+        instance_ids: Vec<String>,
+        /// Username receiving (or, with --remove, losing) the instances
+        #[arg(long)]
+        to: String,
+        /// Also unassign the instances from this user
+        #[arg(long, conflicts_with = "remove")]
+        from: Option<String>,
+        /// Unassign the instances from --to instead of assigning them
+        #[arg(long)]
+        remove: bool,
+    },
+    /// Print the instance's serial console output
+    #[command(about = "Show console/serial output", long_about = "Fetch the serial console output for an instance, useful for debugging boot problems. Only the last `--lines` lines are printed (0 prints everything).")]
+    Logs {
+        instance_id: String,
+        /// Number of trailing lines to print (0 for all)
+        #[arg(long, short = 'n', default_value_t = api::DEFAULT_CONSOLE_LINES)]
+        lines: usize,
+    },
+    /// Manage local key=value labels on an instance (labels.json)
+    #[command(about = "Manage local instance labels", long_about = "Add, remove or list local `key=value` labels on an instance. Labels live in labels.json next to users.json (they are not sent to the API), show up on the instance's web page, and can be used to filter `zy instances list --label key=value`.")]
+    Label {
+        #[command(subcommand)]
+        command: LabelCommands,
+    },
+    /// Print the instance's plan, price, renewal date and refund eligibility
+    #[command(about = "Show subscription/billing details", long_about = "Fetch an instance's subscription: plan, status, price and billing cycle, next renewal date and whether a refund can currently be requested. `--json` prints the parsed fields as a JSON object. Exits non-zero when the API has no subscription for the instance.")]
+    Subscription {
+        instance_id: String,
+        /// Print JSON instead of key/value lines
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print the instance's main IPv4 (or IPv6) address
+    #[command(about = "Print an instance's IP address", long_about = "Print only the instance's main IPv4 address, or its main IPv6 address with `--v6`, for use in scripts such as `ssh root@$(zy instances ip 12345)`. Exits non-zero when the instance has no such address.")]
+    Ip {
+        instance_id: String,
+        /// Print the main IPv6 address instead
+        #[arg(long)]
+        v6: bool,
+    },
+    /// Print (or run) an ssh command for the instance's main IP
+    #[command(about = "SSH into an instance", long_about = "Resolve an instance by ID or hostname and print `ssh <user>@<mainIp>`, or run it with `--connect`. The user comes from `--user`, then `ZY_SSH_USER`, then `root`. Exits non-zero when the instance is unknown or has no main IPv4 address.")]
+    Ssh {
+        /// Instance ID or hostname
+        instance: String,
+        /// Login user (overrides ZY_SSH_USER)
+        #[arg(long, short = 'u')]
+        user: Option<String>,
+        /// Run ssh instead of printing the command
+        #[arg(long)]
+        connect: bool,
+    },
     /// Add traffic amount (e.g., 50) to an instance
-    #[command(about = "Add traffic to an instance", long_about = "Add additional traffic capacity to an instance using a numeric `--amount` (e.g., 50).")]
+    #[command(about = "Add traffic to an instance", long_about = "Add additional traffic capacity to an instance using a numeric `amount` argument (e.g., 50).")]
     AddTraffic { instance_id: String, amount: f64 },
 }
 
+/// Power action for `zy instances power`.
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum PowerArg {
+    On,
+    Off,
+    Reset,
+}
+
+#[derive(Subcommand)]
+enum LabelCommands {
+    /// Set one or more labels, replacing existing values for the same keys
+    #[command(about = "Add or update labels", long_about = "Set `key=value` labels on an instance; an existing value for the same key is replaced. Keys may contain letters, digits, '.', '_', '-' and '/'.")]
+    Add {
+        instance_id: String,
+        #[arg(required = true, value_name = "KEY=VALUE")]
+        labels: Vec<String>,
+    },
+    /// Remove labels by key
+    #[command(about = "Remove labels", long_about = "Remove labels from an instance by key. Exits non-zero if none of the keys were set.")]
+    Rm {
+        instance_id: String,
+        #[arg(required = true, value_name = "KEY")]
+        keys: Vec<String>,
+    },
+    /// Print an instance's labels as key=value lines
+    #[command(about = "List labels", long_about = "Print an instance's labels, one `key=value` per line in key order.")]
+    Ls { instance_id: String },
+}
+
+/// Whether CLI, update and log output may use ANSI colors: not with
+/// `--no-color`, a non-empty `NO_COLOR` (see no-color.org), or when stdout is
+/// redirected to a file or pipe.
+fn color_enabled(no_color_flag: bool, no_color_env: Option<&str>, stdout_is_tty: bool) -> bool {
+    !no_color_flag && no_color_env.is_none_or(str::is_empty) && stdout_is_tty
+}
+
+/// Notice a `.bak` / `.old` left by an update that was killed part-way, and
+/// restore it when the installed binary is broken and `--recover-update` is set.
+fn report_interrupted_update(recover: bool) {
+    let Ok(exe) = update::get_current_executable() else {
+        return;
+    };
+    match update::check_interrupted_update(&exe, recover) {
+        Ok(update::InterruptedUpdate::Clean) => {}
+        Ok(update::InterruptedUpdate::RemovedStaleBackup(backup)) => {
+            eprintln!(
+                "{}",
+                yansi::Paint::new(format!("Removed {} left by an interrupted update; {} is intact.", backup.display(), exe.display())).yellow()
+            );
+        }
+        Ok(update::InterruptedUpdate::RecoveryNeeded { backup, reason }) => {
+            eprintln!(
+                "{}",
+                yansi::Paint::new(format!(
+                    "{} looks broken after an interrupted update ({}). Run any command with --recover-update to restore {}.",
+                    exe.display(),
+                    reason,
+                    backup.display()
+                ))
+                .red()
+            );
+        }
+        Ok(update::InterruptedUpdate::Recovered { backup, reason }) => {
+            eprintln!(
+                "{}",
+                yansi::Paint::new(format!(
+                    "Recovered {} from {} after an interrupted update ({}). Run `zy update` again to retry.",
+                    exe.display(),
+                    backup.display(),
+                    reason
+                ))
+                .green()
+            );
+        }
+        Err(e) => {
+            eprintln!("{}", yansi::Paint::new(format!("Recovery from interrupted update failed: {}", e)).red());
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() {
-    // Initialize tracing
-    tracing_subscriber::registry()
-        .with(fmt::layer())
-        .with(EnvFilter::from_default_env())
-        .init();
-
     // CLI parsing
     let cli = Cli::parse();
 
-    if cli.no_color {
+    let color = color_enabled(
+        cli.no_color,
+        std::env::var("NO_COLOR").ok().as_deref(),
+        std::io::IsTerminal::is_terminal(&std::io::stdout()),
+    );
+    if !color {
         yansi::whenever(yansi::Condition::NEVER);
     }
 
+    // Initialize tracing
+    tracing_subscriber::registry()
+        .with(fmt::layer().with_ansi(color))
+        .with(EnvFilter::from_default_env())
+        .init();
+
     if cli.silent {
         crate::api::client::set_silent(true);
     }
 
-    // If CLI provided an env-file or not, we will load it per command below
-    // Note: we avoid constructing a default `state` here; commands build the per-command state
-    // using `build_state_from_env` so we can pass a custom `--env-file` when executing commands.
+    report_interrupted_update(cli.recover_update);
+
+    // Set before any .env file loads, so the flags win over it
+    if let Some(path) = &cli.ca_cert {
+        std::env::set_var("API_CA_CERT", path);
+    }
+    if cli.insecure {
+        std::env::set_var("API_TLS_INSECURE", "1");
+    }
+    if let Some(url) = &cli.api_base_url {
+        std::env::set_var("API_BASE_URL", url);
+    }
+    if let Some(path) = &cli.api_token_file {
+        match config::read_api_token_file(path) {
+            Ok(token) => std::env::set_var("API_TOKEN", token),
+            Err(e) => {
+                eprintln!("{}", yansi::Paint::new(e).red());
+                process::exit(1);
+            }
+        }
+    }
+    if let Some(token) = &cli.api_token {
+        std::env::set_var("API_TOKEN", token);
+    }
+
+    // Commands build their own state so a per-command `--env-file` can override
+    // the top-level one.
+    let global_env_file = cli.env_file.clone();
 
     // Dispatch CLI commands. If no command provided, serve the web app by default
     if cli.command.is_none() {
-        let state = build_state_from_env(None).await;
-        start_server(state, DEFAULT_HOST, DEFAULT_PORT, None).await;
+        let state = build_state_from_env(global_env_file.as_deref()).await;
+        start_server(state, DEFAULT_HOST, DEFAULT_PORT, None, None, false).await;
         return;
     }
     match cli.command.unwrap() {
@@ -501,13 +1259,18 @@ async fn main() {
             port,
             env_file,
             stylesheet,
+            maintenance,
+            tls_cert,
+            tls_key,
+            require_valid_token,
         } => {
-            let state = build_state_from_env(env_file.as_deref()).await;
-            start_server(state, &host, port, stylesheet).await;
+            let mut state = build_state_from_env(env_file.or(global_env_file).as_deref()).await;
+            state.maintenance_mode |= maintenance;
+            start_server(state, &host, port, stylesheet, tls_cert.zip(tls_key), require_valid_token).await;
             return;
         }
         Commands::CheckConfig { env_file } => {
-            let state = build_state_from_env(env_file.as_deref()).await;
+            let state = build_state_from_env(env_file.or(global_env_file).as_deref()).await;
             // Basic check: ensure API base and token exist; optionally ping regions
             let mut ok = true;
             if state.api_base_url.trim().is_empty() {
@@ -535,20 +1298,28 @@ async fn main() {
                 process::exit(1);
             }
         }
+        Commands::Doctor => {
+            let state = build_state_from_env(global_env_file.as_deref()).await;
+            api::client::set_silent(true);
+            let checks = doctor::run_checks(&state).await;
+            if !doctor::print_report(&checks) {
+                process::exit(1);
+            }
+        }
         Commands::Users { sub } => {
-            let state = build_state_from_env(None).await;
+            let state = build_state_from_env(global_env_file.as_deref()).await;
             match sub {
-                UserCommands::List => {
+                UserCommands::List { format } => {
                     let users = state.users.lock().unwrap();
-                    println!("{}", yansi::Paint::new("username\trole\tassigned_instances").bold().underline());
-                    for (u, rec) in users.iter() {
-                        let assigned = if rec.assigned_instances.is_empty() {
-                            String::new()
-                        } else {
-                            rec.assigned_instances.join(", ")
-                        };
-                        println!("{}\t{}\t{}", u, rec.role, assigned);
-                    }
+                    let mut names: Vec<&String> = users.keys().collect();
+                    names.sort();
+                    let views: Vec<models::UserView> = names.iter().map(|u| models::UserView::from_record(u, &users[*u])).collect();
+                    let rows = views
+                        .iter()
+                        .map(|v| vec![v.username.clone(), v.role.clone(), v.assigned(), v.last_login.clone().unwrap_or_default()])
+                        .collect();
+                    let json = views.iter().map(|v| serde_json::to_value(v).unwrap_or_default()).collect();
+                    print_list(format, &["username", "role", "assigned_instances", "last_login"], rows, json);
                     return;
                 }
                 UserCommands::Add {
@@ -557,22 +1328,24 @@ async fn main() {
                     role,
                 } => {
                     let uname = username.trim().to_lowercase();
-                    let mut users = state.users.lock().unwrap();
-                    if users.contains_key(&uname) {
-                        eprintln!("{} '{}' {}", yansi::Paint::new("User").red(), uname, yansi::Paint::new("already exists").red());
-                        process::exit(1);
+                    {
+                        let mut users = state.users.lock().unwrap();
+                        if users.contains_key(&uname) {
+                            eprintln!("{} '{}' {}", yansi::Paint::new("User").red(), uname, yansi::Paint::new("already exists").red());
+                            process::exit(1);
+                        }
+                        let hash = generate_password_hash(&password, state.password_scheme);
+                        users.insert(
+                            uname.clone(),
+                            UserRecord {
+                                password: hash,
+                                role: role.clone(),
+                                assigned_instances: vec![],
+                                about: String::new(),
+                                last_login: None,
+                            },
+                        );
                     }
-                    let hash = generate_password_hash(&password);
-                    users.insert(
-                        uname.clone(),
-                        UserRecord {
-                            password: hash,
-                            role: role.clone(),
-                            assigned_instances: vec![],
-                            about: String::new(),
-                        },
-                    );
-                    drop(users);
                     if let Err(e) = persist_users_file(&state.users).await {
                         eprintln!("{}: {}", yansi::Paint::new("Failed to persist users.json").red(), e);
                         process::exit(1);
@@ -582,14 +1355,15 @@ async fn main() {
                 }
                 UserCommands::ResetPassword { username, password } => {
                     let uname = username.trim().to_lowercase();
-                    let mut users = state.users.lock().unwrap();
-                    if let Some(rec) = users.get_mut(&uname) {
-                        rec.password = generate_password_hash(&password);
-                    } else {
-                        eprintln!("{} '{}' {}", yansi::Paint::new("User").red(), uname, yansi::Paint::new("not found").red());
-                        process::exit(1);
+                    {
+                        let mut users = state.users.lock().unwrap();
+                        if let Some(rec) = users.get_mut(&uname) {
+                            rec.password = generate_password_hash(&password, state.password_scheme);
+                        } else {
+                            eprintln!("{} '{}' {}", yansi::Paint::new("User").red(), uname, yansi::Paint::new("not found").red());
+                            process::exit(1);
+                        }
                     }
-                    drop(users);
                     if let Err(e) = persist_users_file(&state.users).await {
                         eprintln!("{}: {}", yansi::Paint::new("Failed to persist users.json").red(), e);
                         process::exit(1);
@@ -603,31 +1377,33 @@ async fn main() {
                     force,
                 } => {
                     let uname = username.trim().to_lowercase();
-                    let mut users = state.users.lock().unwrap();
-                    // If an owner already exists and we're not forcing, error out
-                    let owner_exists = users.values().any(|r| r.role == "owner");
-                    if owner_exists && !force {
-                        eprintln!(
-                            "{}", yansi::Paint::new("An owner user already exists; use --force to create another owner or overwrite").red()
+                    {
+                        let mut users = state.users.lock().unwrap();
+                        // If an owner already exists and we're not forcing, error out
+                        let owner_exists = users.values().any(|r| r.role == "owner");
+                        if owner_exists && !force {
+                            eprintln!(
+                                "{}", yansi::Paint::new("An owner user already exists; use --force to create another owner or overwrite").red()
+                            );
+                            process::exit(1);
+                        }
+                        // If the username exists and force is not set, fail (consistent with `Add` semantics)
+                        if users.contains_key(&uname) && !force {
+                            eprintln!("{} '{}' {}; {}", yansi::Paint::new("User").red(), uname, yansi::Paint::new("already exists").red(), yansi::Paint::new("use --force to overwrite").yellow());
+                            process::exit(1);
+                        }
+                        let hash = generate_password_hash(&password, state.password_scheme);
+                        users.insert(
+                            uname.clone(),
+                            UserRecord {
+                                password: hash,
+                                role: "owner".to_string(),
+                                assigned_instances: vec![],
+                                about: String::new(),
+                                last_login: None,
+                            },
                         );
-                        process::exit(1);
-                    }
-                    // If the username exists and force is not set, fail (consistent with `Add` semantics)
-                    if users.contains_key(&uname) && !force {
-                        eprintln!("{} '{}' {}; {}", yansi::Paint::new("User").red(), uname, yansi::Paint::new("already exists").red(), yansi::Paint::new("use --force to overwrite").yellow());
-                        process::exit(1);
                     }
-                    let hash = generate_password_hash(&password);
-                    users.insert(
-                        uname.clone(),
-                        UserRecord {
-                            password: hash,
-                            role: "owner".to_string(),
-                            assigned_instances: vec![],
-                            about: String::new(),
-                        },
-                    );
-                    drop(users);
                     if let Err(e) = persist_users_file(&state.users).await {
                         eprintln!("{}: {}", yansi::Paint::new("Failed to persist users.json").red(), e);
                         process::exit(1);
@@ -638,12 +1414,62 @@ async fn main() {
             }
         }
         Commands::Instances { sub } => {
-            let state = build_state_from_env(None).await;
+            let state = build_state_from_env(global_env_file.as_deref()).await;
             match sub {
-                InstanceCommands::List { username, page, per_page } => {
-                    let uname = username.unwrap_or_default();
-                    let paginated = handlers::helpers::load_instances_for_user_paginated(&state, &uname, page, per_page).await;
-                    
+                InstanceCommands::List { username, all, workspace, labels, page, per_page, format } => {
+                    if format.is_machine_readable() {
+                        api::client::set_silent(true);
+                    }
+                    let scope = {
+                        let users = state.users.lock().unwrap();
+                        services::resolve_list_scope(all, username.as_deref(), config::get_cli_user().as_deref(), &users)
+                    };
+                    let uname = match scope {
+                        Ok(name) => name,
+                        Err(e) => {
+                            eprintln!("{}", yansi::Paint::new(e).red());
+                            process::exit(1);
+                        }
+                    };
+                    let label_filters = match labels.iter().map(|l| services::label_service::parse_label(l)).collect::<Result<Vec<_>, _>>() {
+                        Ok(filters) => filters,
+                        Err(e) => {
+                            eprintln!("{}", yansi::Paint::new(e).red());
+                            process::exit(1);
+                        }
+                    };
+                    let workspace_ids = workspace.map(|slug| {
+                        let users = state.users.lock().unwrap();
+                        let workspaces = state.workspaces.lock().unwrap();
+                        services::workspace_instance_ids(&slug, &uname, &users, &workspaces).unwrap_or_else(|e| {
+                            eprintln!("{}", yansi::Paint::new(e).red());
+                            process::exit(1);
+                        })
+                    });
+                    let paginated = if workspace_ids.is_none() && label_filters.is_empty() {
+                        handlers::helpers::load_instances_for_user_paginated(&state, &uname, page, per_page).await
+                    } else {
+                        let all = handlers::helpers::load_instances_for_user_paginated(&state, &uname, 0, 0).await;
+                        let instances = {
+                            let labels = state.labels.lock().unwrap();
+                            all.instances
+                                .into_iter()
+                                .filter(|i| workspace_ids.as_ref().is_none_or(|ids| ids.contains(&i.id)))
+                                .filter(|i| services::label_service::matches_labels(labels.get(&i.id), &label_filters))
+                                .collect()
+                        };
+                        api::paginate_instances(instances, page, per_page)
+                    };
+
+                    let columns = state.cli_instance_columns();
+                    let headers: Vec<&str> = columns.iter().map(|c| c.header()).collect();
+                    if !matches!(format, ListFormat::Table) {
+                        let rows = paginated.instances.iter().map(|i| i.list_row(&columns, "")).collect();
+                        let json = paginated.instances.iter().map(|i| serde_json::to_value(i).unwrap_or_default()).collect();
+                        print_list(format, &headers, rows, json);
+                        return;
+                    }
+
                     let mut table = Table::new();
                     table.load_preset(presets::UTF8_FULL);
                     table.apply_modifier(modifiers::UTF8_ROUND_CORNERS);
@@ -651,9 +1477,9 @@ async fn main() {
                     if let Some((Width(w), _)) = terminal_size() {
                         table.set_width(w - 4);
                     }
-                    table.set_header(vec!["ID", "Hostname", "Status"]);
+                    table.set_header(headers);
                     for i in &paginated.instances {
-                        table.add_row(vec![&i.id, &i.hostname, &i.status]);
+                        table.add_row(i.list_row(&columns, "—"));
                     }
                     println!("\n{table}");
                     
@@ -690,31 +1516,122 @@ async fn main() {
                     println!();
                     return;
                 }
-                InstanceCommands::Show { instance_id } => {
+                InstanceCommands::Summary { username } => {
+                    let uname = username.unwrap_or_default();
+                    let all = handlers::helpers::load_instances_for_user_paginated(&state, &uname, 0, 0).await;
+                    let mut table = Table::new();
+                    table.load_preset(presets::UTF8_FULL);
+                    table.apply_modifier(modifiers::UTF8_ROUND_CORNERS);
+                    table.set_header(vec!["Status", "Count"]);
+                    for (status, count) in &all.status_counts {
+                        table.add_row(vec![status.clone(), count.to_string()]);
+                    }
+                    println!("\n{table}");
+                    println!("\n{}\n", yansi::Paint::new(api::format_status_summary(&all.status_counts)).cyan());
+                    return;
+                }
+                InstanceCommands::Reassign { instance_ids, to, from, remove } => {
+                    let target = to.trim().to_lowercase();
+                    let source = from.map(|f| f.trim().to_lowercase());
+                    let result = {
+                        let mut users = state.users.lock().unwrap();
+                        services::reassign_instances(&mut users, &instance_ids, &target, source.as_deref(), remove)
+                    };
+                    if let Err(e) = result {
+                        eprintln!("{}", yansi::Paint::new(e).red());
+                        process::exit(1);
+                    }
+                    if let Err(e) = persist_users_file(&state.users).await {
+                        eprintln!("{}: {}", yansi::Paint::new("Failed to persist users.json").red(), e);
+                        process::exit(1);
+                    }
+                    let verb = if remove { "unassigned from" } else { "assigned to" };
+                    println!("{} {} '{}'", yansi::Paint::new(format!("{} instance(s)", instance_ids.len())).green(), verb, target);
+                    return;
+                }
+                InstanceCommands::Show { instance_id: None, username, concurrency, .. } => {
+                    // The JSON dump is the whole output
+                    api::client::set_silent(true);
+                    let cli_user = config::get_cli_user();
+                    let scope = {
+                        let users = state.users.lock().unwrap();
+                        let whole_account = username.is_none() && cli_user.is_none();
+                        services::resolve_list_scope(whole_account, username.as_deref(), cli_user.as_deref(), &users)
+                    };
+                    let uname = match scope {
+                        Ok(name) => name,
+                        Err(e) => {
+                            eprintln!("{}", yansi::Paint::new(e).red());
+                            process::exit(1);
+                        }
+                    };
+                    let ids: Vec<String> = handlers::helpers::load_instances_for_user_paginated(&state, &uname, 0, 0)
+                        .await
+                        .instances
+                        .into_iter()
+                        .map(|i| i.id)
+                        .collect();
+                    let details: Vec<(String, serde_json::Value)> = stream::iter(ids)
+                        .map(|id| {
+                            let state = &state;
+                            async move {
+                                let endpoint = format!("/v1/instances/{}", id);
+                                let payload = api_call_wrapper(state, "GET", &endpoint, None, None).await;
+                                (id, payload)
+                            }
+                        })
+                        .buffered(state.fan_out(concurrency as usize))
+                        .collect()
+                        .await;
+                    let mut failed = 0;
+                    let dump: Vec<serde_json::Value> = details
+                        .into_iter()
+                        .map(|(id, payload)| {
+                            if handlers::helpers::is_api_success(&payload) {
+                                payload.get("data").cloned().unwrap_or(serde_json::Value::Null)
+                            } else {
+                                failed += 1;
+                                let error = payload
+                                    .get("detail")
+                                    .or_else(|| payload.get("error"))
+                                    .and_then(|e| e.as_str())
+                                    .unwrap_or("Unknown error");
+                                serde_json::json!({ "id": id, "error": error })
+                            }
+                        })
+                        .collect();
+                    println!("{}", serde_json::to_string_pretty(&dump).unwrap_or_else(|_| "[]".into()));
+                    if failed > 0 {
+                        eprintln!("{}", yansi::Paint::new(format!("{} of {} instance(s) could not be fetched", failed, dump.len())).red());
+                        process::exit(1);
+                    }
+                    return;
+                }
+                InstanceCommands::Show { instance_id: Some(instance_id), .. } => {
                     let endpoint = format!("/v1/instances/{}", instance_id);
                     let payload = api_call_wrapper(&state, "GET", &endpoint, None, None).await;
-                    print_api_response(&payload);
+                    print_api_result(&payload, "Show instance");
                     return;
                 }
                 InstanceCommands::PowerOn { instance_id } => {
                     let payload = simple_instance_action(&state, "poweron", &instance_id).await;
-                    print_api_response(&payload);
+                    print_api_result(&payload, "Power on");
                     return;
                 }
                 InstanceCommands::PowerOff { instance_id } => {
                     let payload = simple_instance_action(&state, "poweroff", &instance_id).await;
-                    print_api_response(&payload);
+                    print_api_result(&payload, "Power off");
                     return;
                 }
                 InstanceCommands::Reset { instance_id } => {
                     let payload = simple_instance_action(&state, "reset", &instance_id).await;
-                    print_api_response(&payload);
+                    print_api_result(&payload, "Reset");
                     return;
                 }
                 InstanceCommands::Delete { instance_id } => {
                     let endpoint = format!("/v1/instances/{}", instance_id);
                     let payload = api_call_wrapper(&state, "DELETE", &endpoint, None, None).await;
-                    print_api_response(&payload);
+                    print_api_result(&payload, "Delete");
                     return;
                 }
                 InstanceCommands::ChangePass { instance_id } => {
@@ -723,52 +1640,286 @@ async fn main() {
                     if let Some(pass) = payload.get("data").and_then(|d| d.get("password")).and_then(|v| v.as_str()) {
                         println!("{} {}: {}", yansi::Paint::new("New password for").green(), instance_id, yansi::Paint::new(pass).cyan());
                     } else {
-                        print_api_response(&payload);
+                        print_api_result(&payload, "Change password");
                     }
                     return;
                 }
                 InstanceCommands::ChangeOs { instance_id, os_id } => {
                     let endpoint = format!("/v1/instances/{}/change-os", instance_id);
                     let payload = serde_json::json!({"osId": os_id});
-                    let resp = api_call_wrapper(&state, "POST", &endpoint, Some(payload), None).await;
-                    print_api_response(&resp);
+                    let resp = api::timeout::scope(api::timeout::long_operation_timeout(), api_call_wrapper(&state, "POST", &endpoint, Some(payload), None)).await;
+                    print_api_result(&resp, "Change OS");
                     return;
                 }
-                InstanceCommands::Resize { instance_id, r#type, product_id, cpu, ram_in_gb, disk_in_gb, bandwidth_in_tb } => {
+                InstanceCommands::Resize { instance_id, r#type, product_id, cpu, ram_in_gb, disk_in_gb, bandwidth_in_tb, no_validate, allow_downsize, preview } => {
+                    if preview {
+                        let Some(product_id) = product_id.as_deref().filter(|_| r#type.eq_ignore_ascii_case("FIXED")) else {
+                            eprintln!("{}", yansi::Paint::new("--preview needs --type FIXED and --product-id").red());
+                            process::exit(1);
+                        };
+                        match services::instance_service::preview_resize(&state, &instance_id, product_id).await {
+                            Ok(p) => {
+                                println!("Current plan: {}", p.current_display());
+                                println!("Target plan:  {}", p.target_display());
+                                println!("Change:       {}", p.delta_display());
+                            }
+                            Err(e) => {
+                                eprintln!("{}: {}", yansi::Paint::new("Resize preview failed").red(), e);
+                                process::exit(1);
+                            }
+                        }
+                        return;
+                    }
+                    if !no_validate {
+                        if let Err(e) = services::instance_service::validate_resize(&state, &instance_id, &r#type, product_id.as_deref()).await {
+                            eprintln!("{}: {}", yansi::Paint::new("Resize validation failed").red(), e);
+                            eprintln!("{}", yansi::Paint::new("Pass --no-validate to send the request anyway.").dim());
+                            process::exit(1);
+                        }
+                    }
+                    if r#type.eq_ignore_ascii_case("CUSTOM") && !allow_downsize {
+                        let changes = match services::instance_service::custom_resize_downsizes(&state, &instance_id, cpu, ram_in_gb, disk_in_gb).await {
+                            Ok(changes) => changes,
+                            Err(e) => {
+                                eprintln!("{}: {}", yansi::Paint::new("Resize refused").red(), e);
+                                eprintln!("{}", yansi::Paint::new("Pass --allow-downsize to proceed without the check.").dim());
+                                process::exit(1);
+                            }
+                        };
+                        if !changes.is_empty() {
+                            eprintln!("{}: {}", yansi::Paint::new("Resize would shrink").red(), changes.join(", "));
+                            eprintln!("{}", yansi::Paint::new("Pass --allow-downsize to proceed anyway.").dim());
+                            process::exit(1);
+                        }
+                    }
                     let endpoint = format!("/v1/instances/{}/resize", instance_id);
-                    let mut payload = serde_json::json!({"type": r#type});
-                    if payload.get("type").and_then(|t| t.as_str()).unwrap_or("") == "FIXED" {
-                        if let Some(pid) = product_id {
-                            payload["productId"] = serde_json::Value::from(pid);
+                    let spec = services::bulk_service::ResizeSpec { resize_type: r#type, product_id, cpu, ram_in_gb, disk_in_gb, bandwidth_in_tb, allow_downsize };
+                    let resp = api::timeout::scope(api::timeout::long_operation_timeout(), api_call_wrapper(&state, "POST", &endpoint, Some(spec.payload()), None)).await;
+                    print_api_result(&resp, "Resize");
+                    return;
+                }
+                InstanceCommands::Power { action, instance_ids, filters, username, concurrency } => {
+                    use services::bulk_service::{select_targets, BulkAction, InstanceFilter, PowerAction};
+                    let action = BulkAction::Power(match action {
+                        PowerArg::On => PowerAction::On,
+                        PowerArg::Off => PowerAction::Off,
+                        PowerArg::Reset => PowerAction::Reset,
+                    });
+                    let filters = match filters.iter().map(|f| InstanceFilter::parse(f)).collect::<Result<Vec<_>, _>>() {
+                        Ok(filters) => filters,
+                        Err(e) => {
+                            eprintln!("{}", yansi::Paint::new(e).red());
+                            process::exit(1);
                         }
+                    };
+                    let targets = if filters.is_empty() {
+                        instance_ids
                     } else {
-                        // CUSTOM resize: build extraResource with all fields
-                        let mut obj = serde_json::Map::new();
-                        if let Some(cpu) = cpu { obj.insert("cpu".into(), serde_json::Value::from(cpu)); }
-                        if let Some(ram) = ram_in_gb { obj.insert("ramInGB".into(), serde_json::Value::from(ram)); }
-                        if let Some(disk) = disk_in_gb { obj.insert("diskInGB".into(), serde_json::Value::from(disk)); }
-                        if let Some(bw) = bandwidth_in_tb { obj.insert("bandwidthInTB".into(), serde_json::Value::from(bw)); }
-                        if !obj.is_empty() { payload["extraResource"] = serde_json::Value::Object(obj); }
+                        let cli_user = config::get_cli_user();
+                        let scope = {
+                            let users = state.users.lock().unwrap();
+                            let whole_account = username.is_none() && cli_user.is_none();
+                            services::resolve_list_scope(whole_account, username.as_deref(), cli_user.as_deref(), &users)
+                        };
+                        let uname = match scope {
+                            Ok(name) => name,
+                            Err(e) => {
+                                eprintln!("{}", yansi::Paint::new(e).red());
+                                process::exit(1);
+                            }
+                        };
+                        // Decide on current state, not a cached list
+                        let Some(all) = api::fetch_all_instances(&state.client, &state.api_base_url, &state.api_token).await else {
+                            eprintln!("{}", yansi::Paint::new("Could not fetch the full instance list; refusing to pick targets from a partial list.").red());
+                            process::exit(1);
+                        };
+                        let listed = {
+                            let users = state.users.lock().unwrap();
+                            let workspaces = state.workspaces.lock().unwrap();
+                            api::filter_instances_for_user(all, &users, &workspaces, &uname)
+                        };
+                        select_targets(&listed, &instance_ids, &filters)
+                    };
+                    if targets.is_empty() {
+                        println!("{}", yansi::Paint::new("No instances match; nothing to do.").dim());
+                        return;
                     }
-                    let resp = api_call_wrapper(&state, "POST", &endpoint, Some(payload), None).await;
-                    print_api_response(&resp);
+                    eprintln!("{} {} instance(s): {}", action.label(), targets.len(), targets.join(", "));
+                    print_bulk_results(&state, &targets, &action, concurrency).await;
+                    return;
+                }
+                InstanceCommands::BulkChangeOs { instance_ids, os_id, concurrency } => {
+                    let action = services::bulk_service::BulkAction::ChangeOs { os_id };
+                    print_bulk_results(&state, &instance_ids, &action, concurrency).await;
                     return;
                 }
+                InstanceCommands::BulkResize { instance_ids, r#type, product_id, cpu, ram_in_gb, disk_in_gb, bandwidth_in_tb, allow_downsize, concurrency } => {
+                    let resize_type = r#type.to_uppercase();
+                    if resize_type != "FIXED" && resize_type != "CUSTOM" {
+                        eprintln!("{}", yansi::Paint::new("--type must be FIXED or CUSTOM").red());
+                        process::exit(1);
+                    }
+                    let spec = services::bulk_service::ResizeSpec { resize_type, product_id, cpu, ram_in_gb, disk_in_gb, bandwidth_in_tb, allow_downsize };
+                    print_bulk_results(&state, &instance_ids, &services::bulk_service::BulkAction::Resize(spec), concurrency).await;
+                    return;
+                }
+                InstanceCommands::Logs { instance_id, lines } => {
+                    match api::load_instance_console(&state.client, &state.api_base_url, &state.api_token, &instance_id, lines).await {
+                        Some(console) => {
+                            if console.truncated {
+                                eprintln!("{}", yansi::Paint::new(format!(
+                                    "(showing last {} of {} lines; use --lines 0 for everything)",
+                                    console.lines.len(),
+                                    console.total_lines
+                                )).dim());
+                            }
+                            for line in &console.lines {
+                                println!("{}", line);
+                            }
+                        }
+                        None => {
+                            eprintln!("{} {}", yansi::Paint::new("No console output available for instance").red(), instance_id);
+                            process::exit(1);
+                        }
+                    }
+                    return;
+                }
+                InstanceCommands::Label { command } => {
+                    use services::label_service::{label_strings, parse_label, parse_label_key, remove_label, set_label};
+                    let message = match command {
+                        LabelCommands::Ls { instance_id } => {
+                            let labels = state.labels.lock().unwrap();
+                            for label in label_strings(labels.get(&instance_id)) {
+                                println!("{}", label);
+                            }
+                            return;
+                        }
+                        LabelCommands::Add { instance_id, labels } => {
+                            let parsed = match labels.iter().map(|l| parse_label(l)).collect::<Result<Vec<_>, _>>() {
+                                Ok(parsed) => parsed,
+                                Err(e) => {
+                                    eprintln!("{}", yansi::Paint::new(e).red());
+                                    process::exit(1);
+                                }
+                            };
+                            let mut all = state.labels.lock().unwrap();
+                            for (key, value) in &parsed {
+                                set_label(&mut all, &instance_id, key, value);
+                            }
+                            format!("Labels updated on {}", instance_id)
+                        }
+                        LabelCommands::Rm { instance_id, keys } => {
+                            let keys = match keys.iter().map(|k| parse_label_key(k)).collect::<Result<Vec<_>, _>>() {
+                                Ok(keys) => keys,
+                                Err(e) => {
+                                    eprintln!("{}", yansi::Paint::new(e).red());
+                                    process::exit(1);
+                                }
+                            };
+                            let mut all = state.labels.lock().unwrap();
+                            let removed = keys.iter().filter(|k| remove_label(&mut all, &instance_id, k)).count();
+                            if removed == 0 {
+                                eprintln!("{} {}", yansi::Paint::new("No such label on instance").red(), instance_id);
+                                process::exit(1);
+                            }
+                            format!("Removed {} label(s) from {}", removed, instance_id)
+                        }
+                    };
+                    if let Err(e) = services::persist_labels_file(&state.labels).await {
+                        eprintln!("{}: {}", yansi::Paint::new("Failed to persist labels.json").red(), e);
+                        process::exit(1);
+                    }
+                    println!("{}", yansi::Paint::new(message).green());
+                    return;
+                }
+                InstanceCommands::Subscription { instance_id, json } => {
+                    if json {
+                        api::client::set_silent(true);
+                    }
+                    let Some(sub) = api::load_subscription(&state.client, &state.api_base_url, &state.api_token, &instance_id).await else {
+                        eprintln!("{} {}", yansi::Paint::new("No subscription found for instance").red(), instance_id);
+                        process::exit(1);
+                    };
+                    if json {
+                        println!("{}", serde_json::to_string_pretty(&sub).unwrap_or_default());
+                        return;
+                    }
+                    let renews = sub
+                        .renews_at
+                        .as_deref()
+                        .map(|raw| {
+                            let shown = utils::display_time(raw, chrono::Utc::now());
+                            format!("{} ({})", shown.absolute, shown.relative)
+                        })
+                        .unwrap_or_else(|| "—".into());
+                    println!("Plan:            {}", sub.plan);
+                    println!("Status:          {}", sub.status.as_deref().unwrap_or("—"));
+                    println!("Price:           {}", sub.price_display());
+                    println!("Renews:          {}", renews);
+                    println!("Refund eligible: {}", sub.refund_display());
+                    return;
+                }
+                InstanceCommands::Ip { instance_id, v6 } => {
+                    // Only the address may reach stdout, for `ssh root@$(zy instances ip ...)`
+                    api::client::set_silent(true);
+                    let endpoint = format!("/v1/instances/{}", instance_id);
+                    let payload = api_call_wrapper(&state, "GET", &endpoint, None, None).await;
+                    exit_on_api_failure(&payload, "Show instance");
+                    let Some(ip) = api::parse_main_ip(&payload, v6) else {
+                        let family = if v6 { "IPv6" } else { "IPv4" };
+                        eprintln!("{} {}", yansi::Paint::new(format!("No main {} address for instance", family)).red(), instance_id);
+                        process::exit(1);
+                    };
+                    println!("{}", ip);
+                    return;
+                }
+                InstanceCommands::Ssh { instance, user, connect } => {
+                    // The printed command must be safe to pipe or eval
+                    api::client::set_silent(true);
+                    let Some(instances) = api::fetch_all_instances(&state.client, &state.api_base_url, &state.api_token).await else {
+                        eprintln!("{}", yansi::Paint::new("Failed to load instances").red());
+                        process::exit(1);
+                    };
+                    let Some(found) = api::find_instance(&instances, &instance) else {
+                        eprintln!("{} {}", yansi::Paint::new("No instance with ID or hostname").red(), instance);
+                        process::exit(1);
+                    };
+                    let Some(ip) = found.main_ip.as_deref().map(str::trim).filter(|ip| !ip.is_empty()) else {
+                        eprintln!("{} {}", yansi::Paint::new("No main IPv4 address for instance").red(), found.id);
+                        process::exit(1);
+                    };
+                    let target = format!("{}@{}", user.unwrap_or_else(config::get_ssh_user), ip);
+                    if !connect {
+                        println!("ssh {}", target);
+                        return;
+                    }
+                    match std::process::Command::new("ssh").arg(&target).status() {
+                        Ok(status) => process::exit(status.code().unwrap_or(1)),
+                        Err(e) => {
+                            eprintln!("{}: {}", yansi::Paint::new("Failed to run ssh").red(), e);
+                            process::exit(1);
+                        }
+                    }
+                }
                 InstanceCommands::AddTraffic { instance_id, amount } => {
                     let endpoint = format!("/v1/instances/{}/add-traffic", instance_id);
                     let payload = serde_json::json!({"amount": amount});
                     let resp = api_call_wrapper(&state, "POST", &endpoint, Some(payload), None).await;
-                    print_api_response(&resp);
+                    print_api_result(&resp, "Add traffic");
                     return;
                 }
             }
         }
-        Commands::Update { channel, force } => {
-            let channel = match channel.to_lowercase().as_str() {
-                "beta" => update::Channel::Beta,
-                "alpha" => update::Channel::Alpha,
-                "rc" => update::Channel::ReleaseCandidate,
-                _ => update::Channel::Stable,
+        Commands::Update { channel, force, quiet } => {
+            if quiet {
+                update::set_quiet(true);
+            }
+            let channel = match channel.as_deref().map(str::to_lowercase).as_deref() {
+                Some("beta") => update::Channel::Beta,
+                Some("alpha") => update::Channel::Alpha,
+                Some("rc") => update::Channel::ReleaseCandidate,
+                Some(_) => update::Channel::Stable,
+                None => update::Channel::for_current_version(),
             };
 
             match update::check_for_update(channel).await {
@@ -817,7 +1968,8 @@ async fn main() {
                     }
                 }
                 Ok(None) => {
-                    // Already on latest version - message already printed by check_for_update
+                    // The final result is printed even with --quiet, like the Some arm
+                    println!("{}", yansi::Paint::new("You are already running the latest version.").green());
                 }
                 Err(e) => {
                     eprintln!("{}: {}", yansi::Paint::new("Error checking for updates").red(), e);
@@ -826,8 +1978,90 @@ async fn main() {
             }
             return;
         }
+        Commands::Regions { sub } => match sub {
+            RegionCommands::List { all, format } => {
+                let state = build_state_from_env(global_env_file.as_deref()).await;
+                if format.is_machine_readable() {
+                    api::client::set_silent(true);
+                }
+                let (regions, _) = api::load_regions(&state.client, &state.api_base_url, &state.api_token).await;
+                let regions: Vec<models::Region> = regions.into_iter().filter(|r| all || r.is_available()).collect();
+                let yes_no = |b: bool| if b { "yes".to_string() } else { "no".to_string() };
+                let rows = regions
+                    .iter()
+                    .map(|r| vec![r.id.clone(), r.name.clone(), r.abbr.clone(), yes_no(r.is_active), yes_no(r.is_out_of_stock), yes_no(r.config.support_custom_plan)])
+                    .collect();
+                let json = regions.iter().map(|r| serde_json::to_value(r).unwrap_or_default()).collect();
+                print_list(format, &["ID", "Name", "Abbr", "Active", "Out of stock", "Custom plans"], rows, json);
+                return;
+            }
+        },
+        Commands::SelfCmd { sub } => match sub {
+            SelfCommands::Uninstall { binary, yes } => {
+                let exe = match update::get_current_executable() {
+                    Ok(exe) => exe,
+                    Err(e) => {
+                        eprintln!("{}: {}", yansi::Paint::new("Uninstall failed").red(), e);
+                        process::exit(1);
+                    }
+                };
+                if binary && !yes {
+                    println!("{}", yansi::Paint::new(format!("Remove {} and its update backups? [y/N]", exe.display())).yellow());
+                    let mut input = String::new();
+                    if let Err(e) = std::io::stdin().read_line(&mut input) {
+                        eprintln!("{}: {}", yansi::Paint::new("Failed to read input").red(), e);
+                        process::exit(1);
+                    }
+                    if !matches!(input.trim().to_lowercase().as_str(), "y" | "yes") {
+                        println!("{}", yansi::Paint::new("Uninstall cancelled.").yellow());
+                        return;
+                    }
+                }
+                let report = match update::uninstall(&exe, binary) {
+                    Ok(report) => report,
+                    Err(e) => {
+                        eprintln!("{}: {}", yansi::Paint::new("Uninstall failed").red(), e);
+                        process::exit(1);
+                    }
+                };
+                for backup in &report.removed_backups {
+                    println!("Removed {}", backup.display());
+                }
+                if report.removed_backups.is_empty() {
+                    println!("No update backups found next to {}", exe.display());
+                }
+                match report.binary {
+                    update::BinaryRemoval::Kept => {}
+                    update::BinaryRemoval::Removed => {
+                        println!("{}", yansi::Paint::new(format!("Removed {}", exe.display())).green());
+                    }
+                    update::BinaryRemoval::Scheduled => {
+                        println!("{}", yansi::Paint::new(format!("{} will be removed once zy exits", exe.display())).green());
+                    }
+                }
+                return;
+            }
+        },
+        Commands::Cache { sub } => match sub {
+            CacheCommands::Clear { server } => {
+                let message = send_admin_request(global_env_file.as_deref(), server, handlers::admin::CACHE_CLEAR_PATH, "Cache clear failed").await;
+                println!("{}", yansi::Paint::new(message.unwrap_or_else(|| "Server caches cleared".into())).green());
+                return;
+            }
+        },
+        Commands::Config { sub } => match sub {
+            ConfigCommands::Init { path, force } => {
+                config_init(&path, force).await;
+                return;
+            }
+            ConfigCommands::Reload { server } => {
+                let message = send_admin_request(global_env_file.as_deref(), server, handlers::admin::RELOAD_CONFIG_PATH, "Config reload failed").await;
+                println!("{}", yansi::Paint::new(message.unwrap_or_else(|| "Server config reloaded".into())).green());
+                return;
+            }
+        },
         Commands::Mcp { env_file } => {
-            let state = build_state_from_env(env_file.as_deref()).await;
+            let state = build_state_from_env(env_file.or(global_env_file).as_deref()).await;
             // Silence API client logging so it does not pollute the stdio protocol stream
             crate::api::client::set_silent(true);
             mcp::server::run(state.client, state.api_base_url, state.api_token, state.mcp_log_store).await;
@@ -836,3 +2070,147 @@ async fn main() {
     }
 
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    /// Help text of `cmd` and its arguments, paired with the command it belongs to.
+    fn help_texts(cmd: &clap::Command, out: &mut Vec<(Vec<String>, String)>, path: &[String]) {
+        let mut path = path.to_vec();
+        path.push(cmd.get_name().to_string());
+        let texts = [cmd.get_about(), cmd.get_long_about(), cmd.get_before_help(), cmd.get_after_help(), cmd.get_after_long_help()]
+            .into_iter()
+            .flatten()
+            .map(|t| t.to_string())
+            .chain(cmd.get_arguments().flat_map(|a| [a.get_help(), a.get_long_help()]).flatten().map(|t| t.to_string()));
+        out.extend(texts.map(|t| (path.clone(), t)));
+        for sub in cmd.get_subcommands() {
+            help_texts(sub, out, &path);
+        }
+    }
+
+    /// Example command lines in a help text: lines starting with `zy ` or
+    /// `cargo run -- `, and inline `` `zy ...` `` spans. Trailing `# comments` are dropped.
+    fn examples(text: &str) -> Vec<String> {
+        let lines = text.lines().map(str::trim).filter_map(|line| {
+            line.strip_prefix("cargo run -- ").map(|rest| format!("zy {}", rest)).or_else(|| line.starts_with("zy ").then(|| line.to_string()))
+        });
+        let spans = text.split('`').skip(1).step_by(2).filter(|s| s.starts_with("zy ")).map(str::to_string);
+        lines
+            .chain(spans)
+            .map(|e| e.split(" #").next().unwrap_or_default().trim().to_string())
+            .collect()
+    }
+
+    fn find_subcommand<'a>(cmd: &'a clap::Command, name: &str) -> Option<&'a clap::Command> {
+        cmd.get_subcommands().find(|s| s.get_name() == name || s.get_all_aliases().any(|a| a == name))
+    }
+
+    fn find_long<'a>(cmd: &'a clap::Command, name: &str) -> Option<&'a clap::Arg> {
+        cmd.get_arguments().find(|a| a.get_long() == Some(name) || a.get_all_aliases().is_some_and(|al| al.contains(&name)))
+    }
+
+    fn has_long(cmd: &clap::Command, name: &str) -> bool {
+        find_long(cmd, name).is_some()
+    }
+
+    /// Check that the subcommands and flags an example names exist. Positional
+    /// values and `<placeholders>` are not validated.
+    fn check_example(root: &clap::Command, example: &str) -> Result<(), String> {
+        let mut cmd = root;
+        let mut descending = true;
+        let mut tokens = example.split_whitespace().skip(1);
+        while let Some(token) = tokens.next() {
+            if let Some(flag) = token.strip_prefix("--") {
+                let (name, inline_value) = flag.split_once('=').map_or((flag, false), |(n, _)| (n, true));
+                let Some(arg) = find_long(cmd, name) else {
+                    return Err(format!("`{}`: `{}` has no --{}", example, cmd.get_name(), name));
+                };
+                if arg.get_action().takes_values() && !inline_value {
+                    tokens.next();
+                }
+            } else if token.starts_with('<') || token.starts_with('[') {
+                descending = false;
+            } else if descending && cmd.has_subcommands() {
+                match find_subcommand(cmd, token) {
+                    Some(sub) => cmd = sub,
+                    None => return Err(format!("`{}`: `{}` has no subcommand {}", example, cmd.get_name(), token)),
+                }
+            } else {
+                descending = false;
+            }
+        }
+        Ok(())
+    }
+
+    fn built_cli() -> clap::Command {
+        let mut root = Cli::command();
+        root.build();
+        root
+    }
+
+    #[test]
+    fn test_color_enabled_respects_flag_env_and_tty() {
+        assert!(color_enabled(false, None, true));
+        assert!(color_enabled(false, Some(""), true));
+        assert!(!color_enabled(true, None, true));
+        assert!(!color_enabled(false, Some("1"), true));
+        assert!(!color_enabled(false, None, false));
+    }
+
+    #[test]
+    fn test_cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_help_examples_name_real_subcommands_and_flags() {
+        let root = built_cli();
+        let mut texts = Vec::new();
+        help_texts(&root, &mut texts, &[]);
+        let examples: Vec<String> = texts.iter().flat_map(|(_, text)| examples(text)).collect();
+        assert!(examples.len() >= 5, "expected help examples, found {:?}", examples);
+        let errors: Vec<String> = examples.iter().filter_map(|e| check_example(&root, e).err()).collect();
+        assert!(errors.is_empty(), "help examples drifted from the CLI:\n{}", errors.join("\n"));
+    }
+
+    #[test]
+    fn test_help_flag_mentions_exist_on_their_command() {
+        let root = built_cli();
+        let mut texts = Vec::new();
+        help_texts(&root, &mut texts, &[]);
+        let mut errors = Vec::new();
+        for (path, text) in &texts {
+            let cmd = path.iter().skip(1).fold(&root, |cmd, name| find_subcommand(cmd, name).expect("path from walk"));
+            // Flags inside `zy ...` examples are checked against their own command,
+            // and `cargo ...` lines aren't ours
+            let prose: String = text
+                .split('`')
+                .enumerate()
+                .filter(|(i, s)| i % 2 == 0 || !s.starts_with("zy "))
+                .flat_map(|(_, s)| s.lines())
+                .filter(|line| !line.trim_start().starts_with("cargo "))
+                .collect::<Vec<_>>()
+                .join(" ");
+            for word in prose.split(|c: char| !(c.is_ascii_alphanumeric() || c == '-')) {
+                let Some(name) = word.strip_prefix("--").filter(|n| !n.is_empty()) else { continue };
+                if !has_long(cmd, name) && !cmd.get_subcommands().any(|s| has_long(s, name)) {
+                    errors.push(format!("{}: mentions --{}", path.join(" "), name));
+                }
+            }
+        }
+        assert!(errors.is_empty(), "help text mentions unknown flags:\n{}", errors.join("\n"));
+    }
+
+    #[test]
+    fn test_check_example_catches_drift() {
+        let root = built_cli();
+        assert!(check_example(&root, "zy instances show 12345").is_ok());
+        assert!(check_example(&root, "zy --env-file prod.env instances list --all").is_ok());
+        assert!(check_example(&root, "zy <subcommand> --help").is_ok());
+        assert!(check_example(&root, "zy instance list").is_err());
+        assert!(check_example(&root, "zy instances list --no-such-flag").is_err());
+    }
+}
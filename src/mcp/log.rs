@@ -88,7 +88,7 @@ impl McpLogStore {
             return PaginatedLogs { logs: vec![], total: 0, page: 1, per_page, total_pages: 1 };
         }
 
-        let total_pages = (total + per_page - 1) / per_page;
+        let total_pages = total.div_ceil(per_page);
         let page = page.max(1).min(total_pages);
 
         // Reverse to show newest first.
@@ -7,8 +7,16 @@ pub struct InstanceCheckbox {
     pub checked: bool,
 }
 
+/// An admin's instance checkboxes for one region, for the region toggle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionGroup {
+    pub region: String,
+    pub instances: Vec<InstanceCheckbox>,
+    pub all_checked: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AdminView {
     pub username: String,
-    pub instances: Vec<InstanceCheckbox>,
+    pub regions: Vec<RegionGroup>,
 }
@@ -3,28 +3,104 @@ use std::sync::{Arc, Mutex};
 
 use crate::models::user_record::UserRecord;
 use crate::models::workspace_record::WorkspaceRecord;
+use crate::models::instance_note::InstanceNote;
+use crate::models::session::Session;
 use crate::mcp::log::McpLogStore;
+use crate::utils::GlobPattern;
 
 #[derive(Clone)]
 pub struct AppState {
     pub users: Arc<Mutex<HashMap<String, UserRecord>>>,
-    pub sessions: Arc<Mutex<HashMap<String, String>>>,
+    pub sessions: Arc<Mutex<HashMap<String, Session>>>,
     pub flash_store: Arc<Mutex<HashMap<String, Vec<String>>>>,
-    pub default_customer_cache: Arc<Mutex<Option<String>>>,
+    pub default_customer_cache: crate::api::CustomerIdCache,
+    /// How long `default_customer_cache` is trusted (`CUSTOMER_CACHE_SECS`).
+    pub customer_cache_ttl: std::time::Duration,
+    /// Product names across all regions, for instances that report no region.
+    pub product_name_cache: crate::api::ProductNameCache,
+    /// Secret that signs admin requests (`ZY_ADMIN_SECRET`); `None` disables them.
+    pub admin_secret: Option<String>,
     pub api_base_url: String,
     pub api_token: String,
     pub public_base_url: String,
     pub client: reqwest::Client,
     pub disabled_instances: Arc<Mutex<std::collections::HashSet<String>>>,
     pub current_hostname: String,
+    /// Instances whose hostname matches one of these refuse actions
+    /// (`ZY_BLOCKED_HOSTNAME_PATTERNS`).
+    pub blocked_hostname_patterns: Arc<Mutex<Vec<GlobPattern>>>,
+    /// `--env-file` the server started with, re-read by `/admin/reload-config`.
+    pub env_file: Option<String>,
     pub custom_css: Option<String>,
+    /// Mark the session cookie `Secure` (set when serving HTTPS directly).
+    pub secure_cookies: bool,
+    /// Reject mutating actions during a change freeze (`ZY_MAINTENANCE_MODE`).
+    pub maintenance_mode: bool,
+    /// SSH keys merged into every create request (`ZY_REQUIRED_SSH_KEY_IDS`).
+    pub required_ssh_key_ids: Vec<i64>,
+    /// Web deletes need the instance hostname typed on the confirm page
+    /// (`ZY_DELETE_REQUIRE_HOSTNAME`).
+    pub delete_require_hostname: bool,
+    /// Redirect browsers on a 403 instead of rendering the error page
+    /// (`ZY_REDIRECT_ON_DENY`).
+    pub redirect_on_deny: bool,
+    /// Show prices to owners only (`ZY_HIDE_PRICES_FOR_NONOWNERS`).
+    pub hide_prices_for_nonowners: bool,
+    /// Scheme for newly stored passwords (`ZY_PASSWORD_HASH`).
+    pub password_scheme: crate::config::PasswordScheme,
+    /// Cap on parallel upstream calls in fan-outs (`ZY_MAX_CONCURRENCY`).
+    pub max_concurrency: usize,
+    /// Largest accepted request body in bytes (`ZY_MAX_BODY_BYTES`).
+    pub max_body_bytes: usize,
+    /// Last start time per (instance ID, action), used to drop double-submits.
+    pub action_cooldowns: Arc<Mutex<HashMap<(String, String), std::time::Instant>>>,
+    /// How long a repeated action is rejected (`ACTION_COOLDOWN_SECS`).
+    pub action_cooldown: std::time::Duration,
+    /// Full instance list shared across page loads (see `INSTANCE_CACHE_SECS`).
+    pub instance_cache: crate::api::InstanceListCache,
+    /// Background refresh interval for `instance_cache`; zero disables caching.
+    pub instance_cache_ttl: std::time::Duration,
+    /// Where status transitions seen by the background refresh are POSTed
+    /// (`ZY_WEBHOOK_URL`); `None` disables the notifier.
+    pub webhook_url: Option<String>,
+    /// Poll interval for the live status stream, or `None` when `ZY_LIVE_STATUS` is off.
+    pub live_status_interval: Option<std::time::Duration>,
+    /// Idempotency key and issue time per create-request fingerprint, so a
+    /// retried create reuses its key.
+    pub idempotency_keys: Arc<Mutex<HashMap<String, (String, std::time::Instant)>>>,
+    /// Local operational notes keyed by instance ID (`notes.json`).
+    pub notes: Arc<Mutex<HashMap<String, InstanceNote>>>,
+    /// Local `key=value` labels keyed by instance ID (`labels.json`).
+    pub labels: Arc<Mutex<crate::services::InstanceLabels>>,
     /// All workspaces keyed by slug.
     pub workspaces: Arc<Mutex<HashMap<String, WorkspaceRecord>>>,
+    /// Columns from `ZY_INSTANCE_COLUMNS`; `None` keeps each view's default.
+    pub instance_columns: Option<Vec<crate::models::InstanceColumn>>,
+    /// Latest-release check behind `/version`, refreshed every few hours.
+    pub update_check: crate::services::version_service::UpdateCheckCache,
+    /// users.json / workspaces.json writes that failed and are retried after
+    /// the next successful request.
+    pub unsaved: crate::services::UnsavedChanges,
     /// Shared MCP call log store (populated by the stdio MCP server, read by the web UI).
     pub mcp_log_store: McpLogStore,
 }
 
 impl AppState {
+    /// Columns of the web instances table.
+    pub fn web_instance_columns(&self) -> Vec<crate::models::InstanceColumn> {
+        self.instance_columns.clone().unwrap_or_else(|| crate::models::InstanceColumn::WEB_DEFAULT.to_vec())
+    }
+
+    /// `requested` parallelism bounded by `ZY_MAX_CONCURRENCY`, at least 1.
+    pub fn fan_out(&self, requested: usize) -> usize {
+        requested.clamp(1, self.max_concurrency.max(1))
+    }
+
+    /// Columns of `zy instances list` tables, plain and CSV output.
+    pub fn cli_instance_columns(&self) -> Vec<crate::models::InstanceColumn> {
+        self.instance_columns.clone().unwrap_or_else(|| crate::models::InstanceColumn::CLI_DEFAULT.to_vec())
+    }
+
     pub fn is_instance_disabled(&self, id: &str) -> bool {
         self.disabled_instances.lock().unwrap().contains(id)
     }
@@ -35,4 +111,17 @@ impl AppState {
         }
         self.current_hostname.to_lowercase() == instance_hostname.to_lowercase()
     }
+
+    /// The first configured blocked-hostname pattern `instance_hostname` matches.
+    pub fn blocked_hostname_pattern(&self, instance_hostname: &str) -> Option<String> {
+        if instance_hostname.is_empty() {
+            return None;
+        }
+        self.blocked_hostname_patterns
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|p| p.matches(instance_hostname))
+            .map(|p| p.as_str().to_string())
+    }
 }
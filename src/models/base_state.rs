@@ -12,4 +12,6 @@ pub struct BaseState {
     pub ssh_key_ids: Vec<i64>,
     pub os_id: String,
     pub app_id: Option<String>,
+    /// Workspace slug the new instances are assigned to on success.
+    pub workspace: Option<String>,
 }
@@ -41,7 +41,7 @@ impl ConfirmationAction {
     }
 
     #[allow(dead_code)]
-    pub fn to_str(&self) -> &'static str {
+    pub fn to_str(self) -> &'static str {
         match self {
             Self::DeleteUser => "delete-user",
             Self::DeleteInstance => "delete-instance",
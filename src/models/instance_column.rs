@@ -0,0 +1,178 @@
+use crate::models::InstanceView;
+
+/// A column of the instances table (web) or `zy instances list` (CLI),
+/// selectable through `ZY_INSTANCE_COLUMNS`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InstanceColumn {
+    Id,
+    Hostname,
+    Status,
+    /// Main IPv4 address.
+    Ip,
+    Ipv6,
+    /// IPv4 and IPv6 together.
+    Network,
+    Vcpu,
+    Ram,
+    Disk,
+    /// vCPU, RAM and disk together (plus class and DDoS badge on the web).
+    Specs,
+    Region,
+    Class,
+    Os,
+}
+
+impl InstanceColumn {
+    pub const ALL: [InstanceColumn; 13] = [
+        InstanceColumn::Id,
+        InstanceColumn::Hostname,
+        InstanceColumn::Status,
+        InstanceColumn::Ip,
+        InstanceColumn::Ipv6,
+        InstanceColumn::Network,
+        InstanceColumn::Vcpu,
+        InstanceColumn::Ram,
+        InstanceColumn::Disk,
+        InstanceColumn::Specs,
+        InstanceColumn::Region,
+        InstanceColumn::Class,
+        InstanceColumn::Os,
+    ];
+
+    /// Web table layout when `ZY_INSTANCE_COLUMNS` is unset.
+    pub const WEB_DEFAULT: [InstanceColumn; 5] = [
+        InstanceColumn::Hostname,
+        InstanceColumn::Id,
+        InstanceColumn::Specs,
+        InstanceColumn::Network,
+        InstanceColumn::Status,
+    ];
+
+    /// CLI table layout when `ZY_INSTANCE_COLUMNS` is unset.
+    pub const CLI_DEFAULT: [InstanceColumn; 8] = [
+        InstanceColumn::Id,
+        InstanceColumn::Hostname,
+        InstanceColumn::Status,
+        InstanceColumn::Ip,
+        InstanceColumn::Ipv6,
+        InstanceColumn::Vcpu,
+        InstanceColumn::Ram,
+        InstanceColumn::Disk,
+    ];
+
+    /// Name used in `ZY_INSTANCE_COLUMNS`.
+    pub fn key(self) -> &'static str {
+        match self {
+            InstanceColumn::Id => "id",
+            InstanceColumn::Hostname => "hostname",
+            InstanceColumn::Status => "status",
+            InstanceColumn::Ip => "ip",
+            InstanceColumn::Ipv6 => "ipv6",
+            InstanceColumn::Network => "network",
+            InstanceColumn::Vcpu => "vcpu",
+            InstanceColumn::Ram => "ram",
+            InstanceColumn::Disk => "disk",
+            InstanceColumn::Specs => "specs",
+            InstanceColumn::Region => "region",
+            InstanceColumn::Class => "class",
+            InstanceColumn::Os => "os",
+        }
+    }
+
+    /// Column header, shared by the web table and CLI output.
+    pub fn header(self) -> &'static str {
+        match self {
+            InstanceColumn::Id => "ID",
+            InstanceColumn::Hostname => "Hostname",
+            InstanceColumn::Status => "Status",
+            InstanceColumn::Ip => "IPv4",
+            InstanceColumn::Ipv6 => "IPv6",
+            InstanceColumn::Network => "Network",
+            InstanceColumn::Vcpu => "vCPU",
+            InstanceColumn::Ram => "RAM (MB)",
+            InstanceColumn::Disk => "Disk (GB)",
+            InstanceColumn::Specs => "Specs",
+            InstanceColumn::Region => "Region",
+            InstanceColumn::Class => "Class",
+            InstanceColumn::Os => "OS",
+        }
+    }
+
+    /// Parse a comma-separated column list such as `hostname,ip,status`.
+    /// `ipv4` is accepted for `ip`; unknown or repeated names are errors.
+    pub fn parse_list(raw: &str) -> Result<Vec<InstanceColumn>, String> {
+        let mut columns = Vec::new();
+        for name in raw.split(',').map(|s| s.trim().to_ascii_lowercase()).filter(|s| !s.is_empty()) {
+            let name = if name == "ipv4" { "ip".to_string() } else { name };
+            let Some(column) = Self::ALL.iter().copied().find(|c| c.key() == name) else {
+                let known: Vec<&str> = Self::ALL.iter().map(|c| c.key()).collect();
+                return Err(format!("Unknown instance column '{}' (known: {})", name, known.join(", ")));
+            };
+            if columns.contains(&column) {
+                return Err(format!("Instance column '{}' is listed twice", name));
+            }
+            columns.push(column);
+        }
+        if columns.is_empty() {
+            return Err("No instance columns given".into());
+        }
+        Ok(columns)
+    }
+
+    /// Plain-text cell for CLI output; missing values become `placeholder`.
+    pub fn text(self, instance: &InstanceView, placeholder: &str) -> String {
+        let text = |v: Option<&str>| v.filter(|s| !s.is_empty()).unwrap_or(placeholder).to_string();
+        let number = |n: i32| if n > 0 { n.to_string() } else { placeholder.to_string() };
+        match self {
+            InstanceColumn::Id => instance.id.clone(),
+            InstanceColumn::Hostname => text(Some(&instance.hostname)),
+            InstanceColumn::Status => text(Some(&instance.status)),
+            InstanceColumn::Ip => text(instance.main_ip.as_deref()),
+            InstanceColumn::Ipv6 => text(instance.main_ipv6.as_deref()),
+            InstanceColumn::Network => {
+                let ips: Vec<&str> = [instance.main_ip.as_deref(), instance.main_ipv6.as_deref()]
+                    .into_iter()
+                    .flatten()
+                    .filter(|s| !s.is_empty())
+                    .collect();
+                if ips.is_empty() { placeholder.to_string() } else { ips.join(" ") }
+            }
+            InstanceColumn::Vcpu => number(instance.vcpu_count),
+            InstanceColumn::Ram => number(instance.ram),
+            InstanceColumn::Disk => number(instance.disk),
+            InstanceColumn::Specs => format!(
+                "{} vCPU / {} / {}",
+                instance.vcpu_count_display, instance.ram_display, instance.disk_display
+            ),
+            InstanceColumn::Region => text(Some(&instance.region)),
+            InstanceColumn::Class => text(Some(&instance.class)),
+            InstanceColumn::Os => text(instance.os.as_ref().map(|o| o.name.as_str())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_list() {
+        assert_eq!(
+            InstanceColumn::parse_list(" Hostname, ipv4 ,status,region,ram").unwrap(),
+            vec![InstanceColumn::Hostname, InstanceColumn::Ip, InstanceColumn::Status, InstanceColumn::Region, InstanceColumn::Ram]
+        );
+        assert!(InstanceColumn::parse_list("hostname,price").unwrap_err().contains("price"));
+        assert!(InstanceColumn::parse_list("ip,ip").is_err());
+        assert!(InstanceColumn::parse_list(" , ").is_err());
+    }
+
+    #[test]
+    fn test_text_cells() {
+        let mut inst = InstanceView::new_with_defaults("abc".into());
+        inst.main_ip = Some("203.0.113.5".into());
+        inst.main_ipv6 = Some("2001:db8::1".into());
+        assert_eq!(InstanceColumn::Network.text(&inst, "—"), "203.0.113.5 2001:db8::1");
+        assert_eq!(InstanceColumn::Region.text(&inst, "—"), "—");
+        assert_eq!(InstanceColumn::Vcpu.text(&inst, ""), "");
+    }
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// Free-text operational note attached to an instance, kept in `notes.json`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct InstanceNote {
+    pub text: String,
+    /// Local username of the last editor.
+    pub author: String,
+    /// When the note was last edited (ISO 8601, UTC).
+    pub updated_at: String,
+}
@@ -1,3 +1,4 @@
+use crate::models::instance_column::InstanceColumn;
 use crate::models::os_item::OsItem;
 use serde::{Deserialize, Serialize};
 
@@ -50,11 +51,34 @@ pub struct InstanceView {
 }
 
 impl InstanceView {
+    /// One row of `zy instances list` output for `columns`; missing values
+    /// become `placeholder` (a dash in tables, empty in CSV).
+    pub fn list_row(&self, columns: &[InstanceColumn], placeholder: &str) -> Vec<String> {
+        columns.iter().map(|c| c.text(self, placeholder)).collect()
+    }
+
+    /// Severity CSS class for this instance's status badge.
+    pub fn status_class(&self) -> &'static str {
+        crate::utils::status_class(&self.status)
+    }
+
+    /// Recompute the display helpers from `status`, `vcpu_count`, `ram` and
+    /// `disk`; call after filling those in.
+    pub fn fill_display_fields(&mut self) {
+        self.status_display = crate::utils::format_status(&self.status);
+        self.vcpu_count_display = if self.vcpu_count > 0 { self.vcpu_count.to_string() } else { "—".into() };
+        self.ram_display = if self.ram > 0 { format!("{} MB", self.ram) } else { "—".into() };
+        self.disk_display = if self.disk > 0 { format!("{} GB", self.disk) } else { "—".into() };
+    }
+
+    /// Placeholder hostname of an instance whose details could not be loaded.
+    pub const NO_HOSTNAME: &'static str = "(no hostname)";
+
     /// Creates a new InstanceView with default values for the given instance ID.
     pub fn new_with_defaults(instance_id: String) -> Self {
         Self {
             id: instance_id,
-            hostname: "(no hostname)".into(),
+            hostname: Self::NO_HOSTNAME.into(),
             vcpu_count: 0,
             ram: 0,
             disk: 0,
@@ -86,3 +110,31 @@ impl InstanceView {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_row_placeholders() {
+        let mut inst = InstanceView::new_with_defaults("abc".into());
+        inst.status = "active".into();
+        inst.main_ipv6 = Some("2001:db8::1".into());
+        inst.ram = 2048;
+        let columns = InstanceColumn::CLI_DEFAULT;
+        assert_eq!(inst.list_row(&columns, "—"), vec!["abc", "(no hostname)", "active", "—", "2001:db8::1", "—", "2048", "—"]);
+        assert_eq!(inst.list_row(&columns, "")[3], "");
+        assert_eq!(inst.list_row(&[InstanceColumn::Status, InstanceColumn::Id], ""), vec!["active", "abc"]);
+    }
+
+    #[test]
+    fn test_fill_display_fields() {
+        let mut inst = InstanceView::new_with_defaults("abc".into());
+        inst.status = "preparing_disk".into();
+        inst.ram = 2048;
+        inst.fill_display_fields();
+        assert_eq!(inst.status_display, "Preparing Disk");
+        assert_eq!(inst.ram_display, "2048 MB");
+        assert_eq!(inst.vcpu_count_display, "—");
+    }
+}
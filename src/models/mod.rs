@@ -15,19 +15,24 @@ pub mod product_entry;
 pub mod product_view;
 pub mod os_item;
 pub mod instance_view;
+pub mod instance_column;
 pub mod ssh_key_view;
 pub mod ssh_key_display;
 pub mod extras;
 pub mod plan_state;
-pub mod user_row;
+pub mod user_view;
 pub mod admin_view;
 pub mod confirmation;
+pub mod instance_note;
+pub mod session;
 
 // Re-export all models
 pub use user_record::UserRecord;
 pub use current_user::CurrentUser;
 pub use app_state::AppState;
 pub use workspace_record::{WorkspaceRecord, WorkspaceMember, WorkspaceRole};
+pub use instance_note::InstanceNote;
+pub use session::{Session, RecentInstance, InstanceListDiff, revoke_sessions};
 pub use permission::Permission;
 pub use add_traffic_form::AddTrafficForm;
 pub use resize_form::ResizeForm;
@@ -38,14 +43,15 @@ pub use custom_plan_specification_form::CustomPlanSpecificationFormStep3;
 pub use region::Region;
 pub use product_entry::ProductEntry;
 pub use product_view::ProductView;
-pub use os_item::OsItem;
+pub use os_item::{OsItem, OsFamilyGroup, group_os_by_family, pick_default_os};
 pub use instance_view::InstanceView;
+pub use instance_column::InstanceColumn;
 pub use ssh_key_view::SshKeyView;
 pub use ssh_key_display::SshKeyDisplay;
 pub use extras::Extras;
 pub use plan_state::PlanState;
-pub use user_row::UserRow;
-pub use admin_view::{AdminView, InstanceCheckbox};
+pub use user_view::UserView;
+pub use admin_view::{AdminView, InstanceCheckbox, RegionGroup};
 pub use confirmation::ConfirmationAction;
 
 // Type aliases for backward compatibility
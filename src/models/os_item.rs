@@ -11,3 +11,186 @@ pub struct OsItem {
     pub is_default: bool,
     pub is_active: bool,
 }
+
+impl OsItem {
+    /// Minimum RAM in MB, parsed from values like `"1024"`, `"512 MB"` or `"2GB"`.
+    pub fn min_ram_mb(&self) -> Option<i64> {
+        let raw = self.min_ram.as_deref()?.trim().to_lowercase();
+        let split = raw.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(raw.len());
+        let value: f64 = raw[..split].trim().parse().ok()?;
+        let mb = match raw[split..].trim() {
+            "" | "m" | "mb" | "mib" => value,
+            "g" | "gb" | "gib" => value * 1024.0,
+            _ => return None,
+        };
+        Some(mb.round() as i64)
+    }
+
+    /// Why this image can't be installed on an instance with `ram_mb` of
+    /// memory running on `arch`, or `None` when it fits. Unknown values
+    /// (`ram_mb <= 0`, missing arch) are not held against the image.
+    pub fn incompatibility(&self, ram_mb: i32, arch: Option<&str>) -> Option<String> {
+        if !self.is_active {
+            return Some("Unavailable".into());
+        }
+        if let (Some(own), Some(arch)) = (self.arch.as_deref(), arch) {
+            if normalize_arch(own) != normalize_arch(arch) {
+                return Some(format!("Requires {} (instance is {})", own, arch));
+            }
+        }
+        if let Some(min) = self.min_ram_mb() {
+            if ram_mb > 0 && i64::from(ram_mb) < min {
+                return Some(format!("Requires {} MB RAM (instance has {} MB)", min, ram_mb));
+            }
+        }
+        None
+    }
+}
+
+/// The image to preselect from `items`: the flagged default when it can be
+/// installed on an instance with `ram_mb` of memory on `arch`, otherwise the
+/// first image that can. Falls back to the default (or first) image when
+/// nothing fits, so the wizard still preselects something.
+pub fn pick_default_os<'a>(items: &'a [OsItem], ram_mb: i32, arch: Option<&str>) -> Option<&'a OsItem> {
+    let fits = |o: &&OsItem| o.incompatibility(ram_mb, arch).is_none();
+    items
+        .iter()
+        .find(|o| o.is_default)
+        .filter(fits)
+        .or_else(|| items.iter().find(fits))
+        .or_else(|| items.iter().find(|o| o.is_default))
+        .or_else(|| items.first())
+}
+
+fn normalize_arch(arch: &str) -> String {
+    match arch.trim().to_lowercase().as_str() {
+        "amd64" | "x64" | "x86-64" => "x86_64".into(),
+        "arm64" => "aarch64".into(),
+        other => other.to_string(),
+    }
+}
+
+/// Preferred display order for OS families; anything else follows
+/// alphabetically, with family-less images last.
+const FAMILY_ORDER: &[&str] = &[
+    "ubuntu", "debian", "almalinux", "rocky", "centos", "fedora", "alpine", "freebsd", "windows",
+];
+
+/// Active OS images sharing a `family`, for grouped display.
+#[derive(Clone, Debug)]
+pub struct OsFamilyGroup {
+    /// Lowercased family key (`""` for images without a family).
+    pub family: String,
+    /// Human-readable family name.
+    pub label: String,
+    pub items: Vec<OsItem>,
+}
+
+impl OsFamilyGroup {
+    /// The family's image to preselect for the given RAM and architecture;
+    /// see [`pick_default_os`].
+    pub fn default_item_for(&self, ram_mb: i32, arch: Option<&str>) -> Option<&OsItem> {
+        pick_default_os(&self.items, ram_mb, arch)
+    }
+
+    pub fn contains(&self, os_id: &str) -> bool {
+        self.items.iter().any(|o| o.id == os_id)
+    }
+}
+
+fn family_rank(family: &str) -> (usize, String) {
+    match FAMILY_ORDER.iter().position(|f| *f == family) {
+        Some(i) => (i, String::new()),
+        None if family.is_empty() => (usize::MAX, String::new()),
+        None => (FAMILY_ORDER.len(), family.to_string()),
+    }
+}
+
+/// Group active OS images by family in a stable order, keeping the catalog's
+/// order within each family.
+pub fn group_os_by_family(os_list: &[OsItem]) -> Vec<OsFamilyGroup> {
+    let mut groups: Vec<OsFamilyGroup> = Vec::new();
+    for os in os_list.iter().filter(|o| o.is_active) {
+        let family = os.family.trim().to_lowercase();
+        match groups.iter_mut().find(|g| g.family == family) {
+            Some(group) => group.items.push(os.clone()),
+            None => groups.push(OsFamilyGroup {
+                label: if family.is_empty() { "Other".into() } else { os.family.trim().to_string() },
+                family,
+                items: vec![os.clone()],
+            }),
+        }
+    }
+    groups.sort_by_key(|g| family_rank(&g.family));
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn os(id: &str, family: &str, is_default: bool) -> OsItem {
+        OsItem {
+            id: id.into(),
+            name: id.into(),
+            family: family.into(),
+            arch: None,
+            min_ram: None,
+            is_default,
+            is_active: true,
+        }
+    }
+
+    #[test]
+    fn test_groups_in_stable_family_order() {
+        let list = vec![
+            os("win-2022", "Windows", false),
+            os("arch", "Arch", false),
+            os("custom", "", false),
+            os("deb-12", "Debian", false),
+            os("ubuntu-22", "Ubuntu", false),
+            os("ubuntu-24", "Ubuntu", true),
+        ];
+        let groups = group_os_by_family(&list);
+        let families: Vec<&str> = groups.iter().map(|g| g.family.as_str()).collect();
+        assert_eq!(families, vec!["ubuntu", "debian", "windows", "arch", ""]);
+        assert_eq!(groups[0].items.len(), 2);
+        assert_eq!(groups[0].default_item_for(0, None).unwrap().id, "ubuntu-24");
+        assert_eq!(groups[1].default_item_for(0, None).unwrap().id, "deb-12");
+        assert_eq!(groups[4].label, "Other");
+    }
+
+    #[test]
+    fn test_pick_default_os_skips_incompatible_default() {
+        let mut x86 = os("ubuntu-24", "Ubuntu", true);
+        x86.arch = Some("x86_64".into());
+        let mut arm = os("ubuntu-24-arm", "Ubuntu", false);
+        arm.arch = Some("aarch64".into());
+        let list = vec![x86, arm];
+        assert_eq!(pick_default_os(&list, 2048, Some("x86_64")).unwrap().id, "ubuntu-24");
+        assert_eq!(pick_default_os(&list, 2048, Some("arm64")).unwrap().id, "ubuntu-24-arm");
+        assert_eq!(pick_default_os(&list, 2048, None).unwrap().id, "ubuntu-24");
+        assert_eq!(pick_default_os(&list, 2048, Some("riscv64")).unwrap().id, "ubuntu-24");
+        assert!(pick_default_os(&[], 2048, None).is_none());
+    }
+
+    #[test]
+    fn test_incompatibility_checks_ram_and_arch() {
+        let mut image = os("win-2022", "Windows", false);
+        image.min_ram = Some("2 GB".into());
+        image.arch = Some("amd64".into());
+        assert_eq!(image.min_ram_mb(), Some(2048));
+        assert!(image.incompatibility(4096, Some("x86_64")).is_none());
+        assert!(image.incompatibility(0, None).is_none());
+        assert_eq!(
+            image.incompatibility(1024, Some("x86_64")).as_deref(),
+            Some("Requires 2048 MB RAM (instance has 1024 MB)")
+        );
+        assert!(image.incompatibility(4096, Some("arm64")).unwrap().starts_with("Requires amd64"));
+
+        image.min_ram = Some("512".into());
+        assert_eq!(image.min_ram_mb(), Some(512));
+        image.is_active = false;
+        assert_eq!(image.incompatibility(4096, None).as_deref(), Some("Unavailable"));
+    }
+}
@@ -191,7 +191,7 @@ impl Permission {
     /// Rules:
     /// - `owner`  → all permissions.
     /// - `admin`  → instance/resource read+write, workspace management for their
-    ///              own workspaces, but NOT user management or global access assignments.
+    ///   own workspaces, but NOT user management or global access assignments.
     /// - `viewer` → read-only permissions only.
     pub fn for_role(role: &str) -> Vec<Permission> {
         match role {
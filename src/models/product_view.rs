@@ -18,6 +18,8 @@ pub struct Plan {
     pub plan_type: Option<String>,
     pub gpu_name: Option<String>,
     pub gpu_quantity: Option<i32>,
+    /// CPU architecture (e.g. `x86_64`, `arm64`) when the API reports it.
+    pub arch: Option<String>,
     pub specification: PlanSpecification,
     pub is_active: bool,
 }
@@ -59,3 +61,31 @@ pub struct ProductView {
     #[serde(skip)]
     pub price_entries: Vec<ProductEntry>,
 }
+
+impl ProductView {
+    /// Monthly price of the product: the sum of its price items.
+    pub fn monthly_price(&self) -> f64 {
+        self.price_items.iter().map(|item| item.monthly_price).sum()
+    }
+
+    /// Short human-readable name: the GPU (if any) and the spec values, e.g.
+    /// `2 vCPU / 4 GB / 50 GB / 1 TB`. The API has no product name, so this
+    /// falls back to the plan ID and then the product ID.
+    pub fn display_name(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(gpu) = self.plan.gpu_name.as_deref().filter(|g| !g.is_empty()) {
+            match self.plan.gpu_quantity {
+                Some(n) if n > 1 => parts.push(format!("{}× {}", n, gpu)),
+                _ => parts.push(gpu.to_string()),
+            }
+        }
+        parts.extend(self.spec_entries.iter().map(|e| e.value.clone()));
+        if !parts.is_empty() {
+            parts.join(" / ")
+        } else if !self.plan_id.is_empty() {
+            self.plan_id.clone()
+        } else {
+            self.id.clone()
+        }
+    }
+}
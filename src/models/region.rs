@@ -32,3 +32,91 @@ pub struct Region {
     pub position: serde_json::Value, // HashMap<String, i32> in practice
     pub config: RegionConfig,
 }
+
+impl Region {
+    /// Offered for new instances: active and not hidden.
+    pub fn is_available(&self) -> bool {
+        self.is_active && !self.is_hidden
+    }
+
+    /// Latitude/longitude from the API's `position` object, when it carries
+    /// them (`lat`/`latitude` and `lon`/`lng`/`longitude`, numbers or strings).
+    pub fn coordinates(&self) -> Option<(f64, f64)> {
+        let field = |keys: &[&str]| {
+            keys.iter().find_map(|k| {
+                let v = self.position.get(*k)?;
+                v.as_f64().or_else(|| v.as_str().and_then(|s| s.trim().parse().ok()))
+            })
+        };
+        let lat = field(&["lat", "latitude"])?;
+        let lon = field(&["lon", "lng", "longitude"])?;
+        ((-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon)).then_some((lat, lon))
+    }
+
+    /// Great-circle distance in km from `home` (lat, lon), if the region has coordinates.
+    pub fn distance_km_from(&self, home: (f64, f64)) -> Option<f64> {
+        let (lat, lon) = self.coordinates()?;
+        Some(haversine_km(home, (lat, lon)))
+    }
+}
+
+fn haversine_km(a: (f64, f64), b: (f64, f64)) -> f64 {
+    const EARTH_RADIUS_KM: f64 = 6371.0;
+    let (lat1, lat2) = (a.0.to_radians(), b.0.to_radians());
+    let dlat = lat2 - lat1;
+    let dlon = (b.1 - a.1).to_radians();
+    let h = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * h.sqrt().asin()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(id: &str, position: serde_json::Value) -> Region {
+        Region {
+            id: id.into(),
+            name: id.into(),
+            abbr: String::new(),
+            image: String::new(),
+            is_active: true,
+            is_out_of_stock: false,
+            overall_activeness: true,
+            ddos_activeness: None,
+            is_premium: false,
+            is_hidden: false,
+            has_offset_price: false,
+            max_discount_percent: None,
+            position,
+            config: RegionConfig {
+                support_ipv6: true,
+                support_regular_cpu: true,
+                support_high_frequency_cpu: false,
+                support_monitoring: false,
+                support_gpu: false,
+                support_custom_plan: true,
+                ram_threshold_in_gb: 0,
+                ip_threshold: 0,
+                disk_threshold_in_gb: 0,
+                support_ddos_ipv4: None,
+                ddos_ipv4_threshold: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_coordinates_and_distance() {
+        let amsterdam = region("ams", serde_json::json!({"latitude": 52.37, "longitude": 4.90}));
+        let frankfurt = region("fra", serde_json::json!({"lat": "50.11", "lng": "8.68"}));
+        let map_only = region("nyc", serde_json::json!({"x": 120, "y": 40}));
+        assert_eq!(frankfurt.coordinates(), Some((50.11, 8.68)));
+        assert!(map_only.coordinates().is_none());
+
+        let berlin = (52.52, 13.40);
+        let to_ams = amsterdam.distance_km_from(berlin).unwrap();
+        let to_fra = frankfurt.distance_km_from(berlin).unwrap();
+        assert!((to_ams - 577.0).abs() < 10.0, "{}", to_ams);
+        assert!(to_fra < to_ams);
+    }
+}
+
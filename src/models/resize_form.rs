@@ -1,15 +1,46 @@
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 
 #[derive(Deserialize)]
 pub struct ResizeForm {
     pub r#type: String,
     pub product_id: Option<String>,
     pub region_id: Option<String>,
+    #[serde(default, deserialize_with = "string_or_number")]
     pub cpu: Option<String>,
-    #[serde(rename = "ramInGB")]
+    #[serde(rename = "ramInGB", default, deserialize_with = "string_or_number")]
     pub ram_in_gb: Option<String>,
-    #[serde(rename = "diskInGB")]
+    #[serde(rename = "diskInGB", default, deserialize_with = "string_or_number")]
     pub disk_in_gb: Option<String>,
-    #[serde(rename = "bandwidthInTB")]
+    #[serde(rename = "bandwidthInTB", default, deserialize_with = "string_or_number")]
     pub bandwidth_in_tb: Option<String>,
+    /// Set to `1` to go ahead with a resize that shrinks CPU, RAM or disk.
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub confirm_downsize: Option<String>,
+}
+
+/// Accept `"4"` from forms and `4` from JSON bodies alike.
+pub(crate) fn string_or_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Str(String),
+        Num(serde_json::Number),
+    }
+    Ok(Option::<Raw>::deserialize(deserializer)?.map(|raw| match raw {
+        Raw::Str(s) => s,
+        Raw::Num(n) => n.to_string(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resize_form_accepts_numbers_and_strings() {
+        let form: ResizeForm = serde_json::from_str(r#"{"type":"CUSTOM","cpu":2,"ramInGB":"4"}"#).unwrap();
+        assert_eq!(form.cpu.as_deref(), Some("2"));
+        assert_eq!(form.ram_in_gb.as_deref(), Some("4"));
+        assert!(form.disk_in_gb.is_none());
+    }
 }
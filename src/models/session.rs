@@ -0,0 +1,188 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use crate::models::InstanceView;
+use crate::utils::{status_severity, StatusSeverity};
+
+/// How many recently viewed instances a session remembers.
+pub const RECENT_INSTANCES_LIMIT: usize = 5;
+
+/// An instance opened during the session, shown as a quick link.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecentInstance {
+    pub id: String,
+    pub hostname: String,
+}
+
+/// An instance's hostname and status as of the session's last instances page load.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SeenInstance {
+    pub hostname: String,
+    pub status: String,
+}
+
+/// What changed in the instance list since the session last loaded it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InstanceListDiff {
+    /// Instances that weren't there last time.
+    pub new_ids: BTreeSet<String>,
+    /// Instances whose status has since moved into an error state.
+    pub errored_ids: BTreeSet<String>,
+    /// Instances that were there last time and no longer are.
+    pub gone: Vec<RecentInstance>,
+}
+
+impl InstanceListDiff {
+    pub fn is_empty(&self) -> bool {
+        self.new_ids.is_empty() && self.errored_ids.is_empty() && self.gone.is_empty()
+    }
+}
+
+/// Server-side state for one logged-in browser session, keyed by the
+/// `session_id` cookie. Dropped on logout.
+#[derive(Clone, Debug)]
+pub struct Session {
+    pub username: String,
+    /// Most recently viewed first, at most [`RECENT_INSTANCES_LIMIT`] entries.
+    pub recent_instances: VecDeque<RecentInstance>,
+    /// Instances ticked on the instances list, kept across pages until the
+    /// user clears them or logs out.
+    pub selected_instances: BTreeSet<String>,
+    /// Every accessible instance as of the last instances page load, keyed by
+    /// ID; `None` until the first load.
+    pub seen_instances: Option<HashMap<String, SeenInstance>>,
+}
+
+impl Session {
+    pub fn new(username: impl Into<String>) -> Self {
+        Session {
+            username: username.into(),
+            recent_instances: VecDeque::new(),
+            selected_instances: BTreeSet::new(),
+            seen_instances: None,
+        }
+    }
+
+    /// Move `id` to the front of the recent list, dropping the oldest entry
+    /// once the list is full.
+    pub fn record_instance(&mut self, id: &str, hostname: &str) {
+        self.recent_instances.retain(|r| r.id != id);
+        self.recent_instances.push_front(RecentInstance { id: id.to_string(), hostname: hostname.to_string() });
+        self.recent_instances.truncate(RECENT_INSTANCES_LIMIT);
+    }
+
+    /// Compare `current` (the full accessible list) with the last snapshot
+    /// and store it as the new one. The first load reports no changes.
+    pub fn diff_seen_instances(&mut self, current: &[InstanceView]) -> InstanceListDiff {
+        let snapshot: HashMap<String, SeenInstance> = current
+            .iter()
+            .map(|i| (i.id.clone(), SeenInstance { hostname: i.hostname.clone(), status: i.status.clone() }))
+            .collect();
+        let Some(previous) = self.seen_instances.replace(snapshot) else {
+            return InstanceListDiff::default();
+        };
+        let mut diff = InstanceListDiff::default();
+        for instance in current {
+            match previous.get(&instance.id) {
+                None => {
+                    diff.new_ids.insert(instance.id.clone());
+                }
+                Some(seen)
+                    if status_severity(&instance.status) == StatusSeverity::Error
+                        && status_severity(&seen.status) != StatusSeverity::Error =>
+                {
+                    diff.errored_ids.insert(instance.id.clone());
+                }
+                Some(_) => {}
+            }
+        }
+        let current_ids: BTreeSet<&str> = current.iter().map(|i| i.id.as_str()).collect();
+        diff.gone = previous
+            .into_iter()
+            .filter(|(id, _)| !current_ids.contains(id.as_str()))
+            .map(|(id, seen)| RecentInstance { id, hostname: seen.hostname })
+            .collect();
+        diff.gone.sort_by(|a, b| a.hostname.cmp(&b.hostname));
+        diff
+    }
+
+    /// Apply the checkboxes of one list page: every ID in `page_ids` is
+    /// dropped unless it is also in `checked`. Other pages are untouched.
+    pub fn update_selection(&mut self, page_ids: &[String], checked: &[String]) {
+        for id in page_ids {
+            self.selected_instances.remove(id);
+        }
+        self.selected_instances.extend(checked.iter().filter(|id| page_ids.contains(id)).cloned());
+    }
+}
+
+/// Drop every session of `username` except the one keyed `keep`, returning
+/// how many were removed.
+pub fn revoke_sessions(sessions: &mut HashMap<String, Session>, username: &str, keep: Option<&str>) -> usize {
+    let before = sessions.len();
+    sessions.retain(|sid, session| session.username != username || Some(sid.as_str()) == keep);
+    before - sessions.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_instance_dedupes_and_bounds() {
+        let mut session = Session::new("alice");
+        for i in 0..RECENT_INSTANCES_LIMIT + 2 {
+            session.record_instance(&format!("i-{}", i), "host");
+        }
+        session.record_instance("i-4", "renamed");
+        let ids: Vec<&str> = session.recent_instances.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["i-4", "i-6", "i-5", "i-3", "i-2"]);
+        assert_eq!(session.recent_instances[0].hostname, "renamed");
+    }
+
+    #[test]
+    fn update_selection_keeps_other_pages() {
+        let mut session = Session::new("alice");
+        let page1: Vec<String> = vec!["a".into(), "b".into()];
+        let page3: Vec<String> = vec!["x".into(), "y".into()];
+        session.update_selection(&page1, &page1);
+        session.update_selection(&page3, &["y".into(), "not-on-page".into()]);
+        session.update_selection(&page1, &["b".into()]);
+        assert_eq!(session.selected_instances.iter().collect::<Vec<_>>(), vec!["b", "y"]);
+    }
+
+    #[test]
+    fn diff_seen_instances_reports_new_errored_and_gone() {
+        let instance = |id: &str, status: &str| {
+            let mut view = InstanceView::new_with_defaults(id.into());
+            view.hostname = format!("{}-host", id);
+            view.status = status.into();
+            view
+        };
+        let mut session = Session::new("alice");
+        let first = [instance("a", "active"), instance("b", "active"), instance("c", "error")];
+        assert!(session.diff_seen_instances(&first).is_empty());
+
+        let second = [instance("a", "error"), instance("c", "error"), instance("d", "initializing")];
+        let diff = session.diff_seen_instances(&second);
+        assert_eq!(diff.new_ids.iter().collect::<Vec<_>>(), vec!["d"]);
+        assert_eq!(diff.errored_ids.iter().collect::<Vec<_>>(), vec!["a"]);
+        assert_eq!(diff.gone, vec![RecentInstance { id: "b".into(), hostname: "b-host".into() }]);
+
+        assert!(session.diff_seen_instances(&second).is_empty());
+    }
+
+    #[test]
+    fn revoke_sessions_keeps_other_users_and_the_kept_session() {
+        let mut sessions = HashMap::new();
+        sessions.insert("s1".to_string(), Session::new("alice"));
+        sessions.insert("s2".to_string(), Session::new("alice"));
+        sessions.insert("s3".to_string(), Session::new("alice"));
+        sessions.insert("s4".to_string(), Session::new("bob"));
+        assert_eq!(revoke_sessions(&mut sessions, "alice", Some("s2")), 2);
+        let mut left: Vec<&str> = sessions.keys().map(String::as_str).collect();
+        left.sort();
+        assert_eq!(left, vec!["s2", "s4"]);
+        assert_eq!(revoke_sessions(&mut sessions, "alice", None), 1);
+        assert_eq!(revoke_sessions(&mut sessions, "carol", None), 0);
+    }
+}
@@ -5,4 +5,6 @@ pub struct SshKeyDisplay {
     pub id: String,
     pub name: String,
     pub selected: bool,
+    /// Mandated by `ZY_REQUIRED_SSH_KEY_IDS`; always selected.
+    pub required: bool,
 }
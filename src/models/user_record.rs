@@ -4,16 +4,22 @@ use serde::{Deserialize, Serialize};
 ///
 /// - `owner`  – full administrative access, can manage users and workspaces.
 /// - `admin`  – can manage resources (instances, snapshots, …) for their assigned
-///              instances / workspaces, but cannot manage users or global settings.
+///   instances / workspaces, but cannot manage users or global settings.
 /// - `viewer` – read-only access to all resources they are assigned to.
 #[derive(Clone, Serialize, Deserialize)]
 pub struct UserRecord {
+    /// PBKDF2 hash. users.json is written field by field, so serde never
+    /// emits it; show users through [`UserView`](crate::models::UserView).
+    #[serde(skip_serializing)]
     pub password: String,
     /// One of `"owner"`, `"admin"`, or `"viewer"`.
     pub role: String,
     pub assigned_instances: Vec<String>,
     #[serde(default)]
     pub about: String,
+    /// ISO 8601 time of the last successful web login.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_login: Option<String>,
 }
 
 impl UserRecord {
@@ -62,4 +68,17 @@ mod tests {
         assert!(!UserRecord::is_valid_role(""));
         assert!(!UserRecord::is_valid_role("OWNER"));
     }
+
+    #[test]
+    fn password_never_serialized() {
+        let rec = UserRecord {
+            password: "pbkdf2:sha256:1$salt$hash".into(),
+            role: "viewer".into(),
+            assigned_instances: vec![],
+            about: String::new(),
+            last_login: None,
+        };
+        let json = serde_json::to_string(&rec).unwrap();
+        assert!(!json.contains("password") && !json.contains("pbkdf2"));
+    }
 }
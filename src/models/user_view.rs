@@ -0,0 +1,53 @@
+use serde::Serialize;
+
+use crate::models::user_record::UserRecord;
+
+/// What the panel shows or prints about a user. Every listing and
+/// serialization path goes through this projection of [`UserRecord`], which
+/// has no password field, so the hash can't leak into pages, logs or JSON.
+#[derive(Debug, Clone, Serialize)]
+pub struct UserView {
+    pub username: String,
+    pub role: String,
+    pub assigned_instances: Vec<String>,
+    pub about: String,
+    /// ISO 8601 time of the last successful web login, if any.
+    pub last_login: Option<String>,
+}
+
+impl UserView {
+    pub fn from_record(username: &str, record: &UserRecord) -> Self {
+        UserView {
+            username: username.to_string(),
+            role: record.role.clone(),
+            assigned_instances: record.assigned_instances.clone(),
+            about: record.about.clone(),
+            last_login: record.last_login.clone(),
+        }
+    }
+
+    /// Assigned instance IDs joined for display.
+    pub fn assigned(&self) -> String {
+        self.assigned_instances.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_view_never_serializes_password() {
+        let record = UserRecord {
+            password: "pbkdf2:sha256:1$salt$hash".into(),
+            role: "admin".into(),
+            assigned_instances: vec!["i-1".into()],
+            about: String::new(),
+            last_login: None,
+        };
+        let json = serde_json::to_string(&UserView::from_record("alice", &record)).unwrap();
+        assert!(!json.contains("password"));
+        assert!(!json.contains("pbkdf2"));
+        assert!(json.contains("\"username\":\"alice\""));
+    }
+}
@@ -83,12 +83,22 @@ pub struct WorkspaceRecord {
     /// ISO-8601 creation timestamp.
     #[serde(default)]
     pub created_at: String,
+    /// ISO-8601 timestamp of the last edit, member or instance change. Empty
+    /// for records saved before it was tracked.
+    #[serde(default)]
+    pub updated_at: String,
     /// Members of this workspace and their workspace-level roles.
     #[serde(default)]
     pub members: Vec<WorkspaceMember>,
     /// Instance IDs that belong to this workspace.
     #[serde(default)]
     pub assigned_instances: Vec<String>,
+    /// Region the creation wizard preselects for this workspace (empty: none).
+    #[serde(default)]
+    pub default_region: String,
+    /// Plan type (`fixed` or `custom`) the wizard preselects (empty: none).
+    #[serde(default)]
+    pub default_plan_type: String,
 }
 
 impl WorkspaceRecord {
@@ -96,6 +106,24 @@ impl WorkspaceRecord {
     pub fn has_instance(&self, id: &str) -> bool {
         self.assigned_instances.iter().any(|i| i == id)
     }
+
+    /// When the workspace last changed: `updated_at`, or `created_at` for
+    /// records that predate change tracking.
+    pub fn last_changed(&self) -> &str {
+        if self.updated_at.is_empty() {
+            &self.created_at
+        } else {
+            &self.updated_at
+        }
+    }
+
+    /// Whether `username` may create instances in this workspace as a
+    /// manager or editor (panel owners are checked separately).
+    pub fn can_create_instances(&self, username: &str) -> bool {
+        self.members
+            .iter()
+            .any(|m| m.username == username && matches!(m.role, WorkspaceRole::Manager | WorkspaceRole::Editor))
+    }
 }
 
 #[cfg(test)]
@@ -116,6 +144,26 @@ mod tests {
         assert!(WorkspaceRole::from_str("superuser").is_none());
     }
 
+    #[test]
+    fn only_managers_and_editors_create_instances() {
+        let member = |username: &str, role| WorkspaceMember { username: username.to_string(), role };
+        let ws = WorkspaceRecord {
+            name: "Payments".to_string(),
+            description: String::new(),
+            slug: "payments".to_string(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            members: vec![member("ann", WorkspaceRole::Manager), member("ed", WorkspaceRole::Editor), member("vi", WorkspaceRole::Viewer)],
+            assigned_instances: vec![],
+            default_region: String::new(),
+            default_plan_type: String::new(),
+        };
+        assert!(ws.can_create_instances("ann"));
+        assert!(ws.can_create_instances("ed"));
+        assert!(!ws.can_create_instances("vi"));
+        assert!(!ws.can_create_instances("stranger"));
+    }
+
     #[test]
     fn workspace_role_labels_non_empty() {
         for role in WorkspaceRole::all() {
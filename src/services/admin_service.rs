@@ -0,0 +1,94 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::models::AppState;
+use crate::services::load_clocked_instances_from_file;
+use crate::utils::GlobPattern;
+
+/// Unix timestamp (seconds) the request was signed at.
+pub const ADMIN_TIMESTAMP_HEADER: &str = "x-zy-timestamp";
+/// Hex HMAC-SHA256 of `"{timestamp}\n{METHOD}\n{path}"` keyed with `ZY_ADMIN_SECRET`.
+pub const ADMIN_SIGNATURE_HEADER: &str = "x-zy-signature";
+/// How far a signed request's timestamp may drift from the server clock.
+pub const MAX_ADMIN_CLOCK_SKEW_SECS: i64 = 300;
+
+fn admin_mac(secret: &str, method: &str, path: &str, timestamp: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(format!("{}\n{}\n{}", timestamp, method.to_uppercase(), path).as_bytes());
+    mac
+}
+
+/// Signature for an admin request to `path`, sent with the
+/// [`ADMIN_TIMESTAMP_HEADER`] and [`ADMIN_SIGNATURE_HEADER`] headers.
+pub fn sign_admin_request(secret: &str, method: &str, path: &str, timestamp: i64) -> String {
+    hex::encode(admin_mac(secret, method, path, &timestamp.to_string()).finalize().into_bytes())
+}
+
+/// Check a signed admin request; `now` is the server's Unix time.
+pub fn verify_admin_request(secret: &str, method: &str, path: &str, timestamp: &str, signature: &str, now: i64) -> Result<(), String> {
+    let signed_at: i64 = timestamp.trim().parse().map_err(|_| "Missing or invalid timestamp".to_string())?;
+    if (now - signed_at).abs() > MAX_ADMIN_CLOCK_SKEW_SECS {
+        return Err("Request timestamp is too far from the server clock".into());
+    }
+    let signature = hex::decode(signature.trim()).map_err(|_| "Missing or invalid signature".to_string())?;
+    admin_mac(secret, method, path, timestamp.trim())
+        .verify_slice(&signature)
+        .map_err(|_| "Signature mismatch".to_string())
+}
+
+/// What [`reload_block_lists`] put in effect: how many disabled-instance IDs
+/// and blocked-hostname patterns, and where each list came from.
+#[derive(Debug)]
+pub struct BlockListReload {
+    pub disabled_instances: usize,
+    pub disabled_instances_source: &'static str,
+    pub blocked_hostname_patterns: usize,
+    pub blocked_hostname_patterns_source: &'static str,
+}
+
+/// Re-read the env file and replace the disabled-instance IDs
+/// (`clocked_instances.json` if present, else `DISABLED_INSTANCE_IDS`) and the
+/// blocked-hostname patterns. Only those two keys are taken from the file; a
+/// key the file doesn't set falls back to the process environment, as at
+/// startup. Every other setting stays as it was. Returns the lists now in
+/// effect, or why the file could not be read.
+pub async fn reload_block_lists(state: &AppState) -> Result<BlockListReload, String> {
+    let vars = crate::config::read_env_file_vars(state.env_file.as_deref())?;
+    let (ids, disabled_instances_source) = match load_clocked_instances_from_file().await {
+        Some(ids) => (ids, "clocked_instances.json"),
+        None => {
+            let (raw, source) = crate::config::env_file_or_process_var(&vars, "DISABLED_INSTANCE_IDS");
+            (crate::config::parse_disabled_instance_ids(&raw), source)
+        }
+    };
+    let (raw_patterns, blocked_hostname_patterns_source) = crate::config::env_file_or_process_var(&vars, "ZY_BLOCKED_HOSTNAME_PATTERNS");
+    let patterns: Vec<GlobPattern> = crate::config::parse_blocked_hostname_patterns(&raw_patterns)
+        .iter()
+        .map(|p| GlobPattern::new(p))
+        .collect();
+    let reload = BlockListReload {
+        disabled_instances: ids.len(),
+        disabled_instances_source,
+        blocked_hostname_patterns: patterns.len(),
+        blocked_hostname_patterns_source,
+    };
+    *state.disabled_instances.lock().unwrap() = ids;
+    *state.blocked_hostname_patterns.lock().unwrap() = patterns;
+    Ok(reload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_admin_signature_round_trip() {
+        let sig = sign_admin_request("secret", "POST", "/admin/cache/clear", 1_000);
+        assert!(verify_admin_request("secret", "POST", "/admin/cache/clear", "1000", &sig, 1_100).is_ok());
+        assert!(verify_admin_request("other", "POST", "/admin/cache/clear", "1000", &sig, 1_100).is_err());
+        assert!(verify_admin_request("secret", "POST", "/admin/other", "1000", &sig, 1_100).is_err());
+        assert!(verify_admin_request("secret", "POST", "/admin/cache/clear", "1001", &sig, 1_100).is_err());
+        assert!(verify_admin_request("secret", "POST", "/admin/cache/clear", "1000", &sig, 2_000).is_err());
+        assert!(verify_admin_request("secret", "POST", "/admin/cache/clear", "", "", 1_000).is_err());
+    }
+}
@@ -0,0 +1,298 @@
+use futures_util::stream::{self, StreamExt};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::models::{AppState, InstanceColumn, InstanceView, OsItem};
+use crate::services::instance_service::{check_action_cooldown, check_instance_block, custom_resize_downsizes, get_instance_for_action};
+
+/// How many instances a bulk action changes at once by default.
+pub const DEFAULT_BULK_CONCURRENCY: usize = 4;
+
+/// Target size for a bulk resize, mirroring the single-instance resize form.
+#[derive(Clone, Debug, Default)]
+pub struct ResizeSpec {
+    /// `FIXED` or `CUSTOM`.
+    pub resize_type: String,
+    pub product_id: Option<String>,
+    pub cpu: Option<i64>,
+    pub ram_in_gb: Option<i64>,
+    pub disk_in_gb: Option<i64>,
+    pub bandwidth_in_tb: Option<i64>,
+    /// Go ahead with CUSTOM resizes that shrink CPU, RAM or disk.
+    pub allow_downsize: bool,
+}
+
+impl ResizeSpec {
+    /// Request body for `POST /v1/instances/:id/resize`. FIXED sends the
+    /// product plus any extra disk/bandwidth; CUSTOM sends every given resource.
+    pub fn payload(&self) -> Value {
+        let resize_type = self.resize_type.to_uppercase();
+        let mut payload = serde_json::json!({"type": resize_type});
+        let mut extra = serde_json::Map::new();
+        if resize_type == "FIXED" {
+            if let Some(pid) = self.product_id.as_deref().filter(|p| !p.trim().is_empty()) {
+                payload["productId"] = Value::from(pid.trim());
+            }
+            if let Some(disk) = self.disk_in_gb.filter(|&n| n > 0) { extra.insert("diskInGB".into(), Value::from(disk)); }
+            if let Some(bw) = self.bandwidth_in_tb.filter(|&n| n > 0) { extra.insert("bandwidthInTB".into(), Value::from(bw)); }
+        } else {
+            if let Some(cpu) = self.cpu { extra.insert("cpu".into(), Value::from(cpu)); }
+            if let Some(ram) = self.ram_in_gb { extra.insert("ramInGB".into(), Value::from(ram)); }
+            if let Some(disk) = self.disk_in_gb { extra.insert("diskInGB".into(), Value::from(disk)); }
+            if let Some(bw) = self.bandwidth_in_tb { extra.insert("bandwidthInTB".into(), Value::from(bw)); }
+        }
+        if !extra.is_empty() {
+            payload["extraResource"] = Value::Object(extra);
+        }
+        payload
+    }
+}
+
+/// Power operations that take no request body.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PowerAction {
+    On,
+    Off,
+    Reset,
+}
+
+impl PowerAction {
+    /// `poweron`, `poweroff` or `reset`, as used in instance action URLs.
+    pub fn parse(action: &str) -> Option<Self> {
+        match action {
+            "poweron" => Some(PowerAction::On),
+            "poweroff" => Some(PowerAction::Off),
+            "reset" => Some(PowerAction::Reset),
+            _ => None,
+        }
+    }
+}
+
+/// The change applied to every instance in a bulk request.
+#[derive(Clone, Debug)]
+pub enum BulkAction {
+    ChangeOs { os_id: String },
+    Resize(ResizeSpec),
+    Power(PowerAction),
+}
+
+impl BulkAction {
+    /// Action name used for cooldowns and the upstream endpoint.
+    fn endpoint_action(&self) -> &'static str {
+        match self {
+            BulkAction::ChangeOs { .. } => "change-os",
+            BulkAction::Resize(_) => "resize",
+            BulkAction::Power(PowerAction::On) => "poweron",
+            BulkAction::Power(PowerAction::Off) => "poweroff",
+            BulkAction::Power(PowerAction::Reset) => "reset",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BulkAction::ChangeOs { .. } => "Change OS",
+            BulkAction::Resize(_) => "Resize",
+            BulkAction::Power(PowerAction::On) => "Power on",
+            BulkAction::Power(PowerAction::Off) => "Power off",
+            BulkAction::Power(PowerAction::Reset) => "Reset",
+        }
+    }
+}
+
+/// What happened to one instance in a bulk request.
+#[derive(Clone, Debug, Serialize)]
+pub struct BulkOutcome {
+    pub instance_id: String,
+    pub ok: bool,
+    pub message: String,
+}
+
+impl BulkOutcome {
+    fn failed(instance_id: &str, message: impl Into<String>) -> Self {
+        BulkOutcome { instance_id: instance_id.to_string(), ok: false, message: message.into() }
+    }
+}
+
+/// Split pasted instance IDs on commas and whitespace, dropping blanks and
+/// repeats while keeping the first-seen order.
+pub fn parse_instance_ids(raw: &str) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+    for id in raw.split(|c: char| c == ',' || c.is_whitespace()).filter(|s| !s.is_empty()) {
+        if !ids.iter().any(|existing| existing == id) {
+            ids.push(id.to_string());
+        }
+    }
+    ids
+}
+
+/// A `key=value` condition selecting bulk targets from the instance list,
+/// e.g. `status=stopped`. Keys are `ZY_INSTANCE_COLUMNS` names; values
+/// compare case-insensitively against the column's text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InstanceFilter {
+    pub column: InstanceColumn,
+    pub value: String,
+}
+
+impl InstanceFilter {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let Some((key, value)) = raw.split_once('=') else {
+            return Err(format!("Expected key=value, got '{}'", raw));
+        };
+        let columns = InstanceColumn::parse_list(key)?;
+        match columns.as_slice() {
+            [column] => Ok(InstanceFilter { column: *column, value: value.trim().to_string() }),
+            _ => Err(format!("Expected a single column name, got '{}'", key.trim())),
+        }
+    }
+
+    pub fn matches(&self, instance: &InstanceView) -> bool {
+        self.column.text(instance, "").eq_ignore_ascii_case(&self.value)
+    }
+}
+
+/// IDs of the instances matching every filter, in list order. A non-empty
+/// `ids` narrows the candidates to those instances.
+pub fn select_targets(instances: &[InstanceView], ids: &[String], filters: &[InstanceFilter]) -> Vec<String> {
+    instances
+        .iter()
+        .filter(|i| ids.is_empty() || ids.contains(&i.id))
+        .filter(|i| filters.iter().all(|f| f.matches(i)))
+        .map(|i| i.id.clone())
+        .collect()
+}
+
+/// `"Change OS: 28 succeeded, 2 failed"`.
+pub fn summarize(action: &BulkAction, outcomes: &[BulkOutcome]) -> String {
+    let succeeded = outcomes.iter().filter(|o| o.ok).count();
+    format!("{}: {} succeeded, {} failed", action.label(), succeeded, outcomes.len() - succeeded)
+}
+
+async fn run_one(state: &AppState, instance_id: &str, action: &BulkAction, os_list: &[OsItem]) -> BulkOutcome {
+    if let Some(reason) = check_instance_block(state, instance_id, None).await {
+        return BulkOutcome::failed(instance_id, format!("Skipped: {}", reason.message()));
+    }
+    let body = match action {
+        BulkAction::ChangeOs { os_id } => {
+            let Some(os) = os_list.iter().find(|os| os.id == *os_id) else {
+                return BulkOutcome::failed(instance_id, format!("Unknown operating system '{}'.", os_id));
+            };
+            let instance = get_instance_for_action(state, instance_id).await;
+            if let Some(reason) = os.incompatibility(instance.ram, instance.os.as_ref().and_then(|o| o.arch.as_deref())) {
+                return BulkOutcome::failed(instance_id, format!("Skipped: cannot install {}: {}.", os.name, reason));
+            }
+            Some(serde_json::json!({"osId": os_id}))
+        }
+        BulkAction::Resize(spec) => {
+            if spec.resize_type.eq_ignore_ascii_case("CUSTOM") && !spec.allow_downsize {
+                let changes = match custom_resize_downsizes(state, instance_id, spec.cpu, spec.ram_in_gb, spec.disk_in_gb).await {
+                    Ok(changes) => changes,
+                    Err(e) => return BulkOutcome::failed(instance_id, format!("Skipped: {}.", e)),
+                };
+                if !changes.is_empty() {
+                    return BulkOutcome::failed(instance_id, format!("Skipped: resize would shrink {}.", changes.join(", ")));
+                }
+            }
+            Some(spec.payload())
+        }
+        BulkAction::Power(_) => None,
+    };
+    // Only a request that is about to go upstream starts the cooldown
+    if let Some(reason) = check_action_cooldown(state, instance_id, action.endpoint_action()) {
+        return BulkOutcome::failed(instance_id, format!("Skipped: {}", reason.message()));
+    }
+    let endpoint = format!("/v1/instances/{}/{}", instance_id, action.endpoint_action());
+    let call = crate::api::api_call(&state.client, &state.api_base_url, &state.api_token, "POST", &endpoint, body, None);
+    let resp = match action {
+        BulkAction::ChangeOs { .. } | BulkAction::Resize(_) => {
+            let call = crate::api::timeout::scope(crate::api::timeout::long_operation_timeout(), call);
+            state.instance_cache.invalidate_around(call).await
+        }
+        BulkAction::Power(_) => state.instance_cache.invalidate_around(call).await,
+    };
+    let ok = matches!(resp.get("code").and_then(|c| c.as_str()), Some("OKAY") | Some("CREATED"));
+    let message = if ok {
+        format!("{} requested.", action.label())
+    } else if let Some(message) = crate::api::auth_failure_message(&resp) {
+        message
+    } else {
+        resp.get("detail")
+            .or_else(|| resp.get("error"))
+            .and_then(|d| d.as_str())
+            .unwrap_or("Unknown error")
+            .to_string()
+    };
+    tracing::info!(%instance_id, action = action.endpoint_action(), ok, "Bulk instance action");
+    BulkOutcome { instance_id: instance_id.to_string(), ok, message }
+}
+
+/// Apply `action` to every instance in `ids`, at most `concurrency` (bounded
+/// by `ZY_MAX_CONCURRENCY`) at a time.
+/// Each instance is checked against the block list, hostname patterns and
+/// action cooldown on its own; outcomes come back in the order of `ids`.
+pub async fn run_bulk_action(state: &AppState, ids: &[String], action: &BulkAction, concurrency: usize) -> Vec<BulkOutcome> {
+    let os_list = match action {
+        BulkAction::ChangeOs { .. } => crate::api::load_os_list(&state.client, &state.api_base_url, &state.api_token).await,
+        BulkAction::Resize(_) | BulkAction::Power(_) => Vec::new(),
+    };
+    let os_list = &os_list;
+    stream::iter(ids.iter().cloned())
+        .map(|id| async move { run_one(state, &id, action, os_list).await })
+        .buffered(state.fan_out(concurrency))
+        .collect()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_targets_by_filter() {
+        let mut running = InstanceView::new_with_defaults("a".into());
+        running.status = "active".into();
+        let mut stopped = InstanceView::new_with_defaults("b".into());
+        stopped.status = "Stopped".into();
+        let mut other = InstanceView::new_with_defaults("c".into());
+        other.status = "stopped".into();
+        other.region = "eu-1".into();
+        let list = vec![running, stopped, other];
+
+        let filters = vec![InstanceFilter::parse("status=stopped").unwrap()];
+        assert_eq!(select_targets(&list, &[], &filters), vec!["b", "c"]);
+        assert_eq!(select_targets(&list, &["c".into(), "a".into()], &filters), vec!["c"]);
+        let both = vec![filters[0].clone(), InstanceFilter::parse("region=EU-1").unwrap()];
+        assert_eq!(select_targets(&list, &[], &both), vec!["c"]);
+
+        assert!(InstanceFilter::parse("status").is_err());
+        assert!(InstanceFilter::parse("colour=red").is_err());
+        assert!(InstanceFilter::parse("status,region=x").is_err());
+    }
+
+    #[test]
+    fn test_parse_instance_ids() {
+        assert_eq!(parse_instance_ids("a, b\nc  a,,"), vec!["a", "b", "c"]);
+        assert!(parse_instance_ids(" \n").is_empty());
+    }
+
+    #[test]
+    fn test_resize_spec_payload() {
+        let fixed = ResizeSpec {
+            resize_type: "fixed".into(),
+            product_id: Some("p-2".into()),
+            cpu: Some(4),
+            disk_in_gb: Some(0),
+            bandwidth_in_tb: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            fixed.payload(),
+            serde_json::json!({"type": "FIXED", "productId": "p-2", "extraResource": {"bandwidthInTB": 2}})
+        );
+        let custom = ResizeSpec { resize_type: "CUSTOM".into(), cpu: Some(2), ram_in_gb: Some(4), ..Default::default() };
+        assert_eq!(
+            custom.payload(),
+            serde_json::json!({"type": "CUSTOM", "extraResource": {"cpu": 2, "ramInGB": 4}})
+        );
+    }
+}
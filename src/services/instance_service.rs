@@ -1,15 +1,23 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
 use serde_json::Value;
 
-use crate::models::{AppState, InstanceView, OsItem};
+use crate::models::{AppState, InstanceView, OsItem, SshKeyView};
 
 pub async fn simple_instance_action(state: &AppState, action: &str, instance_id: &str) -> Value {
     let endpoint = format!("/v1/instances/{}/{}", instance_id, action);
-    crate::api::api_call(&state.client, &state.api_base_url, &state.api_token, "POST", &endpoint, None, None).await
+    let call = crate::api::api_call(&state.client, &state.api_base_url, &state.api_token, "POST", &endpoint, None, None);
+    state.instance_cache.invalidate_around(call).await
 }
 
 pub enum BlockReason {
     Blacklisted,
     HostnameMatch(String),
+    /// The instance hostname matches a `ZY_BLOCKED_HOSTNAME_PATTERNS` entry.
+    HostnamePattern { hostname: String, pattern: String },
+    /// The same action was submitted for this instance moments ago.
+    Cooldown(Duration),
 }
 
 impl BlockReason {
@@ -17,6 +25,8 @@ impl BlockReason {
         match self {
             BlockReason::Blacklisted => "Actions are disabled for this instance.".into(),
             BlockReason::HostnameMatch(h) => format!("Actions are disabled because the instance hostname ({}) matches the hostname of this application server.", h),
+            BlockReason::HostnamePattern { hostname, pattern } => format!("Actions are disabled because the instance hostname ({}) matches the blocked pattern '{}'.", hostname, pattern),
+            BlockReason::Cooldown(wait) => format!("This action was just submitted; please wait {}s before trying again.", wait.as_secs().max(1)),
         }
     }
 }
@@ -26,21 +36,258 @@ pub async fn check_instance_block(state: &AppState, instance_id: &str, hostname:
         return Some(BlockReason::Blacklisted);
     }
     
-    if let Some(h) = hostname {
-        if state.is_hostname_blocked(h) {
-            return Some(BlockReason::HostnameMatch(h.to_string()));
-        }
-    } else {
+    let hostname = match hostname {
+        Some(h) => h.to_string(),
         // Fetch hostname if not provided
-        let instance = get_instance_for_action(state, instance_id).await;
-        if state.is_hostname_blocked(&instance.hostname) {
-            return Some(BlockReason::HostnameMatch(instance.hostname));
+        None => get_instance_for_action(state, instance_id).await.hostname,
+    };
+    if state.is_hostname_blocked(&hostname) {
+        return Some(BlockReason::HostnameMatch(hostname));
+    }
+    if let Some(pattern) = state.blocked_hostname_pattern(&hostname) {
+        return Some(BlockReason::HostnamePattern { hostname, pattern });
+    }
+    None
+}
+
+/// Whether the hostname typed to confirm a delete names `hostname`
+/// (surrounding whitespace, a trailing dot and case are ignored). The
+/// placeholder of an instance that failed to load never confirms.
+pub fn hostname_confirmed(hostname: &str, typed: &str) -> bool {
+    let normalize = |s: &str| s.trim().trim_end_matches('.').to_ascii_lowercase();
+    !hostname.trim().is_empty() && hostname != InstanceView::NO_HOSTNAME && normalize(hostname) == normalize(typed)
+}
+
+/// The hostname a delete must be confirmed with, or `None` when the instance
+/// (or its hostname) could not be loaded and there is nothing to check against.
+pub async fn confirmation_hostname(state: &AppState, instance_id: &str) -> Option<String> {
+    try_get_instance_for_action(state, instance_id)
+        .await
+        .map(|instance| instance.hostname)
+        .filter(|hostname| !hostname.trim().is_empty() && hostname != InstanceView::NO_HOSTNAME)
+}
+
+/// Check `id` against the catalog's `valid` IDs, listing the valid ones on a
+/// mismatch so CLI users get an actionable message instead of an upstream error.
+pub fn ensure_known_id(kind: &str, id: &str, valid: &[String]) -> Result<(), String> {
+    if valid.iter().any(|v| v == id) {
+        return Ok(());
+    }
+    if valid.is_empty() {
+        return Err(format!("Unknown {} '{}'; no {}s are available", kind, id, kind));
+    }
+    Err(format!("Unknown {} '{}'. Valid {}s are: {}", kind, id, kind, valid.join(", ")))
+}
+
+/// Resources a CUSTOM resize would shrink relative to `current`, e.g.
+/// `"disk 50 GB → 25 GB"`; empty when nothing shrinks. Unknown current values
+/// (zero) are never treated as a downsize.
+pub fn downsize_changes(current: &InstanceView, cpu: Option<i64>, ram_in_gb: Option<i64>, disk_in_gb: Option<i64>) -> Vec<String> {
+    let mut changes = Vec::new();
+    if let Some(cpu) = cpu.filter(|&c| current.vcpu_count > 0 && c < current.vcpu_count as i64) {
+        changes.push(format!("CPU {} → {} cores", current.vcpu_count, cpu));
+    }
+    // Instance RAM is reported in MB, the resize takes GB
+    if let Some(ram) = ram_in_gb.filter(|&r| current.ram > 0 && r * 1024 < current.ram as i64) {
+        changes.push(format!("RAM {} MB → {} GB", current.ram, ram));
+    }
+    if let Some(disk) = disk_in_gb.filter(|&d| current.disk > 0 && d < current.disk as i64) {
+        changes.push(format!("disk {} GB → {} GB", current.disk, disk));
+    }
+    changes
+}
+
+/// [`downsize_changes`] for a CUSTOM resize of `instance_id` against its
+/// current specs. Fails closed: when the instance can't be loaded a shrink
+/// can't be ruled out, so an error is returned instead of no changes.
+pub async fn custom_resize_downsizes(state: &AppState, instance_id: &str, cpu: Option<i64>, ram_in_gb: Option<i64>, disk_in_gb: Option<i64>) -> Result<Vec<String>, String> {
+    let current = try_get_instance_for_action(state, instance_id)
+        .await
+        .ok_or_else(|| format!("Could not load the current specs of instance {} to check for a downsize", instance_id))?;
+    Ok(downsize_changes(&current, cpu, ram_in_gb, disk_in_gb))
+}
+
+fn json_id(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// SSH keys an instance detail says it was provisioned with, as (ID, name)
+/// pairs. Reads `sshKeyIds` (bare IDs) and `sshKeys` (IDs or `{id, name}`
+/// objects); the name is `None` when the upstream only gave an ID.
+pub fn provisioned_ssh_keys(data: &Value) -> Vec<(String, Option<String>)> {
+    let mut keys: Vec<(String, Option<String>)> = Vec::new();
+    let entries = ["sshKeyIds", "sshKeys"]
+        .iter()
+        .filter_map(|field| data.get(*field).and_then(|v| v.as_array()))
+        .flatten();
+    for entry in entries {
+        let (id, name) = match entry {
+            Value::Object(obj) => (
+                obj.get("id").and_then(json_id),
+                obj.get("name").and_then(|n| n.as_str()).filter(|n| !n.is_empty()).map(str::to_string),
+            ),
+            other => (json_id(other), None),
+        };
+        let Some(id) = id else { continue };
+        match keys.iter_mut().find(|(existing, _)| *existing == id) {
+            Some(existing) => existing.1 = existing.1.take().or(name),
+            None => keys.push((id, name)),
         }
     }
-    
+    keys
+}
+
+/// Display labels for provisioned keys: the upstream name, else the name of
+/// the account key with that ID, else `"<id> (unknown)"` for deleted keys.
+pub fn ssh_key_labels(keys: &[(String, Option<String>)], known: &[SshKeyView]) -> Vec<String> {
+    keys.iter()
+        .map(|(id, name)| {
+            name.clone()
+                .or_else(|| known.iter().find(|k| k.id == *id).map(|k| k.name.clone()))
+                .unwrap_or_else(|| format!("{} (unknown)", id))
+        })
+        .collect()
+}
+
+/// Monthly prices before and after a FIXED resize.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResizePreview {
+    /// The instance's current product, if the API reports one.
+    pub current_product: Option<String>,
+    /// `None` when the current product isn't in the region's catalog.
+    pub current_monthly: Option<f64>,
+    pub target_product: String,
+    pub target_monthly: f64,
+}
+
+impl ResizePreview {
+    /// Change in monthly price; `None` when the current price is unknown.
+    pub fn delta(&self) -> Option<f64> {
+        self.current_monthly.map(|current| self.target_monthly - current)
+    }
+
+    /// e.g. `"+$6.00/mo"`, `"-$2.50/mo"` or `"no change"`.
+    pub fn delta_display(&self) -> String {
+        match self.delta() {
+            None => "unknown (current plan price unavailable)".to_string(),
+            Some(d) if d.abs() < 0.005 => "no change".to_string(),
+            Some(d) if d > 0.0 => format!("+${:.2}/mo", d),
+            Some(d) => format!("-${:.2}/mo", -d),
+        }
+    }
+
+    pub fn current_display(&self) -> String {
+        let product = self.current_product.as_deref().unwrap_or("unknown");
+        match self.current_monthly {
+            Some(price) => format!("{} (${:.2}/mo)", product, price),
+            None => product.to_string(),
+        }
+    }
+
+    pub fn target_display(&self) -> String {
+        format!("{} (${:.2}/mo)", self.target_product, self.target_monthly)
+    }
+}
+
+/// Price a FIXED resize to `target` from the region's `(product ID, monthly
+/// price)` catalog. Fails when `target` isn't in the catalog.
+pub fn resize_preview(current: Option<&str>, target: &str, prices: &[(String, f64)]) -> Result<ResizePreview, String> {
+    let price_of = |id: &str| prices.iter().find(|(pid, _)| pid == id).map(|(_, price)| *price);
+    let target_monthly = price_of(target).ok_or_else(|| format!("Product '{}' is not offered in this instance's region", target))?;
+    Ok(ResizePreview {
+        current_product: current.map(str::to_string),
+        current_monthly: current.and_then(price_of),
+        target_product: target.to_string(),
+        target_monthly,
+    })
+}
+
+/// Look up the instance's region catalog and price a FIXED resize to `product_id`.
+pub async fn preview_resize(state: &AppState, instance_id: &str, product_id: &str) -> Result<ResizePreview, String> {
+    let instance = get_instance_for_action(state, instance_id).await;
+    if instance.region.is_empty() {
+        return Err(format!("Could not determine the region of instance {}", instance_id));
+    }
+    let products = crate::api::load_products(&state.client, &state.api_base_url, &state.api_token, &instance.region).await;
+    let prices: Vec<(String, f64)> = products.iter().map(|p| (p.id.clone(), p.monthly_price())).collect();
+    resize_preview(instance.product_id.as_deref(), product_id, &prices)
+}
+
+/// The canonical (uppercase) resize type for `raw`, which may be any case.
+pub fn parse_resize_type(raw: &str) -> Result<&'static str, String> {
+    ["FIXED", "CUSTOM"]
+        .into_iter()
+        .find(|t| t.eq_ignore_ascii_case(raw.trim()))
+        .ok_or_else(|| format!("Unknown resize type '{}'. Valid types are: FIXED, CUSTOM", raw))
+}
+
+/// Pre-flight check for a CLI resize: the type must be FIXED or CUSTOM (any
+/// case) and, for FIXED, the product must exist in the instance's region.
+/// When the region's catalog can't be loaded the product check is skipped
+/// with a warning and the API decides, as for change-os.
+pub async fn validate_resize(state: &AppState, instance_id: &str, resize_type: &str, product_id: Option<&str>) -> Result<(), String> {
+    if parse_resize_type(resize_type)? == "CUSTOM" {
+        return Ok(());
+    }
+    let product_id = product_id.ok_or("FIXED resize requires --product-id")?;
+    let instance = get_instance_for_action(state, instance_id).await;
+    if instance.region.is_empty() {
+        return Err(format!("Could not determine the region of instance {}", instance_id));
+    }
+    let products = crate::api::load_products(&state.client, &state.api_base_url, &state.api_token, &instance.region).await;
+    if products.is_empty() {
+        tracing::warn!(%instance_id, region = %instance.region, "Product list unavailable; skipping resize product check");
+        return Ok(());
+    }
+    let ids: Vec<String> = products.into_iter().map(|p| p.id).collect();
+    ensure_known_id("product", product_id, &ids).map_err(|e| format!("Region {}: {}", instance.region, e))
+}
+
+/// Record `action` on `instance_id` at `now` unless the same action ran within
+/// `window`, in which case the remaining wait is returned and nothing changes.
+pub fn try_start_action(
+    cooldowns: &mut HashMap<(String, String), Instant>,
+    instance_id: &str,
+    action: &str,
+    now: Instant,
+    window: Duration,
+) -> Option<Duration> {
+    let key = (instance_id.to_string(), action.to_string());
+    if let Some(last) = cooldowns.get(&key) {
+        let elapsed = now.saturating_duration_since(*last);
+        if elapsed < window {
+            return Some(window - elapsed);
+        }
+    }
+    cooldowns.retain(|_, t| now.saturating_duration_since(*t) < window);
+    cooldowns.insert(key, now);
     None
 }
 
+/// Instances whose status differs from `previous` (keyed by ID), including
+/// ones not seen before.
+pub fn status_changes<'a>(previous: &HashMap<String, String>, current: &'a [InstanceView]) -> Vec<&'a InstanceView> {
+    current
+        .iter()
+        .filter(|inst| previous.get(&inst.id) != Some(&inst.status))
+        .collect()
+}
+
+/// Debounce duplicate submits: blocks `action` on `instance_id` if it was
+/// started within the configured cooldown. Complements [`check_instance_block`].
+pub fn check_action_cooldown(state: &AppState, instance_id: &str, action: &str) -> Option<BlockReason> {
+    if state.action_cooldown.is_zero() {
+        return None;
+    }
+    let mut cooldowns = state.action_cooldowns.lock().unwrap();
+    try_start_action(&mut cooldowns, instance_id, action, Instant::now(), state.action_cooldown)
+        .map(BlockReason::Cooldown)
+}
+
 pub async fn enforce_instance_access(state: &AppState, username: Option<&str>, instance_id: &str) -> bool {
     if let Some(username) = username {
         let users = state.users.lock().unwrap();
@@ -66,64 +313,186 @@ pub async fn enforce_instance_access(state: &AppState, username: Option<&str>, i
 
 #[allow(dead_code)]
 pub async fn get_instance_for_action(state: &AppState, instance_id: &str) -> InstanceView {
+    try_get_instance_for_action(state, instance_id)
+        .await
+        .unwrap_or_else(|| InstanceView::new_with_defaults(instance_id.to_string()))
+}
+
+/// Like [`get_instance_for_action`], but `None` when the upstream GET failed
+/// instead of a placeholder with zeroed specs.
+pub async fn try_get_instance_for_action(state: &AppState, instance_id: &str) -> Option<InstanceView> {
     let endpoint = format!("/v1/instances/{}", instance_id);
     let payload = crate::api::api_call(&state.client, &state.api_base_url, &state.api_token, "GET", &endpoint, None, None).await;
     let mut instance = InstanceView::new_with_defaults(instance_id.to_string());
-    if let Some(obj) = payload.as_object() {
-        if let Some(data) = obj.get("data").and_then(|d| d.as_object()) {
-            instance.hostname = data.get("hostname").and_then(|v| v.as_str()).unwrap_or(&instance.hostname).to_string();
-            instance.vcpu_count = data.get("vcpuCount").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
-            instance.ram = data.get("ram").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
-            instance.disk = data.get("disk").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
-            instance.inserted_at = data.get("insertedAt").and_then(|v| v.as_str()).map(|s| s.to_string());
-            instance.os_id = data.get("osId").and_then(|v| v.as_str()).map(|s| s.to_string());
-            instance.iso_id = data.get("isoId").and_then(|v| v.as_str()).map(|s| s.to_string());
-            instance.from_image = data.get("fromImage").and_then(|v| v.as_str()).map(|s| s.to_string());
-            instance.region = data.get("region").and_then(|v| v.as_str()).unwrap_or("").to_string();
-            instance.user_id = data.get("userId").and_then(|v| v.as_str()).map(|s| s.to_string());
-            instance.app_id = data.get("appId").and_then(|v| v.as_str()).map(|s| s.to_string());
-            instance.status = data.get("status").and_then(|v| v.as_str()).unwrap_or("").to_string();
-            instance.main_ip = data.get("mainIp").and_then(|v| v.as_str()).map(|s| s.to_string());
-            instance.main_ipv6 = data.get("mainIpv6").and_then(|v| v.as_str()).map(|s| s.to_string());
-            instance.product_id = data.get("productId").and_then(|v| v.as_str()).map(|s| s.to_string());
-            instance.network_status = data.get("networkStatus").and_then(|v| v.as_str()).map(|s| s.to_string());
-            instance.discount_percent = data.get("discountPercent").and_then(|v| v.as_i64()).map(|i| i as i32);
-            instance.attach_iso = data.get("attachIso").and_then(|v| v.as_bool());
-            instance.class = data.get("class").and_then(|v| v.as_str()).unwrap_or("").to_string();
-            instance.oca_data = data.get("ocaData").cloned();
-            instance.is_ddos_protected = data.get("isDdosProtected").and_then(|v| v.as_bool());
-            instance.customer_note = data.get("customerNote").and_then(|v| v.as_str()).map(|s| s.to_string());
-            instance.admin_note = data.get("adminNote").and_then(|v| v.as_str()).map(|s| s.to_string());
-
-            // Parse extra_resource if present
-            if let Some(er_obj) = data.get("extraResource").and_then(|v| v.as_object()) {
-                use crate::models::instance_view::ExtraResource;
-                instance.extra_resource = Some(ExtraResource {
-                    cpu: er_obj.get("cpu").and_then(|v| v.as_i64()).map(|i| i as i32),
-                    ram_in_gb: er_obj.get("ramInGB").and_then(|v| v.as_i64()).map(|i| i as i32),
-                    disk_in_gb: er_obj.get("diskInGB").and_then(|v| v.as_i64()).map(|i| i as i32),
-                    bandwidth_in_tb: er_obj.get("bandwidthInTB").and_then(|v| v.as_i64()).map(|i| i as i32),
-                });
-            }
+    let data = payload.get("data").and_then(|d| d.as_object())?;
+    instance.hostname = data.get("hostname").and_then(|v| v.as_str()).unwrap_or(&instance.hostname).to_string();
+    instance.vcpu_count = data.get("vcpuCount").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
+    instance.ram = data.get("ram").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
+    instance.disk = data.get("disk").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
+    instance.inserted_at = data.get("insertedAt").and_then(|v| v.as_str()).map(|s| s.to_string());
+    instance.os_id = data.get("osId").and_then(|v| v.as_str()).map(|s| s.to_string());
+    instance.iso_id = data.get("isoId").and_then(|v| v.as_str()).map(|s| s.to_string());
+    instance.from_image = data.get("fromImage").and_then(|v| v.as_str()).map(|s| s.to_string());
+    instance.region = data.get("region").and_then(|v| v.as_str()).unwrap_or("").to_string();
+    instance.user_id = data.get("userId").and_then(|v| v.as_str()).map(|s| s.to_string());
+    instance.app_id = data.get("appId").and_then(|v| v.as_str()).map(|s| s.to_string());
+    instance.status = data.get("status").and_then(|v| v.as_str()).unwrap_or("").to_string();
+    instance.main_ip = data.get("mainIp").and_then(|v| v.as_str()).map(|s| s.to_string());
+    instance.main_ipv6 = data.get("mainIpv6").and_then(|v| v.as_str()).map(|s| s.to_string());
+    instance.product_id = data.get("productId").and_then(|v| v.as_str()).map(|s| s.to_string());
+    instance.network_status = data.get("networkStatus").and_then(|v| v.as_str()).map(|s| s.to_string());
+    instance.discount_percent = data.get("discountPercent").and_then(|v| v.as_i64()).map(|i| i as i32);
+    instance.attach_iso = data.get("attachIso").and_then(|v| v.as_bool());
+    instance.class = data.get("class").and_then(|v| v.as_str()).unwrap_or("").to_string();
+    instance.oca_data = data.get("ocaData").cloned();
+    instance.is_ddos_protected = data.get("isDdosProtected").and_then(|v| v.as_bool());
+    instance.customer_note = data.get("customerNote").and_then(|v| v.as_str()).map(|s| s.to_string());
+    instance.admin_note = data.get("adminNote").and_then(|v| v.as_str()).map(|s| s.to_string());
 
-            if let Some(os_obj) = data.get("os").and_then(|v| v.as_object()) {
-                instance.os = Some(OsItem {
-                    id: os_obj.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                    name: os_obj.get("name").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                    family: os_obj.get("family").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                    arch: os_obj.get("arch").and_then(|v| v.as_str()).map(|s| s.to_string()),
-                    min_ram: os_obj.get("minRam").and_then(|v| v.as_str()).map(|s| s.to_string()),
-                    is_default: os_obj.get("isDefault").and_then(|v| v.as_bool()).unwrap_or(false),
-                    is_active: os_obj.get("isActive").and_then(|v| v.as_bool()).unwrap_or(true),
-                });
-            }
+    // Parse extra_resource if present
+    if let Some(er_obj) = data.get("extraResource").and_then(|v| v.as_object()) {
+        use crate::models::instance_view::ExtraResource;
+        instance.extra_resource = Some(ExtraResource {
+            cpu: er_obj.get("cpu").and_then(|v| v.as_i64()).map(|i| i as i32),
+            ram_in_gb: er_obj.get("ramInGB").and_then(|v| v.as_i64()).map(|i| i as i32),
+            disk_in_gb: er_obj.get("diskInGB").and_then(|v| v.as_i64()).map(|i| i as i32),
+            bandwidth_in_tb: er_obj.get("bandwidthInTB").and_then(|v| v.as_i64()).map(|i| i as i32),
+        });
+    }
 
-            // Build display fields
-            instance.status_display = crate::utils::format_status(&instance.status);
-            instance.vcpu_count_display = if instance.vcpu_count > 0 { instance.vcpu_count.to_string() } else { "—".into() };
-            instance.ram_display = if instance.ram > 0 { format!("{} MB", instance.ram) } else { "—".into() };
-            instance.disk_display = if instance.disk > 0 { format!("{} GB", instance.disk) } else { "—".into() };
-        }
+    if let Some(os_obj) = data.get("os").and_then(|v| v.as_object()) {
+        instance.os = Some(OsItem {
+            id: os_obj.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+            name: os_obj.get("name").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+            family: os_obj.get("family").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+            arch: os_obj.get("arch").and_then(|v| v.as_str()).map(|s| s.to_string()),
+            min_ram: os_obj.get("minRam").and_then(|v| v.as_str()).map(|s| s.to_string()),
+            is_default: os_obj.get("isDefault").and_then(|v| v.as_bool()).unwrap_or(false),
+            is_active: os_obj.get("isActive").and_then(|v| v.as_bool()).unwrap_or(true),
+        });
+    }
+
+    // Build display fields
+    instance.fill_display_fields();
+    Some(instance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resize_preview_prices_the_change() {
+        let prices = vec![("small".to_string(), 6.0), ("large".to_string(), 12.0)];
+        let up = resize_preview(Some("small"), "large", &prices).unwrap();
+        assert_eq!(up.delta(), Some(6.0));
+        assert_eq!(up.delta_display(), "+$6.00/mo");
+        assert_eq!(up.current_display(), "small ($6.00/mo)");
+        assert_eq!(resize_preview(Some("large"), "small", &prices).unwrap().delta_display(), "-$6.00/mo");
+        assert_eq!(resize_preview(Some("small"), "small", &prices).unwrap().delta_display(), "no change");
+
+        let unknown = resize_preview(None, "large", &prices).unwrap();
+        assert_eq!(unknown.delta(), None);
+        assert_eq!(unknown.current_display(), "unknown");
+        assert!(resize_preview(Some("small"), "huge", &prices).unwrap_err().contains("huge"));
+    }
+
+    #[test]
+    fn test_ssh_key_labels_resolve_ids_and_flag_missing() {
+        let data = serde_json::json!({
+            "sshKeyIds": ["k1", 7, "gone"],
+            "sshKeys": [{"id": "k1", "name": ""}, {"id": "k9", "name": "ci"}],
+        });
+        let keys = provisioned_ssh_keys(&data);
+        assert_eq!(keys.len(), 4);
+        let known = vec![SshKeyView {
+            id: "k1".into(),
+            name: "laptop".into(),
+            fingerprint: String::new(),
+            public_key: String::new(),
+            customer_id: None,
+        }];
+        assert_eq!(ssh_key_labels(&keys, &known), vec!["laptop", "7 (unknown)", "gone (unknown)", "ci"]);
+        assert!(provisioned_ssh_keys(&serde_json::json!({})).is_empty());
+    }
+
+    #[test]
+    fn test_parse_resize_type_ignores_case() {
+        assert_eq!(parse_resize_type("fixed"), Ok("FIXED"));
+        assert_eq!(parse_resize_type("Custom"), Ok("CUSTOM"));
+        assert_eq!(parse_resize_type("CUSTOM"), Ok("CUSTOM"));
+        assert!(parse_resize_type("bigger").unwrap_err().contains("Valid types are: FIXED, CUSTOM"));
+    }
+
+    #[test]
+    fn test_ensure_known_id_lists_valid_ids() {
+        let valid = vec!["us-west".to_string(), "eu-central".to_string()];
+        assert!(ensure_known_id("region", "us-west", &valid).is_ok());
+        let err = ensure_known_id("region", "us-wset", &valid).unwrap_err();
+        assert_eq!(err, "Unknown region 'us-wset'. Valid regions are: us-west, eu-central");
+    }
+
+    #[test]
+    fn test_hostname_confirmed() {
+        assert!(hostname_confirmed("web-prod-1", " Web-Prod-1. "));
+        assert!(!hostname_confirmed("web-prod-1", "web-prod-2"));
+        assert!(!hostname_confirmed("web-prod-1", ""));
+        assert!(!hostname_confirmed("", ""));
+        assert!(!hostname_confirmed(InstanceView::NO_HOSTNAME, InstanceView::NO_HOSTNAME));
+    }
+
+    #[test]
+    fn test_downsize_changes() {
+        let mut current = InstanceView::new_with_defaults("i-1".into());
+        current.vcpu_count = 4;
+        current.ram = 8192;
+        current.disk = 50;
+        assert!(downsize_changes(&current, Some(4), Some(8), Some(80)).is_empty());
+        assert_eq!(
+            downsize_changes(&current, Some(2), Some(4), Some(25)),
+            vec!["CPU 4 → 2 cores", "RAM 8192 MB → 4 GB", "disk 50 GB → 25 GB"]
+        );
+        // Fields left out of the request aren't compared
+        assert!(downsize_changes(&current, None, None, None).is_empty());
+        // Unknown current sizing can't be judged
+        let unknown = InstanceView::new_with_defaults("i-2".into());
+        assert!(downsize_changes(&unknown, Some(1), Some(1), Some(1)).is_empty());
+    }
+
+    #[test]
+    fn test_hostname_pattern_reason_names_pattern() {
+        let reason = BlockReason::HostnamePattern { hostname: "web-prod-01".into(), pattern: "*-prod-*".into() };
+        assert!(reason.message().contains("web-prod-01"));
+        assert!(reason.message().contains("'*-prod-*'"));
+    }
+
+    #[test]
+    fn test_duplicate_action_blocked_within_window() {
+        let mut cooldowns = HashMap::new();
+        let now = Instant::now();
+        let window = Duration::from_secs(5);
+        assert!(try_start_action(&mut cooldowns, "i-1", "poweroff", now, window).is_none());
+        let wait = try_start_action(&mut cooldowns, "i-1", "poweroff", now + Duration::from_secs(2), window);
+        assert_eq!(wait, Some(Duration::from_secs(3)));
+        // Other actions and other instances are independent
+        assert!(try_start_action(&mut cooldowns, "i-1", "poweron", now, window).is_none());
+        assert!(try_start_action(&mut cooldowns, "i-2", "poweroff", now, window).is_none());
+        assert!(try_start_action(&mut cooldowns, "i-1", "poweroff", now + window, window).is_none());
+    }
+
+    #[test]
+    fn test_status_changes_reports_new_and_changed() {
+        let mut running = InstanceView::new_with_defaults("i-1".into());
+        running.status = "active".into();
+        let mut stopped = InstanceView::new_with_defaults("i-2".into());
+        stopped.status = "stopped".into();
+        let fresh = InstanceView::new_with_defaults("i-3".into());
+        let previous = HashMap::from([
+            ("i-1".to_string(), "active".to_string()),
+            ("i-2".to_string(), "active".to_string()),
+        ]);
+        let current = [running, stopped, fresh];
+        let ids: Vec<&str> = status_changes(&previous, &current).iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, vec!["i-2", "i-3"]);
     }
-    instance
 }
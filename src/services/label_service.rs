@@ -0,0 +1,116 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use super::note_service::write_atomic;
+
+const LABELS_FILE: &str = "labels.json";
+
+/// Local `key=value` labels per instance ID, kept in `labels.json`.
+pub type InstanceLabels = HashMap<String, BTreeMap<String, String>>;
+
+/// Load instance labels from `labels.json`.
+/// Returns an empty map if the file is missing or unreadable.
+pub async fn load_labels_from_file() -> Arc<Mutex<InstanceLabels>> {
+    let labels = match tokio::fs::read_to_string(LABELS_FILE).await {
+        Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+            tracing::warn!(%e, "Ignoring invalid {}", LABELS_FILE);
+            HashMap::new()
+        }),
+        Err(_) => HashMap::new(),
+    };
+    Arc::new(Mutex::new(labels))
+}
+
+/// Persist all labels to `labels.json` via [`write_atomic`].
+pub async fn persist_labels_file(labels: &Arc<Mutex<InstanceLabels>>) -> Result<(), std::io::Error> {
+    let content = {
+        let labels = labels.lock().unwrap();
+        let sorted: BTreeMap<_, _> = labels.iter().collect();
+        serde_json::to_string_pretty(&sorted)?
+    };
+    write_atomic(Path::new(LABELS_FILE), &content).await
+}
+
+/// Validate a label key: letters, digits, `.`, `_`, `-` and `/`.
+pub fn parse_label_key(raw: &str) -> Result<String, String> {
+    let key = raw.trim();
+    if key.is_empty() {
+        return Err("Label key is empty".into());
+    }
+    if !key.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '/')) {
+        return Err(format!("Invalid label key '{}' (use letters, digits, '.', '_', '-' and '/')", key));
+    }
+    Ok(key.to_string())
+}
+
+/// Parse `key=value`; the value may be empty but the `=` is required.
+pub fn parse_label(raw: &str) -> Result<(String, String), String> {
+    let Some((key, value)) = raw.split_once('=') else {
+        return Err(format!("Expected key=value, got '{}'", raw));
+    };
+    Ok((parse_label_key(key)?, value.trim().to_string()))
+}
+
+/// Set `key` on `instance_id`, replacing any previous value.
+pub fn set_label(labels: &mut InstanceLabels, instance_id: &str, key: &str, value: &str) {
+    labels.entry(instance_id.to_string()).or_default().insert(key.to_string(), value.to_string());
+}
+
+/// Remove `key` from `instance_id`; instances left without labels are dropped.
+/// Returns whether the key was present.
+pub fn remove_label(labels: &mut InstanceLabels, instance_id: &str, key: &str) -> bool {
+    let Some(entry) = labels.get_mut(instance_id) else { return false };
+    let removed = entry.remove(key).is_some();
+    if entry.is_empty() {
+        labels.remove(instance_id);
+    }
+    removed
+}
+
+/// Whether an instance carries every `(key, value)` in `filters`.
+pub fn matches_labels(instance_labels: Option<&BTreeMap<String, String>>, filters: &[(String, String)]) -> bool {
+    filters
+        .iter()
+        .all(|(key, value)| instance_labels.and_then(|l| l.get(key)) == Some(value))
+}
+
+/// `key=value` strings in key order, as shown by the CLI and the web UI.
+pub fn label_strings(instance_labels: Option<&BTreeMap<String, String>>) -> Vec<String> {
+    instance_labels
+        .map(|l| l.iter().map(|(k, v)| format!("{}={}", k, v)).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_label_validates_key() {
+        assert_eq!(parse_label(" env = prod ").unwrap(), ("env".into(), "prod".into()));
+        assert_eq!(parse_label("team/owner=").unwrap(), ("team/owner".into(), String::new()));
+        assert!(parse_label("env").is_err());
+        assert!(parse_label("=prod").is_err());
+        assert!(parse_label("bad key=x").is_err());
+    }
+
+    #[test]
+    fn set_remove_and_filter() {
+        let mut labels = InstanceLabels::new();
+        set_label(&mut labels, "i-1", "env", "prod");
+        set_label(&mut labels, "i-1", "role", "db");
+        set_label(&mut labels, "i-2", "env", "staging");
+        assert_eq!(label_strings(labels.get("i-1")), vec!["env=prod", "role=db"]);
+
+        let prod = vec![("env".to_string(), "prod".to_string())];
+        assert!(matches_labels(labels.get("i-1"), &prod));
+        assert!(!matches_labels(labels.get("i-2"), &prod));
+        assert!(!matches_labels(labels.get("i-3"), &prod));
+        assert!(matches_labels(labels.get("i-3"), &[]));
+
+        assert!(remove_label(&mut labels, "i-2", "env"));
+        assert!(!remove_label(&mut labels, "i-2", "env"));
+        assert!(!labels.contains_key("i-2"));
+    }
+}
@@ -2,9 +2,19 @@ pub mod user_service;
 pub mod instance_service;
 pub mod wizard_service;
 pub mod workspace_service;
+pub mod note_service;
+pub mod label_service;
+pub mod admin_service;
+pub mod bulk_service;
+pub mod persistence;
+pub mod version_service;
+pub mod webhook_service;
 
 // Re-export commonly used functions
-pub use user_service::{generate_password_hash, verify_password, random_session_id, load_users_from_file, persist_users_file, load_clocked_instances_from_file, persist_clocked_instances_file};
+pub use user_service::{generate_password_hash, verify_password, password_needs_rehash, random_session_id, load_users_from_file, persist_users_file, load_clocked_instances_from_file, persist_clocked_instances_file, reassign_instances, resolve_access_selection, resolve_list_scope};
 pub use instance_service::simple_instance_action;
-pub use wizard_service::{parse_wizard_base, build_base_query_pairs};
-pub use workspace_service::{load_workspaces_from_file, persist_workspaces_file, slugify, now_iso8601, get_accessible_instance_ids};
+pub use wizard_service::{parse_wizard_base, build_base_query_pairs, pick_default_region, order_regions_by_distance, merge_required_ssh_keys, missing_required_ssh_keys, INSTANCE_CLASSES};
+pub use note_service::{load_notes_from_file, persist_notes_file, apply_note};
+pub use label_service::{load_labels_from_file, persist_labels_file, InstanceLabels};
+pub use persistence::{save_users, save_workspaces, unsaved_message, write_with_retry, UnsavedChanges};
+pub use workspace_service::{load_workspaces_from_file, persist_workspaces_file, slugify, now_iso8601, get_accessible_instance_ids, workspace_instance_ids, is_last_manager, sort_workspaces, WorkspaceSort};
@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::models::InstanceNote;
+
+const NOTES_FILE: &str = "notes.json";
+
+/// Load instance notes from `notes.json`, keyed by instance ID.
+/// Returns an empty map if the file is missing or unreadable.
+pub async fn load_notes_from_file() -> Arc<Mutex<HashMap<String, InstanceNote>>> {
+    let notes = match tokio::fs::read_to_string(NOTES_FILE).await {
+        Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+            tracing::warn!(%e, "Ignoring invalid {}", NOTES_FILE);
+            HashMap::new()
+        }),
+        Err(_) => HashMap::new(),
+    };
+    Arc::new(Mutex::new(notes))
+}
+
+/// Persist all notes to `notes.json` via [`write_atomic`].
+pub async fn persist_notes_file(notes: &Arc<Mutex<HashMap<String, InstanceNote>>>) -> Result<(), std::io::Error> {
+    let content = {
+        let notes = notes.lock().unwrap();
+        let sorted: std::collections::BTreeMap<_, _> = notes.iter().collect();
+        serde_json::to_string_pretty(&sorted)?
+    };
+    write_atomic(Path::new(NOTES_FILE), &content).await
+}
+
+/// Write `content` to a temporary sibling of `path` and rename it into place,
+/// so a crash mid-write never leaves a truncated file behind.
+pub async fn write_atomic(path: &Path, content: &str) -> Result<(), std::io::Error> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    tokio::fs::write(&tmp, content).await?;
+    tokio::fs::rename(&tmp, path).await
+}
+
+/// Set, replace or (for blank `text`) clear the note on `instance_id`.
+pub fn apply_note(notes: &mut HashMap<String, InstanceNote>, instance_id: &str, text: &str, author: &str, now: String) {
+    let text = text.trim();
+    if text.is_empty() {
+        notes.remove(instance_id);
+    } else {
+        notes.insert(
+            instance_id.to_string(),
+            InstanceNote { text: text.to_string(), author: author.to_string(), updated_at: now },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_note_sets_and_clears() {
+        let mut notes = HashMap::new();
+        apply_note(&mut notes, "inst-1", "  hosts the payroll DB \n", "alice", "2026-01-01T00:00:00Z".into());
+        let note = notes.get("inst-1").unwrap();
+        assert_eq!(note.text, "hosts the payroll DB");
+        assert_eq!(note.author, "alice");
+
+        apply_note(&mut notes, "inst-1", "   ", "bob", "2026-01-02T00:00:00Z".into());
+        assert!(notes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn write_atomic_replaces_file() {
+        let path = std::env::temp_dir().join(format!("zy-notes-{}.json", std::process::id()));
+        write_atomic(&path, "first").await.unwrap();
+        write_atomic(&path, "second").await.unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "second");
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        assert!(!Path::new(&tmp).exists());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,106 @@
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::models::AppState;
+use crate::services::{persist_users_file, persist_workspaces_file};
+
+/// How many times a state file write is tried before giving up.
+pub const PERSIST_ATTEMPTS: u32 = 3;
+/// Wait before the second attempt; doubles for each later one.
+const PERSIST_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Write `content` to `path`, retrying transient failures (disk full,
+/// `EBUSY`, ...) with exponential backoff. Returns the last error.
+pub async fn write_with_retry(path: impl AsRef<Path>, content: String) -> std::io::Result<()> {
+    write_with_backoff(path.as_ref(), &content, PERSIST_ATTEMPTS, PERSIST_BACKOFF).await
+}
+
+async fn write_with_backoff(path: &Path, content: &str, attempts: u32, backoff: Duration) -> std::io::Result<()> {
+    let mut delay = backoff;
+    let mut attempt = 1;
+    loop {
+        match tokio::fs::write(path, content).await {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt < attempts => {
+                tracing::warn!(path = %path.display(), attempt, %e, "State file write failed, retrying");
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// State files whose in-memory copy changed but couldn't be written.
+/// [`retry_unsaved`] tries them again after the next successful request.
+#[derive(Clone, Default)]
+pub struct UnsavedChanges {
+    users: Arc<AtomicBool>,
+    workspaces: Arc<AtomicBool>,
+}
+
+impl UnsavedChanges {
+    pub fn any(&self) -> bool {
+        self.users.load(Ordering::Relaxed) || self.workspaces.load(Ordering::Relaxed)
+    }
+}
+
+/// Flash shown when a change stays in memory but didn't reach disk.
+pub fn unsaved_message(what: &str, err: &std::io::Error) -> String {
+    format!(
+        "⚠️ Your change to {} was applied but NOT saved to disk ({}). It is retried automatically and will be lost if the server restarts first.",
+        what, err
+    )
+}
+
+/// Write users.json, remembering a failure so it is retried later.
+pub async fn save_users(state: &AppState) -> std::io::Result<()> {
+    let result = persist_users_file(&state.users).await;
+    state.unsaved.users.store(result.is_err(), Ordering::Relaxed);
+    result
+}
+
+/// Write workspaces.json, remembering a failure so it is retried later.
+pub async fn save_workspaces(state: &AppState) -> std::io::Result<()> {
+    let result = persist_workspaces_file(&state.workspaces).await;
+    state.unsaved.workspaces.store(result.is_err(), Ordering::Relaxed);
+    result
+}
+
+/// Write every state file an earlier request failed to save.
+pub async fn retry_unsaved(state: &AppState) {
+    if state.unsaved.users.load(Ordering::Relaxed) {
+        match save_users(state).await {
+            Ok(()) => tracing::info!("Saved pending users.json changes"),
+            Err(e) => tracing::error!(%e, "users.json still unsaved"),
+        }
+    }
+    if state.unsaved.workspaces.load(Ordering::Relaxed) {
+        match save_workspaces(state).await {
+            Ok(()) => tracing::info!("Saved pending workspaces.json changes"),
+            Err(e) => tracing::error!(%e, "workspaces.json still unsaved"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_write_with_backoff() {
+        let dir = std::env::temp_dir().join(format!("zy-persist-{}", std::process::id()));
+        let missing = dir.join("no-such-dir").join("state.json");
+        let err = write_with_backoff(&missing, "{}", 3, Duration::from_millis(1)).await;
+        assert!(err.is_err());
+
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("state.json");
+        write_with_backoff(&path, "{}", 3, Duration::from_millis(1)).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{}");
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -4,21 +4,41 @@ use pbkdf2::pbkdf2_hmac;
 use sha2::Sha256;
 use rand::RngCore;
 use hex::encode as hex_encode;
+use argon2::{Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier};
+use argon2::password_hash::SaltString;
 
-use crate::config::{DEFAULT_PBKDF2_ITERATIONS, DEFAULT_OWNER_USERNAME, DEFAULT_OWNER_PASSWORD, DEFAULT_OWNER_ROLE};
+use crate::config::{PasswordScheme, DEFAULT_PBKDF2_ITERATIONS, DEFAULT_OWNER_USERNAME, DEFAULT_OWNER_PASSWORD, DEFAULT_OWNER_ROLE};
 use crate::models::UserRecord;
 
-pub fn generate_password_hash(password: &str) -> String {
-    let mut salt_bytes = [0u8; 12];
-    rand::rngs::OsRng.fill_bytes(&mut salt_bytes);
-    let salt = hex_encode(salt_bytes);
-    let mut dk = [0u8; 32];
-    pbkdf2_hmac::<Sha256>(password.as_bytes(), salt.as_bytes(), DEFAULT_PBKDF2_ITERATIONS, &mut dk);
-    let hash_hex = hex_encode(dk);
-    format!("pbkdf2:sha256:{}${}${}", DEFAULT_PBKDF2_ITERATIONS, salt, hash_hex)
+/// Hash `password` for storage with `scheme`. PBKDF2 hashes look like
+/// `pbkdf2:sha256:<iterations>$<salt>$<hex>`; Argon2id hashes are PHC strings
+/// (`$argon2id$v=19$m=...`), so `verify_password` can tell them apart.
+pub fn generate_password_hash(password: &str, scheme: PasswordScheme) -> String {
+    match scheme {
+        PasswordScheme::Pbkdf2 => {
+            let mut salt_bytes = [0u8; 12];
+            rand::rngs::OsRng.fill_bytes(&mut salt_bytes);
+            let salt = hex_encode(salt_bytes);
+            let mut dk = [0u8; 32];
+            pbkdf2_hmac::<Sha256>(password.as_bytes(), salt.as_bytes(), DEFAULT_PBKDF2_ITERATIONS, &mut dk);
+            let hash_hex = hex_encode(dk);
+            format!("pbkdf2:sha256:{}${}${}", DEFAULT_PBKDF2_ITERATIONS, salt, hash_hex)
+        }
+        PasswordScheme::Argon2id => {
+            let salt = SaltString::generate(&mut argon2::password_hash::rand_core::OsRng);
+            Argon2::default()
+                .hash_password(password.as_bytes(), &salt)
+                .expect("default Argon2 parameters are valid")
+                .to_string()
+        }
+    }
 }
 
 pub fn verify_password(stored: &str, candidate: &str) -> bool {
+    if stored.starts_with("$argon2") {
+        return PasswordHash::new(stored)
+            .is_ok_and(|hash| Argon2::default().verify_password(candidate.as_bytes(), &hash).is_ok());
+    }
     if let Some(rest) = stored.strip_prefix("pbkdf2:sha256:") {
         if let Some((iter_s, salt_hash)) = rest.split_once('$') {
             if let Some((salt, expected_hash)) = salt_hash.split_once('$') {
@@ -34,6 +54,27 @@ pub fn verify_password(stored: &str, candidate: &str) -> bool {
     false
 }
 
+/// Whether a stored hash uses a different scheme than `scheme`, or weaker
+/// cost parameters than it would get today, and should be replaced after
+/// the next successful login.
+pub fn password_needs_rehash(stored: &str, scheme: PasswordScheme) -> bool {
+    match scheme {
+        PasswordScheme::Pbkdf2 => stored
+            .strip_prefix("pbkdf2:sha256:")
+            .and_then(|rest| rest.split_once('$'))
+            .and_then(|(iter_s, _)| iter_s.parse::<u32>().ok())
+            .is_none_or(|iter| iter < DEFAULT_PBKDF2_ITERATIONS),
+        PasswordScheme::Argon2id => {
+            let target = Params::default();
+            PasswordHash::new(stored)
+                .ok()
+                .filter(|hash| hash.algorithm == argon2::Algorithm::Argon2id.ident())
+                .and_then(|hash| Params::try_from(&hash).ok())
+                .is_none_or(|p| p.m_cost() < target.m_cost() || p.t_cost() < target.t_cost() || p.p_cost() < target.p_cost())
+        }
+    }
+}
+
 pub fn random_session_id() -> String {
     let mut b = [0u8; 16];
     rand::rngs::OsRng.fill_bytes(&mut b);
@@ -68,7 +109,7 @@ pub async fn load_users_from_file() -> Arc<Mutex<HashMap<String, UserRecord>>> {
                                         .filter_map(|x| x.as_str().map(|s| s.to_string()))
                                         .collect()
                                 })
-                                .unwrap_or_else(|| vec![]);
+                                .unwrap_or_else(std::vec::Vec::new);
                             map.insert(
                                 k.to_lowercase(),
                                 UserRecord {
@@ -76,6 +117,10 @@ pub async fn load_users_from_file() -> Arc<Mutex<HashMap<String, UserRecord>>> {
                                     role,
                                     assigned_instances,
                                     about,
+                                    last_login: v
+                                        .get("last_login")
+                                        .and_then(|x| x.as_str())
+                                        .map(|s| s.to_string()),
                                 },
                             );
                         }
@@ -100,6 +145,7 @@ pub async fn load_users_from_file() -> Arc<Mutex<HashMap<String, UserRecord>>> {
                 role: DEFAULT_OWNER_ROLE.into(),
                 assigned_instances: vec![],
                 about: String::new(),
+                last_login: None,
             },
         );
         let mut serialized: serde_json::Map<String, serde_json::Value> = serde_json::Map::new();
@@ -131,10 +177,13 @@ pub async fn persist_users_file(users_arc: &Arc<Mutex<HashMap<String, UserRecord
                 "assigned_instances": rec.assigned_instances,
                 "about": rec.about
             }));
+            if let Some(last_login) = &rec.last_login {
+                serialized[u]["last_login"] = serde_json::Value::from(last_login.as_str());
+            }
         }
         serde_json::to_string_pretty(&serde_json::Value::Object(serialized))?
     };
-    tokio::fs::write("users.json", content).await
+    crate::services::write_with_retry("users.json", content).await
 }
 
 pub async fn load_clocked_instances_from_file() -> Option<std::collections::HashSet<String>> {
@@ -158,6 +207,206 @@ pub async fn persist_clocked_instances_file(ids: &std::collections::HashSet<Stri
     let mut sorted: Vec<&String> = ids.iter().collect();
     sorted.sort();
     let content = serde_json::to_string_pretty(&sorted)
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        .map_err(std::io::Error::other)?;
     tokio::fs::write("clocked_instances.json", content).await
 }
+
+/// Assign `instance_ids` to `target` (or unassign them when `remove` is set),
+/// mirroring the `/access` page: only admins and viewers carry assignments,
+/// since owners already see every instance. When `from` is given the IDs are
+/// also taken off that user, which moves them in one step.
+pub fn reassign_instances(
+    users: &mut HashMap<String, UserRecord>,
+    instance_ids: &[String],
+    target: &str,
+    from: Option<&str>,
+    remove: bool,
+) -> Result<(), String> {
+    let check = |users: &HashMap<String, UserRecord>, name: &str| -> Result<(), String> {
+        match users.get(name) {
+            None => Err(format!("User '{}' not found", name)),
+            Some(rec) if rec.role != "admin" && rec.role != "viewer" => {
+                Err(format!("User '{}' is a {}; only admins and viewers have assigned instances", name, rec.role))
+            }
+            Some(_) => Ok(()),
+        }
+    };
+    check(users, target)?;
+    if let Some(source) = from {
+        check(users, source)?;
+        if let Some(rec) = users.get_mut(source) {
+            rec.assigned_instances.retain(|id| !instance_ids.contains(id));
+        }
+    }
+    if let Some(rec) = users.get_mut(target) {
+        if remove {
+            rec.assigned_instances.retain(|id| !instance_ids.contains(id));
+        } else {
+            rec.assigned_instances.extend(instance_ids.iter().cloned());
+            rec.assigned_instances.sort();
+            rec.assigned_instances.dedup();
+        }
+    }
+    Ok(())
+}
+
+/// Whose instances `zy instances list` shows, as the username to filter by
+/// (`""` for the whole account). Precedence: `--all`, then `--username`, then
+/// the `ZY_CLI_USER` user; with none of them the command refuses rather than
+/// listing everything. When `ZY_CLI_USER` is set to a non-owner, both `--all`
+/// and a `--username` other than that user are refused, and named users must
+/// exist (an unknown name would otherwise see everything).
+pub fn resolve_list_scope(
+    all: bool,
+    username: Option<&str>,
+    cli_user: Option<&str>,
+    users: &HashMap<String, UserRecord>,
+) -> Result<String, String> {
+    let acting_non_owner = cli_user
+        .map(|acting| acting.to_lowercase())
+        .filter(|acting| users.get(acting).map(|u| u.role.as_str()) != Some("owner"));
+    if all {
+        if let Some(acting) = acting_non_owner {
+            return Err(format!("--all is owner-only, and ZY_CLI_USER '{}' is not an owner", acting));
+        }
+        return Ok(String::new());
+    }
+    let name = match username.or(cli_user) {
+        Some(name) => name.to_lowercase(),
+        None => return Err("Specify --username <user> or --all (or set ZY_CLI_USER)".into()),
+    };
+    if let Some(acting) = acting_non_owner.filter(|acting| *acting != name) {
+        return Err(format!(
+            "Listing another user's instances is owner-only, and ZY_CLI_USER '{}' is not an owner",
+            acting
+        ));
+    }
+    if !users.contains_key(&name) {
+        return Err(format!("Unknown user '{}'", name));
+    }
+    Ok(name)
+}
+
+/// Resolve an `/access` form submission into the sorted, de-duplicated list
+/// of assigned instance IDs. `bulk` comes from the form's bulk buttons:
+/// `all`, `none`, or `region:<id>` to toggle a region (add all of it, or
+/// remove all of it when every instance there was already selected).
+/// `instances` is every instance as (ID, region).
+pub fn resolve_access_selection(selected: &[String], bulk: Option<&str>, instances: &[(String, String)]) -> Vec<String> {
+    let mut ids: Vec<String> = selected
+        .iter()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    match bulk {
+        Some("all") => ids.extend(instances.iter().map(|(id, _)| id.clone())),
+        Some("none") => ids.clear(),
+        Some(other) => {
+            if let Some(region) = other.strip_prefix("region:") {
+                let in_region: Vec<&String> = instances.iter().filter(|(_, r)| r == region).map(|(id, _)| id).collect();
+                if in_region.iter().all(|id| ids.contains(id)) {
+                    ids.retain(|id| !in_region.contains(&id));
+                } else {
+                    ids.extend(in_region.into_iter().cloned());
+                }
+            }
+        }
+        None => {}
+    }
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_password_schemes_verify_and_rehash() {
+        let pbkdf2 = generate_password_hash("hunter2", PasswordScheme::Pbkdf2);
+        let argon = generate_password_hash("hunter2", PasswordScheme::Argon2id);
+        assert!(argon.starts_with("$argon2id$"));
+        for stored in [&pbkdf2, &argon] {
+            assert!(verify_password(stored, "hunter2"));
+            assert!(!verify_password(stored, "hunter3"));
+        }
+        assert!(!password_needs_rehash(&pbkdf2, PasswordScheme::Pbkdf2));
+        assert!(password_needs_rehash(&pbkdf2, PasswordScheme::Argon2id));
+        assert!(!password_needs_rehash(&argon, PasswordScheme::Argon2id));
+        assert!(password_needs_rehash(&argon, PasswordScheme::Pbkdf2));
+        // Fewer PBKDF2 rounds or a cheaper Argon2 cost than today's target
+        assert!(password_needs_rehash("pbkdf2:sha256:1000$abc$def", PasswordScheme::Pbkdf2));
+        let weak = Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, Params::new(1024, 1, 1, None).unwrap())
+            .hash_password(b"hunter2", &SaltString::generate(&mut argon2::password_hash::rand_core::OsRng))
+            .unwrap()
+            .to_string();
+        assert!(verify_password(&weak, "hunter2"));
+        assert!(password_needs_rehash(&weak, PasswordScheme::Argon2id));
+    }
+
+    fn user(role: &str, assigned: &[&str]) -> UserRecord {
+        UserRecord {
+            password: String::new(),
+            role: role.to_string(),
+            assigned_instances: assigned.iter().map(|s| s.to_string()).collect(),
+            about: String::new(),
+            last_login: None,
+        }
+    }
+
+    #[test]
+    fn test_reassign_moves_instances_between_admins() {
+        let mut users = HashMap::new();
+        users.insert("alice".to_string(), user("admin", &["i-1", "i-2"]));
+        users.insert("bob".to_string(), user("admin", &["i-3"]));
+        let ids = vec!["i-1".to_string(), "i-2".to_string()];
+        reassign_instances(&mut users, &ids, "bob", Some("alice"), false).unwrap();
+        assert!(users["alice"].assigned_instances.is_empty());
+        assert_eq!(users["bob"].assigned_instances, vec!["i-1", "i-2", "i-3"]);
+
+        reassign_instances(&mut users, &ids[..1], "bob", None, true).unwrap();
+        assert_eq!(users["bob"].assigned_instances, vec!["i-2", "i-3"]);
+    }
+
+    #[test]
+    fn test_reassign_rejects_owner_and_unknown_targets() {
+        let mut users = HashMap::new();
+        users.insert("root".to_string(), user("owner", &[]));
+        let ids = vec!["i-1".to_string()];
+        assert!(reassign_instances(&mut users, &ids, "root", None, false).is_err());
+        assert!(reassign_instances(&mut users, &ids, "ghost", None, false).is_err());
+    }
+
+    #[test]
+    fn test_resolve_access_selection_bulk_actions() {
+        let instances: Vec<(String, String)> = [("a", "fra"), ("b", "fra"), ("c", "ams")]
+            .iter()
+            .map(|(id, r)| (id.to_string(), r.to_string()))
+            .collect();
+        let picked = vec![" c ".to_string(), "c".to_string(), "".to_string()];
+        assert_eq!(resolve_access_selection(&picked, None, &instances), vec!["c"]);
+        assert_eq!(resolve_access_selection(&picked, Some("all"), &instances), vec!["a", "b", "c"]);
+        assert!(resolve_access_selection(&picked, Some("none"), &instances).is_empty());
+        assert_eq!(resolve_access_selection(&picked, Some("region:fra"), &instances), vec!["a", "b", "c"]);
+        let all_fra = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        assert_eq!(resolve_access_selection(&all_fra, Some("region:fra"), &instances), vec!["c"]);
+    }
+
+    #[test]
+    fn test_resolve_list_scope_precedence() {
+        let mut users = HashMap::new();
+        users.insert("owner".to_string(), user("owner", &[]));
+        users.insert("alice".to_string(), user("admin", &[]));
+        assert_eq!(resolve_list_scope(true, None, None, &users).unwrap(), "");
+        assert_eq!(resolve_list_scope(true, None, Some("owner"), &users).unwrap(), "");
+        assert!(resolve_list_scope(true, None, Some("alice"), &users).is_err());
+        assert_eq!(resolve_list_scope(false, Some("Alice"), Some("owner"), &users).unwrap(), "alice");
+        assert_eq!(resolve_list_scope(false, None, Some("alice"), &users).unwrap(), "alice");
+        assert!(resolve_list_scope(false, None, None, &users).is_err());
+        assert!(resolve_list_scope(false, Some("bob"), None, &users).is_err());
+        // A non-owner CLI user may name only themselves
+        assert_eq!(resolve_list_scope(false, Some("ALICE"), Some("alice"), &users).unwrap(), "alice");
+        assert!(resolve_list_scope(false, Some("owner"), Some("alice"), &users).is_err());
+    }
+}
@@ -0,0 +1,97 @@
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::update::{BuildInfo, Channel, GitHubClient, UpdateError, Version, REPO_NAME, REPO_OWNER};
+
+/// How long a GitHub release check is reused, so `/version` polling stays
+/// well inside GitHub's unauthenticated rate limit.
+pub const UPDATE_CHECK_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Result of the last release check for the running version's channel.
+#[derive(Clone, Debug, Serialize)]
+pub struct UpdateCheck {
+    pub latest_version: Option<String>,
+    /// `None` when GitHub couldn't be reached.
+    pub update_available: Option<bool>,
+    pub checked_at: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct UpdateCheckCache {
+    inner: Arc<RwLock<Option<(Instant, UpdateCheck)>>>,
+}
+
+impl UpdateCheckCache {
+    /// The cached check if it was stored less than `max_age` ago.
+    pub fn get(&self, max_age: Duration) -> Option<UpdateCheck> {
+        let guard = self.inner.read().unwrap();
+        guard
+            .as_ref()
+            .filter(|(stored_at, _)| stored_at.elapsed() < max_age)
+            .map(|(_, check)| check.clone())
+    }
+
+    pub fn store(&self, check: UpdateCheck) {
+        *self.inner.write().unwrap() = Some((Instant::now(), check));
+    }
+}
+
+/// Body of `GET /version`.
+#[derive(Clone, Debug, Serialize)]
+pub struct VersionReport {
+    #[serde(flatten)]
+    pub build: BuildInfo,
+    #[serde(flatten)]
+    pub update: UpdateCheck,
+}
+
+fn update_check_from(result: Result<Version, UpdateError>, current: &Version) -> UpdateCheck {
+    let checked_at = chrono::Utc::now().to_rfc3339();
+    match result {
+        Ok(latest) => UpdateCheck {
+            update_available: Some(latest.is_newer_than(current)),
+            latest_version: Some(latest.to_string()),
+            checked_at,
+        },
+        Err(UpdateError::NoReleaseFound(_)) => UpdateCheck { latest_version: None, update_available: Some(false), checked_at },
+        Err(e) => {
+            tracing::warn!(%e, "Update check failed");
+            UpdateCheck { latest_version: None, update_available: None, checked_at }
+        }
+    }
+}
+
+/// The running build plus the latest release, checking GitHub at most once
+/// per [`UPDATE_CHECK_TTL`]. Failed checks are cached too.
+pub async fn version_report(cache: &UpdateCheckCache) -> VersionReport {
+    let update = match cache.get(UPDATE_CHECK_TTL) {
+        Some(check) => check,
+        None => {
+            let client = GitHubClient::new(REPO_OWNER.to_string(), REPO_NAME.to_string());
+            let latest = client.get_latest_release(Channel::for_current_version()).await.map(|r| r.version);
+            let check = update_check_from(latest, &Version::current());
+            cache.store(check.clone());
+            check
+        }
+    };
+    VersionReport { build: BuildInfo::current(), update }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_check_from() {
+        let current = Version::parse("1.0.2").unwrap();
+        let newer = update_check_from(Ok(Version::parse("1.1.0").unwrap()), &current);
+        assert_eq!(newer.update_available, Some(true));
+        assert_eq!(newer.latest_version.as_deref(), Some("1.1.0"));
+        let same = update_check_from(Ok(current.clone()), &current);
+        assert_eq!(same.update_available, Some(false));
+        let none = update_check_from(Err(UpdateError::NoReleaseFound(Channel::Stable)), &current);
+        assert_eq!(none.update_available, Some(false));
+    }
+}
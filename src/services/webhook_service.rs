@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::models::InstanceView;
+
+/// How many times a webhook POST is tried before the notification is dropped.
+pub const WEBHOOK_ATTEMPTS: u32 = 3;
+/// Wait before the second attempt; doubles for each later one.
+const WEBHOOK_BACKOFF: Duration = Duration::from_secs(1);
+/// Per-attempt timeout, so a slow receiver can't stall the refresh loop.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// One instance status transition seen between two background refreshes.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct StatusChange {
+    pub instance_id: String,
+    pub hostname: String,
+    pub old_status: String,
+    pub new_status: String,
+    pub timestamp: String,
+}
+
+impl StatusChange {
+    /// One-line summary, sent as `text` so Slack-style receivers display it.
+    pub fn summary(&self) -> String {
+        format!("Instance {} ({}) changed status: {} → {}", self.hostname, self.instance_id, self.old_status, self.new_status)
+    }
+}
+
+/// Status per instance ID, kept between refreshes to detect transitions.
+pub fn status_snapshot(instances: &[InstanceView]) -> HashMap<String, String> {
+    instances.iter().map(|i| (i.id.clone(), i.status.clone())).collect()
+}
+
+/// Instances in `current` whose status differs from `previous`. Instances that
+/// are new since the last refresh are not reported.
+pub fn detect_status_changes(previous: &HashMap<String, String>, current: &[InstanceView], timestamp: &str) -> Vec<StatusChange> {
+    current
+        .iter()
+        .filter_map(|instance| {
+            let old = previous.get(&instance.id)?;
+            (old != &instance.status).then(|| StatusChange {
+                instance_id: instance.id.clone(),
+                hostname: instance.hostname.clone(),
+                old_status: old.clone(),
+                new_status: instance.status.clone(),
+                timestamp: timestamp.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// POST each change to `url` (`ZY_WEBHOOK_URL`). Best-effort: failures are
+/// retried with backoff, then logged and dropped.
+pub async fn notify_status_changes(client: &reqwest::Client, url: &str, changes: &[StatusChange]) {
+    for change in changes {
+        let mut payload = serde_json::to_value(change).unwrap_or_default();
+        payload["text"] = serde_json::Value::String(change.summary());
+        if let Err(e) = post_with_backoff(client, url, &payload, WEBHOOK_ATTEMPTS, WEBHOOK_BACKOFF).await {
+            tracing::warn!(instance_id = %change.instance_id, %e, "Status webhook failed; notification dropped");
+        }
+    }
+}
+
+async fn post_with_backoff(
+    client: &reqwest::Client,
+    url: &str,
+    payload: &serde_json::Value,
+    attempts: u32,
+    backoff: Duration,
+) -> Result<(), String> {
+    let mut delay = backoff;
+    let mut attempt = 1;
+    loop {
+        let result = match client.post(url).timeout(WEBHOOK_TIMEOUT).json(payload).send().await {
+            Ok(resp) if resp.status().is_success() => Ok(()),
+            Ok(resp) => Err(format!("HTTP {}", resp.status())),
+            Err(e) => Err(e.to_string()),
+        };
+        match result {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt < attempts => {
+                tracing::debug!(attempt, %e, "Status webhook failed, retrying");
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instance(id: &str, status: &str) -> InstanceView {
+        let mut view = InstanceView::new_with_defaults(id.into());
+        view.hostname = format!("{}.example", id);
+        view.status = status.into();
+        view
+    }
+
+    #[test]
+    fn test_detect_status_changes_reports_transitions_only() {
+        let previous = status_snapshot(&[instance("a", "active"), instance("b", "active")]);
+        let current = [instance("a", "error"), instance("b", "active"), instance("c", "pending")];
+        let changes = detect_status_changes(&previous, &current, "2026-01-01T00:00:00Z");
+        assert_eq!(
+            changes,
+            vec![StatusChange {
+                instance_id: "a".into(),
+                hostname: "a.example".into(),
+                old_status: "active".into(),
+                new_status: "error".into(),
+                timestamp: "2026-01-01T00:00:00Z".into(),
+            }]
+        );
+        assert_eq!(changes[0].summary(), "Instance a.example (a) changed status: active → error");
+    }
+
+    #[tokio::test]
+    async fn test_post_with_backoff_gives_up_after_attempts() {
+        let client = reqwest::Client::new();
+        // Nothing listens on port 9 locally, so every attempt fails fast
+        let result = post_with_backoff(&client, "http://127.0.0.1:9/hook", &serde_json::json!({}), 2, Duration::ZERO).await;
+        assert!(result.is_err());
+    }
+}
@@ -1,7 +1,10 @@
 use std::collections::HashMap;
 
 use crate::utils::{parse_flag, parse_optional_int, parse_int_list};
-use crate::models::BaseState;
+use crate::models::{BaseState, Region, SshKeyView};
+
+/// Instance classes offered by the creation wizard.
+pub const INSTANCE_CLASSES: &[&str] = &["default", "cpu-optimized", "gpu-provided"];
 
 pub fn parse_wizard_base(query: &HashMap<String, String>) -> BaseState {
     let mut hostnames: Vec<String> = query
@@ -48,6 +51,10 @@ pub fn parse_wizard_base(query: &HashMap<String, String>) -> BaseState {
         .get("app_id")
         .map(|s| s.trim().to_string())
         .filter(|s| !s.is_empty());
+    let workspace = query
+        .get("workspace")
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
     BaseState {
         hostnames,
         region,
@@ -59,6 +66,7 @@ pub fn parse_wizard_base(query: &HashMap<String, String>) -> BaseState {
         ssh_key_ids,
         os_id,
         app_id,
+        workspace,
     }
 }
 
@@ -91,5 +99,80 @@ pub fn build_base_query_pairs(state: &BaseState) -> Vec<(String, String)> {
             pairs.push(("app_id".into(), app_id.clone()));
         }
     }
+    if let Some(ref workspace) = state.workspace {
+        pairs.push(("workspace".into(), workspace.clone()));
+    }
     pairs
 }
+
+/// `selected` SSH key IDs followed by any `required` ones not already in it.
+pub fn merge_required_ssh_keys(selected: &[i64], required: &[i64]) -> Vec<i64> {
+    let mut merged = selected.to_vec();
+    merged.extend(required.iter().filter(|id| !selected.contains(id)));
+    merged
+}
+
+/// The `required` SSH key IDs missing from the account's `available` keys.
+pub fn missing_required_ssh_keys(required: &[i64], available: &[SshKeyView]) -> Vec<i64> {
+    required
+        .iter()
+        .copied()
+        .filter(|id| !available.iter().any(|key| key.id == id.to_string()))
+        .collect()
+}
+
+/// Pair each region with its distance in km from `home`, nearest first.
+/// Regions without coordinates keep their API order after the rest; without a
+/// home location nothing is reordered.
+pub fn order_regions_by_distance(regions: &[Region], home: Option<(f64, f64)>) -> Vec<(&Region, Option<u32>)> {
+    let mut ordered: Vec<(&Region, Option<f64>)> = regions
+        .iter()
+        .map(|r| (r, home.and_then(|h| r.distance_km_from(h))))
+        .collect();
+    // sort_by is stable, so ties and unknown distances keep their order
+    ordered.sort_by(|a, b| match (a.1, b.1) {
+        (Some(x), Some(y)) => x.total_cmp(&y),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+    ordered.into_iter().map(|(r, d)| (r, d.map(|km| km.round() as u32))).collect()
+}
+
+/// Region to preselect in wizard step 1: the configured default when it is one
+/// of `regions`, otherwise the first region (or empty when there are none).
+pub fn pick_default_region(regions: &[&Region], configured: Option<&str>) -> String {
+    if let Some(wanted) = configured {
+        if let Some(r) = regions.iter().find(|r| r.id.eq_ignore_ascii_case(wanted)) {
+            return r.id.clone();
+        }
+        tracing::warn!(region = wanted, "ZY_DEFAULT_REGION is not an available region; using the first region");
+    }
+    regions.first().map(|r| r.id.clone()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_required_ssh_keys() {
+        assert_eq!(merge_required_ssh_keys(&[3, 1], &[1, 9]), vec![3, 1, 9]);
+        assert_eq!(merge_required_ssh_keys(&[], &[9]), vec![9]);
+        assert_eq!(merge_required_ssh_keys(&[3], &[]), vec![3]);
+    }
+
+    #[test]
+    fn test_missing_required_ssh_keys() {
+        let key = |id: &str| SshKeyView {
+            id: id.into(),
+            name: format!("key-{}", id),
+            fingerprint: String::new(),
+            public_key: String::new(),
+            customer_id: None,
+        };
+        let available = vec![key("1"), key("9")];
+        assert_eq!(missing_required_ssh_keys(&[9, 4], &available), vec![4]);
+        assert!(missing_required_ssh_keys(&[], &available).is_empty());
+    }
+}
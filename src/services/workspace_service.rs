@@ -30,6 +30,9 @@ pub async fn load_workspaces_from_file() -> Arc<Mutex<HashMap<String, WorkspaceR
                             .and_then(|v| v.as_str())
                             .unwrap_or(slug)
                             .to_string();
+                        let text_field = |key: &str| {
+                            entry.get(key).and_then(|v| v.as_str()).unwrap_or("").to_string()
+                        };
                         let description = entry
                             .get("description")
                             .and_then(|v| v.as_str())
@@ -78,8 +81,11 @@ pub async fn load_workspaces_from_file() -> Arc<Mutex<HashMap<String, WorkspaceR
                                 description,
                                 slug: slug.to_string(),
                                 created_at,
+                                updated_at: text_field("updated_at"),
                                 members,
                                 assigned_instances,
+                                default_region: text_field("default_region"),
+                                default_plan_type: text_field("default_plan_type"),
                             },
                         );
                     }
@@ -115,14 +121,17 @@ pub async fn persist_workspaces_file(
                     "name": ws.name,
                     "description": ws.description,
                     "created_at": ws.created_at,
+                    "updated_at": ws.updated_at,
                     "members": members,
-                    "assigned_instances": ws.assigned_instances
+                    "assigned_instances": ws.assigned_instances,
+                    "default_region": ws.default_region,
+                    "default_plan_type": ws.default_plan_type
                 })
             })
             .collect();
         serde_json::to_string_pretty(&serde_json::Value::Array(arr))?
     };
-    tokio::fs::write(WORKSPACES_FILE, content).await
+    crate::services::write_with_retry(WORKSPACES_FILE, content).await
 }
 
 /// Generate a URL-safe slug from a display name.
@@ -153,6 +162,53 @@ pub fn now_iso8601() -> String {
     chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string()
 }
 
+/// Order of the workspaces list (`?sort=`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WorkspaceSort {
+    Name,
+    /// Oldest first, so long-lived workspaces surface at the top.
+    Created,
+    /// Least recently changed first, to spot stale workspaces.
+    Updated,
+}
+
+impl WorkspaceSort {
+    /// Parse the `sort` query value; anything unknown sorts by name.
+    pub fn parse(raw: &str) -> WorkspaceSort {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "created" => WorkspaceSort::Created,
+            "updated" => WorkspaceSort::Updated,
+            _ => WorkspaceSort::Name,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WorkspaceSort::Name => "name",
+            WorkspaceSort::Created => "created",
+            WorkspaceSort::Updated => "updated",
+        }
+    }
+}
+
+/// Sort `list` in place. Workspaces without a (parseable) timestamp count as
+/// the oldest; ties fall back to the name.
+pub fn sort_workspaces(list: &mut [WorkspaceRecord], sort: WorkspaceSort) {
+    let time = |ws: &WorkspaceRecord| {
+        let raw = match sort {
+            WorkspaceSort::Updated => ws.last_changed(),
+            _ => ws.created_at.as_str(),
+        };
+        crate::utils::parse_timestamp(raw)
+    };
+    match sort {
+        WorkspaceSort::Name => list.sort_by(|a, b| a.name.cmp(&b.name)),
+        WorkspaceSort::Created | WorkspaceSort::Updated => {
+            list.sort_by(|a, b| time(a).cmp(&time(b)).then_with(|| a.name.cmp(&b.name)))
+        }
+    }
+}
+
 /// Compute the set of instance IDs accessible to a user, merging direct user
 /// assignments with instances from every workspace the user is a member of.
 ///
@@ -185,6 +241,42 @@ pub fn get_accessible_instance_ids(
     Some(sorted)
 }
 
+/// Instance IDs assigned to workspace `slug` that `username` may access.
+///
+/// An empty `username` or an owner sees every instance in the workspace;
+/// other users get the intersection with [`get_accessible_instance_ids`].
+/// Returns an error if the workspace does not exist.
+pub fn workspace_instance_ids(
+    slug: &str,
+    username: &str,
+    users_map: &std::collections::HashMap<String, crate::models::UserRecord>,
+    workspaces_map: &std::collections::HashMap<String, WorkspaceRecord>,
+) -> Result<Vec<String>, String> {
+    let ws = workspaces_map
+        .get(slug)
+        .ok_or_else(|| format!("Workspace '{}' not found", slug))?;
+    let accessible = if username.is_empty() {
+        None
+    } else {
+        get_accessible_instance_ids(username, users_map, workspaces_map)
+    };
+    Ok(ws
+        .assigned_instances
+        .iter()
+        .filter(|id| accessible.as_ref().is_none_or(|ids| ids.contains(id)))
+        .cloned()
+        .collect())
+}
+
+/// Whether `username` is the only Manager among `members`.
+///
+/// Removing or demoting that member would leave nobody but owners able to
+/// manage the workspace, so membership changes check this first.
+pub fn is_last_manager(members: &[WorkspaceMember], username: &str) -> bool {
+    let mut managers = members.iter().filter(|m| m.role == WorkspaceRole::Manager);
+    matches!((managers.next(), managers.next()), (Some(only), None) if only.username == username)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -219,6 +311,7 @@ mod tests {
             role: "owner".to_string(),
             assigned_instances: vec!["inst-1".to_string()],
             about: String::new(),
+            last_login: None,
         });
         let workspaces = HashMap::new();
         assert!(get_accessible_instance_ids("alice", &users, &workspaces).is_none());
@@ -234,6 +327,7 @@ mod tests {
             role: "admin".to_string(),
             assigned_instances: vec!["inst-direct".to_string()],
             about: String::new(),
+            last_login: None,
         });
         let mut workspaces = HashMap::new();
         workspaces.insert("ws-1".to_string(), WorkspaceRecord {
@@ -241,8 +335,11 @@ mod tests {
             description: String::new(),
             slug: "ws-1".to_string(),
             created_at: String::new(),
+            updated_at: String::new(),
             members: vec![WorkspaceMember { username: "bob".to_string(), role: WorkspaceRole::Editor }],
             assigned_instances: vec!["inst-ws".to_string()],
+            default_region: String::new(),
+            default_plan_type: String::new(),
         });
         let ids = get_accessible_instance_ids("bob", &users, &workspaces)
             .expect("admin should get Some(ids)");
@@ -261,6 +358,7 @@ mod tests {
             role: "viewer".to_string(),
             assigned_instances: vec![],
             about: String::new(),
+            last_login: None,
         });
         let mut workspaces = HashMap::new();
         workspaces.insert("ws-x".to_string(), WorkspaceRecord {
@@ -268,12 +366,89 @@ mod tests {
             description: String::new(),
             slug: "ws-x".to_string(),
             created_at: String::new(),
+            updated_at: String::new(),
             members: vec![WorkspaceMember { username: "alice".to_string(), role: WorkspaceRole::Manager }],
             assigned_instances: vec!["inst-secret".to_string()],
+            default_region: String::new(),
+            default_plan_type: String::new(),
         });
         let ids = get_accessible_instance_ids("carol", &users, &workspaces)
             .expect("viewer should get Some(ids)");
         assert!(!ids.contains(&"inst-secret".to_string()));
         assert_eq!(ids.len(), 0);
     }
+
+    #[test]
+    fn workspace_instance_ids_intersects_with_user_access() {
+        use std::collections::HashMap;
+        use crate::models::{UserRecord, workspace_record::WorkspaceRecord};
+        let mut users = HashMap::new();
+        users.insert("dave".to_string(), UserRecord {
+            password: "x".to_string(),
+            role: "viewer".to_string(),
+            assigned_instances: vec!["inst-a".to_string()],
+            about: String::new(),
+            last_login: None,
+        });
+        let mut workspaces = HashMap::new();
+        workspaces.insert("payments".to_string(), WorkspaceRecord {
+            name: "Payments".to_string(),
+            description: String::new(),
+            slug: "payments".to_string(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            members: vec![],
+            assigned_instances: vec!["inst-a".to_string(), "inst-b".to_string()],
+            default_region: String::new(),
+            default_plan_type: String::new(),
+        });
+        assert_eq!(workspace_instance_ids("payments", "", &users, &workspaces).unwrap(), vec!["inst-a", "inst-b"]);
+        assert_eq!(workspace_instance_ids("payments", "dave", &users, &workspaces).unwrap(), vec!["inst-a"]);
+        assert!(workspace_instance_ids("missing", "", &users, &workspaces).is_err());
+    }
+
+    #[test]
+    fn sort_workspaces_by_created_and_updated() {
+        let ws = |name: &str, created: &str, updated: &str| WorkspaceRecord {
+            name: name.to_string(),
+            description: String::new(),
+            slug: slugify(name),
+            created_at: created.to_string(),
+            updated_at: updated.to_string(),
+            members: vec![],
+            assigned_instances: vec![],
+            default_region: String::new(),
+            default_plan_type: String::new(),
+        };
+        let mut list = vec![
+            ws("Alpha", "2026-03-01T00:00:00Z", "2026-03-02T00:00:00Z"),
+            ws("Beta", "2025-01-01T00:00:00Z", "2026-06-01T00:00:00Z"),
+            // Legacy record: no updated_at, so its creation time counts
+            ws("Gamma", "2026-02-01T00:00:00Z", ""),
+            ws("Delta", "", ""),
+        ];
+        let names = |list: &[WorkspaceRecord]| list.iter().map(|w| w.name.clone()).collect::<Vec<_>>();
+
+        sort_workspaces(&mut list, WorkspaceSort::Created);
+        assert_eq!(names(&list), vec!["Delta", "Beta", "Gamma", "Alpha"]);
+        sort_workspaces(&mut list, WorkspaceSort::Updated);
+        assert_eq!(names(&list), vec!["Delta", "Gamma", "Alpha", "Beta"]);
+        sort_workspaces(&mut list, WorkspaceSort::parse("bogus"));
+        assert_eq!(names(&list), vec!["Alpha", "Beta", "Delta", "Gamma"]);
+    }
+
+    #[test]
+    fn last_manager_detection() {
+        use crate::models::workspace_record::{WorkspaceMember, WorkspaceRole};
+        let member = |name: &str, role| WorkspaceMember { username: name.to_string(), role };
+        let members = vec![member("alice", WorkspaceRole::Manager), member("bob", WorkspaceRole::Editor)];
+        assert!(is_last_manager(&members, "alice"));
+        assert!(!is_last_manager(&members, "bob"));
+
+        let two_managers = vec![member("alice", WorkspaceRole::Manager), member("carol", WorkspaceRole::Manager)];
+        assert!(!is_last_manager(&two_managers, "alice"));
+
+        // A workspace that never had a manager isn't guarded
+        assert!(!is_last_manager(&[member("bob", WorkspaceRole::Viewer)], "bob"));
+    }
 }
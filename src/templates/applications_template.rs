@@ -0,0 +1,22 @@
+use askama::Template;
+use crate::api::ApiStatus;
+use crate::models::CurrentUser;
+use crate::api::ApplicationGroup;
+
+#[derive(Template)]
+#[template(path = "applications.html")]
+pub struct ApplicationsTemplate<'a> {
+    pub current_user: Option<CurrentUser>,
+    pub api_hostname: String,
+    pub base_url: String,
+    pub flash_messages: Vec<String>,
+    pub has_flash_messages: bool,
+    pub maintenance_mode: bool,
+    pub api_status: ApiStatus,
+    /// Active applications by support level (see `api::group_by_support_level`).
+    pub groups: Vec<ApplicationGroup<'a>>,
+    /// False when `ZY_HIDE_PRICES_FOR_NONOWNERS` hides application prices.
+    pub show_prices: bool,
+}
+
+crate::impl_base_template!(ApplicationsTemplate<'_>);
@@ -1,3 +1,4 @@
+use crate::api::ApiStatus;
 use crate::models::CurrentUser;
 
 /// Base template trait providing common properties for all templates.
@@ -12,6 +13,8 @@ pub trait BaseTemplate {
     fn base_url(&self) -> &str;
     fn flash_messages(&self) -> &Vec<String>;
     fn has_flash_messages(&self) -> bool;
+    fn maintenance_mode(&self) -> bool;
+    fn api_status(&self) -> &ApiStatus;
 }
 
 /// Macro to implement BaseTemplate for a struct with standard fields
@@ -35,6 +38,12 @@ macro_rules! impl_base_template {
             fn has_flash_messages(&self) -> bool {
                 self.has_flash_messages
             }
+            fn maintenance_mode(&self) -> bool {
+                self.maintenance_mode
+            }
+            fn api_status(&self) -> &$crate::api::ApiStatus {
+                &self.api_status
+            }
         }
     };
     // For structs without lifetimes
@@ -55,6 +64,12 @@ macro_rules! impl_base_template {
             fn has_flash_messages(&self) -> bool {
                 self.has_flash_messages
             }
+            fn maintenance_mode(&self) -> bool {
+                self.maintenance_mode
+            }
+            fn api_status(&self) -> &$crate::api::ApiStatus {
+                &self.api_status
+            }
         }
     };
 }
@@ -1,6 +1,13 @@
 use askama::Template;
+use crate::api::ApiStatus;
 use crate::models::{CurrentUser, InstanceView, OsItem};
 
+/// An OS choice on the change-os page, disabled when it doesn't fit the instance.
+pub struct ChangeOsOption {
+    pub os: OsItem,
+    pub disabled_reason: Option<String>,
+}
+
 #[derive(Template)]
 #[template(path = "change_os_instance.html")]
 pub struct ChangeOsInstanceTemplate {
@@ -9,8 +16,10 @@ pub struct ChangeOsInstanceTemplate {
     pub base_url: String,
     pub flash_messages: Vec<String>,
     pub has_flash_messages: bool,
+    pub maintenance_mode: bool,
+    pub api_status: ApiStatus,
     pub instance: InstanceView,
-    pub os_list: Vec<OsItem>,
+    pub os_options: Vec<ChangeOsOption>,
     pub disabled_by_env: bool,
     pub disabled_by_host: bool,
 }
@@ -1,4 +1,5 @@
 use askama::Template;
+use crate::api::ApiStatus;
 use crate::models::CurrentUser;
 
 #[derive(Template)]
@@ -9,6 +10,8 @@ pub struct ComingSoonTemplate {
     pub base_url: String,
     pub flash_messages: Vec<String>,
     pub has_flash_messages: bool,
+    pub maintenance_mode: bool,
+    pub api_status: ApiStatus,
     pub feature_name: String,
 }
 
@@ -1,4 +1,5 @@
 use askama::Template;
+use crate::api::ApiStatus;
 use crate::models::CurrentUser;
 
 #[derive(Template)]
@@ -9,6 +10,8 @@ pub struct ConfirmationTemplate {
     pub base_url: String,
     pub flash_messages: Vec<String>,
     pub has_flash_messages: bool,
+    pub maintenance_mode: bool,
+    pub api_status: ApiStatus,
     
     pub title: String,
     pub message: String,
@@ -17,6 +20,8 @@ pub struct ConfirmationTemplate {
     pub cancel_url: String,
     pub button_class: String,
     pub hidden_fields: Vec<(String, String)>,
+    /// Hostname that must be typed before submitting, if required.
+    pub confirm_hostname: Option<String>,
 }
 
 crate::impl_base_template!(ConfirmationTemplate);
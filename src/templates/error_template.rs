@@ -0,0 +1,21 @@
+use askama::Template;
+use crate::api::ApiStatus;
+use crate::models::CurrentUser;
+
+#[derive(Template)]
+#[template(path = "error.html")]
+pub struct ErrorTemplate {
+    pub current_user: Option<CurrentUser>,
+    pub api_hostname: String,
+    pub base_url: String,
+    pub flash_messages: Vec<String>,
+    pub has_flash_messages: bool,
+    pub maintenance_mode: bool,
+    pub api_status: ApiStatus,
+    pub status_code: u16,
+    /// Canonical reason phrase, e.g. "Not Found".
+    pub reason: String,
+    pub message: String,
+}
+
+crate::impl_base_template!(ErrorTemplate);
@@ -1,4 +1,5 @@
 use askama::Template;
+use crate::api::ApiStatus;
 use crate::models::{CurrentUser, Region};
 use crate::api::FloatingIpView;
 
@@ -10,6 +11,8 @@ pub struct FloatingIpsTemplate<'a> {
     pub base_url: String,
     pub flash_messages: Vec<String>,
     pub has_flash_messages: bool,
+    pub maintenance_mode: bool,
+    pub api_status: ApiStatus,
     pub floating_ips: &'a [FloatingIpView],
     pub current_page: usize,
     pub total_pages: usize,
@@ -1,5 +1,8 @@
 use askama::Template;
-use crate::models::CurrentUser;
+use crate::api::ApiStatus;
+use crate::models::{CurrentUser, InstanceNote};
+use crate::api::{InstanceConsole, InstanceEvent, InstanceSubscription};
+use crate::utils::DisplayTime;
 
 #[derive(Template)]
 #[template(path = "instance_detail.html")]
@@ -9,12 +12,43 @@ pub struct InstanceDetailTemplate {
     pub base_url: String,
     pub flash_messages: Vec<String>,
     pub has_flash_messages: bool,
+    pub maintenance_mode: bool,
+    pub api_status: ApiStatus,
     pub instance_id: String,
     pub hostname: String,
     pub status: String,
+    /// Severity CSS class for the status badge (see `utils::status_class`).
+    pub status_class: &'static str,
     pub details: Vec<(String, String)>,
+    /// Parsed creation time; the "Created" row shows it relative with the
+    /// absolute time on hover.
+    pub created: Option<DisplayTime>,
+    pub events: Vec<InstanceEvent>,
+    /// Billing summary; the section is hidden when the API has none.
+    pub subscription: Option<InstanceSubscription>,
+    /// Parsed renewal date of `subscription`.
+    pub renews: Option<DisplayTime>,
+    /// Local note from `notes.json`, if any.
+    pub note: Option<InstanceNote>,
+    /// Local labels from `labels.json` as `key=value`, in key order.
+    pub labels: Vec<String>,
+    /// Admins and owners may edit the note.
+    pub can_edit_note: bool,
+    /// Power On is hidden while the instance is running.
+    pub can_power_on: bool,
+    /// Power Off is hidden while the instance is powered off.
+    pub can_power_off: bool,
     pub disabled_by_env: bool,
     pub disabled_by_host: bool,
 }
 
 crate::impl_base_template!(InstanceDetailTemplate);
+
+/// The serial console panel alone, loaded into the detail page on expand.
+#[derive(Template)]
+#[template(path = "instance_console.html")]
+pub struct InstanceConsoleTemplate {
+    pub instance_id: String,
+    /// `None` when the API has no console output for the instance.
+    pub console: Option<InstanceConsole>,
+}
@@ -0,0 +1,25 @@
+use askama::Template;
+use crate::api::ApiStatus;
+use crate::models::{CurrentUser, OsItem};
+use crate::services::bulk_service::BulkOutcome;
+
+#[derive(Template)]
+#[template(path = "instances_bulk.html")]
+pub struct InstancesBulkTemplate {
+    pub current_user: Option<CurrentUser>,
+    pub api_hostname: String,
+    pub base_url: String,
+    pub flash_messages: Vec<String>,
+    pub has_flash_messages: bool,
+    pub maintenance_mode: bool,
+    pub api_status: ApiStatus,
+    /// Active images offered for a bulk change-os.
+    pub os_options: Vec<OsItem>,
+    /// The submitted IDs, kept in the textarea after a run.
+    pub instance_ids: String,
+    /// Per-instance results of the last run; empty before one.
+    pub results: Vec<BulkOutcome>,
+    pub summary: String,
+}
+
+crate::impl_base_template!(InstancesBulkTemplate);
@@ -1,5 +1,6 @@
 use askama::Template;
-use crate::models::{CurrentUser, InstanceView};
+use crate::api::ApiStatus;
+use crate::models::{CurrentUser, RecentInstance, InstanceColumn, InstanceListDiff, InstanceView};
 
 #[derive(Template)]
 #[template(path = "instances.html")]
@@ -9,11 +10,47 @@ pub struct InstancesPageTemplate<'a> {
     pub base_url: String,
     pub flash_messages: Vec<String>,
     pub has_flash_messages: bool,
+    pub maintenance_mode: bool,
+    pub api_status: ApiStatus,
     pub instances: &'a [InstanceView],
+    /// Table columns, from `ZY_INSTANCE_COLUMNS` or the default layout.
+    pub columns: Vec<InstanceColumn>,
     pub current_page: usize,
     pub total_pages: usize,
     pub per_page: usize,
     pub total_count: usize,
+    /// Counts per status across all pages, e.g. ("active", 12).
+    pub status_counts: &'a [(String, usize)],
+    /// True when the list is restricted by `?ids=`.
+    pub filtered: bool,
+    /// Subscribe to `/events/instances` to update status badges in place.
+    pub live_status: bool,
+    /// Instances this session opened lately, most recent first.
+    pub recent_instances: Vec<RecentInstance>,
+    /// Show the bulk-selection checkboxes (admins and owners).
+    pub can_select: bool,
+    /// Instance IDs ticked for a bulk action, across all pages.
+    pub selected_instances: Vec<String>,
+    /// New, newly errored and vanished instances since this session's last visit.
+    pub changes: InstanceListDiff,
+}
+
+impl InstancesPageTemplate<'_> {
+    /// Whether `id` is part of the bulk selection.
+    pub fn is_selected(&self, id: &str) -> bool {
+        self.selected_instances.iter().any(|s| s == id)
+    }
+
+    /// Row class highlighting `id` if it changed since the last visit.
+    pub fn change_class(&self, id: &str) -> &'static str {
+        if self.changes.errored_ids.contains(id) {
+            "row-errored"
+        } else if self.changes.new_ids.contains(id) {
+            "row-new"
+        } else {
+            ""
+        }
+    }
 }
 
 crate::impl_base_template!(InstancesPageTemplate<'_>);
@@ -1,4 +1,5 @@
 use askama::Template;
+use crate::api::ApiStatus;
 use crate::models::{CurrentUser, Region};
 use crate::api::IsoView;
 
@@ -10,6 +11,8 @@ pub struct IsosTemplate<'a> {
     pub base_url: String,
     pub flash_messages: Vec<String>,
     pub has_flash_messages: bool,
+    pub maintenance_mode: bool,
+    pub api_status: ApiStatus,
     pub isos: &'a [IsoView],
     pub regions: &'a [Region],
     pub total_count: usize,
@@ -16,11 +16,14 @@ pub mod instances_page_template;
 pub mod change_pass_instance_template;
 pub mod change_os_instance_template;
 pub mod resize_template;
+pub mod instances_bulk_template;
 pub mod coming_soon_template;
+pub mod error_template;
 pub mod snapshots_template;
 pub mod snapshot_detail_template;
 pub mod floating_ips_template;
 pub mod isos_template;
+pub mod applications_template;
 pub mod images_template;
 pub mod backups_template;
 pub mod workspaces_template;
@@ -42,7 +45,7 @@ pub mod step8_template;
 
 // Re-export all templates
 pub use login_template::LoginTemplate;
-pub use instance_detail_template::InstanceDetailTemplate;
+pub use instance_detail_template::{InstanceConsoleTemplate, InstanceDetailTemplate};
 pub use users_page_template::UsersPageTemplate;
 pub use user_detail_template::UserDetailTemplate;
 pub use access_page_template::AccessPageTemplate;
@@ -52,13 +55,16 @@ pub use ssh_keys_page_template::SshKeysPageTemplate;
 pub use ssh_key_detail_template::SshKeyDetailTemplate;
 pub use instances_page_template::InstancesPageTemplate;
 pub use change_pass_instance_template::ChangePassInstanceTemplate;
-pub use change_os_instance_template::ChangeOsInstanceTemplate;
+pub use change_os_instance_template::{ChangeOsInstanceTemplate, ChangeOsOption};
 pub use resize_template::ResizeTemplate;
+pub use instances_bulk_template::InstancesBulkTemplate;
 pub use coming_soon_template::ComingSoonTemplate;
+pub use error_template::ErrorTemplate;
 pub use snapshots_template::SnapshotsTemplate;
 pub use snapshot_detail_template::SnapshotDetailTemplate;
 pub use floating_ips_template::FloatingIpsTemplate;
 pub use isos_template::IsosTemplate;
+pub use applications_template::ApplicationsTemplate;
 pub use images_template::ImagesTemplate;
 pub use backups_template::BackupsTemplate;
 pub use workspaces_template::WorkspacesTemplate;
@@ -73,7 +79,7 @@ pub use step2_template::Step2Template;
 pub use step3_fixed_template::Step3FixedTemplate;
 pub use step3_custom_template::Step3CustomTemplate;
 pub use step4_template::Step4Template;
-pub use step5_template::Step5Template;
+pub use step5_template::{OsFamilyFilter, Step5Template};
 pub use step6_template::Step6Template;
 pub use step7_template::Step7Template;
 pub use step8_template::Step8Template;
@@ -1,4 +1,5 @@
 use askama::Template;
+use crate::api::ApiStatus;
 use crate::models::{CurrentUser, Permission};
 
 /// A single row in the permissions reference table.
@@ -18,6 +19,8 @@ pub struct PermissionsTemplate {
     pub base_url: String,
     pub flash_messages: Vec<String>,
     pub has_flash_messages: bool,
+    pub maintenance_mode: bool,
+    pub api_status: ApiStatus,
     pub rows: Vec<PermissionRow>,
 }
 
@@ -30,6 +33,8 @@ impl PermissionsTemplate {
         base_url: String,
         flash_messages: Vec<String>,
         has_flash_messages: bool,
+        maintenance_mode: bool,
+        api_status: ApiStatus,
     ) -> Self {
         let rows = Permission::all()
             .iter()
@@ -47,6 +52,8 @@ impl PermissionsTemplate {
             base_url,
             flash_messages,
             has_flash_messages,
+            maintenance_mode,
+            api_status,
             rows,
         }
     }
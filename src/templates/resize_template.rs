@@ -1,5 +1,7 @@
 use askama::Template;
+use crate::api::ApiStatus;
 use crate::models::{CurrentUser, Region, InstanceView};
+use crate::services::instance_service::ResizePreview;
 
 #[derive(Template)]
 #[template(path = "resize.html")]
@@ -9,8 +11,16 @@ pub struct ResizeTemplate<'a> {
     pub base_url: String,
     pub flash_messages: Vec<String>,
     pub has_flash_messages: bool,
+    pub maintenance_mode: bool,
+    pub api_status: ApiStatus,
     pub instance: InstanceView,
     pub regions: &'a [Region],
+    /// Product ID to prefill, from a "Preview price" submit.
+    pub product_id: String,
+    /// Price change for `product_id`, or why it couldn't be priced.
+    pub preview: Option<Result<ResizePreview, String>>,
+    /// Prices may be hidden from non-owners (`ZY_HIDE_PRICES_FOR_NONOWNERS`).
+    pub can_preview: bool,
     pub disabled_by_env: bool,
     pub disabled_by_host: bool,
 }
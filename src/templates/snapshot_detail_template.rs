@@ -1,4 +1,5 @@
 use askama::Template;
+use crate::api::ApiStatus;
 use crate::models::CurrentUser;
 use serde_json::Map;
 
@@ -10,6 +11,8 @@ pub struct SnapshotDetailTemplate {
     pub base_url: String,
     pub flash_messages: Vec<String>,
     pub has_flash_messages: bool,
+    pub maintenance_mode: bool,
+    pub api_status: ApiStatus,
     pub snapshot_id: String,
     pub snapshot_data: Option<Map<String, serde_json::Value>>,
 }
@@ -1,4 +1,5 @@
 use askama::Template;
+use crate::api::ApiStatus;
 use crate::models::CurrentUser;
 use crate::api::SnapshotView;
 
@@ -10,6 +11,8 @@ pub struct SnapshotsTemplate<'a> {
     pub base_url: String,
     pub flash_messages: Vec<String>,
     pub has_flash_messages: bool,
+    pub maintenance_mode: bool,
+    pub api_status: ApiStatus,
     pub snapshots: &'a [SnapshotView],
     pub current_page: usize,
     pub total_pages: usize,
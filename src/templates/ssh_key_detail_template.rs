@@ -1,4 +1,5 @@
 use askama::Template;
+use crate::api::ApiStatus;
 use crate::models::{CurrentUser, SshKeyView};
 
 #[derive(Template)]
@@ -9,6 +10,8 @@ pub struct SshKeyDetailTemplate {
     pub base_url: String,
     pub flash_messages: Vec<String>,
     pub has_flash_messages: bool,
+    pub maintenance_mode: bool,
+    pub api_status: ApiStatus,
     pub ssh_key: Option<SshKeyView>,
     pub key_id: String,
 }
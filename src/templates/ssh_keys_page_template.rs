@@ -1,4 +1,5 @@
 use askama::Template;
+use crate::api::ApiStatus;
 use crate::models::{CurrentUser, SshKeyView};
 
 #[derive(Template)]
@@ -9,6 +10,8 @@ pub struct SshKeysPageTemplate<'a> {
     pub base_url: String,
     pub flash_messages: Vec<String>,
     pub has_flash_messages: bool,
+    pub maintenance_mode: bool,
+    pub api_status: ApiStatus,
     pub ssh_keys: &'a [SshKeyView],
     pub customer_id: Option<String>,
     pub current_page: usize,
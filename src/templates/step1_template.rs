@@ -1,5 +1,6 @@
 use askama::Template;
-use crate::models::{CurrentUser, Step1FormData, Region};
+use crate::api::ApiStatus;
+use crate::models::{CurrentUser, Step1FormData, Region, WorkspaceRecord};
 
 #[derive(Template)]
 #[template(path = "step_1.html")]
@@ -9,8 +10,13 @@ pub struct Step1Template<'a> {
     pub base_url: String,
     pub flash_messages: Vec<String>,
     pub has_flash_messages: bool,
-    pub regions: &'a [Region],
+    pub maintenance_mode: bool,
+    pub api_status: ApiStatus,
+    /// Regions with their distance in km from `ZY_HOME_LAT`/`ZY_HOME_LON`, nearest first.
+    pub regions: Vec<(&'a Region, Option<u32>)>,
     pub form_data: Step1FormData,
+    /// Workspace the wizard was started from (`?workspace=`), if allowed.
+    pub workspace: Option<WorkspaceRecord>,
 }
 
 crate::impl_base_template!(Step1Template<'_>);
@@ -1,4 +1,5 @@
 use askama::Template;
+use crate::api::ApiStatus;
 use crate::models::{CurrentUser, BaseState, Step2FormData};
 
 #[derive(Template)]
@@ -9,8 +10,11 @@ pub struct Step2Template<'a> {
     pub base_url: String,
     pub flash_messages: Vec<String>,
     pub has_flash_messages: bool,
+    pub maintenance_mode: bool,
+    pub api_status: ApiStatus,
     pub base_state: &'a BaseState,
     pub form_data: Step2FormData,
+    pub hostname_errors: Vec<String>,
     pub back_url: String,
     pub submit_url: String,
 }
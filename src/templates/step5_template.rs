@@ -1,7 +1,15 @@
 use askama::Template;
-use crate::models::{CurrentUser, BaseState, CustomPlanFormValues, OsItem};
+use crate::api::ApiStatus;
+use crate::models::{CurrentUser, BaseState, CustomPlanFormValues, OsFamilyGroup};
 use crate::api::Application;
 
+/// A link in the OS family filter bar.
+pub struct OsFamilyFilter {
+    pub label: String,
+    pub url: String,
+    pub active: bool,
+}
+
 #[derive(Template)]
 #[template(path = "step_5.html")]
 pub struct Step5Template<'a> {
@@ -10,6 +18,8 @@ pub struct Step5Template<'a> {
     pub base_url: String,
     pub flash_messages: Vec<String>,
     pub has_flash_messages: bool,
+    pub maintenance_mode: bool,
+    pub api_status: ApiStatus,
     pub base_state: &'a BaseState,
     pub product_id: String,
     pub hostnames_csv: String,
@@ -18,9 +28,12 @@ pub struct Step5Template<'a> {
     pub extra_disk: String,
     pub extra_bandwidth: String,
     pub custom_plan: CustomPlanFormValues,
-    pub os_list: &'a [OsItem],
+    pub os_groups: Vec<OsFamilyGroup>,
+    pub family_filters: Vec<OsFamilyFilter>,
     pub selected_os_id: String,
     pub applications: &'a [Application],
+    /// False when `ZY_HIDE_PRICES_FOR_NONOWNERS` hides application prices.
+    pub show_prices: bool,
     pub selected_app_id: String,
     pub back_url: String,
     pub submit_url: String,
@@ -1,4 +1,5 @@
 use askama::Template;
+use crate::api::ApiStatus;
 use crate::models::{CurrentUser, BaseState, PlanState, ProductEntry};
 
 #[derive(Template)]
@@ -9,10 +10,16 @@ pub struct Step7Template<'a> {
     pub base_url: String,
     pub flash_messages: Vec<String>,
     pub has_flash_messages: bool,
+    pub maintenance_mode: bool,
+    pub api_status: ApiStatus,
     pub base_state: &'a BaseState,
     pub plan_state: PlanState,
     pub hostnames_csv: String,
     pub floating_ip_count: String,
+    /// Per-IP and total monthly price, when the API reports it.
+    pub floating_ip_price: Option<String>,
+    /// Set when the requested count exceeds the account's remaining allocation.
+    pub floating_ip_warning: Option<String>,
     pub ssh_key_ids_csv: String,
     pub region_name: String,
     pub plan_type_label: String,
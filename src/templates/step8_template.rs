@@ -1,4 +1,5 @@
 use askama::Template;
+use crate::api::{ApiStatus, HostCreateResult};
 use crate::models::CurrentUser;
 
 #[derive(Template)]
@@ -9,10 +10,14 @@ pub struct Step8Template {
     pub base_url: String,
     pub flash_messages: Vec<String>,
     pub has_flash_messages: bool,
+    pub maintenance_mode: bool,
+    pub api_status: ApiStatus,
     pub status_label: String,
     pub code: Option<String>,
     pub detail: Option<String>,
     pub errors: Vec<String>,
+    /// Per-host outcome of a multi-host create; empty when not reported.
+    pub hosts: Vec<HostCreateResult>,
     pub back_url: String,
 }
 
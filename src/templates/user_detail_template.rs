@@ -1,5 +1,6 @@
 use askama::Template;
-use crate::models::{CurrentUser, UserRow};
+use crate::api::ApiStatus;
+use crate::models::{CurrentUser, UserView};
 
 #[derive(Template)]
 #[template(path = "user_detail.html")]
@@ -9,7 +10,11 @@ pub struct UserDetailTemplate {
     pub base_url: String,
     pub flash_messages: Vec<String>,
     pub has_flash_messages: bool,
-    pub user: UserRow,
+    pub maintenance_mode: bool,
+    pub api_status: ApiStatus,
+    pub user: UserView,
+    /// Sessions currently open for `user`.
+    pub active_sessions: usize,
 }
 
 crate::impl_base_template!(UserDetailTemplate);
@@ -1,5 +1,6 @@
 use askama::Template;
-use crate::models::{CurrentUser, UserRow};
+use crate::api::ApiStatus;
+use crate::models::{CurrentUser, UserView};
 
 #[derive(Template)]
 #[template(path = "users.html")]
@@ -9,7 +10,9 @@ pub struct UsersPageTemplate<'a> {
     pub base_url: String,
     pub flash_messages: Vec<String>,
     pub has_flash_messages: bool,
-    pub rows: &'a [UserRow],
+    pub maintenance_mode: bool,
+    pub api_status: ApiStatus,
+    pub rows: &'a [UserView],
 }
 
 crate::impl_base_template!(UsersPageTemplate<'_>);
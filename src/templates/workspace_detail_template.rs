@@ -1,4 +1,5 @@
 use askama::Template;
+use crate::api::ApiStatus;
 use crate::models::{CurrentUser, WorkspaceRecord, InstanceView};
 
 #[derive(Template)]
@@ -9,7 +10,11 @@ pub struct WorkspaceDetailTemplate<'a> {
     pub base_url: String,
     pub flash_messages: Vec<String>,
     pub has_flash_messages: bool,
+    pub maintenance_mode: bool,
+    pub api_status: ApiStatus,
     pub workspace: &'a WorkspaceRecord,
+    /// Whether the viewer may start the wizard for this workspace.
+    pub can_create: bool,
     pub all_users: &'a [String],
     /// All instances available to the owner (for the assignment checkbox list).
     pub all_instances: &'a [InstanceView],
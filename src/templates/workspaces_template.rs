@@ -1,5 +1,7 @@
 use askama::Template;
-use crate::models::{CurrentUser, WorkspaceRecord};
+use crate::api::ApiStatus;
+use crate::models::{CurrentUser, RecentInstance, WorkspaceRecord};
+use crate::utils::DisplayTime;
 
 #[derive(Template)]
 #[template(path = "workspaces.html")]
@@ -9,7 +11,24 @@ pub struct WorkspacesTemplate<'a> {
     pub base_url: String,
     pub flash_messages: Vec<String>,
     pub has_flash_messages: bool,
+    pub maintenance_mode: bool,
+    pub api_status: ApiStatus,
     pub workspaces: &'a [WorkspaceRecord],
+    pub current_page: usize,
+    pub total_pages: usize,
+    pub per_page: usize,
+    pub total_count: usize,
+    /// Current `?sort=` value, kept in pagination links.
+    pub sort: &'static str,
+    /// Instances this session opened lately, most recent first.
+    pub recent_instances: Vec<RecentInstance>,
+}
+
+impl WorkspacesTemplate<'_> {
+    /// `raw` prepared for a `<time>` element; `None` when the record has no timestamp.
+    pub fn shown(&self, raw: &str) -> Option<DisplayTime> {
+        (!raw.is_empty()).then(|| crate::utils::display_time(raw, chrono::Utc::now()))
+    }
 }
 
 crate::impl_base_template!(WorkspacesTemplate<'_>);
@@ -51,7 +51,7 @@ pub fn parse_asset_name(name: &str) -> Option<(String, String)> {
     
     let mut version_end_idx = None;
     for (i, part) in parts.iter().enumerate().skip(1) {
-        if arch_indicators.iter().any(|&arch| *part == arch) {
+        if arch_indicators.contains(part) {
             version_end_idx = Some(i);
             break;
         }
@@ -69,8 +69,90 @@ pub fn parse_asset_name(name: &str) -> Option<(String, String)> {
     None
 }
 
+/// Alternative spellings of an operating system seen in release asset names.
+fn os_aliases(os: &str) -> &'static [&'static str] {
+    match os {
+        "linux" => &["linux"],
+        "macos" => &["macos", "darwin", "apple", "osx", "mac"],
+        "windows" => &["windows", "win64", "win", "msvc"],
+        _ => &[],
+    }
+}
+
+/// Alternative spellings of a CPU architecture seen in release asset names.
+fn arch_aliases(arch: &str) -> &'static [&'static str] {
+    match arch {
+        "x86_64" => &["x86_64", "amd64", "x64"],
+        "aarch64" => &["aarch64", "arm64"],
+        "x86" | "i686" => &["i686", "i386", "x86"],
+        _ => &[],
+    }
+}
+
+/// Whether an asset's extension is right for the platform (`.exe` only on Windows).
+fn extension_matches(asset: &Asset, platform: &Platform) -> bool {
+    if platform.extension.is_some() {
+        asset.name.ends_with(".exe")
+    } else {
+        !asset.name.ends_with(".exe")
+    }
+}
+
+/// Extensions of release assets that are never the `zy` binary itself:
+/// archives, checksums, signatures, notes and OS packages.
+const NON_BINARY_EXTENSIONS: &[&str] = &[
+    ".tar.gz", ".tgz", ".zip", ".sha256", ".sha512", ".sig", ".asc", ".txt", ".md", ".deb",
+    ".rpm", ".msi", ".dmg", ".pkg",
+];
+
+/// Whether an asset name is an archive, checksum or other non-binary file.
+fn is_non_binary_asset(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    NON_BINARY_EXTENSIONS.iter().any(|ext| lower.ends_with(ext))
+}
+
+/// How well an asset name matches a platform; lower is better.
+///
+/// Priority order:
+/// 1. `0` – the parsed `zy-{VERSION}-{TARGET}` target equals the target triple.
+/// 2. `1` – the target triple appears verbatim in the name (e.g. `zy-x86_64-unknown-linux-gnu`).
+/// 3. `2` – the name contains both an OS alias and an architecture alias
+///    (e.g. `zy-linux-amd64`, `zy-darwin-arm64`, `zy-windows-x64.exe`).
+///
+/// Archives and checksum files (`.tar.gz`, `.zip`, `.sha256`, …) never match.
+pub fn asset_match_rank(name: &str, platform: &Platform) -> Option<u8> {
+    if is_non_binary_asset(name) {
+        return None;
+    }
+    let target_triple = platform.to_target_triple();
+    if let Some((_, asset_target)) = parse_asset_name(name) {
+        if asset_target == target_triple {
+            return Some(0);
+        }
+    }
+
+    let lower = name.to_lowercase();
+    let stem = lower.strip_suffix(".exe").unwrap_or(&lower);
+    if stem.contains(&target_triple.to_lowercase()) {
+        return Some(1);
+    }
+
+    let tokens: Vec<&str> = stem.split(['-', '.']).collect();
+    let has_os = os_aliases(&platform.os).iter().any(|a| tokens.contains(a));
+    let has_arch = arch_aliases(&platform.arch).iter().any(|a| tokens.contains(a));
+    if has_os && has_arch {
+        return Some(2);
+    }
+    None
+}
+
 /// Select the correct asset for the current platform from a list of assets
 /// 
+/// Assets are ranked with [`asset_match_rank`], so exact target-triple names are
+/// preferred and common alternatives (`linux-amd64`, `darwin-arm64`, …) are used
+/// as a fallback. When nothing matches, `UpdateError::NoCompatibleAsset` lists
+/// the binaries that were available.
+/// 
 /// # Examples
 /// 
 /// ```no_run
@@ -97,41 +179,39 @@ pub fn select_asset_for_platform(
     
     tracing::debug!("Selecting asset for platform: {}", target_triple);
     
+    let mut best: Option<(u8, &Asset)> = None;
+    let mut available = Vec::new();
     for asset in assets {
-        // Skip non-binary assets (like SHA256SUMS.txt)
-        if !asset.name.starts_with("zy-") {
+        // Skip non-binary assets (like SHA256SUMS.txt or release archives)
+        if !asset.name.starts_with("zy-") || is_non_binary_asset(&asset.name) {
             continue;
         }
+        available.push(asset.name.clone());
         
-        if asset.name == "SHA256SUMS.txt" {
+        let Some(rank) = asset_match_rank(&asset.name, platform) else {
+            continue;
+        };
+        tracing::debug!("Asset {} matches with rank {}", asset.name, rank);
+        
+        // Verify extension matches platform expectations
+        if !extension_matches(asset, platform) {
+            tracing::debug!("Asset {} has the wrong extension for {}", asset.name, target_triple);
             continue;
         }
         
-        // Parse the asset name
-        if let Some((_, asset_target)) = parse_asset_name(&asset.name) {
-            tracing::debug!("Found asset with target: {}", asset_target);
-            
-            // Check if target matches
-            if asset_target == target_triple {
-                // Verify extension matches platform expectations
-                if platform.extension.is_some() {
-                    if !asset.name.ends_with(".exe") {
-                        tracing::debug!("Asset {} missing .exe extension for Windows", asset.name);
-                        continue;
-                    }
-                } else if asset.name.ends_with(".exe") {
-                    tracing::debug!("Asset {} has .exe extension for non-Windows", asset.name);
-                    continue;
-                }
-                
-                tracing::info!("Selected asset: {}", asset.name);
-                return Ok(asset.clone());
-            }
+        if best.is_none_or(|(best_rank, _)| rank < best_rank) {
+            best = Some((rank, asset));
         }
     }
     
-    Err(UpdateError::NoAssetFound(format!(
-        "No matching asset found for platform {}",
-        target_triple
-    )))
+    match best {
+        Some((_, asset)) => {
+            tracing::info!("Selected asset: {}", asset.name);
+            Ok(asset.clone())
+        }
+        None => Err(UpdateError::NoCompatibleAsset {
+            platform: target_triple,
+            available,
+        }),
+    }
 }
@@ -0,0 +1,49 @@
+//! Build metadata embedded by `build.rs`.
+
+use super::Version;
+
+/// Short git commit the binary was built from, or `unknown`.
+pub const GIT_COMMIT: &str = env!("ZY_GIT_COMMIT");
+
+/// UTC date of the build (`YYYY-MM-DD`).
+pub const BUILD_DATE: &str = env!("ZY_BUILD_DATE");
+
+/// `zy --version` text: `1.0.2 (commit abc123def456, built 2026-10-16)`.
+pub const LONG_VERSION: &str = concat!(
+    env!("CARGO_PKG_VERSION"),
+    " (commit ",
+    env!("ZY_GIT_COMMIT"),
+    ", built ",
+    env!("ZY_BUILD_DATE"),
+    ")"
+);
+
+/// What is running: version, commit and build date.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BuildInfo {
+    pub version: String,
+    pub git_commit: &'static str,
+    pub build_date: &'static str,
+}
+
+impl BuildInfo {
+    pub fn current() -> Self {
+        BuildInfo {
+            version: Version::current().to_string(),
+            git_commit: GIT_COMMIT,
+            build_date: BUILD_DATE,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_long_version_starts_with_package_version() {
+        assert!(LONG_VERSION.starts_with(env!("CARGO_PKG_VERSION")));
+        assert_eq!(BuildInfo::current().version, Version::current().to_string());
+        assert_eq!(BUILD_DATE.len(), 10);
+    }
+}
@@ -41,6 +41,23 @@ impl Channel {
         }
     }
     
+    /// Detect the channel of the currently running binary
+    /// 
+    /// A `-beta` build keeps tracking beta releases, an `-rc` build tracks
+    /// release candidates, and so on; plain versions map to `Stable`.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use zy::update::{Channel, Version};
+    /// 
+    /// let channel = Channel::for_current_version();
+    /// assert_eq!(channel, Channel::from_version(&Version::current().to_string()));
+    /// ```
+    pub fn for_current_version() -> Self {
+        Self::from_version(&super::version::Version::current().to_string())
+    }
+    
     /// Check if this channel should include pre-release versions
     /// 
     /// # Examples
@@ -1,12 +1,22 @@
 //! Binary download functionality with progress reporting
 
 use super::error::UpdateError;
-use indicatif::{ProgressBar, ProgressStyle};
-use std::io::Write;
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::io::{IsTerminal, Write};
 use std::path::Path;
 
+/// Number of attempts made by [`download_file`] before giving up; each retry
+/// resumes from the bytes already written.
+const MAX_DOWNLOAD_ATTEMPTS: u32 = 3;
+
 /// Download a file from a URL with progress reporting
 ///
+/// If `dest_path` already holds a partial download, an HTTP `Range` request is
+/// sent to resume from its current length. Servers that ignore the range (and
+/// answer `200 OK`) cause a full re-download. Interrupted transfers are retried
+/// up to [`MAX_DOWNLOAD_ATTEMPTS`] times, resuming each time. The caller is
+/// expected to verify the finished file against its checksum.
+///
 /// # Arguments
 ///
 /// * `url` - The URL to download from
@@ -16,6 +26,54 @@ use std::path::Path;
 ///
 /// Returns `UpdateError::DownloadFailed` if the download fails
 pub async fn download_file(url: &str, dest_path: &Path) -> Result<(), UpdateError> {
+    let pb = ProgressBar::hidden();
+    // Only draw when a human is watching: skip non-TTY stderr and quiet mode
+    if std::io::stderr().is_terminal() && !super::is_quiet() {
+        pb.set_draw_target(ProgressDrawTarget::stderr());
+    }
+    pb.set_style(
+        ProgressStyle::default_spinner()
+            .template("{spinner:.green} Downloaded {bytes}")
+            .map_err(|e| UpdateError::DownloadFailed(format!("Failed to set progress style: {}", e)))?
+    );
+    let bar_style = ProgressStyle::default_bar()
+        .template("{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {bytes}/{total_bytes} ({eta})")
+        .map_err(|e| UpdateError::DownloadFailed(format!("Failed to set progress style: {}", e)))?
+        .progress_chars("#>-");
+    
+    let mut has_length = false;
+    let result = download_file_with_progress(url, dest_path, |downloaded, total| {
+        if let Some(total) = total {
+            if !has_length {
+                pb.set_style(bar_style.clone());
+                has_length = true;
+            }
+            pb.set_length(total);
+        }
+        pb.set_position(downloaded);
+    })
+    .await;
+    
+    match result {
+        Ok(()) => pb.finish_with_message("Download complete"),
+        Err(_) => pb.abandon(),
+    }
+    result
+}
+
+/// Download a file, reporting progress through a callback instead of drawing a bar.
+///
+/// `on_progress` receives the number of bytes written so far (including any
+/// resumed prefix) and the expected total size when the server sends
+/// `Content-Length`.
+///
+/// # Errors
+///
+/// Returns `UpdateError::DownloadFailed` if the download fails
+pub async fn download_file_with_progress<F>(url: &str, dest_path: &Path, mut on_progress: F) -> Result<(), UpdateError>
+where
+    F: FnMut(u64, Option<u64>),
+{
     tracing::info!("Downloading from: {}", url);
     
     // Create HTTP client with default settings
@@ -24,13 +82,49 @@ pub async fn download_file(url: &str, dest_path: &Path) -> Result<(), UpdateErro
         .build()
         .map_err(|e| UpdateError::DownloadFailed(format!("Failed to create HTTP client: {}", e)))?;
     
-    // Send GET request
-    let response = client
-        .get(url)
+    let mut attempt = 1;
+    loop {
+        match download_attempt(&client, url, dest_path, &mut on_progress).await {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt < MAX_DOWNLOAD_ATTEMPTS => {
+                tracing::warn!(%e, attempt, "Download interrupted; resuming");
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Length of an existing partial download at `path`, or 0 if there is none.
+fn existing_len(path: &Path) -> u64 {
+    std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+async fn download_attempt(
+    client: &reqwest::Client,
+    url: &str,
+    dest_path: &Path,
+    on_progress: &mut dyn FnMut(u64, Option<u64>),
+) -> Result<(), UpdateError> {
+    let offset = existing_len(dest_path);
+    
+    // Send GET request, asking only for the missing bytes when resuming
+    let mut request = client.get(url);
+    if offset > 0 {
+        tracing::info!("Resuming download from byte {}", offset);
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+    }
+    let response = request
         .send()
         .await
         .map_err(|e| UpdateError::DownloadFailed(format!("Failed to send request: {}", e)))?;
     
+    // The partial file already covers the whole resource
+    if offset > 0 && response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+        tracing::info!("Partial download already complete ({} bytes)", offset);
+        return Ok(());
+    }
+    
     // Check if response is successful
     if !response.status().is_success() {
         return Err(UpdateError::DownloadFailed(format!(
@@ -39,35 +133,27 @@ pub async fn download_file(url: &str, dest_path: &Path) -> Result<(), UpdateErro
         )));
     }
     
-    // Get content length for progress bar
-    let total_size = response.content_length();
-    
-    // Create progress bar
-    let pb = if let Some(size) = total_size {
-        let pb = ProgressBar::new(size);
-        pb.set_style(
-            ProgressStyle::default_bar()
-                .template("{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {bytes}/{total_bytes} ({eta})")
-                .map_err(|e| UpdateError::DownloadFailed(format!("Failed to set progress style: {}", e)))?
-                .progress_chars("#>-"),
-        );
-        pb
+    // 206 means the server honoured the range; anything else is a full body
+    let resuming = offset > 0 && response.status() == reqwest::StatusCode::PARTIAL_CONTENT;
+    let start = if resuming { offset } else { 0 };
+    if offset > 0 && !resuming {
+        tracing::info!("Server does not support range requests; restarting download");
+    }
+    
+    // Get content length for progress reporting
+    let total_size = response.content_length().map(|len| len + start);
+    
+    // Open the destination file, appending when resuming
+    let mut file = if resuming {
+        std::fs::OpenOptions::new().append(true).open(dest_path)
     } else {
-        let pb = ProgressBar::new_spinner();
-        pb.set_style(
-            ProgressStyle::default_spinner()
-                .template("{spinner:.green} Downloaded {bytes}")
-                .map_err(|e| UpdateError::DownloadFailed(format!("Failed to set progress style: {}", e)))?
-        );
-        pb
-    };
-    
-    // Create destination file
-    let mut file = std::fs::File::create(dest_path)
-        .map_err(|e| UpdateError::DownloadFailed(format!("Failed to create file: {}", e)))?;
+        std::fs::File::create(dest_path)
+    }
+    .map_err(|e| UpdateError::DownloadFailed(format!("Failed to open file: {}", e)))?;
     
     // Download with progress
-    let mut downloaded = 0u64;
+    let mut downloaded = start;
+    on_progress(downloaded, total_size);
     
     use futures_util::StreamExt;
     
@@ -75,16 +161,22 @@ pub async fn download_file(url: &str, dest_path: &Path) -> Result<(), UpdateErro
     let mut stream = response.bytes_stream();
     
     while let Some(chunk_result) = stream.next().await {
-        let chunk = chunk_result.map_err(|e| UpdateError::DownloadFailed(format!("Failed to read chunk: {}", e)))?;
+        let chunk = match chunk_result {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                // Keep what we have so the next attempt can resume from it
+                let _ = file.sync_all();
+                return Err(UpdateError::DownloadFailed(format!("Failed to read chunk: {}", e)));
+            }
+        };
         
         file.write_all(&chunk)
             .map_err(|e| UpdateError::DownloadFailed(format!("Failed to write to file: {}", e)))?;
         
         downloaded += chunk.len() as u64;
-        pb.set_position(downloaded);
+        on_progress(downloaded, total_size);
     }
     
-    pb.finish_with_message("Download complete");
     
     // Ensure all data is written
     file.sync_all()
@@ -145,6 +237,16 @@ mod tests {
     
     use tokio::time::{timeout, Duration};
 
+    #[test]
+    fn test_existing_len_for_partial_download() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("partial.bin");
+        assert_eq!(existing_len(&path), 0);
+        
+        std::fs::write(&path, b"12345").unwrap();
+        assert_eq!(existing_len(&path), 5);
+    }
+
     #[tokio::test]
     async fn test_download_checksums_file() {
         // This test requires network access and a real release
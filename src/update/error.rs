@@ -21,9 +21,14 @@ pub enum UpdateError {
     #[error("No release found for channel: {0:?}")]
     NoReleaseFound(Channel),
     
-    /// No asset found for the current platform
-    #[error("No asset found for platform: {0}")]
-    NoAssetFound(String),
+    /// None of the release's binaries match the current platform
+    #[error("No compatible asset for platform {platform}; available assets: {}", if available.is_empty() { "(none)".to_string() } else { available.join(", ") })]
+    NoCompatibleAsset {
+        /// Target triple of the current platform
+        platform: String,
+        /// Names of the binary assets that were considered
+        available: Vec<String>,
+    },
     
     /// Invalid semantic version format
     #[error("Invalid version format: {0}")]
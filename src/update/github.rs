@@ -97,7 +97,7 @@ impl GitHubClient {
             Paint::new("'Accept: application/vnd.github.v3+json'").fg(yansi::Color::Magenta)
         ));
         
-        println!("Request:\n{}", parts.join(" "));
+        progress!("Request:\n{}", parts.join(" "));
         // --------------------
         
         let response = self
@@ -109,9 +109,7 @@ impl GitHubClient {
             .map_err(|e| UpdateError::Network(e.to_string()))?;
         
         // Check rate limiting
-        if let Err(e) = self.check_rate_limit(&response) {
-            return Err(e);
-        }
+        self.check_rate_limit(&response)?;
         
         let status = response.status();
         if !status.is_success() {
@@ -120,7 +118,7 @@ impl GitHubClient {
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
             
-            println!("Response:\n{}", Paint::new(format!("HTTP {}: {}", status, error_text)).fg(yansi::Color::Red));
+            progress!("Response:\n{}", Paint::new(format!("HTTP {}: {}", status, error_text)).fg(yansi::Color::Red));
             
             return Err(UpdateError::GitHubApiError(format!(
                 "HTTP {}: {}",
@@ -133,7 +131,7 @@ impl GitHubClient {
         // Colorize the response JSON for better readability in the terminal
         // Grayed out color (dimmed/dark gray)
         let response_str = Paint::new(&text).rgb(100, 100, 100).to_string();
-        println!("Response:\n{}", response_str);
+        progress!("Response:\n{}", response_str);
         
         let github_releases: Vec<GitHubRelease> = serde_json::from_str(&text)
             .map_err(|e| UpdateError::GitHubApiError(format!("Failed to parse JSON: {}", e)))?;
@@ -224,7 +222,7 @@ impl GitHubClient {
             filtered.len(),
             channel
         );
-        println!("Found {} releases matching channel {:?}", filtered.len(), channel);
+        progress!("Found {} releases matching channel {:?}", filtered.len(), channel);
         
         // Find the newest version
         let latest = filtered
@@ -241,7 +239,7 @@ impl GitHubClient {
             .ok_or(UpdateError::NoReleaseFound(channel));
 
         if let Ok(ref release) = latest {
-            println!("Latest release for channel {:?}: {} (tag: {})", channel, release.version, release.tag_name);
+            progress!("Latest release for channel {:?}: {} (tag: {})", channel, release.version, release.tag_name);
         }
 
         latest
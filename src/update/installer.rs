@@ -2,6 +2,14 @@
 
 use super::error::UpdateError;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Extension of the lock file `install_binary` holds next to the executable
+const UPDATE_LOCK_EXTENSION: &str = "update-lock";
+
+/// A lock older than this was left by an update that was killed, not one
+/// that is still running
+const UPDATE_LOCK_STALE_AFTER: Duration = Duration::from_secs(30 * 60);
 
 /// Get the path to the current executable
 ///
@@ -113,6 +121,96 @@ pub fn restore_from_backup(backup_path: &Path, target_path: &Path) -> Result<(),
     Ok(())
 }
 
+/// Lock file marking an install in progress; removed when dropped
+struct UpdateLock(PathBuf);
+
+impl UpdateLock {
+    /// Take the lock for `current_path`, replacing a stale one
+    fn acquire(current_path: &Path) -> Result<UpdateLock, UpdateError> {
+        let path = current_path.with_extension(UPDATE_LOCK_EXTENSION);
+        if update_in_progress(current_path) {
+            return Err(UpdateError::InstallationFailed(format!(
+                "Another update is in progress (remove {:?} if it is not)",
+                path
+            )));
+        }
+        std::fs::write(&path, std::process::id().to_string()).map_err(|e| {
+            UpdateError::InstallationFailed(format!("Failed to create update lock {:?}: {}", path, e))
+        })?;
+        Ok(UpdateLock(path))
+    }
+}
+
+impl Drop for UpdateLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Whether another process holds a fresh update lock for `current_path`
+fn update_in_progress(current_path: &Path) -> bool {
+    std::fs::metadata(current_path.with_extension(UPDATE_LOCK_EXTENSION))
+        .and_then(|m| m.modified())
+        .is_ok_and(|modified| modified.elapsed().unwrap_or_default() < UPDATE_LOCK_STALE_AFTER)
+}
+
+/// What [`check_interrupted_update`] found next to the executable
+#[derive(Debug, PartialEq)]
+pub enum InterruptedUpdate {
+    /// No `.bak` / `.old` backup was left behind
+    Clean,
+    /// The binary passed its self-check, so the leftover backup was removed
+    RemovedStaleBackup(PathBuf),
+    /// The binary failed its self-check; the backup was kept for recovery
+    RecoveryNeeded { backup: PathBuf, reason: String },
+    /// The binary failed its self-check and was restored from the backup
+    Recovered { backup: PathBuf, reason: String },
+}
+
+/// Detect an update that was interrupted between installing and cleaning up
+///
+/// `install_binary` leaves a backup (one of `BACKUP_EXTENSIONS`, e.g. `<exe>.bak`)
+/// only while it is running, so finding one at startup means an earlier
+/// update was killed part-way. While another process holds the update lock
+/// the backup belongs to that running update and is left alone. When the
+/// current binary still passes the installation self-check the backup is
+/// stale and is removed; otherwise the backup is restored if `recover` is
+/// set, or reported so the caller can offer recovery.
+///
+/// # Errors
+///
+/// Returns `UpdateError::RollbackFailed` if restoring the backup fails
+pub fn check_interrupted_update(current_path: &Path, recover: bool) -> Result<InterruptedUpdate, UpdateError> {
+    if update_in_progress(current_path) {
+        tracing::debug!("Update in progress for {:?}; not checking backups", current_path);
+        return Ok(InterruptedUpdate::Clean);
+    }
+    let Some(backup) = find_update_backups(current_path).into_iter().next() else {
+        return Ok(InterruptedUpdate::Clean);
+    };
+    tracing::warn!("Found leftover update backup {:?}", backup);
+
+    let reason = match verify_installation(current_path) {
+        Ok(()) => {
+            if let Err(e) = std::fs::remove_file(&backup) {
+                tracing::warn!("Failed to remove stale backup {:?}: {}", backup, e);
+            }
+            return Ok(InterruptedUpdate::RemovedStaleBackup(backup));
+        }
+        Err(e) => e.to_string(),
+    };
+    tracing::error!("Current binary {:?} failed its self-check: {}", current_path, reason);
+    if !recover {
+        return Ok(InterruptedUpdate::RecoveryNeeded { backup, reason });
+    }
+    verify_installation(&backup).map_err(|e| {
+        UpdateError::RollbackFailed(format!("Backup {:?} is not usable either: {}", backup, e))
+    })?;
+    restore_from_backup(&backup, current_path)?;
+    tracing::warn!("Recovered {:?} from interrupted update backup {:?}", current_path, backup);
+    Ok(InterruptedUpdate::Recovered { backup, reason })
+}
+
 /// Install a new binary, replacing the current one
 ///
 /// This function:
@@ -139,6 +237,9 @@ pub fn restore_from_backup(backup_path: &Path, target_path: &Path) -> Result<(),
 pub async fn install_binary(new_binary_path: &Path, current_path: &Path) -> Result<(), UpdateError> {
     tracing::info!("Installing new binary: {:?} -> {:?}", new_binary_path, current_path);
     
+    // Keeps other `zy` processes from treating the backup as stale meanwhile
+    let _lock = UpdateLock::acquire(current_path)?;
+    
     // Step 1: Create backup
     let backup_path = create_backup(current_path)?;
     
@@ -311,6 +412,89 @@ fn verify_installation(binary_path: &Path) -> Result<(), UpdateError> {
     Ok(())
 }
 
+/// Extensions of the copies the updater (or a manual rollback) can leave next
+/// to the executable, e.g. `zy.bak`, `zy.old`, `zy.previous`
+pub const BACKUP_EXTENSIONS: &[&str] = &["bak", "old", "previous"];
+
+/// Leftover update backups next to `current_path` that exist on disk
+pub fn find_update_backups(current_path: &Path) -> Vec<PathBuf> {
+    BACKUP_EXTENSIONS
+        .iter()
+        .map(|ext| current_path.with_extension(ext))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// What happened to the executable itself during [`uninstall`]
+#[derive(Debug, PartialEq)]
+pub enum BinaryRemoval {
+    /// Only backups were removed
+    Kept,
+    /// The executable was deleted
+    Removed,
+    /// The executable is running and locked (Windows); it was moved aside and
+    /// is deleted shortly after this process exits
+    #[cfg_attr(not(windows), allow(dead_code))]
+    Scheduled,
+}
+
+/// Outcome of [`uninstall`]
+#[derive(Debug, PartialEq)]
+pub struct UninstallReport {
+    /// Backups that were deleted
+    pub removed_backups: Vec<PathBuf>,
+    pub binary: BinaryRemoval,
+}
+
+/// Remove leftover update backups next to `current_path` and, when
+/// `remove_binary` is set, the executable itself
+///
+/// Holds the update lock meanwhile, so it refuses to run during another
+/// process's `zy update` (whose rollback backup it would otherwise delete).
+///
+/// # Errors
+///
+/// Returns `UpdateError::InstallationFailed` while an update is in progress,
+/// and `UpdateError::IoError` if a backup or the binary cannot be removed.
+/// Backups deleted before the failure stay deleted.
+pub fn uninstall(current_path: &Path, remove_binary: bool) -> Result<UninstallReport, UpdateError> {
+    let _lock = UpdateLock::acquire(current_path)?;
+    let mut removed_backups = Vec::new();
+    for backup in find_update_backups(current_path) {
+        std::fs::remove_file(&backup)?;
+        tracing::info!("Removed update backup {:?}", backup);
+        removed_backups.push(backup);
+    }
+    let binary = if remove_binary { remove_executable(current_path)? } else { BinaryRemoval::Kept };
+    Ok(UninstallReport { removed_backups, binary })
+}
+
+/// Delete the executable (Unix allows unlinking a running binary)
+#[cfg(unix)]
+fn remove_executable(current_path: &Path) -> Result<BinaryRemoval, UpdateError> {
+    std::fs::remove_file(current_path)?;
+    Ok(BinaryRemoval::Removed)
+}
+
+/// Delete the executable (Windows-specific implementation)
+#[cfg(windows)]
+fn remove_executable(current_path: &Path) -> Result<BinaryRemoval, UpdateError> {
+    match std::fs::remove_file(current_path) {
+        Ok(()) => Ok(BinaryRemoval::Removed),
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+            // A running executable can be renamed but not deleted; move it out
+            // of the way and let a detached shell delete it once we exit
+            let doomed = current_path.with_extension("uninstall");
+            let _ = std::fs::remove_file(&doomed);
+            std::fs::rename(current_path, &doomed)?;
+            let command = format!("ping -n 3 127.0.0.1 >NUL & del /F /Q \"{}\"", doomed.display());
+            std::process::Command::new("cmd").args(["/C", &command]).spawn()?;
+            Ok(BinaryRemoval::Scheduled)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -399,4 +583,80 @@ mod tests {
         let result = verify_installation(&binary_path);
         assert!(result.is_err());
     }
+    
+    #[test]
+    fn test_check_interrupted_update() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let binary_path = temp_dir.path().join("test-binary");
+        let backup_path = temp_dir.path().join("test-binary.bak");
+        let write_binary = |path: &Path, len: usize| {
+            std::fs::write(path, vec![1u8; len]).unwrap();
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+            }
+        };
+        
+        // No backup: nothing to do
+        write_binary(&binary_path, 150_000);
+        assert_eq!(check_interrupted_update(&binary_path, false).unwrap(), InterruptedUpdate::Clean);
+        
+        // Backup of an update that is still running: left alone
+        write_binary(&backup_path, 150_000);
+        let lock = UpdateLock::acquire(&binary_path).unwrap();
+        assert!(UpdateLock::acquire(&binary_path).is_err());
+        assert_eq!(check_interrupted_update(&binary_path, false).unwrap(), InterruptedUpdate::Clean);
+        assert!(backup_path.exists());
+        drop(lock);
+        
+        // Healthy binary: the stale backup is removed
+        assert_eq!(
+            check_interrupted_update(&binary_path, false).unwrap(),
+            InterruptedUpdate::RemovedStaleBackup(backup_path.clone())
+        );
+        assert!(!backup_path.exists());
+        
+        // Truncated binary: reported, then restored when asked
+        write_binary(&binary_path, 10);
+        write_binary(&backup_path, 200_000);
+        assert!(matches!(
+            check_interrupted_update(&binary_path, false).unwrap(),
+            InterruptedUpdate::RecoveryNeeded { .. }
+        ));
+        assert!(backup_path.exists());
+        assert!(matches!(
+            check_interrupted_update(&binary_path, true).unwrap(),
+            InterruptedUpdate::Recovered { .. }
+        ));
+        assert_eq!(std::fs::metadata(&binary_path).unwrap().len(), 200_000);
+    }
+    
+    #[test]
+    fn test_uninstall_removes_backups_and_optionally_the_binary() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let binary_path = temp_dir.path().join("zy");
+        std::fs::write(&binary_path, b"binary").unwrap();
+        std::fs::write(temp_dir.path().join("zy.bak"), b"bak").unwrap();
+        std::fs::write(temp_dir.path().join("zy.previous"), b"previous").unwrap();
+        std::fs::write(temp_dir.path().join("zy.toml"), b"unrelated").unwrap();
+        
+        // Another process's update owns the backups
+        let lock = UpdateLock::acquire(&binary_path).unwrap();
+        assert!(uninstall(&binary_path, false).is_err());
+        assert_eq!(find_update_backups(&binary_path).len(), 2);
+        drop(lock);
+        
+        let report = uninstall(&binary_path, false).unwrap();
+        assert_eq!(report.removed_backups, vec![temp_dir.path().join("zy.bak"), temp_dir.path().join("zy.previous")]);
+        assert_eq!(report.binary, BinaryRemoval::Kept);
+        assert!(binary_path.exists());
+        assert!(temp_dir.path().join("zy.toml").exists());
+        assert!(find_update_backups(&binary_path).is_empty());
+        
+        let report = uninstall(&binary_path, true).unwrap();
+        assert!(report.removed_backups.is_empty());
+        assert_eq!(report.binary, BinaryRemoval::Removed);
+        assert!(!binary_path.exists());
+    }
 }
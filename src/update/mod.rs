@@ -52,6 +52,35 @@
 //! # }
 //! ```
 
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Enable or disable quiet mode for update progress output.
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+/// Whether quiet mode is active, either via [`set_quiet`] or the `ZY_QUIET`
+/// environment variable (any value other than empty, `0` or `false`).
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+        || std::env::var("ZY_QUIET")
+            .map(|v| !matches!(v.trim().to_lowercase().as_str(), "" | "0" | "false"))
+            .unwrap_or(false)
+}
+
+/// Print update progress to stdout, or route it to `tracing::debug!` in quiet mode.
+macro_rules! progress {
+    ($($arg:tt)*) => {
+        if $crate::update::is_quiet() {
+            tracing::debug!($($arg)*);
+        } else {
+            println!($($arg)*);
+        }
+    };
+}
+
 mod error;
 mod version;
 mod channel;
@@ -61,6 +90,7 @@ mod github;
 pub mod checksum;
 mod download;
 mod installer;
+pub mod build_info;
 
 // Re-export public API
 pub use error::UpdateError;
@@ -69,8 +99,10 @@ pub use channel::Channel;
 #[allow(unused_imports)]
 pub use platform::Platform;
 #[allow(unused_imports)]
-pub use asset::{Asset, parse_asset_name, select_asset_for_platform};
+pub use asset::{Asset, asset_match_rank, parse_asset_name, select_asset_for_platform};
 pub use github::{GitHubClient, Release};
+pub use build_info::{BuildInfo, LONG_VERSION};
+pub use installer::{check_interrupted_update, get_current_executable, uninstall, BinaryRemoval, InterruptedUpdate};
 
 /// Repository owner on GitHub
 pub const REPO_OWNER: &str = "CloudzyVPS";
@@ -121,30 +153,30 @@ pub const REPO_NAME: &str = "cli";
 /// ```
 pub async fn check_for_update(channel: Channel) -> Result<Option<Release>, UpdateError> {
     tracing::info!("Checking for updates on channel: {:?}", channel);
-    println!("Checking for updates on channel: {:?}...", channel);
+    progress!("Checking for updates on channel: {:?}...", channel);
     
     let current_version = Version::current();
     tracing::debug!("Current version: {}", current_version);
-    println!("Current binary version: {}", current_version);
+    progress!("Current binary version: {}", current_version);
     
-    println!("Connecting to GitHub repository: {}/{}...", REPO_OWNER, REPO_NAME);
+    progress!("Connecting to GitHub repository: {}/{}...", REPO_OWNER, REPO_NAME);
     let client = GitHubClient::new(REPO_OWNER.to_string(), REPO_NAME.to_string());
     let latest_release = match client.get_latest_release(channel).await {
         Ok(release) => release,
         Err(UpdateError::NoReleaseFound(_)) => {
             tracing::info!("No releases found for channel {:?}", channel);
-            println!("No releases found for channel: {:?}", channel);
+            progress!("No releases found for channel: {:?}", channel);
             return Ok(None);
         }
         Err(e) => {
             tracing::error!(%e, "Failed to fetch latest release");
-            println!("Error: {}", e);
+            progress!("Error: {}", e);
             return Err(e);
         }
     };
     
     tracing::debug!("Latest release found: {} (tag: {})", latest_release.version, latest_release.tag_name);
-    println!("Latest release found on GitHub: {} (tag: {})", latest_release.version, latest_release.tag_name);
+    progress!("Latest release found on GitHub: {} (tag: {})", latest_release.version, latest_release.tag_name);
     
     if latest_release.version.is_newer_than(&current_version) {
         tracing::info!(
@@ -152,11 +184,10 @@ pub async fn check_for_update(channel: Channel) -> Result<Option<Release>, Updat
             current_version,
             latest_release.version
         );
-        println!("Update available: {} -> {}", current_version, latest_release.version);
+        progress!("Update available: {} -> {}", current_version, latest_release.version);
         Ok(Some(latest_release))
     } else {
         tracing::info!("Already on the latest version");
-        println!("You are already running the latest version.");
         Ok(None)
     }
 }
@@ -199,23 +230,23 @@ pub async fn check_for_update(channel: Channel) -> Result<Option<Release>, Updat
 /// ```
 pub async fn perform_update(release: Release) -> Result<(), UpdateError> {
     tracing::info!("Starting update to version {}", release.version);
-    println!("\n{}", yansi::Paint::new("Starting update process...").bold());
+    progress!("\n{}", yansi::Paint::new("Starting update process...").bold());
     
     // Step 1: Select the appropriate asset for this platform
-    println!("Step 1/5: Selecting binary for your platform...");
+    progress!("Step 1/5: Selecting binary for your platform...");
     let platform = Platform::current();
     platform.is_supported()?;
     
     let binary_asset = asset::select_asset_for_platform(&release.assets, &platform)?;
     
-    println!(
+    progress!(
         "  Selected: {} ({} bytes)",
         yansi::Paint::new(&binary_asset.name).cyan(),
         format_bytes(binary_asset.size)
     );
     
     // Step 2: Find and download the SHA256SUMS.txt file
-    println!("\nStep 2/5: Downloading checksums...");
+    progress!("\nStep 2/5: Downloading checksums...");
     let checksums_asset = release
         .assets
         .iter()
@@ -227,42 +258,85 @@ pub async fn perform_update(release: Release) -> Result<(), UpdateError> {
     
     let expected_hash = checksums
         .get(&binary_asset.name)
-        .ok_or_else(|| {
-            UpdateError::ChecksumFileNotFound
-        })?
+        .ok_or(UpdateError::ChecksumFileNotFound)?
         .clone();
     
-    println!("  Expected SHA256: {}", yansi::Paint::new(&expected_hash).dim());
+    progress!("  Expected SHA256: {}", yansi::Paint::new(&expected_hash).dim());
     
     // Step 3: Download the new binary
-    println!("\nStep 3/5: Downloading new binary...");
-    let temp_dir = tempfile::tempdir().map_err(|e| {
-        UpdateError::DownloadFailed(format!("Failed to create temp directory: {}", e))
+    progress!("\nStep 3/5: Downloading new binary...");
+    // Use a stable per-user location so an interrupted download can be resumed
+    // by a later run. The name carries the version and digest, so only a
+    // partial file of this exact release is ever resumed.
+    let download_dir = download_dir();
+    std::fs::create_dir_all(&download_dir).map_err(|e| {
+        UpdateError::DownloadFailed(format!("Failed to create download directory: {}", e))
     })?;
     
-    let download_path = temp_dir.path().join(&binary_asset.name);
+    let digest_prefix = expected_hash.get(..16).unwrap_or(&expected_hash);
+    let download_path = download_dir.join(format!("{}-{}-{}", release.version, digest_prefix, binary_asset.name));
+    discard_stale_downloads(&download_dir, &download_path, binary_asset.size);
     download::download_file(&binary_asset.download_url, &download_path).await?;
     
     // Step 4: Verify checksum
-    println!("\nStep 4/5: Verifying checksum...");
-    checksum::verify_file_hash(&download_path, &expected_hash).await?;
-    println!("  {}", yansi::Paint::new("✓ Checksum verified successfully").green());
+    progress!("\nStep 4/5: Verifying checksum...");
+    if let Err(e) = checksum::verify_file_hash(&download_path, &expected_hash).await {
+        // A corrupt (or stale) partial file must not be resumed next time
+        let _ = std::fs::remove_file(&download_path);
+        return Err(e);
+    }
+    progress!("  {}", yansi::Paint::new("✓ Checksum verified successfully").green());
     
     // Step 5: Install the new binary
-    println!("\nStep 5/5: Installing new binary...");
+    progress!("\nStep 5/5: Installing new binary...");
     let current_exe = installer::get_current_executable()?;
     
-    println!("  Creating backup of current binary...");
+    progress!("  Creating backup of current binary...");
     installer::install_binary(&download_path, &current_exe).await?;
+    let _ = std::fs::remove_file(&download_path);
     
-    println!("\n{}", yansi::Paint::new("✓ Update completed successfully!").green().bold());
-    println!("\n{}", yansi::Paint::new("Please restart the application to use the new version.").yellow());
+    progress!("\n{}", yansi::Paint::new("✓ Update completed successfully!").green().bold());
+    progress!("\n{}", yansi::Paint::new("Please restart the application to use the new version.").yellow());
     
     tracing::info!("Update completed successfully");
     
     Ok(())
 }
 
+/// Where update downloads are kept between runs: the user's cache directory,
+/// falling back to a per-user subdirectory of the temp dir.
+fn download_dir() -> std::path::PathBuf {
+    let cache = if cfg!(windows) {
+        std::env::var_os("LOCALAPPDATA").map(std::path::PathBuf::from)
+    } else {
+        std::env::var_os("XDG_CACHE_HOME")
+            .map(std::path::PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| std::path::PathBuf::from(home).join(".cache")))
+    };
+    match cache {
+        Some(dir) => dir.join("zy").join("update"),
+        None => {
+            let user = std::env::var("USER").or_else(|_| std::env::var("USERNAME")).unwrap_or_default();
+            std::env::temp_dir().join(format!("zy-update-{}", user))
+        }
+    }
+}
+
+/// Remove partial downloads in `dir` other than `keep`, and `keep` itself when
+/// it is already larger than the asset's advertised `size` (a server answering
+/// a range request with 416 would otherwise make it look complete).
+fn discard_stale_downloads(dir: &std::path::Path, keep: &std::path::Path, size: u64) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let oversized = size > 0 && entry.metadata().is_ok_and(|m| m.len() > size);
+        if path.is_file() && (path != keep || oversized) {
+            tracing::debug!("Discarding stale download {:?}", path);
+            let _ = std::fs::remove_file(&path);
+        }
+    }
+}
+
 /// Format bytes as a human-readable string
 fn format_bytes(bytes: u64) -> String {
     const KB: u64 = 1024;
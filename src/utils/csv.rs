@@ -0,0 +1,27 @@
+/// Join fields into one RFC 4180 CSV line, quoting fields that contain a
+/// comma, quote or newline.
+pub fn csv_line<S: AsRef<str>>(fields: &[S]) -> String {
+    fields
+        .iter()
+        .map(|f| {
+            let f = f.as_ref();
+            if f.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", f.replace('"', "\"\""))
+            } else {
+                f.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_line_quotes_when_needed() {
+        assert_eq!(csv_line(&["a", "", "b c"]), "a,,b c");
+        assert_eq!(csv_line(&["x,y", "say \"hi\""]), "\"x,y\",\"say \"\"hi\"\"\"");
+    }
+}
@@ -0,0 +1,68 @@
+/// Case-insensitive shell-style pattern (`*` matches any run, `?` any one
+/// character), parsed once so matching doesn't re-lowercase the pattern.
+#[derive(Clone, Debug)]
+pub struct GlobPattern {
+    raw: String,
+    chars: Vec<char>,
+}
+
+impl GlobPattern {
+    pub fn new(pattern: &str) -> Self {
+        GlobPattern { raw: pattern.to_string(), chars: pattern.to_lowercase().chars().collect() }
+    }
+
+    /// The pattern as configured.
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+
+    pub fn matches(&self, text: &str) -> bool {
+        let text: Vec<char> = text.to_lowercase().chars().collect();
+        let (mut p, mut t) = (0, 0);
+        // Position of the last `*` and the text index it was tried at
+        let mut backtrack: Option<(usize, usize)> = None;
+        while t < text.len() {
+            match self.chars.get(p) {
+                Some('*') => {
+                    backtrack = Some((p, t));
+                    p += 1;
+                }
+                Some(&c) if c == '?' || c == text[t] => {
+                    p += 1;
+                    t += 1;
+                }
+                _ => match backtrack {
+                    Some((star, tried)) => {
+                        p = star + 1;
+                        t = tried + 1;
+                        backtrack = Some((star, tried + 1));
+                    }
+                    None => return false,
+                },
+            }
+        }
+        self.chars[p..].iter().all(|&c| c == '*')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_matches() {
+        let prod = GlobPattern::new("*-prod-*");
+        assert!(prod.matches("web-prod-01"));
+        assert!(prod.matches("API-PROD-eu"));
+        assert!(!prod.matches("web-staging-01"));
+        assert!(!prod.matches("prod-01"));
+
+        assert!(GlobPattern::new("payroll-*").matches("payroll-db"));
+        assert!(!GlobPattern::new("payroll-*").matches("old-payroll-db"));
+        assert!(GlobPattern::new("db-?").matches("db-1"));
+        assert!(!GlobPattern::new("db-?").matches("db-10"));
+        assert!(GlobPattern::new("exact").matches("Exact"));
+        assert!(GlobPattern::new("*").matches(""));
+        assert!(GlobPattern::new("a*b*c").matches("a-x-b-y-b-c"));
+    }
+}
@@ -0,0 +1,102 @@
+/// Hostnames beyond this many are refused outright. Not an upstream limit:
+/// it only bounds the per-name checks against a flooded form.
+const MAX_HOSTNAMES_CHECKED: usize = 1000;
+
+/// Longest allowed hostname, in characters (RFC 1123).
+const MAX_HOSTNAME_LEN: usize = 253;
+
+/// Longest allowed dot-separated label.
+const MAX_LABEL_LEN: usize = 63;
+
+/// Why `name` is not a valid RFC 1123 hostname, if it isn't. Expects a
+/// trimmed, lowercased name without a trailing dot.
+fn hostname_problem(name: &str) -> Option<String> {
+    if name.len() > MAX_HOSTNAME_LEN {
+        return Some(format!("'{}' is longer than {} characters", name, MAX_HOSTNAME_LEN));
+    }
+    for label in name.split('.') {
+        if label.is_empty() {
+            return Some(format!("'{}' has an empty label (two dots in a row, or a leading dot)", name));
+        }
+        if label.len() > MAX_LABEL_LEN {
+            return Some(format!("'{}' has a label longer than {} characters", name, MAX_LABEL_LEN));
+        }
+        if let Some(c) = label.chars().find(|c| !(c.is_ascii_alphanumeric() || *c == '-')) {
+            let shown = if c.is_whitespace() { "a space".to_string() } else { format!("'{}'", c) };
+            return Some(format!("'{}' contains {}; use only letters, digits, hyphens and dots", name, shown));
+        }
+        if label.starts_with('-') || label.ends_with('-') {
+            return Some(format!("'{}' has a label starting or ending with a hyphen", name));
+        }
+    }
+    None
+}
+
+/// Normalize wizard hostnames (trimmed, lowercased, trailing dot dropped) and
+/// check them against RFC 1123 label rules and duplicates.
+/// Returns the normalized names, or every problem found.
+pub fn validate_hostnames(raw: &[String]) -> Result<Vec<String>, Vec<String>> {
+    // Refuse oversized lists before the per-name checks, which compare every
+    // name against the ones already accepted.
+    let given = raw.iter().filter(|entry| !entry.trim().is_empty()).count();
+    if given > MAX_HOSTNAMES_CHECKED {
+        return Err(vec![format!("Too many hostnames ({} given, at most {} are accepted)", given, MAX_HOSTNAMES_CHECKED)]);
+    }
+    let mut names: Vec<String> = Vec::new();
+    let mut errors: Vec<String> = Vec::new();
+    for entry in raw {
+        let name = entry.trim().trim_end_matches('.').to_ascii_lowercase();
+        if name.is_empty() {
+            continue;
+        }
+        if let Some(problem) = hostname_problem(&name) {
+            errors.push(problem);
+        } else if names.contains(&name) {
+            errors.push(format!("'{}' is listed more than once", name));
+        } else {
+            names.push(name);
+        }
+    }
+    if names.is_empty() && errors.is_empty() {
+        errors.push("Enter at least one hostname".to_string());
+    }
+    if errors.is_empty() {
+        Ok(names)
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn owned(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_validate_hostnames_normalizes_valid_names() {
+        let ok = validate_hostnames(&owned(&[" Web-1 ", "db.example.com.", ""])).unwrap();
+        assert_eq!(ok, vec!["web-1", "db.example.com"]);
+    }
+
+    #[test]
+    fn test_validate_hostnames_reports_every_problem() {
+        let long_label = "a".repeat(64);
+        let errors = validate_hostnames(&owned(&["my host", "web_1", "-edge", "a..b", &long_label, "web", "WEB"])).unwrap_err();
+        assert_eq!(errors.len(), 6);
+        assert!(errors[0].contains("a space"));
+        assert!(errors[1].contains("'_'"));
+        assert!(errors[2].contains("hyphen"));
+        assert!(errors[3].contains("empty label"));
+        assert!(errors[4].contains("63"));
+        assert!(errors[5].contains("more than once"));
+
+        assert!(validate_hostnames(&owned(&[" ", ""])).is_err());
+        let many: Vec<String> = (0..20).map(|i| format!("web{}", i)).collect();
+        assert_eq!(validate_hostnames(&many).unwrap().len(), 20);
+        let flood: Vec<String> = vec!["web".to_string(); 100_000];
+        assert!(validate_hostnames(&flood).unwrap_err()[0].contains("Too many"));
+    }
+}
@@ -12,6 +12,21 @@ pub mod parse_int_list;
 // Status formatting
 pub mod status_formatter;
 
+// Pagination
+pub mod pagination;
+
+// Export formatting
+pub mod csv;
+
+// Pattern matching
+pub mod glob;
+
+// Hostname validation
+pub mod hostname;
+
+// Timestamp display
+pub mod time_format;
+
 // Re-export all utilities for convenient access
 pub use url_encoding::parse_urlencoded_body;
 pub use url_parser::hostname_from_url;
@@ -20,4 +35,9 @@ pub use query_string::build_query_string;
 pub use parse_flag::parse_flag;
 pub use parse_int::parse_optional_int;
 pub use parse_int_list::parse_int_list;
-pub use status_formatter::format_status;
+pub use status_formatter::{format_status, power_transition_problem, status_class, status_severity, StatusSeverity};
+pub use pagination::paginate;
+pub use csv::csv_line;
+pub use glob::GlobPattern;
+pub use hostname::validate_hostnames;
+pub use time_format::{display_time, parse_timestamp, relative_time, DisplayTime};
@@ -0,0 +1,79 @@
+/// One page of an in-memory list, plus the totals needed to render pager links.
+#[derive(Clone, Debug)]
+pub struct Paginated<T> {
+    pub items: Vec<T>,
+    pub total_count: usize,
+    /// 1-based page number, or 0 when pagination was disabled.
+    pub current_page: usize,
+    /// Always at least 1, so an empty list still renders as "page 1 of 1".
+    pub total_pages: usize,
+    pub per_page: usize,
+}
+
+/// Slice `items` into the requested page.
+///
+/// A `page` or `per_page` of 0 disables pagination and returns everything on a
+/// single page. Pages beyond the end are clamped to the last page.
+pub fn paginate<T>(items: Vec<T>, page: usize, per_page: usize) -> Paginated<T> {
+    let total_count = items.len();
+    if page == 0 || per_page == 0 {
+        return Paginated {
+            items,
+            total_count,
+            current_page: 0,
+            total_pages: 1,
+            per_page: total_count,
+        };
+    }
+
+    let total_pages = total_count.div_ceil(per_page).max(1);
+    let current_page = page.min(total_pages);
+    let items = items
+        .into_iter()
+        .skip((current_page - 1) * per_page)
+        .take(per_page)
+        .collect();
+    Paginated {
+        items,
+        total_count,
+        current_page,
+        total_pages,
+        per_page,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paginate_slices_and_clamps() {
+        let p = paginate((1..=25).collect::<Vec<_>>(), 2, 10);
+        assert_eq!(p.items, (11..=20).collect::<Vec<_>>());
+        assert_eq!((p.current_page, p.total_pages, p.total_count), (2, 3, 25));
+
+        let last = paginate((1..=25).collect::<Vec<_>>(), 9, 10);
+        assert_eq!(last.items, vec![21, 22, 23, 24, 25]);
+        assert_eq!(last.current_page, 3);
+
+        // An exact multiple must not produce a trailing empty page
+        let exact = paginate((1..=20).collect::<Vec<_>>(), 3, 10);
+        assert_eq!(exact.total_pages, 2);
+        assert_eq!(exact.items, (11..=20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_paginate_edge_cases() {
+        let empty = paginate(Vec::<u8>::new(), 1, 10);
+        assert!(empty.items.is_empty());
+        assert_eq!((empty.current_page, empty.total_pages), (1, 1));
+
+        let all = paginate(vec![1, 2, 3], 0, 10);
+        assert_eq!(all.items, vec![1, 2, 3]);
+        assert_eq!((all.current_page, all.total_pages, all.per_page), (0, 1, 3));
+
+        let unpaged = paginate(vec![1, 2, 3], 2, 0);
+        assert_eq!(unpaged.items.len(), 3);
+        assert_eq!(unpaged.total_pages, 1);
+    }
+}
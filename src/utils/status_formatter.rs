@@ -31,3 +31,95 @@ pub fn format_status(status: &str) -> String {
     }
 }
 
+/// How urgently a status needs attention, used to color status badges.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusSeverity {
+    /// Running normally (e.g. `active`).
+    Ok,
+    /// A transition that should finish on its own (e.g. `initializing`).
+    Pending,
+    /// Not serving, but intentionally so (e.g. `shutdown`).
+    Warn,
+    /// Failed or broken (e.g. `error`).
+    Error,
+    /// Anything not recognized.
+    Unknown,
+}
+
+impl StatusSeverity {
+    /// CSS class applied alongside `status-badge`.
+    pub fn css_class(self) -> &'static str {
+        match self {
+            StatusSeverity::Ok => "status-ok",
+            StatusSeverity::Pending => "status-pending",
+            StatusSeverity::Warn => "status-warn",
+            StatusSeverity::Error => "status-error",
+            StatusSeverity::Unknown => "status-unknown",
+        }
+    }
+}
+
+/// Classifies a raw upstream status into a [`StatusSeverity`].
+pub fn status_severity(status: &str) -> StatusSeverity {
+    match status.to_lowercase().as_str() {
+        "active" | "running" | "online" => StatusSeverity::Ok,
+        "preparing_disk" | "initializing" | "pending" | "creating" | "starting" | "stopping"
+        | "rebooting" | "resizing" | "reinstalling" | "migrating" => StatusSeverity::Pending,
+        "shutdown" | "stopped" | "off" | "poweroff" | "suspended" | "paused" => StatusSeverity::Warn,
+        "error" | "failed" | "crashed" | "unreachable" => StatusSeverity::Error,
+        _ => StatusSeverity::Unknown,
+    }
+}
+
+/// Shorthand for `status_severity(status).css_class()`.
+pub fn status_class(status: &str) -> &'static str {
+    status_severity(status).css_class()
+}
+
+/// Why `action` (`poweron` or `poweroff`) is not a valid transition from
+/// `status`, if it isn't. Transitional and unknown statuses allow both, since
+/// the API has the final say there.
+pub fn power_transition_problem(status: &str, action: &str) -> Option<String> {
+    let lowered = status.to_lowercase();
+    let running = matches!(lowered.as_str(), "active" | "running" | "online");
+    let stopped = matches!(lowered.as_str(), "shutdown" | "stopped" | "off" | "poweroff");
+    match action {
+        "poweron" if running => Some(format!("Instance is already running ({}); nothing to power on.", status)),
+        "poweroff" if stopped => Some(format!("Instance is already powered off ({}); nothing to power off.", status)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_statuses_map_to_severities() {
+        assert_eq!(status_severity("active"), StatusSeverity::Ok);
+        assert_eq!(status_severity("ACTIVE"), StatusSeverity::Ok);
+        assert_eq!(status_severity("preparing_disk"), StatusSeverity::Pending);
+        assert_eq!(status_severity("initializing"), StatusSeverity::Pending);
+        assert_eq!(status_severity("shutdown"), StatusSeverity::Warn);
+        assert_eq!(status_severity("error"), StatusSeverity::Error);
+        assert_eq!(status_severity("something_new"), StatusSeverity::Unknown);
+    }
+
+    #[test]
+    fn test_status_class() {
+        assert_eq!(status_class("active"), "status-ok");
+        assert_eq!(status_class("failed"), "status-error");
+        assert_eq!(status_class(""), "status-unknown");
+    }
+
+    #[test]
+    fn test_power_transition_problem() {
+        assert!(power_transition_problem("active", "poweron").unwrap().contains("already running"));
+        assert!(power_transition_problem("Shutdown", "poweroff").unwrap().contains("already powered off"));
+        assert_eq!(power_transition_problem("active", "poweroff"), None);
+        assert_eq!(power_transition_problem("stopped", "poweron"), None);
+        assert_eq!(power_transition_problem("rebooting", "poweron"), None);
+        assert_eq!(power_transition_problem("", "poweroff"), None);
+        assert_eq!(power_transition_problem("active", "reset"), None);
+    }
+}
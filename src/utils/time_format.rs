@@ -0,0 +1,87 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+/// A timestamp prepared for display: a relative phrase for the page and the
+/// absolute time for a hover title.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DisplayTime {
+    /// e.g. "3 days ago"; the raw input when it couldn't be parsed.
+    pub relative: String,
+    /// e.g. "2026-01-05 14:03 UTC"; the raw input when it couldn't be parsed.
+    pub absolute: String,
+    /// RFC 3339 value for `<time datetime>`; empty when unparseable.
+    pub iso: String,
+}
+
+/// Parse an RFC 3339 or naive ISO 8601 (assumed UTC) timestamp.
+pub fn parse_timestamp(raw: &str) -> Option<DateTime<Utc>> {
+    let raw = raw.trim();
+    DateTime::parse_from_rfc3339(raw)
+        .map(|dt| dt.with_timezone(&Utc))
+        .or_else(|_| NaiveDateTime::parse_from_str(raw, "%Y-%m-%dT%H:%M:%S%.f").map(|dt| dt.and_utc()))
+        .or_else(|_| NaiveDateTime::parse_from_str(raw, "%Y-%m-%d %H:%M:%S%.f").map(|dt| dt.and_utc()))
+        .ok()
+}
+
+/// "just now", "5 minutes ago", "in 2 hours", ... for `then` relative to `now`.
+pub fn relative_time(then: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let secs = (now - then).num_seconds();
+    let (future, secs) = (secs < 0, secs.unsigned_abs());
+    if secs < 60 {
+        return "just now".into();
+    }
+    let (n, unit) = match secs {
+        s if s < 3_600 => (s / 60, "minute"),
+        s if s < 86_400 => (s / 3_600, "hour"),
+        s if s < 30 * 86_400 => (s / 86_400, "day"),
+        s if s < 365 * 86_400 => (s / (30 * 86_400), "month"),
+        s => (s / (365 * 86_400), "year"),
+    };
+    let plural = if n == 1 { "" } else { "s" };
+    if future {
+        format!("in {} {}{}", n, unit, plural)
+    } else {
+        format!("{} {}{} ago", n, unit, plural)
+    }
+}
+
+/// Prepare `raw` for display, falling back to the raw string when unparseable.
+pub fn display_time(raw: &str, now: DateTime<Utc>) -> DisplayTime {
+    match parse_timestamp(raw) {
+        Some(dt) => DisplayTime {
+            relative: relative_time(dt, now),
+            absolute: dt.format("%Y-%m-%d %H:%M UTC").to_string(),
+            iso: dt.to_rfc3339(),
+        },
+        None => DisplayTime { relative: raw.to_string(), absolute: raw.to_string(), iso: String::new() },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_time() {
+        let now = parse_timestamp("2026-03-10T12:00:00Z").unwrap();
+        let ago = |s: &str| relative_time(parse_timestamp(s).unwrap(), now);
+        assert_eq!(ago("2026-03-10T11:59:30Z"), "just now");
+        assert_eq!(ago("2026-03-10T11:55:00Z"), "5 minutes ago");
+        assert_eq!(ago("2026-03-10T10:00:00Z"), "2 hours ago");
+        assert_eq!(ago("2026-03-09T12:00:00Z"), "1 day ago");
+        assert_eq!(ago("2025-03-01T12:00:00Z"), "1 year ago");
+        assert_eq!(ago("2026-03-10T15:00:00Z"), "in 3 hours");
+    }
+
+    #[test]
+    fn test_display_time_falls_back_to_raw() {
+        let now = parse_timestamp("2026-03-10T12:00:00Z").unwrap();
+        let shown = display_time("2026-03-07T11:30:00.123", now);
+        assert_eq!(shown.relative, "3 days ago");
+        assert_eq!(shown.absolute, "2026-03-07 11:30 UTC");
+
+        let raw = display_time("last tuesday", now);
+        assert_eq!(raw.relative, "last tuesday");
+        assert_eq!(raw.absolute, "last tuesday");
+        assert!(raw.iso.is_empty());
+    }
+}
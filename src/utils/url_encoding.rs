@@ -1,11 +1,19 @@
 use std::collections::HashMap;
 
-/// Parse URL-encoded form body into a HashMap
-pub fn parse_urlencoded_body(body: &axum::body::Bytes) -> HashMap<String, Vec<String>> {
+/// Most `key=value` pairs accepted in a form body, so a pathological body
+/// can't build an unbounded map.
+pub const MAX_FORM_FIELDS: usize = 1000;
+
+/// Parse URL-encoded form body into a HashMap. A body with more than
+/// `MAX_FORM_FIELDS` fields is refused rather than silently truncated.
+pub fn parse_urlencoded_body(body: &axum::body::Bytes) -> Result<HashMap<String, Vec<String>>, String> {
     let mut map: HashMap<String, Vec<String>> = HashMap::new();
     let raw = String::from_utf8_lossy(body);
-    for pair in raw.split('&') {
-        if pair.is_empty() { continue; }
+    let pairs: Vec<&str> = raw.split('&').filter(|pair| !pair.is_empty()).take(MAX_FORM_FIELDS + 1).collect();
+    if pairs.len() > MAX_FORM_FIELDS {
+        return Err(format!("The submitted form has more than {} fields.", MAX_FORM_FIELDS));
+    }
+    for pair in pairs {
         let mut parts = pair.splitn(2, '=');
         let key_enc = parts.next().unwrap_or("");
         let val_enc = parts.next().unwrap_or("");
@@ -13,5 +21,23 @@ pub fn parse_urlencoded_body(body: &axum::body::Bytes) -> HashMap<String, Vec<St
         let val = urlencoding::decode(val_enc).unwrap_or_else(|_| val_enc.into()).to_string();
         map.entry(key).or_default().push(val);
     }
-    map
+    Ok(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_urlencoded_body_caps_field_count() {
+        let body = axum::body::Bytes::from("hostnames=web%201&&hostnames=db&os=");
+        let map = parse_urlencoded_body(&body).unwrap();
+        assert_eq!(map["hostnames"], vec!["web 1", "db"]);
+        assert_eq!(map["os"], vec![""]);
+
+        let full = axum::body::Bytes::from("h=x&".repeat(MAX_FORM_FIELDS));
+        assert_eq!(parse_urlencoded_body(&full).unwrap()["h"].len(), MAX_FORM_FIELDS);
+        let flood = axum::body::Bytes::from("h=x&".repeat(MAX_FORM_FIELDS + 1));
+        assert!(parse_urlencoded_body(&flood).is_err());
+    }
 }
@@ -108,6 +108,16 @@ fn test_get_api_base_url_uses_default() {
     assert_eq!(result, "http://localhost:5000");
 }
 
+#[test]
+fn test_get_blocked_hostname_patterns() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    env::remove_var("ZY_BLOCKED_HOSTNAME_PATTERNS");
+    assert!(config::get_blocked_hostname_patterns().is_empty());
+
+    let _guard = EnvGuard::set("ZY_BLOCKED_HOSTNAME_PATTERNS", "*-prod-*, payroll-* ,,");
+    assert_eq!(config::get_blocked_hostname_patterns(), vec!["*-prod-*", "payroll-*"]);
+}
+
 #[test]
 fn test_get_disabled_instance_ids_empty() {
     let _lock = ENV_MUTEX.lock().unwrap();
@@ -141,3 +151,326 @@ fn test_get_disabled_instance_ids_multiple() {
     assert!(result.contains("id-2"));
     assert!(result.contains("id-3"));
 }
+
+#[test]
+fn test_get_api_rate_limit_uses_default() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    env::remove_var("API_RATE_LIMIT");
+
+    assert_eq!(config::get_api_rate_limit(), config::DEFAULT_API_RATE_LIMIT);
+}
+
+#[test]
+fn test_get_api_rate_limit_from_env() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let _guard = EnvGuard::set("API_RATE_LIMIT", "2.5");
+
+    assert_eq!(config::get_api_rate_limit(), 2.5);
+}
+
+#[test]
+fn test_get_api_rate_limit_invalid_falls_back() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let _guard = EnvGuard::set("API_RATE_LIMIT", "-3");
+
+    assert_eq!(config::get_api_rate_limit(), config::DEFAULT_API_RATE_LIMIT);
+}
+
+#[test]
+fn test_get_maintenance_mode() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    env::remove_var("ZY_MAINTENANCE_MODE");
+    assert!(!config::get_maintenance_mode());
+
+    let _guard = EnvGuard::set("ZY_MAINTENANCE_MODE", "On");
+    assert!(config::get_maintenance_mode());
+    env::set_var("ZY_MAINTENANCE_MODE", "0");
+    assert!(!config::get_maintenance_mode());
+}
+
+#[test]
+fn test_get_redirect_on_deny() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    env::remove_var("ZY_REDIRECT_ON_DENY");
+    assert!(!config::get_redirect_on_deny());
+
+    let _guard = EnvGuard::set("ZY_REDIRECT_ON_DENY", "yes");
+    assert!(config::get_redirect_on_deny());
+}
+
+#[test]
+fn test_get_max_concurrency() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    env::remove_var("ZY_MAX_CONCURRENCY");
+    assert_eq!(config::get_max_concurrency(), config::DEFAULT_MAX_CONCURRENCY);
+    let _guard = EnvGuard::set("ZY_MAX_CONCURRENCY", " 2 ");
+    assert_eq!(config::get_max_concurrency(), 2);
+    let _guard = EnvGuard::set("ZY_MAX_CONCURRENCY", "0");
+    assert_eq!(config::get_max_concurrency(), config::DEFAULT_MAX_CONCURRENCY);
+}
+
+#[test]
+fn test_get_password_scheme() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    env::remove_var("ZY_PASSWORD_HASH");
+    assert_eq!(config::get_password_scheme(), config::PasswordScheme::Pbkdf2);
+    let _guard = EnvGuard::set("ZY_PASSWORD_HASH", " Argon2id ");
+    assert_eq!(config::get_password_scheme(), config::PasswordScheme::Argon2id);
+    let _guard = EnvGuard::set("ZY_PASSWORD_HASH", "bcrypt");
+    assert_eq!(config::get_password_scheme(), config::PasswordScheme::Pbkdf2);
+}
+
+#[test]
+fn test_get_hide_prices_for_nonowners() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    env::remove_var("ZY_HIDE_PRICES_FOR_NONOWNERS");
+    assert!(!config::get_hide_prices_for_nonowners());
+
+    let _guard = EnvGuard::set("ZY_HIDE_PRICES_FOR_NONOWNERS", "1");
+    assert!(config::get_hide_prices_for_nonowners());
+    env::set_var("ZY_HIDE_PRICES_FOR_NONOWNERS", "off");
+    assert!(!config::get_hide_prices_for_nonowners());
+}
+
+#[test]
+fn test_get_api_timeouts() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    env::remove_var("API_TIMEOUT_SECS");
+    env::remove_var("API_WRITE_TIMEOUT_SECS");
+    assert_eq!(config::get_api_timeout_secs(), config::DEFAULT_API_TIMEOUT_SECS);
+    assert_eq!(config::get_api_write_timeout_secs(), config::DEFAULT_API_WRITE_TIMEOUT_SECS);
+
+    let _read = EnvGuard::set("API_TIMEOUT_SECS", "0");
+    let _write = EnvGuard::set("API_WRITE_TIMEOUT_SECS", " 300 ");
+    assert_eq!(config::get_api_timeout_secs(), 0);
+    assert_eq!(config::get_api_write_timeout_secs(), 300);
+    env::set_var("API_WRITE_TIMEOUT_SECS", "soon");
+    assert_eq!(config::get_api_write_timeout_secs(), config::DEFAULT_API_WRITE_TIMEOUT_SECS);
+}
+
+#[test]
+fn test_get_action_cooldown_secs() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    env::remove_var("ACTION_COOLDOWN_SECS");
+    assert_eq!(config::get_action_cooldown_secs(), config::DEFAULT_ACTION_COOLDOWN_SECS);
+
+    let _guard = EnvGuard::set("ACTION_COOLDOWN_SECS", "0");
+    assert_eq!(config::get_action_cooldown_secs(), 0);
+}
+
+#[test]
+fn test_wizard_defaults_from_env() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    env::remove_var("ZY_DEFAULT_REGION");
+    assert_eq!(config::get_default_region(), None);
+
+    let _region = EnvGuard::set("ZY_DEFAULT_REGION", " us-west ");
+    let _class = EnvGuard::set("ZY_DEFAULT_INSTANCE_CLASS", "CPU-Optimized");
+    let _plan = EnvGuard::set("ZY_DEFAULT_PLAN_TYPE", "Custom");
+    assert_eq!(config::get_default_region().as_deref(), Some("us-west"));
+    assert_eq!(config::get_default_instance_class().as_deref(), Some("cpu-optimized"));
+    assert_eq!(config::get_default_plan_type().as_deref(), Some("custom"));
+}
+
+#[test]
+fn test_invalid_default_plan_type_is_ignored() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let _plan = EnvGuard::set("ZY_DEFAULT_PLAN_TYPE", "hourly");
+    assert_eq!(config::get_default_plan_type(), None);
+}
+
+#[test]
+fn test_live_status_settings() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    env::remove_var("ZY_LIVE_STATUS");
+    env::remove_var("LIVE_STATUS_INTERVAL_SECS");
+    assert!(!config::get_live_status_enabled());
+    assert_eq!(config::get_live_status_interval_secs(), config::DEFAULT_LIVE_STATUS_INTERVAL_SECS);
+
+    let _flag = EnvGuard::set("ZY_LIVE_STATUS", "true");
+    let _interval = EnvGuard::set("LIVE_STATUS_INTERVAL_SECS", "0");
+    assert!(config::get_live_status_enabled());
+    assert_eq!(config::get_live_status_interval_secs(), 1);
+}
+
+#[test]
+fn test_get_api_version() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    env::remove_var("API_VERSION");
+    assert_eq!(config::get_api_version(), "v1");
+    let _guard = EnvGuard::set("API_VERSION", " /v2/ ");
+    assert_eq!(config::get_api_version(), "v2");
+}
+
+#[test]
+fn test_render_env_file_quotes_when_needed() {
+    let out = config::render_env_file("https://api.example.com", "tok#en", "");
+    assert!(out.contains("API_BASE_URL=https://api.example.com\n"));
+    assert!(out.contains("API_TOKEN=\"tok#en\"\n"));
+    assert!(!out.contains("PUBLIC_BASE_URL"));
+
+    let out = config::render_env_file("https://api.example.com", "abc", "https://zy.example.com");
+    assert!(out.contains("API_TOKEN=abc\n"));
+    assert!(out.contains("PUBLIC_BASE_URL=https://zy.example.com\n"));
+}
+
+#[test]
+fn test_render_env_file_round_trips_through_dotenv() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(".env");
+    std::fs::write(&path, config::render_env_file("https://api.example.com", "a \"b\"#c\\d", "")).unwrap();
+    env::remove_var("API_TOKEN");
+    config::load_env_file(path.to_str());
+    assert_eq!(config::get_api_token(), "a \"b\"#c\\d");
+    env::remove_var("API_TOKEN");
+    env::remove_var("API_BASE_URL");
+}
+
+#[test]
+fn test_read_api_token_file_trims_and_rejects_empty() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("token");
+    std::fs::write(&path, "  secret-token\n").unwrap();
+    assert_eq!(config::read_api_token_file(path.to_str().unwrap()).unwrap(), "secret-token");
+    std::fs::write(&path, "\n").unwrap();
+    assert!(config::read_api_token_file(path.to_str().unwrap()).is_err());
+    assert!(config::read_api_token_file(dir.path().join("missing").to_str().unwrap()).is_err());
+}
+
+#[test]
+fn test_get_home_location_requires_both_in_range() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    env::remove_var("ZY_HOME_LON");
+    let _lat = EnvGuard::set("ZY_HOME_LAT", "52.52");
+    assert_eq!(config::get_home_location(), None);
+    let _lon = EnvGuard::set("ZY_HOME_LON", "13.40");
+    assert_eq!(config::get_home_location(), Some((52.52, 13.40)));
+    let _bad = EnvGuard::set("ZY_HOME_LAT", "123");
+    assert_eq!(config::get_home_location(), None);
+}
+
+#[test]
+fn test_get_cli_user() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    env::remove_var("ZY_CLI_USER");
+    assert_eq!(config::get_cli_user(), None);
+    let _guard = EnvGuard::set("ZY_CLI_USER", " Owner ");
+    assert_eq!(config::get_cli_user().as_deref(), Some("owner"));
+}
+
+#[test]
+fn test_customer_cache_and_admin_secret() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    env::remove_var("CUSTOMER_CACHE_SECS");
+    env::remove_var("ZY_ADMIN_SECRET");
+    assert_eq!(config::get_customer_cache_secs(), config::DEFAULT_CUSTOMER_CACHE_SECS);
+    assert_eq!(config::get_admin_secret(), None);
+
+    let _ttl = EnvGuard::set("CUSTOMER_CACHE_SECS", "60");
+    let _secret = EnvGuard::set("ZY_ADMIN_SECRET", "s3cret");
+    assert_eq!(config::get_customer_cache_secs(), 60);
+    assert_eq!(config::get_admin_secret().as_deref(), Some("s3cret"));
+}
+
+#[test]
+fn test_api_tls_options() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    env::remove_var("API_CA_CERT");
+    env::remove_var("API_TLS_INSECURE");
+    assert_eq!(config::get_api_ca_cert(), None);
+    assert!(!config::get_api_tls_insecure());
+
+    let _ca = EnvGuard::set("API_CA_CERT", "/etc/zy/ca.pem");
+    let _insecure = EnvGuard::set("API_TLS_INSECURE", "true");
+    assert_eq!(config::get_api_ca_cert().as_deref(), Some("/etc/zy/ca.pem"));
+    assert!(config::get_api_tls_insecure());
+}
+
+#[test]
+fn test_get_instance_columns() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    env::remove_var("ZY_INSTANCE_COLUMNS");
+    assert_eq!(config::get_instance_columns(), None);
+    let _guard = EnvGuard::set("ZY_INSTANCE_COLUMNS", "hostname,ip,status");
+    assert_eq!(config::get_instance_columns().as_deref(), Some("hostname,ip,status"));
+}
+
+#[test]
+fn test_get_webhook_url() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    env::remove_var("ZY_WEBHOOK_URL");
+    assert_eq!(config::get_webhook_url(), None);
+    let _blank = EnvGuard::set("ZY_WEBHOOK_URL", "  ");
+    assert_eq!(config::get_webhook_url(), None);
+    let _guard = EnvGuard::set("ZY_WEBHOOK_URL", "https://hooks.example.com/zy");
+    assert_eq!(config::get_webhook_url().as_deref(), Some("https://hooks.example.com/zy"));
+}
+
+#[test]
+fn test_get_ssh_user() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    env::remove_var("ZY_SSH_USER");
+    assert_eq!(config::get_ssh_user(), config::DEFAULT_SSH_USER);
+    let _guard = EnvGuard::set("ZY_SSH_USER", " ubuntu ");
+    assert_eq!(config::get_ssh_user(), "ubuntu");
+}
+
+#[test]
+fn test_get_max_body_bytes() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    env::remove_var("ZY_MAX_BODY_BYTES");
+    assert_eq!(config::get_max_body_bytes(), config::DEFAULT_MAX_BODY_BYTES);
+    let _guard = EnvGuard::set("ZY_MAX_BODY_BYTES", "10");
+    assert_eq!(config::get_max_body_bytes(), 1024);
+}
+
+#[test]
+fn test_get_delete_require_hostname() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    env::remove_var("ZY_DELETE_REQUIRE_HOSTNAME");
+    assert!(!config::get_delete_require_hostname());
+    let _guard = EnvGuard::set("ZY_DELETE_REQUIRE_HOSTNAME", "1");
+    assert!(config::get_delete_require_hostname());
+}
+
+#[test]
+fn test_get_required_ssh_key_ids() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    env::remove_var("ZY_REQUIRED_SSH_KEY_IDS");
+    assert!(config::get_required_ssh_key_ids().is_empty());
+    let _guard = EnvGuard::set("ZY_REQUIRED_SSH_KEY_IDS", " 42, ops-key ,7,42,");
+    assert_eq!(config::get_required_ssh_key_ids(), vec![42, 7]);
+}
+
+#[test]
+fn test_read_env_file_vars_leaves_environment_alone() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(".env");
+    let _guard = EnvGuard::set("DISABLED_INSTANCE_IDS", "i-old");
+    std::fs::write(&path, "DISABLED_INSTANCE_IDS=i-1,i-2\nAPI_TOKEN=from-file\n").unwrap();
+    let vars = config::read_env_file_vars(path.to_str()).unwrap();
+    let ids = config::parse_disabled_instance_ids(&vars["DISABLED_INSTANCE_IDS"]);
+    assert!(ids.contains("i-1") && ids.contains("i-2") && !ids.contains("i-old"));
+    assert_eq!(vars["API_TOKEN"], "from-file");
+    assert!(config::get_disabled_instance_ids().contains("i-old"));
+    assert!(config::read_env_file_vars(dir.path().join("missing").to_str()).is_err());
+}
+
+#[test]
+fn test_env_file_or_process_var_falls_back_to_environment() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(".env");
+    let _guard = EnvGuard::set("ZY_BLOCKED_HOSTNAME_PATTERNS", "prod-*,db-*");
+    std::fs::write(&path, "DISABLED_INSTANCE_IDS=i-1\n").unwrap();
+    let vars = config::read_env_file_vars(path.to_str()).unwrap();
+    let (patterns, source) = config::env_file_or_process_var(&vars, "ZY_BLOCKED_HOSTNAME_PATTERNS");
+    assert_eq!(config::parse_blocked_hostname_patterns(&patterns), vec!["prod-*", "db-*"]);
+    assert_eq!(source, "environment");
+    assert_eq!(config::env_file_or_process_var(&vars, "DISABLED_INSTANCE_IDS"), ("i-1".to_string(), "env file"));
+
+    std::env::remove_var("ZY_BLOCKED_HOSTNAME_PATTERNS");
+    assert_eq!(config::env_file_or_process_var(&vars, "ZY_BLOCKED_HOSTNAME_PATTERNS"), (String::new(), "unset"));
+}
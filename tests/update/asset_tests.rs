@@ -1,5 +1,5 @@
 /// Tests for asset selection and parsing
-use zy::update::{Asset, Platform, parse_asset_name, select_asset_for_platform};
+use zy::update::{Asset, Platform, UpdateError, asset_match_rank, parse_asset_name, select_asset_for_platform};
 
 #[test]
 fn test_parse_asset_name_linux_x64() {
@@ -266,3 +266,112 @@ fn test_select_asset_skips_checksums() {
     let result = select_asset_for_platform(&assets, &platform).unwrap();
     assert_eq!(result.name, "zy-1.0.1-x86_64-unknown-linux-gnu");
 }
+
+fn binary(name: &str) -> Asset {
+    Asset {
+        name: name.to_string(),
+        download_url: format!("https://example.com/{}", name),
+        size: 1024,
+        content_type: "application/octet-stream".to_string(),
+    }
+}
+
+fn linux_x64() -> Platform {
+    Platform {
+        target: "x86_64-unknown-linux-gnu".to_string(),
+        os: "linux".to_string(),
+        arch: "x86_64".to_string(),
+        extension: None,
+    }
+}
+
+#[test]
+fn test_asset_match_rank_priority() {
+    let platform = linux_x64();
+    assert_eq!(asset_match_rank("zy-1.0.1-x86_64-unknown-linux-gnu", &platform), Some(0));
+    assert_eq!(asset_match_rank("zy-x86_64-unknown-linux-gnu", &platform), Some(1));
+    assert_eq!(asset_match_rank("zy-linux-amd64", &platform), Some(2));
+    assert_eq!(asset_match_rank("zy-linux-arm64", &platform), None);
+    assert_eq!(asset_match_rank("zy-darwin-amd64", &platform), None);
+    assert_eq!(asset_match_rank("zy-linux-amd64.tar.gz", &platform), None);
+    assert_eq!(asset_match_rank("zy-linux-amd64.sha256", &platform), None);
+}
+
+#[test]
+fn test_select_asset_skips_archives_and_checksums() {
+    let assets = vec![
+        binary("zy-linux-amd64.tar.gz"),
+        binary("zy-linux-amd64.zip"),
+        binary("zy-linux-amd64.sha256"),
+        binary("zy-linux-amd64"),
+        binary("zy-darwin-arm64.tar.gz"),
+    ];
+
+    let result = select_asset_for_platform(&assets, &linux_x64()).unwrap();
+    assert_eq!(result.name, "zy-linux-amd64");
+
+    let archives_only = vec![binary("zy-linux-amd64.tar.gz"), binary("zy-linux-amd64.sha256")];
+    assert!(matches!(
+        select_asset_for_platform(&archives_only, &linux_x64()),
+        Err(UpdateError::NoCompatibleAsset { .. })
+    ));
+}
+
+#[test]
+fn test_select_asset_fallback_linux_amd64() {
+    let assets = vec![binary("zy-darwin-arm64"), binary("zy-linux-amd64"), binary("zy-windows-amd64.exe")];
+
+    let result = select_asset_for_platform(&assets, &linux_x64()).unwrap();
+    assert_eq!(result.name, "zy-linux-amd64");
+}
+
+#[test]
+fn test_select_asset_fallback_darwin_arm64() {
+    let platform = Platform {
+        target: "aarch64-apple-darwin".to_string(),
+        os: "macos".to_string(),
+        arch: "aarch64".to_string(),
+        extension: None,
+    };
+    let assets = vec![binary("zy-linux-arm64"), binary("zy-v1.2.0-darwin-arm64")];
+
+    let result = select_asset_for_platform(&assets, &platform).unwrap();
+    assert_eq!(result.name, "zy-v1.2.0-darwin-arm64");
+}
+
+#[test]
+fn test_select_asset_fallback_windows_x64() {
+    let platform = Platform {
+        target: "x86_64-pc-windows-msvc".to_string(),
+        os: "windows".to_string(),
+        arch: "x86_64".to_string(),
+        extension: Some(".exe".to_string()),
+    };
+    let assets = vec![binary("zy-windows-x64"), binary("zy-windows-x64.exe")];
+
+    let result = select_asset_for_platform(&assets, &platform).unwrap();
+    assert_eq!(result.name, "zy-windows-x64.exe");
+}
+
+#[test]
+fn test_select_asset_prefers_exact_triple_over_alias() {
+    let assets = vec![binary("zy-linux-amd64"), binary("zy-1.0.1-x86_64-unknown-linux-gnu")];
+
+    let result = select_asset_for_platform(&assets, &linux_x64()).unwrap();
+    assert_eq!(result.name, "zy-1.0.1-x86_64-unknown-linux-gnu");
+}
+
+#[test]
+fn test_select_asset_error_lists_available() {
+    let assets = vec![binary("SHA256SUMS.txt"), binary("zy-darwin-arm64"), binary("zy-windows-amd64.exe")];
+
+    let err = select_asset_for_platform(&assets, &linux_x64()).unwrap_err();
+    match &err {
+        UpdateError::NoCompatibleAsset { platform, available } => {
+            assert_eq!(platform, "x86_64-unknown-linux-gnu");
+            assert_eq!(available, &vec!["zy-darwin-arm64".to_string(), "zy-windows-amd64.exe".to_string()]);
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+    assert!(err.to_string().contains("zy-darwin-arm64, zy-windows-amd64.exe"));
+}
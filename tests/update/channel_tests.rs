@@ -36,3 +36,9 @@ fn test_should_include_prerelease() {
     assert!(Channel::ReleaseCandidate.should_include_prerelease());
 }
 
+
+#[test]
+fn test_for_current_version_matches_build_version() {
+    let expected = Channel::from_version(env!("CARGO_PKG_VERSION"));
+    assert_eq!(Channel::for_current_version(), expected);
+}
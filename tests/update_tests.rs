@@ -0,0 +1,2 @@
+//! Update module test suite (see tests/update/)
+mod update;